@@ -0,0 +1,359 @@
+use crate::{Exception, HalfWord, Instruction, Word, sign_extend_u32};
+
+/// Extracts bits `hi..=lo` of a compressed instruction, shifted down to bit 0
+fn bits(parcel: Word, hi: u32, lo: u32) -> Word {
+    (parcel >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+/// Maps a 3-bit compressed register field (rs1', rs2', rd') to x8-x15
+fn creg(field: Word) -> usize {
+    field as usize + 8
+}
+
+impl Instruction {
+    const C_QUADRANT_0: Word = 0b00;
+    const C_QUADRANT_1: Word = 0b01;
+    const C_QUADRANT_2: Word = 0b10;
+
+    // Register numbers used implicitly by compressed instructions
+    const C_RA: usize = 1; // link register of C.JAL / C.JALR
+    const C_SP: usize = 2; // stack pointer of the *SP loads and stores
+
+    /// Instructions whose two lowest bits are not 0b11 are 16 bits long
+    pub fn is_compressed(instruction: Word) -> bool {
+        instruction & 0b11 != 0b11
+    }
+
+    /// Expands a 16-bit RVC instruction into the equivalent RV32 base instruction
+    pub fn parse_compressed(parcel: HalfWord) -> Result<Instruction, Exception> {
+        Instruction::parse_compressed_xlen(parcel, 32)
+    }
+
+    /// Expands a 16-bit RVC instruction for a hart whose registers are `xlen` bits wide.
+    /// RV64 replaces C.JAL with C.ADDIW and the single-precision loads and stores with
+    /// C.LD / C.SD, and allows 6-bit shift amounts.
+    pub fn parse_compressed_xlen(parcel: HalfWord, xlen: u32) -> Result<Instruction, Exception> {
+        let rv64: bool = xlen == 64;
+        let parcel: Word = parcel as Word;
+        let funct3: Word = bits(parcel, 15, 13);
+        let rd: usize = bits(parcel, 11, 7) as usize; // also rs1 in the CR / CI formats
+        let rs2: usize = bits(parcel, 6, 2) as usize;
+        let rd_prime: usize = creg(bits(parcel, 4, 2)); // also rs2' in the CS format
+        let rs1_prime: usize = creg(bits(parcel, 9, 7));
+        // 6-bit immediate of the CI format: imm[5] = bit 12, imm[4:0] = bits 6:2
+        let ci_imm: Word = (bits(parcel, 12, 12) << 5) | bits(parcel, 6, 2);
+        let shamt_legal: bool = rv64 || ci_imm >> 5 == 0; // shamt[5] must be 0 on RV32
+
+        let instruction: Instruction = match (bits(parcel, 1, 0), funct3) {
+            // C.ADDI4SPN: nzuimm[5:4|9:6|2|3] = bits 12:5
+            (Instruction::C_QUADRANT_0, 0b000) => {
+                let nzuimm: Word = (bits(parcel, 12, 11) << 4)
+                    | (bits(parcel, 10, 7) << 6)
+                    | (bits(parcel, 6, 6) << 2)
+                    | (bits(parcel, 5, 5) << 3);
+                if nzuimm == 0 {
+                    return Err(Exception::IllegalInstruction); // includes the all-zero instruction
+                }
+                Instruction::ADDI {
+                    imm: nzuimm as i32,
+                    rs1: Instruction::C_SP,
+                    rd: rd_prime,
+                }
+            }
+            // C.FLD / C.FSD: uimm[5:3] = bits 12:10, uimm[7:6] = bits 6:5
+            (Instruction::C_QUADRANT_0, 0b001 | 0b101) => {
+                let offset: i32 = ((bits(parcel, 12, 10) << 3) | (bits(parcel, 6, 5) << 6)) as i32;
+                if funct3 == 0b001 {
+                    Instruction::FLD {
+                        offset,
+                        rs1: rs1_prime,
+                        rd: rd_prime,
+                    }
+                } else {
+                    Instruction::FSD {
+                        offset,
+                        rs1: rs1_prime,
+                        rs2: rd_prime,
+                    }
+                }
+            }
+            // C.LD / C.SD (RV64): uimm[5:3] = bits 12:10, uimm[7:6] = bits 6:5
+            (Instruction::C_QUADRANT_0, 0b011 | 0b111) if rv64 => {
+                let offset: i32 = ((bits(parcel, 12, 10) << 3) | (bits(parcel, 6, 5) << 6)) as i32;
+                if funct3 == 0b011 {
+                    Instruction::LD {
+                        offset,
+                        rs1: rs1_prime,
+                        rd: rd_prime,
+                    }
+                } else {
+                    Instruction::SD {
+                        offset,
+                        rs1: rs1_prime,
+                        rs2: rd_prime,
+                    }
+                }
+            }
+            // C.LW / C.FLW / C.SW / C.FSW: uimm[5:3] = bits 12:10, uimm[2] = bit 6, uimm[6] = bit 5
+            (Instruction::C_QUADRANT_0, 0b010 | 0b011 | 0b110 | 0b111) => {
+                let offset: i32 = ((bits(parcel, 12, 10) << 3)
+                    | (bits(parcel, 6, 6) << 2)
+                    | (bits(parcel, 5, 5) << 6)) as i32;
+                let (rs1, reg): (usize, usize) = (rs1_prime, rd_prime);
+                match funct3 {
+                    0b010 => Instruction::LW {
+                        offset,
+                        rs1,
+                        rd: reg,
+                    },
+                    0b011 => Instruction::FLW {
+                        offset,
+                        rs1,
+                        rd: reg,
+                    },
+                    0b110 => Instruction::SW {
+                        offset,
+                        rs1,
+                        rs2: reg,
+                    },
+                    _ => Instruction::FSW {
+                        offset,
+                        rs1,
+                        rs2: reg,
+                    },
+                }
+            }
+            // C.ADDI (C.NOP when rd = x0)
+            (Instruction::C_QUADRANT_1, 0b000) => Instruction::ADDI {
+                imm: sign_extend_u32(ci_imm as usize, 6),
+                rs1: rd,
+                rd,
+            },
+            // C.ADDIW (RV64, in place of C.JAL): rd = x0 is reserved
+            (Instruction::C_QUADRANT_1, 0b001) if rv64 => {
+                if rd == 0 {
+                    return Err(Exception::IllegalInstruction);
+                }
+                Instruction::ADDIW {
+                    imm: sign_extend_u32(ci_imm as usize, 6),
+                    rs1: rd,
+                    rd,
+                }
+            }
+            // C.JAL / C.J: offset[11|4|9:8|10|6|7|3:1|5] = bits 12:2
+            (Instruction::C_QUADRANT_1, 0b001 | 0b101) => {
+                let offset: Word = (bits(parcel, 12, 12) << 11)
+                    | (bits(parcel, 11, 11) << 4)
+                    | (bits(parcel, 10, 9) << 8)
+                    | (bits(parcel, 8, 8) << 10)
+                    | (bits(parcel, 7, 7) << 6)
+                    | (bits(parcel, 6, 6) << 7)
+                    | (bits(parcel, 5, 3) << 1)
+                    | (bits(parcel, 2, 2) << 5);
+                Instruction::JAL {
+                    offset: sign_extend_u32(offset as usize, 12),
+                    rd: if funct3 == 0b001 {
+                        Instruction::C_RA
+                    } else {
+                        0
+                    },
+                }
+            }
+            // C.LI
+            (Instruction::C_QUADRANT_1, 0b010) => Instruction::ADDI {
+                imm: sign_extend_u32(ci_imm as usize, 6),
+                rs1: 0,
+                rd,
+            },
+            // C.ADDI16SP: nzimm[9] = bit 12, nzimm[4|6|8:7|5] = bits 6:2
+            (Instruction::C_QUADRANT_1, 0b011) if rd == Instruction::C_SP => {
+                let nzimm: Word = (bits(parcel, 12, 12) << 9)
+                    | (bits(parcel, 6, 6) << 4)
+                    | (bits(parcel, 5, 5) << 6)
+                    | (bits(parcel, 4, 3) << 7)
+                    | (bits(parcel, 2, 2) << 5);
+                if nzimm == 0 {
+                    return Err(Exception::IllegalInstruction);
+                }
+                Instruction::ADDI {
+                    imm: sign_extend_u32(nzimm as usize, 10),
+                    rs1: Instruction::C_SP,
+                    rd: Instruction::C_SP,
+                }
+            }
+            // C.LUI: nzimm[17] = bit 12, nzimm[16:12] = bits 6:2
+            (Instruction::C_QUADRANT_1, 0b011) => {
+                if ci_imm == 0 {
+                    return Err(Exception::IllegalInstruction);
+                }
+                Instruction::LUI {
+                    imm: (sign_extend_u32(ci_imm as usize, 6) as Word) << 12,
+                    rd,
+                }
+            }
+            (Instruction::C_QUADRANT_1, 0b100) => match bits(parcel, 11, 10) {
+                // C.SRLI / C.SRAI
+                0b00 | 0b01 if shamt_legal => {
+                    if bits(parcel, 10, 10) == 0 {
+                        Instruction::SRLI {
+                            shamt: ci_imm,
+                            rs1: rs1_prime,
+                            rd: rs1_prime,
+                        }
+                    } else {
+                        Instruction::SRAI {
+                            shamt: ci_imm,
+                            rs1: rs1_prime,
+                            rd: rs1_prime,
+                        }
+                    }
+                }
+                // C.ANDI
+                0b10 => Instruction::ANDI {
+                    imm: sign_extend_u32(ci_imm as usize, 6) as Word,
+                    rs1: rs1_prime,
+                    rd: rs1_prime,
+                },
+                // C.SUB / C.XOR / C.OR / C.AND
+                0b11 if bits(parcel, 12, 12) == 0 => {
+                    let (rs1, rs2, rd): (usize, usize, usize) = (rs1_prime, rd_prime, rs1_prime);
+                    match bits(parcel, 6, 5) {
+                        0b00 => Instruction::SUB { rs1, rs2, rd },
+                        0b01 => Instruction::XOR { rs1, rs2, rd },
+                        0b10 => Instruction::OR { rs1, rs2, rd },
+                        _ => Instruction::AND { rs1, rs2, rd },
+                    }
+                }
+                // C.SUBW / C.ADDW (RV64)
+                0b11 if rv64 && bits(parcel, 6, 6) == 0 => {
+                    let (rs1, rs2, rd): (usize, usize, usize) = (rs1_prime, rd_prime, rs1_prime);
+                    if bits(parcel, 5, 5) == 0 {
+                        Instruction::SUBW { rs1, rs2, rd }
+                    } else {
+                        Instruction::ADDW { rs1, rs2, rd }
+                    }
+                }
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            // C.BEQZ / C.BNEZ: offset[8|4:3] = bits 12:10, offset[7:6|2:1|5] = bits 6:2
+            (Instruction::C_QUADRANT_1, 0b110 | 0b111) => {
+                let offset: Word = (bits(parcel, 12, 12) << 8)
+                    | (bits(parcel, 11, 10) << 3)
+                    | (bits(parcel, 6, 5) << 6)
+                    | (bits(parcel, 4, 3) << 1)
+                    | (bits(parcel, 2, 2) << 5);
+                let offset: i32 = sign_extend_u32(offset as usize, 9);
+                if funct3 == 0b110 {
+                    Instruction::BEQ {
+                        offset,
+                        rs1: rs1_prime,
+                        rs2: 0,
+                    }
+                } else {
+                    Instruction::BNE {
+                        offset,
+                        rs1: rs1_prime,
+                        rs2: 0,
+                    }
+                }
+            }
+            // C.SLLI
+            (Instruction::C_QUADRANT_2, 0b000) if shamt_legal => Instruction::SLLI {
+                shamt: ci_imm,
+                rs1: rd,
+                rd,
+            },
+            // C.FLDSP: uimm[5] = bit 12, uimm[4:3|8:6] = bits 6:2
+            (Instruction::C_QUADRANT_2, 0b001) => {
+                let offset: Word = (bits(parcel, 12, 12) << 5)
+                    | (bits(parcel, 6, 5) << 3)
+                    | (bits(parcel, 4, 2) << 6);
+                Instruction::FLD {
+                    offset: offset as i32,
+                    rs1: Instruction::C_SP,
+                    rd,
+                }
+            }
+            // C.LDSP (RV64): uimm[5] = bit 12, uimm[4:3|8:6] = bits 6:2, rd = x0 is reserved
+            (Instruction::C_QUADRANT_2, 0b011) if rv64 => {
+                if rd == 0 {
+                    return Err(Exception::IllegalInstruction);
+                }
+                let offset: Word = (bits(parcel, 12, 12) << 5)
+                    | (bits(parcel, 6, 5) << 3)
+                    | (bits(parcel, 4, 2) << 6);
+                Instruction::LD {
+                    offset: offset as i32,
+                    rs1: Instruction::C_SP,
+                    rd,
+                }
+            }
+            // C.LWSP / C.FLWSP: uimm[5] = bit 12, uimm[4:2|7:6] = bits 6:2
+            (Instruction::C_QUADRANT_2, 0b010 | 0b011) => {
+                let offset: i32 = ((bits(parcel, 12, 12) << 5)
+                    | (bits(parcel, 6, 4) << 2)
+                    | (bits(parcel, 3, 2) << 6)) as i32;
+                let rs1: usize = Instruction::C_SP;
+                match funct3 {
+                    // rd = x0 is reserved for C.LWSP, while f0 is a valid C.FLWSP target
+                    0b010 if rd == 0 => return Err(Exception::IllegalInstruction),
+                    0b010 => Instruction::LW { offset, rs1, rd },
+                    _ => Instruction::FLW { offset, rs1, rd },
+                }
+            }
+            (Instruction::C_QUADRANT_2, 0b100) => match (bits(parcel, 12, 12), rd, rs2) {
+                (0, 0, 0) => return Err(Exception::IllegalInstruction),
+                // C.JR
+                (0, rs1, 0) => Instruction::JALR {
+                    offset: 0,
+                    rs1,
+                    rd: 0,
+                },
+                // C.MV
+                (0, rd, rs2) => Instruction::ADD { rs1: 0, rs2, rd },
+                (_, 0, 0) => Instruction::EBREAK,
+                // C.JALR
+                (_, rs1, 0) => Instruction::JALR {
+                    offset: 0,
+                    rs1,
+                    rd: Instruction::C_RA,
+                },
+                // C.NTL.* (C.ADD x0, x2-x5) / C.ADD
+                (_, rd, rs2) => match Instruction::ntl_hint(rs2) {
+                    Some(hint) if rd == 0 => hint,
+                    _ => Instruction::ADD { rs1: rd, rs2, rd },
+                },
+            },
+            // C.FSDSP: uimm[5:3|8:6] = bits 12:7
+            (Instruction::C_QUADRANT_2, 0b101) => {
+                let offset: Word = (bits(parcel, 12, 10) << 3) | (bits(parcel, 9, 7) << 6);
+                Instruction::FSD {
+                    offset: offset as i32,
+                    rs1: Instruction::C_SP,
+                    rs2,
+                }
+            }
+            // C.SDSP (RV64): uimm[5:3|8:6] = bits 12:7
+            (Instruction::C_QUADRANT_2, 0b111) if rv64 => {
+                let offset: Word = (bits(parcel, 12, 10) << 3) | (bits(parcel, 9, 7) << 6);
+                Instruction::SD {
+                    offset: offset as i32,
+                    rs1: Instruction::C_SP,
+                    rs2,
+                }
+            }
+            // C.SWSP / C.FSWSP: uimm[5:2|7:6] = bits 12:7
+            (Instruction::C_QUADRANT_2, 0b110 | 0b111) => {
+                let offset: i32 = ((bits(parcel, 12, 9) << 2) | (bits(parcel, 8, 7) << 6)) as i32;
+                let rs1: usize = Instruction::C_SP;
+                if funct3 == 0b110 {
+                    Instruction::SW { offset, rs1, rs2 }
+                } else {
+                    Instruction::FSW { offset, rs1, rs2 }
+                }
+            }
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+}
@@ -0,0 +1,672 @@
+use crate::{DEFAULT_VLEN, Exception, PagingMode, Word, Xlen};
+
+// Floating-point control and status
+pub const FFLAGS: usize = 0x001; // Accrued exceptions (view of fcsr[4:0])
+pub const FRM: usize = 0x002; // Dynamic rounding mode (view of fcsr[7:5])
+pub const FCSR: usize = 0x003; // Floating-point control and status register
+
+// Vector control and status
+pub const VSTART: usize = 0x008; // First element to execute, set when a vector access traps
+pub const VXSAT: usize = 0x009; // Fixed-point saturation flag (view of vcsr[0])
+pub const VXRM: usize = 0x00A; // Fixed-point rounding mode (view of vcsr[2:1])
+pub const VCSR: usize = 0x00F; // Vector control and status register
+pub const VL: usize = 0xC20; // Vector length, set by vsetvl
+pub const VTYPE: usize = 0xC21; // Vector data type, set by vsetvl
+pub const VLENB: usize = 0xC22; // VLEN / 8
+
+// Unprivileged counters and timers (read-only shadows of the machine counters)
+pub const CYCLE: usize = 0xC00; // Cycle counter for RDCYCLE
+pub const TIME: usize = 0xC01; // Timer for RDTIME
+pub const INSTRET: usize = 0xC02; // Instructions-retired counter for RDINSTRET
+pub const HPMCOUNTER3: usize = 0xC03; // Performance-monitoring counters 3 to 31
+pub const HPMCOUNTER31: usize = 0xC1F;
+pub const CYCLEH: usize = 0xC80; // Upper halves of the counters (RV32 only)
+pub const TIMEH: usize = 0xC81;
+pub const INSTRETH: usize = 0xC82;
+pub const HPMCOUNTER3H: usize = 0xC83;
+pub const HPMCOUNTER31H: usize = 0xC9F;
+
+// Machine information registers (read-only)
+pub const MVENDORID: usize = 0xF11; // Vendor ID
+pub const MARCHID: usize = 0xF12; // Architecture ID
+pub const MIMPID: usize = 0xF13; // Implementation ID
+pub const MHARTID: usize = 0xF14; // Hardware thread ID
+
+// Supervisor trap setup
+pub const SSTATUS: usize = 0x100; // Supervisor status register (view of mstatus)
+pub const SIE: usize = 0x104; // Supervisor interrupt-enable register (view of mie)
+pub const STVEC: usize = 0x105; // Supervisor trap-handler base address
+pub const SCOUNTEREN: usize = 0x106; // Supervisor counter enable
+
+// Supervisor configuration
+pub const SENVCFG: usize = 0x10A; // Supervisor environment configuration (U-mode CBOs)
+
+// Supervisor trap handling
+pub const SSCRATCH: usize = 0x140; // Scratch register for supervisor trap handlers
+pub const SEPC: usize = 0x141; // Supervisor exception program counter
+pub const SCAUSE: usize = 0x142; // Supervisor trap cause
+pub const STVAL: usize = 0x143; // Supervisor bad address or instruction
+pub const SIP: usize = 0x144; // Supervisor interrupt pending (view of mip)
+
+// Supervisor protection and translation
+pub const SATP: usize = 0x180; // Supervisor address translation and protection
+
+// Machine trap setup
+pub const MSTATUS: usize = 0x300; // Machine status register
+pub const MISA: usize = 0x301; // ISA and extensions
+pub const MEDELEG: usize = 0x302; // Machine exception delegation register
+pub const MIDELEG: usize = 0x303; // Machine interrupt delegation register
+pub const MIE: usize = 0x304; // Machine interrupt-enable register
+pub const MTVEC: usize = 0x305; // Machine trap-handler base address
+pub const MCOUNTEREN: usize = 0x306; // Machine counter enable
+pub const MSTATUSH: usize = 0x310; // Upper half of mstatus (RV32 only)
+
+// Machine configuration
+pub const MENVCFG: usize = 0x30A; // Machine environment configuration (S-mode and U-mode CBOs)
+pub const MENVCFGH: usize = 0x31A; // Upper half of menvcfg (RV32 only)
+
+// Machine memory protection
+pub const PMPCFG0: usize = 0x3A0; // PMP configuration, a byte per entry (odd registers are RV32 only)
+pub const PMPCFG15: usize = 0x3AF;
+pub const PMPADDR0: usize = 0x3B0; // PMP address registers 0 to 63
+pub const PMPADDR63: usize = 0x3EF;
+
+// Machine counter setup
+pub const MCOUNTINHIBIT: usize = 0x320; // Machine counter-inhibit register
+pub const MHPMEVENT3: usize = 0x323; // Event selectors of mhpmcounter3 to mhpmcounter31
+pub const MHPMEVENT31: usize = 0x33F;
+
+// Machine trap handling
+pub const MSCRATCH: usize = 0x340; // Scratch register for machine trap handlers
+pub const MEPC: usize = 0x341; // Machine exception program counter
+pub const MCAUSE: usize = 0x342; // Machine trap cause
+pub const MTVAL: usize = 0x343; // Machine bad address or instruction
+pub const MIP: usize = 0x344; // Machine interrupt pending
+
+// Machine counters
+pub const MCYCLE: usize = 0xB00; // Machine cycle counter
+pub const MINSTRET: usize = 0xB02; // Machine instructions-retired counter
+pub const MHPMCOUNTER3: usize = 0xB03; // Machine performance-monitoring counters 3 to 31
+pub const MHPMCOUNTER31: usize = 0xB1F;
+pub const MCYCLEH: usize = 0xB80; // Upper halves of the machine counters (RV32 only)
+pub const MINSTRETH: usize = 0xB82;
+pub const MHPMCOUNTER3H: usize = 0xB83;
+pub const MHPMCOUNTER31H: usize = 0xB9F;
+
+// Counter numbers, also the bits of mcountinhibit and mcounteren
+pub const COUNTER_CY: usize = 0; // cycle
+pub const COUNTER_TM: usize = 1; // time (cannot be inhibited)
+pub const COUNTER_IR: usize = 2; // instret
+const COUNTER_COUNT: usize = 32; // cycle, time, instret and 29 hpmcounters
+
+// Events selected by mhpmevent, counted once per instruction that causes them
+pub const HPM_EVENT_NONE: Word = 0; // the counter does not count
+pub const HPM_EVENT_LOAD: Word = 1; // integer, floating-point and LR loads
+pub const HPM_EVENT_STORE: Word = 2; // integer, floating-point and SC stores
+pub const HPM_EVENT_ATOMIC: Word = 3; // AMOs
+pub const HPM_EVENT_BRANCH: Word = 4; // conditional branches
+pub const HPM_EVENT_BRANCH_TAKEN: Word = 5; // conditional branches that were taken
+pub const HPM_EVENT_JUMP: Word = 6; // JAL / JALR
+pub const HPM_EVENT_FP: Word = 7; // floating-point instructions
+pub const HPM_EVENT_EXCEPTION: Word = 8; // instructions that raised an exception
+const HPM_EVENT_COUNT: Word = 9;
+
+// mstatus fields
+pub const MSTATUS_SIE: Word = 1 << 1; // Supervisor interrupt enable
+pub const MSTATUS_MIE: Word = 1 << 3; // Machine interrupt enable
+pub const MSTATUS_SPIE: Word = 1 << 5; // Supervisor interrupt enable before the trap
+pub const MSTATUS_MPIE: Word = 1 << 7; // Machine interrupt enable before the trap
+pub const MSTATUS_SPP: Word = 1 << 8; // Privilege mode before a supervisor trap (U or S)
+pub const MSTATUS_VS: Word = 0b11 << 9; // Vector unit state
+pub const MSTATUS_MPP: Word = 0b11 << 11; // Privilege mode before a machine trap
+pub const MSTATUS_FS: Word = 0b11 << 13; // Floating-point unit state
+pub const MSTATUS_MPRV: Word = 1 << 17; // Loads and stores use the privilege in MPP
+pub const MSTATUS_SUM: Word = 1 << 18; // S-mode may access user pages
+pub const MSTATUS_MXR: Word = 1 << 19; // Executable pages are also readable
+pub const MSTATUS_TVM: Word = 1 << 20; // satp and SFENCE.VMA are illegal in S-mode
+pub const MSTATUS_TW: Word = 1 << 21; // WFI is illegal in S-mode
+pub const MSTATUS_TSR: Word = 1 << 22; // SRET is illegal in supervisor mode
+pub const MSTATUS_SD: Word = 1 << 31; // Some extension state is dirty (read-only, bit 63 on RV64)
+
+// mstatus.FS values
+pub const FS_OFF: Word = 0b00 << 13; // floating-point instructions are illegal
+pub const FS_INITIAL: Word = 0b01 << 13;
+pub const FS_CLEAN: Word = 0b10 << 13;
+pub const FS_DIRTY: Word = 0b11 << 13; // floating-point state was modified
+
+// mstatus.VS values
+pub const VS_OFF: Word = 0b00 << 9; // vector instructions are illegal
+pub const VS_INITIAL: Word = 0b01 << 9;
+pub const VS_CLEAN: Word = 0b10 << 9;
+pub const VS_DIRTY: Word = 0b11 << 9; // vector state was modified
+
+// mtvec modes
+pub const MTVEC_MODE: Word = 0b11; // mode field in the low bits of mtvec
+pub const MTVEC_DIRECT: Word = 0b00; // all traps go to BASE
+pub const MTVEC_VECTORED: Word = 0b01; // interrupts go to BASE + 4 * cause
+
+// menvcfg / senvcfg fields, enabling cache-block operations for the modes below
+pub const ENVCFG_CBIE: Word = 0b11 << 4; // CBO.INVAL: 00 illegal, 01 flushes, 11 invalidates
+pub const ENVCFG_CBCFE: Word = 1 << 6; // CBO.CLEAN and CBO.FLUSH
+pub const ENVCFG_CBZE: Word = 1 << 7; // CBO.ZERO
+const ENVCFG_CBIE_RESERVED: Word = 0b10 << 4;
+
+// mie / mip fields
+pub const MIP_SSIP: Word = 1 << 1; // Supervisor software interrupt
+pub const MIP_MSIP: Word = 1 << 3; // Machine software interrupt
+pub const MIP_STIP: Word = 1 << 5; // Supervisor timer interrupt
+pub const MIP_MTIP: Word = 1 << 7; // Machine timer interrupt
+pub const MIP_SEIP: Word = 1 << 9; // Supervisor external interrupt
+pub const MIP_MEIP: Word = 1 << 11; // Machine external interrupt
+
+const MSTATUS_MPP_SHIFT: u32 = 11; // MPP sits above VS
+const MSTATUS_UXL_SHIFT: u32 = 32; // UXL and SXL (RV64 only) give the XLEN of U and S
+const MSTATUS_SXL_SHIFT: u32 = 34;
+// the mstatus fields visible through sstatus (besides SD)
+const SSTATUS_FIELDS: Word =
+    MSTATUS_SIE | MSTATUS_SPIE | MSTATUS_SPP | MSTATUS_VS | MSTATUS_FS | MSTATUS_SUM | MSTATUS_MXR;
+const SUPERVISOR_INTERRUPTS: Word = MIP_SSIP | MIP_STIP | MIP_SEIP; // delegable through mideleg
+const MACHINE_INTERRUPTS: Word = MIP_MSIP | MIP_MTIP | MIP_MEIP;
+// every exception except ECALL from M-mode can be delegated
+const DELEGABLE_EXCEPTIONS: Word = 0xFFFF & !(1 << 11);
+
+const CSR_COUNT: usize = 4096; // CSR addresses are 12 bits wide
+const FCSR_FFLAGS: Word = 0b11111; // accrued exception flags
+const FCSR_FRM_SHIFT: Word = 5; // frm sits above fflags
+const VCSR_VXSAT: Word = 0b1; // saturation flag
+const VCSR_VXRM_SHIFT: Word = 1; // vxrm sits above vxsat
+
+const MISA_A: Word = 1 << 0; // Atomic extension
+const MISA_B: Word = 1 << 1; // Bit-manipulation extension (Zba, Zbb and Zbs)
+pub const MISA_C: Word = 1 << 2; // Compressed extension (the only writable misa bit)
+const MISA_D: Word = 1 << 3; // Double-precision floating-point extension
+const MISA_E: Word = 1 << 4; // RV32E / RV64E embedded base ISA
+const MISA_F: Word = 1 << 5; // Single-precision floating-point extension
+const MISA_I: Word = 1 << 8; // RV32I / RV64I base ISA
+const MISA_M: Word = 1 << 12; // Integer multiply / divide extension
+const MISA_S: Word = 1 << 18; // Supervisor mode
+const MISA_U: Word = 1 << 20; // User mode
+const MISA_V: Word = 1 << 21; // Vector extension
+
+/// Privilege modes, numbered as in mstatus.MPP and the CSR address bits 9:8
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Privilege {
+    User = 0,
+    Supervisor = 1,
+    Machine = 3,
+}
+
+impl Privilege {
+    /// Decodes an MPP value, None for the reserved encoding 2
+    pub fn from_bits(bits: Word) -> Option<Privilege> {
+        match bits {
+            0 => Some(Privilege::User),
+            1 => Some(Privilege::Supervisor),
+            3 => Some(Privilege::Machine),
+            _ => None,
+        }
+    }
+
+    /// The mode saved in mstatus.MPP
+    pub fn from_mstatus<X: Xlen>(mstatus: X) -> Privilege {
+        let bits: Word = ((mstatus & X::from(MSTATUS_MPP)) >> MSTATUS_MPP_SHIFT).to_u64() as Word;
+        Privilege::from_bits(bits).unwrap_or(Privilege::Machine) // WARL keeps MPP legal
+    }
+
+    /// This mode placed in the MPP field of mstatus
+    pub fn mpp(self) -> Word {
+        (self as Word) << MSTATUS_MPP_SHIFT
+    }
+}
+
+/// Control and status registers of a single hart, addressed by their 12-bit CSR number
+pub struct CSRFile<X: Xlen = Word> {
+    csr: Vec<X>,                    // backing storage for every CSR address
+    counters: [u64; COUNTER_COUNT], // 64-bit counters, indexed by counter number
+    counters_written: u32,          // counters written by the current instruction
+}
+
+impl<X: Xlen> Default for CSRFile<X> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<X: Xlen> CSRFile<X> {
+    pub fn new() -> Self {
+        let mut csr: Vec<X> = vec![X::default(); CSR_COUNT];
+        // MXL in the top two bits of misa encodes XLEN: 1 for 32, 2 for 64
+        let mxl: X = X::from_u64(((X::XLEN / 32) as u64) << (X::XLEN - 2));
+        csr[MISA] = mxl
+            | X::from(
+                MISA_A
+                    | MISA_B
+                    | MISA_C
+                    | MISA_D
+                    | MISA_F
+                    | MISA_I
+                    | MISA_M
+                    | MISA_S
+                    | MISA_U
+                    | MISA_V,
+            );
+        // the hart resets in M-mode with the FPU and vector unit enabled
+        csr[MSTATUS] = X::from(MSTATUS_MPP | FS_INITIAL | VS_INITIAL);
+        if X::XLEN == 64 {
+            // U-mode and S-mode run with the full 64 bits, UXL and SXL are read-only
+            csr[MSTATUS] =
+                csr[MSTATUS] | X::from_u64((2 << MSTATUS_UXL_SHIFT) | (2 << MSTATUS_SXL_SHIFT));
+        }
+        csr[VTYPE] = X::from_u64(1 << (X::XLEN - 1)); // vill until the first vsetvl
+        csr[VLENB] = X::from((DEFAULT_VLEN / 8) as Word);
+        CSRFile {
+            csr,
+            counters: [0; COUNTER_COUNT],
+            counters_written: 0,
+        }
+    }
+
+    /// CSRs of a hart with the embedded base ISA (RV32E / RV64E) in place of I
+    pub fn new_embedded() -> Self {
+        let mut csr_file: Self = Self::new();
+        csr_file.csr[MISA] = (csr_file.csr[MISA] & !X::from(MISA_I)) | X::from(MISA_E);
+        csr_file
+    }
+
+    /// Whether the CSR at `addr` is implemented by this hart
+    pub fn exists(addr: usize) -> bool {
+        if matches!(
+            addr,
+            MSTATUSH | MENVCFGH | CYCLEH..=HPMCOUNTER31H | MCYCLEH | MINSTRETH..=MHPMCOUNTER31H
+        ) {
+            return X::XLEN == 32;
+        }
+        if let PMPCFG0..=PMPCFG15 = addr {
+            return X::XLEN == 32 || addr.is_multiple_of(2); // RV64 packs two into each even one
+        }
+        matches!(
+            addr,
+            PMPADDR0..=PMPADDR63
+                | CYCLE..=HPMCOUNTER31
+                | MCYCLE
+                | MINSTRET..=MHPMCOUNTER31
+                | MCOUNTEREN
+                | SCOUNTEREN
+                | SENVCFG
+                | MENVCFG
+                | MCOUNTINHIBIT
+                | MHPMEVENT3..=MHPMEVENT31
+                | FFLAGS
+                | FRM
+                | FCSR
+                | VSTART
+                | VXSAT
+                | VXRM
+                | VCSR
+                | VL
+                | VTYPE
+                | VLENB
+                | MVENDORID
+                | MARCHID
+                | MIMPID
+                | MHARTID
+                | SSTATUS
+                | SIE
+                | STVEC
+                | SSCRATCH
+                | SEPC
+                | SCAUSE
+                | STVAL
+                | SIP
+                | SATP
+                | MSTATUS
+                | MISA
+                | MEDELEG
+                | MIDELEG
+                | MIE
+                | MTVEC
+                | MSCRATCH
+                | MEPC
+                | MCAUSE
+                | MTVAL
+                | MIP
+        )
+    }
+
+    /// CSRs whose top two address bits are set are read-only
+    pub fn is_read_only(addr: usize) -> bool {
+        (addr >> 10) & 0b11 == 0b11
+    }
+
+    /// Reads a CSR as an instruction would, failing for unimplemented CSRs
+    pub fn read(&self, addr: usize) -> Result<X, Exception> {
+        if !Self::exists(addr)
+            || (Self::is_fp_csr(addr) && !self.fp_enabled())
+            || (Self::is_vector_csr(addr) && !self.vector_enabled())
+        {
+            return Err(Exception::IllegalInstruction);
+        }
+        match addr {
+            FFLAGS => Ok(self.csr[FCSR] & X::from(FCSR_FFLAGS)),
+            FRM => Ok(self.csr[FCSR] >> FCSR_FRM_SHIFT),
+            VXSAT => Ok(self.csr[VCSR] & X::from(VCSR_VXSAT)),
+            VXRM => Ok(self.csr[VCSR] >> VCSR_VXRM_SHIFT),
+            // mepc[1] and sepc[1] read as 0 without C
+            MEPC | SEPC => Ok(self.csr[addr] & !X::from(self.ialign() - 1)),
+            SSTATUS => Ok(self.csr[MSTATUS] & Self::sstatus_mask()),
+            SIE => Ok(self.csr[MIE] & self.csr[MIDELEG]),
+            SIP => Ok(self.csr[MIP] & self.csr[MIDELEG]),
+            CYCLE..=HPMCOUNTER31 => Ok(X::from_u64(self.counters[addr - CYCLE])),
+            MCYCLE..=MHPMCOUNTER31 => Ok(X::from_u64(self.counters[addr - MCYCLE])),
+            CYCLEH..=HPMCOUNTER31H => Ok(X::from_u64(self.counters[addr - CYCLEH] >> 32)),
+            MCYCLEH..=MHPMCOUNTER31H => Ok(X::from_u64(self.counters[addr - MCYCLEH] >> 32)),
+            _ => Ok(self.csr[addr]),
+        }
+    }
+
+    /// Writes a CSR as an instruction would, failing for unimplemented or read-only CSRs
+    pub fn write(&mut self, addr: usize, value: X) -> Result<(), Exception> {
+        if !Self::exists(addr)
+            || Self::is_read_only(addr)
+            || (Self::is_fp_csr(addr) && !self.fp_enabled())
+            || (Self::is_vector_csr(addr) && !self.vector_enabled())
+        {
+            return Err(Exception::IllegalInstruction);
+        }
+        match addr {
+            FFLAGS => {
+                let fflags: X = X::from(FCSR_FFLAGS);
+                self.set_fcsr((self.csr[FCSR] & !fflags) | (value & fflags))
+            }
+            FRM => self.set_fcsr(
+                (self.csr[FCSR] & X::from(FCSR_FFLAGS))
+                    | ((value & X::from(0b111)) << FCSR_FRM_SHIFT),
+            ),
+            FCSR => self.set_fcsr(value & X::from(0xFF)),
+            VXSAT => {
+                let vxsat: X = X::from(VCSR_VXSAT);
+                self.set_vcsr((self.csr[VCSR] & !vxsat) | (value & vxsat))
+            }
+            VXRM => self.set_vcsr(
+                (self.csr[VCSR] & X::from(VCSR_VXSAT))
+                    | ((value & X::from(0b11)) << VCSR_VXRM_SHIFT),
+            ),
+            VCSR => self.set_vcsr(value & X::from(0b111)),
+            // only indices below VLMAX at SEW = 8 and LMUL = 8 (that is, VLEN) are held
+            VSTART => {
+                self.csr[addr] = value & X::from_u64(self.csr[VLENB].to_u64() * 8 - 1);
+                self.set_vector_dirty();
+            }
+            // WARL: only the C extension can be toggled
+            MISA => {
+                let c: X = X::from(MISA_C);
+                self.csr[addr] = (self.csr[addr] & !c) | (value & c);
+            }
+            MSTATUS => {
+                let mut writable: X = X::from(
+                    SSTATUS_FIELDS
+                        | MSTATUS_MIE
+                        | MSTATUS_MPIE
+                        | MSTATUS_MPRV
+                        | MSTATUS_TVM
+                        | MSTATUS_TW
+                        | MSTATUS_TSR,
+                );
+                // WARL: the reserved MPP value 2 leaves the field unchanged
+                let mpp: Word =
+                    ((value & X::from(MSTATUS_MPP)) >> MSTATUS_MPP_SHIFT).to_u64() as Word;
+                if Privilege::from_bits(mpp).is_some() {
+                    writable = writable | X::from(MSTATUS_MPP);
+                }
+                self.set_mstatus((self.csr[addr] & !writable) | (value & writable));
+            }
+            MSTATUSH => {} // no big-endian support, all fields are hardwired to 0
+            MENVCFGH => {} // PBMTE, ADUE and STCE are not implemented
+            // WARL: only the CBO enables are implemented, and the reserved CBIE value 10 leaves
+            // the field unchanged
+            MENVCFG | SENVCFG => {
+                let mut writable: X = X::from(ENVCFG_CBCFE | ENVCFG_CBZE);
+                if value & X::from(ENVCFG_CBIE) != X::from(ENVCFG_CBIE_RESERVED) {
+                    writable = writable | X::from(ENVCFG_CBIE);
+                }
+                self.csr[addr] = (self.csr[addr] & !writable) | (value & writable);
+            }
+            SSTATUS => {
+                let writable: X = X::from(SSTATUS_FIELDS);
+                self.set_mstatus((self.csr[MSTATUS] & !writable) | (value & writable));
+            }
+            MIE => self.csr[addr] = value & X::from(MACHINE_INTERRUPTS | SUPERVISOR_INTERRUPTS),
+            // only the delegated bits of mie and mip are visible to the supervisor
+            SIE => {
+                let delegated: X = self.csr[MIDELEG];
+                self.csr[MIE] = (self.csr[MIE] & !delegated) | (value & delegated);
+            }
+            SIP => {
+                let writable: X = self.csr[MIDELEG] & X::from(MIP_SSIP);
+                self.csr[MIP] = (self.csr[MIP] & !writable) | (value & writable);
+            }
+            // WARL: a write selecting an unsupported paging mode has no effect at all
+            SATP => {
+                if PagingMode::from_satp(value).is_some() {
+                    self.csr[addr] = value;
+                }
+            }
+            MEDELEG => self.csr[addr] = value & X::from(DELEGABLE_EXCEPTIONS),
+            MIDELEG => self.csr[addr] = value & X::from(SUPERVISOR_INTERRUPTS),
+            MTVEC | STVEC => {
+                // WARL: reserved modes (>= 2) fall back to direct mode
+                let mode: X = value & X::from(MTVEC_MODE);
+                let mode: X = if mode == X::from(MTVEC_VECTORED) {
+                    mode
+                } else {
+                    X::from(MTVEC_DIRECT)
+                };
+                self.csr[addr] = (value & !X::from(MTVEC_MODE)) | mode;
+            }
+            MEPC | SEPC => self.csr[addr] = value & !X::from(0b1), // IALIGN = 16
+            // machine-level pending bits are driven by the platform, M-mode software raises
+            // the supervisor ones
+            MIP => {
+                let writable: X = X::from(SUPERVISOR_INTERRUPTS);
+                self.csr[addr] = (self.csr[addr] & !writable) | (value & writable);
+            }
+            MCYCLE..=MHPMCOUNTER31 => {
+                // on RV32 only the lower half is replaced
+                let counter: usize = addr - MCYCLE;
+                let upper: u64 = if X::XLEN == 32 {
+                    self.counters[counter] & !0xFFFF_FFFF
+                } else {
+                    0
+                };
+                self.write_counter(counter, upper | value.to_u64());
+            }
+            MCYCLEH..=MHPMCOUNTER31H => {
+                let counter: usize = addr - MCYCLEH;
+                let lower: u64 = self.counters[counter] & 0xFFFF_FFFF;
+                self.write_counter(counter, (value.to_u64() << 32) | lower);
+            }
+            // WARL: unknown events select nothing
+            MHPMEVENT3..=MHPMEVENT31 => {
+                self.csr[addr] = if value.to_u64() < HPM_EVENT_COUNT as u64 {
+                    value
+                } else {
+                    X::from(HPM_EVENT_NONE)
+                };
+            }
+            // time cannot be inhibited, and there are only 32 counters
+            MCOUNTINHIBIT => self.csr[addr] = value & X::from(!(1 << COUNTER_TM)),
+            MCOUNTEREN | SCOUNTEREN => self.csr[addr] = value & X::from(Word::MAX),
+            PMPCFG0..=PMPCFG15 => self.write_pmpcfg(addr, value),
+            PMPADDR0..=PMPADDR63 => self.write_pmpaddr(addr, value),
+            _ => self.csr[addr] = value,
+        }
+        Ok(())
+    }
+
+    /// Instruction alignment in bytes: 2 while the C extension is enabled, 4 otherwise
+    pub fn ialign(&self) -> Word {
+        if self.csr[MISA] & X::from(MISA_C) != X::default() {
+            2
+        } else {
+            4
+        }
+    }
+
+    /// Whether only the integer registers x0-x15 exist (misa.E)
+    pub fn is_embedded(&self) -> bool {
+        self.csr[MISA] & X::from(MISA_E) != X::default()
+    }
+
+    /// fflags, frm and fcsr are only accessible while the FPU is enabled
+    fn is_fp_csr(addr: usize) -> bool {
+        matches!(addr, FFLAGS | FRM | FCSR)
+    }
+
+    /// Whether floating-point instructions may execute (mstatus.FS is not Off)
+    pub fn fp_enabled(&self) -> bool {
+        self.csr[MSTATUS] & X::from(MSTATUS_FS) != X::from(FS_OFF)
+    }
+
+    /// Records that the floating-point registers or fcsr were modified
+    pub fn set_fp_dirty(&mut self) {
+        self.set_mstatus(self.csr[MSTATUS] | X::from(FS_DIRTY));
+    }
+
+    /// Ors exception flags raised by a floating-point instruction into fflags
+    pub fn accrue_fflags(&mut self, flags: Word) {
+        if flags != 0 {
+            self.set_fcsr(self.csr[FCSR] | X::from(flags));
+        }
+    }
+
+    fn set_fcsr(&mut self, value: X) {
+        self.csr[FCSR] = value;
+        self.set_fp_dirty();
+    }
+
+    /// vstart, vxsat, vxrm, vcsr, vl, vtype and vlenb are only accessible while the vector
+    /// unit is enabled
+    fn is_vector_csr(addr: usize) -> bool {
+        matches!(addr, VSTART | VXSAT | VXRM | VCSR | VL | VTYPE | VLENB)
+    }
+
+    /// Whether vector instructions may execute (mstatus.VS is not Off)
+    pub fn vector_enabled(&self) -> bool {
+        self.csr[MSTATUS] & X::from(MSTATUS_VS) != X::from(VS_OFF)
+    }
+
+    /// Records that the vector registers or vector CSRs were modified
+    pub fn set_vector_dirty(&mut self) {
+        self.set_mstatus(self.csr[MSTATUS] | X::from(VS_DIRTY));
+    }
+
+    fn set_vcsr(&mut self, value: X) {
+        self.csr[VCSR] = value;
+        self.set_vector_dirty();
+    }
+
+    /// The mstatus bits that read through sstatus: the supervisor fields, SD and UXL
+    fn sstatus_mask() -> X {
+        let sd_bit: X = X::from_u64(1 << (X::XLEN - 1));
+        let uxl: X = if X::XLEN == 64 {
+            X::from_u64(0b11 << MSTATUS_UXL_SHIFT)
+        } else {
+            X::default()
+        };
+        X::from(SSTATUS_FIELDS) | sd_bit | uxl
+    }
+
+    /// Whether the counter read through `addr` (cycle, time, instret or an hpmcounter) may
+    /// be accessed from `privilege`, as granted by mcounteren and scounteren
+    pub fn counter_enabled(&self, addr: usize, privilege: Privilege) -> bool {
+        let counter: usize = match addr {
+            CYCLE..=HPMCOUNTER31 => addr - CYCLE,
+            CYCLEH..=HPMCOUNTER31H => addr - CYCLEH,
+            _ => return true,
+        };
+        let enabled = |csr: usize| self.csr[csr].to_u64() & (1 << counter) != 0;
+        match privilege {
+            Privilege::Machine => true,
+            Privilege::Supervisor => enabled(MCOUNTEREN),
+            Privilege::User => enabled(MCOUNTEREN) && enabled(SCOUNTEREN),
+        }
+    }
+
+    /// Whether the cache-block operations enabled by `field` of menvcfg and senvcfg may be
+    /// executed from `privilege`
+    pub fn cbo_enabled(&self, field: Word, privilege: Privilege) -> bool {
+        let enabled = |csr: usize| self.csr[csr] & X::from(field) != X::default();
+        match privilege {
+            Privilege::Machine => true,
+            Privilege::Supervisor => enabled(MENVCFG),
+            Privilege::User => enabled(MENVCFG) && enabled(SENVCFG),
+        }
+    }
+
+    /// Writes mstatus, keeping SD (the top bit) in sync with the extension state fields
+    fn set_mstatus(&mut self, value: X) {
+        let sd_bit: X = X::from_u64(1 << (X::XLEN - 1));
+        let sd: X = if value & X::from(MSTATUS_FS) == X::from(FS_DIRTY)
+            || value & X::from(MSTATUS_VS) == X::from(VS_DIRTY)
+        {
+            sd_bit
+        } else {
+            X::default()
+        };
+        self.csr[MSTATUS] = (value & !sd_bit) | sd;
+    }
+
+    fn write_counter(&mut self, counter: usize, value: u64) {
+        self.counters[counter] = value;
+        self.counters_written |= 1 << counter; // the written value is not incremented this cycle
+    }
+
+    /// Advances the counters by one clock cycle. `retired` tells whether the instruction
+    /// completed, `events` holds a bit for each HPM_EVENT_* it caused.
+    pub fn tick_counters(&mut self, retired: bool, events: Word) {
+        // counters written this cycle keep the written value, like inhibited ones
+        let inhibit: u64 = self.csr[MCOUNTINHIBIT].to_u64() | self.counters_written as u64;
+        self.counters_written = 0;
+        let counting = |counter: usize| inhibit & (1 << counter) == 0;
+
+        for counter in [COUNTER_CY, COUNTER_TM] {
+            if counting(counter) {
+                self.counters[counter] = self.counters[counter].wrapping_add(1);
+            }
+        }
+        if retired && counting(COUNTER_IR) {
+            self.counters[COUNTER_IR] = self.counters[COUNTER_IR].wrapping_add(1);
+        }
+        for counter in 3..COUNTER_COUNT {
+            let event: u64 = self.csr[MHPMEVENT3 + counter - 3].to_u64();
+            if counting(counter) && event != HPM_EVENT_NONE as u64 && events & (1 << event) != 0 {
+                self.counters[counter] = self.counters[counter].wrapping_add(1);
+            }
+        }
+    }
+
+    /// Charges `cycles` extra clock cycles to mcycle for an instruction that takes longer
+    /// than one cycle
+    pub fn stall(&mut self, cycles: u64) {
+        if self.csr[MCOUNTINHIBIT].to_u64() & (1 << COUNTER_CY) == 0 {
+            self.counters[COUNTER_CY] = self.counters[COUNTER_CY].wrapping_add(cycles);
+        }
+    }
+
+    /// Sets the real-time counter read by the time CSR, for platforms that keep their own
+    /// mtime. Otherwise time advances once per clock cycle.
+    pub fn set_time(&mut self, ticks: u64) {
+        self.counters[COUNTER_TM] = ticks;
+        self.counters_written |= 1 << COUNTER_TM;
+    }
+
+    /// Reads a CSR directly, the way the hardware does when taking traps
+    pub fn get(&self, addr: usize) -> X {
+        self.csr[addr]
+    }
+
+    /// Writes a CSR directly, bypassing the access checks and WARL rules of `write`
+    pub fn set(&mut self, addr: usize, value: X) {
+        self.csr[addr] = value;
+    }
+}
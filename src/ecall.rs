@@ -0,0 +1,130 @@
+use std::io::{Read, Write};
+
+use crate::{AccessType, MEM_SIZE, Memory, RISCV, Word, Xlen};
+
+// Registers used by the syscall calling convention
+const T0: usize = 5; // syscall number on RV32E, which has no a7 (newlib ilp32e)
+const A0: usize = 10; // first argument and return value
+const A1: usize = 11; // second argument
+const A2: usize = 12; // third argument
+const A7: usize = 17; // syscall number
+
+// Syscall numbers (same as riscv-pk / newlib)
+pub const SYS_READ: Word = 63;
+pub const SYS_WRITE: Word = 64;
+pub const SYS_EXIT: Word = 93;
+
+/// What the hart should do after the host handled an ECALL or EBREAK
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EcallAction {
+    Continue,   // the request was serviced, resume after the instruction
+    Exit(Word), // the guest asked to stop with the given exit code
+    Break,      // stop execution and hand control to the debugger
+    Trap,       // not handled by the host, raise the exception in the guest
+}
+
+/// Why the hart stopped executing instructions
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Halt {
+    Exit(Word), // the guest exited with the given code
+    Breakpoint, // an EBREAK handed control to the debugger, resume() steps over it
+}
+
+/// Host-side services the guest can request through ECALL and EBREAK.
+/// The handler gets full access to the hart and its memory.
+pub trait EcallHandler<X: Xlen = Word> {
+    fn ecall(&mut self, cpu: &mut RISCV<X>, mem: &mut Memory) -> EcallAction;
+
+    /// EBREAK raises a breakpoint exception in the guest unless the handler overrides it
+    fn ebreak(&mut self, _cpu: &mut RISCV<X>, _mem: &mut Memory) -> EcallAction {
+        EcallAction::Trap
+    }
+}
+
+/// Minimal syscall layer: a7 (t0 on RV32E) selects read/write/exit, a0-a2 hold the
+/// arguments and a0 receives the result (-1 on failure). Buffers are virtual addresses of the
+/// calling mode, translated and checked against PMP like the guest's own accesses.
+pub struct SyscallHandler<R: Read, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: Read, W: Write> SyscallHandler<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        SyscallHandler { input, output }
+    }
+
+    /// write(fd, buf, count) to stdout/stderr
+    fn write<X: Xlen>(&mut self, cpu: &mut RISCV<X>, mem: &Memory) -> Option<u64> {
+        let (fd, buf, count): (u64, u64, u64) =
+            (cpu.reg[A0].into(), cpu.reg[A1].into(), cpu.reg[A2].into());
+        if fd != 1 && fd != 2 {
+            return None;
+        }
+
+        let mut bytes: Vec<u8> = Vec::new();
+        for i in 0..count {
+            let addr: usize = guest_byte(cpu, mem, buf.wrapping_add(i), AccessType::Load)?;
+            bytes.push(mem.fetch_byte(addr).ok()?);
+        }
+        self.output.write_all(&bytes).ok()?;
+        self.output.flush().ok()?;
+        Some(count)
+    }
+
+    /// read(fd, buf, count) from stdin
+    fn read<X: Xlen>(&mut self, cpu: &mut RISCV<X>, mem: &mut Memory) -> Option<u64> {
+        let (fd, buf, count): (u64, u64, u64) =
+            (cpu.reg[A0].into(), cpu.reg[A1].into(), cpu.reg[A2].into());
+        if fd != 0 {
+            return None;
+        }
+        if count == 0 {
+            return Some(0);
+        }
+
+        // the buffer must be writable before any input is consumed, and it cannot hold more
+        // than the memory left after its start
+        let start: usize = guest_byte(cpu, mem, buf, AccessType::Store)?;
+        if !Memory::contains(start, 1) {
+            return None;
+        }
+        let mut bytes: Vec<u8> = vec![0; count.min((MEM_SIZE - start) as u64) as usize];
+        let read: usize = self.input.read(&mut bytes).ok()?;
+        for (i, byte) in bytes.iter().take(read).enumerate() {
+            let addr: usize = guest_byte(cpu, mem, buf.wrapping_add(i as u64), AccessType::Store)?;
+            mem.store_byte(addr, *byte).ok()?;
+        }
+        Some(read as u64)
+    }
+}
+
+/// The physical address of the guest byte at virtual `addr`, if the hart may access it
+fn guest_byte<X: Xlen>(
+    cpu: &mut RISCV<X>,
+    mem: &Memory,
+    addr: u64,
+    access: AccessType,
+) -> Option<usize> {
+    cpu.translate(mem, X::from_u64(addr), 1, access).ok()
+}
+
+impl<X: Xlen, R: Read, W: Write> EcallHandler<X> for SyscallHandler<R, W> {
+    fn ecall(&mut self, cpu: &mut RISCV<X>, mem: &mut Memory) -> EcallAction {
+        let number: X = cpu.reg[if cpu.csr.is_embedded() { T0 } else { A7 }];
+        let result: Option<u64> = match Word::try_from(number.to_u64()) {
+            // only the low 32 bits of the exit code are reported to the host
+            Ok(SYS_EXIT) => return EcallAction::Exit(cpu.reg[A0].to_u64() as Word),
+            Ok(SYS_WRITE) => self.write(cpu, mem),
+            Ok(SYS_READ) => self.read(cpu, mem),
+            _ => return EcallAction::Trap,
+        };
+        cpu.reg[A0] = X::from_u64(result.unwrap_or(u64::MAX)); // -1 on failure
+        EcallAction::Continue
+    }
+
+    /// Stop at breakpoints so the host can inspect the hart
+    fn ebreak(&mut self, _cpu: &mut RISCV<X>, _mem: &mut Memory) -> EcallAction {
+        EcallAction::Break
+    }
+}
@@ -1,5 +1,27 @@
+mod compressed;
+mod crypto;
+mod csr;
+mod ecall;
+mod mmu;
+mod pmp;
 mod risc_v;
+mod softfloat;
+mod tlb;
+mod trap;
 mod utils;
+mod vector;
+mod xlen;
 
+pub(crate) use crypto::*;
+pub use csr::*;
+pub use ecall::*;
+pub use mmu::*;
+pub use pmp::*;
 pub use risc_v::*;
+pub(crate) use softfloat::*;
+pub use softfloat::{FFLAGS_DZ, FFLAGS_NV, FFLAGS_NX, FFLAGS_OF, FFLAGS_UF};
+pub use tlb::*;
+pub use trap::*;
 pub use utils::*;
+pub use vector::*;
+pub use xlen::*;
@@ -1,24 +1,35 @@
-use rust_risc_v::{Memory, RISCV, Word, load_from_file};
+use std::io;
+
+use rust_risc_v::{Memory, RISCV, SyscallHandler, Word, load_from_file};
 
 fn main() {
     println!("Hello, world!");
     let instructions: Vec<Word> = load_from_file();
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(io::stdin(), io::stdout())));
 
     let mut i: usize = 0;
     while i < instructions.len() {
         println!("addr 0x{:0X}: 0b{:032b}", 4 * i, instructions[i]);
         let current_instr: Word = instructions[i];
 
-        mem.store_word(4 * i, current_instr);
+        mem.store_word(4 * i, current_instr)
+            .expect("program does not fit in memory");
 
         i += 1;
     }
 
     let mut i: usize = 0;
     while i < instructions.len() {
-        cpu.clock_cycle(&mut mem);
+        if let Err(trap) = cpu.clock_cycle(&mut mem) {
+            println!("trap at pc 0x{:0X}: {:?}", cpu.pc, trap);
+            break;
+        }
+        if let Some(halt) = cpu.halt {
+            println!("halted at pc 0x{:0X}: {:?}", cpu.pc, halt);
+            break;
+        }
 
         i += 1;
     }
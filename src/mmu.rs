@@ -0,0 +1,349 @@
+use crate::{
+    Exception, Leaf, MSTATUS, MSTATUS_MPRV, MSTATUS_MXR, MSTATUS_SUM, Memory, Privilege, RISCV,
+    SATP, Tlb, Trap, Word, Xlen,
+};
+
+pub const PAGE_SIZE: usize = 4096; // bytes in a base page
+const PAGE_SHIFT: u32 = 12;
+
+// page-table entry fields
+pub const PTE_V: u64 = 1 << 0; // valid
+pub const PTE_R: u64 = 1 << 1; // readable
+pub const PTE_W: u64 = 1 << 2; // writable
+pub const PTE_X: u64 = 1 << 3; // executable
+pub const PTE_U: u64 = 1 << 4; // accessible to user mode
+pub const PTE_G: u64 = 1 << 5; // global mapping, present in every address space
+pub const PTE_A: u64 = 1 << 6; // accessed
+pub const PTE_D: u64 = 1 << 7; // dirty
+const PTE_PPN_SHIFT: u32 = 10; // the PPN sits above the flags and the RSW field
+
+// Sv39 / Sv48 / Sv57 page-table entry fields above the PPN
+const PTE_RESERVED: u64 = 0x7F << 54; // must be zero
+pub const PTE_PBMT: u64 = 0b11 << 61; // Svpbmt page-based memory type
+pub const PTE_N: u64 = 1 << 63; // Svnapot naturally aligned power-of-two page
+
+// page-based memory types, only recorded since memory has no caches or side effects
+pub const PBMT_PMA: u64 = 0b00 << 61; // the physical memory attributes of the region
+pub const PBMT_NC: u64 = 0b01 << 61; // non-cacheable, idempotent main memory
+pub const PBMT_IO: u64 = 0b10 << 61; // non-cacheable, non-idempotent I/O
+
+// a 64 KiB NAPOT page: the low 4 PPN bits hold 0b1000 and are replaced by the VPN
+const NAPOT_BITS: u32 = 4;
+const NAPOT_64K: u64 = 0b1000;
+
+/// The kinds of memory access, which select the permission a page must grant and the
+/// exception raised when it does not
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AccessType {
+    Fetch, // instruction fetch, needs X
+    Load,  // load or LR, needs R (or X with MXR)
+    Store, // store, SC or AMO, needs W
+}
+
+impl AccessType {
+    /// The exception raised when the page tables forbid the access
+    pub fn page_fault(self) -> Exception {
+        match self {
+            AccessType::Fetch => Exception::InstructionPageFault,
+            AccessType::Load => Exception::LoadPageFault,
+            AccessType::Store => Exception::StorePageFault,
+        }
+    }
+
+    /// The exception raised when the access, or a page-table entry it reads, lies outside of
+    /// memory or is denied by PMP
+    pub fn access_fault(self) -> Exception {
+        match self {
+            AccessType::Fetch => Exception::InstructionAccessFault,
+            AccessType::Load => Exception::LoadAccessFault,
+            AccessType::Store => Exception::StoreAccessFault,
+        }
+    }
+}
+
+/// Address-translation schemes selected by satp.MODE
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PagingMode {
+    Bare, // no translation or protection
+    Sv32, // RV32, two levels of 4-byte entries, 4 MiB superpages
+    Sv39, // RV64, three levels of 8-byte entries, 39-bit virtual addresses
+    Sv48, // RV64, four levels, 48-bit virtual addresses
+    Sv57, // RV64, five levels, 57-bit virtual addresses
+}
+
+impl PagingMode {
+    /// The scheme selected by a satp value, None for modes this hart does not support
+    pub fn from_satp<X: Xlen>(satp: X) -> Option<PagingMode> {
+        if X::XLEN == 32 {
+            match satp.to_u64() >> 31 {
+                0 => Some(PagingMode::Bare),
+                _ => Some(PagingMode::Sv32),
+            }
+        } else {
+            match satp.to_u64() >> 60 {
+                0 => Some(PagingMode::Bare),
+                8 => Some(PagingMode::Sv39),
+                9 => Some(PagingMode::Sv48),
+                10 => Some(PagingMode::Sv57),
+                _ => None,
+            }
+        }
+    }
+
+    /// Levels of page tables walked
+    pub fn levels(self) -> usize {
+        match self {
+            PagingMode::Bare => 0,
+            PagingMode::Sv32 => 2,
+            PagingMode::Sv39 => 3,
+            PagingMode::Sv48 => 4,
+            PagingMode::Sv57 => 5,
+        }
+    }
+
+    /// Bytes in each page-table entry
+    pub fn pte_size(self) -> usize {
+        match self {
+            PagingMode::Bare => 0,
+            PagingMode::Sv32 => 4,
+            _ => 8,
+        }
+    }
+
+    /// Bits of the virtual page number translated at each level
+    fn vpn_bits(self) -> u32 {
+        match self {
+            PagingMode::Bare => 0,
+            PagingMode::Sv32 => 10,
+            _ => 9,
+        }
+    }
+
+    /// Bits of the physical page number held in an entry
+    fn ppn_bits(self) -> u32 {
+        match self {
+            PagingMode::Bare => 0,
+            PagingMode::Sv32 => 22,
+            _ => 44,
+        }
+    }
+
+    /// Whether the bits of `vaddr` above the virtual address width all copy its top bit.
+    /// Sv32 translates every bit of an RV32 address.
+    pub fn is_canonical(self, vaddr: u64) -> bool {
+        if self == PagingMode::Sv32 {
+            return true;
+        }
+        let va_bits: u32 = PAGE_SHIFT + self.levels() as u32 * self.vpn_bits();
+        let upper: i64 = (vaddr as i64) >> (va_bits - 1);
+        upper == 0 || upper == -1
+    }
+
+    /// Checks the bits an Sv39-Sv57 entry holds above its PPN: the reserved bits and the
+    /// reserved memory type are never allowed, PBMT and N only in leaves, and N only for a
+    /// 64 KiB page at level 0
+    fn upper_bits_valid(self, pte: u64, ppn: u64, leaf: bool, level: u32) -> bool {
+        if self == PagingMode::Sv32 {
+            return true; // the PPN fills the whole entry
+        }
+        if pte & PTE_RESERVED != 0 || pte & PTE_PBMT == PTE_PBMT {
+            return false;
+        }
+        if !leaf {
+            return pte & (PTE_PBMT | PTE_N) == 0;
+        }
+        pte & PTE_N == 0 || (level == 0 && ppn & ((1 << NAPOT_BITS) - 1) == NAPOT_64K)
+    }
+}
+
+/// The physical page number of the root page table, held in the low bits of satp
+pub fn satp_ppn<X: Xlen>(satp: X) -> u64 {
+    let ppn_bits: u32 = if X::XLEN == 32 { 22 } else { 44 };
+    satp.to_u64() & ((1 << ppn_bits) - 1)
+}
+
+/// The ASIDs satp can hold: 9 bits on RV32, 16 on RV64
+pub fn asid_mask<X: Xlen>() -> u64 {
+    if X::XLEN == 32 { 0x1FF } else { 0xFFFF }
+}
+
+/// The address-space identifier in satp, which tags the translations cached in the TLB
+pub fn satp_asid<X: Xlen>(satp: X) -> u64 {
+    let asid_shift: u32 = if X::XLEN == 32 { 22 } else { 44 };
+    (satp.to_u64() >> asid_shift) & asid_mask::<X>()
+}
+
+impl<X: Xlen> RISCV<X> {
+    /// Translates the virtual `address` of a `size`-byte access into a physical address and
+    /// checks that PMP allows it
+    pub fn translate(
+        &mut self,
+        mem: &Memory,
+        address: X,
+        size: usize,
+        access: AccessType,
+    ) -> Result<usize, Trap<X>> {
+        let physical: usize = self.translate_virtual(mem, address, access)?;
+        self.check_pmp(physical, size, address, access)?;
+        Ok(physical)
+    }
+
+    /// Translates a virtual address when satp enables paging for the effective privilege mode,
+    /// from the TLB or else by walking the page tables
+    fn translate_virtual(
+        &mut self,
+        mem: &Memory,
+        address: X,
+        access: AccessType,
+    ) -> Result<usize, Trap<X>> {
+        let privilege: Privilege = self.effective_privilege(access);
+        let satp: X = self.csr.get(SATP);
+        let mode: PagingMode = PagingMode::from_satp(satp).unwrap_or(PagingMode::Bare);
+        if privilege == Privilege::Machine || mode == PagingMode::Bare {
+            return Ok(address.to_usize());
+        }
+        let page_fault: Trap<X> = Trap::new(access.page_fault(), address);
+        let vaddr: u64 = address.to_u64();
+        if !mode.is_canonical(vaddr) {
+            return Err(page_fault);
+        }
+
+        let asid: u64 = satp_asid(satp);
+        let cached: Option<Leaf> = self.tlb_for(access).lookup(vaddr, asid);
+        let leaf: Leaf = match cached {
+            Some(leaf) => leaf,
+            None => self.walk(mem, mode, satp_ppn(satp), address, access)?,
+        };
+        // permissions depend on the mode, SUM and MXR, so cached entries are checked again
+        if !self.permitted(leaf.pte, access, privilege) {
+            return Err(page_fault);
+        }
+        // A and D are managed by software (Svade), which is told through a page fault
+        if leaf.pte & PTE_A == 0 || (access == AccessType::Store && leaf.pte & PTE_D == 0) {
+            return Err(page_fault);
+        }
+        if cached.is_none() {
+            self.tlb_for(access).insert(vaddr, asid, leaf);
+        }
+        Ok(leaf.physical(vaddr) as usize)
+    }
+
+    /// The TLB caching translations for `access`: fetches have their own unless it is shared
+    fn tlb_for(&mut self, access: AccessType) -> &mut Tlb {
+        match (&mut self.itlb, access) {
+            (Some(itlb), AccessType::Fetch) => itlb,
+            _ => &mut self.dtlb,
+        }
+    }
+
+    /// Invalidates the cached translations of `vaddr` (or of every address when None) in
+    /// address space `asid` (or in every one when None), as SFENCE.VMA does
+    pub fn sfence_vma(&mut self, vaddr: Option<u64>, asid: Option<u64>) {
+        self.dtlb.flush(vaddr, asid);
+        if let Some(itlb) = &mut self.itlb {
+            itlb.flush(vaddr, asid);
+        }
+    }
+
+    /// The mode whose permissions apply to an access: while mstatus.MPRV is set, M-mode loads
+    /// and stores are translated as if made from the mode in MPP
+    pub fn effective_privilege(&self, access: AccessType) -> Privilege {
+        let mstatus: X = self.csr.get(MSTATUS);
+        if access != AccessType::Fetch
+            && self.privilege == Privilege::Machine
+            && mstatus & X::from(MSTATUS_MPRV) != X::default()
+        {
+            Privilege::from_mstatus(mstatus)
+        } else {
+            self.privilege
+        }
+    }
+
+    /// Walks the page tables rooted at physical page `root`, from the top level down to the
+    /// first leaf entry, which is returned without checking its permissions
+    fn walk(
+        &self,
+        mem: &Memory,
+        mode: PagingMode,
+        root: u64,
+        address: X,
+        access: AccessType,
+    ) -> Result<Leaf, Trap<X>> {
+        let page_fault: Trap<X> = Trap::new(access.page_fault(), address);
+        let vaddr: u64 = address.to_u64();
+        let vpn_bits: u32 = mode.vpn_bits();
+        let mut table: u64 = root << PAGE_SHIFT;
+        let mut global: bool = false;
+
+        for level in (0..mode.levels() as u32).rev() {
+            let offset_bits: u32 = PAGE_SHIFT + level * vpn_bits; // bits below this level's VPN
+            let vpn: u64 = (vaddr >> offset_bits) & ((1 << vpn_bits) - 1);
+            let pte_address: u64 = table + vpn * mode.pte_size() as u64;
+            // the walk reads the tables with S-mode permissions, whatever the access
+            let pte_size: u64 = mode.pte_size() as u64;
+            if !self.csr.pmp_allows(
+                pte_address,
+                pte_size,
+                AccessType::Load,
+                Privilege::Supervisor,
+            ) {
+                return Err(Trap::new(access.access_fault(), address));
+            }
+            let pte: u64 = mem
+                .fetch_sized(pte_address as usize, mode.pte_size())
+                .map_err(|_| Trap::new(access.access_fault(), address))?;
+            let ppn: u64 = (pte >> PTE_PPN_SHIFT) & ((1 << mode.ppn_bits()) - 1);
+            let leaf: bool = pte & (PTE_R | PTE_X) != 0;
+            if pte & PTE_V == 0
+                || (pte & PTE_R == 0 && pte & PTE_W != 0)
+                || !mode.upper_bits_valid(pte, ppn, leaf, level)
+            {
+                return Err(page_fault); // invalid, or a reserved encoding
+            }
+            global |= pte & PTE_G != 0; // a global pointer makes the whole subtree global
+            if !leaf {
+                table = ppn << PAGE_SHIFT; // pointer to the next level
+                continue;
+            }
+
+            // a leaf: a superpage must be aligned to its size
+            if ppn & ((1 << (level * vpn_bits)) - 1) != 0 {
+                return Err(page_fault);
+            }
+            // a NAPOT entry maps the whole 64 KiB range, the low VPN bits picking the page
+            let page_shift: u32 = if pte & PTE_N != 0 {
+                PAGE_SHIFT + NAPOT_BITS
+            } else {
+                offset_bits
+            };
+            let ppn_mask: u64 = (1 << (page_shift - PAGE_SHIFT)) - 1;
+            return Ok(Leaf {
+                pte,
+                base: (ppn & !ppn_mask) << PAGE_SHIFT,
+                page_shift,
+                global,
+            });
+        }
+        Err(page_fault) // the last level is not a leaf
+    }
+
+    /// Whether a leaf entry grants `access` to `privilege`
+    fn permitted(&self, pte: u64, access: AccessType, privilege: Privilege) -> bool {
+        let mstatus: X = self.csr.get(MSTATUS);
+        let set = |bit: Word| mstatus & X::from(bit) != X::default();
+        let user_page: bool = pte & PTE_U != 0;
+        let mode_allowed: bool = if privilege == Privilege::User {
+            user_page
+        } else {
+            // S-mode may read and write user pages while SUM is set, but never execute them
+            !user_page || (access != AccessType::Fetch && set(MSTATUS_SUM))
+        };
+        let access_allowed: bool = match access {
+            AccessType::Fetch => pte & PTE_X != 0,
+            // MXR makes executable pages readable too
+            AccessType::Load => pte & PTE_R != 0 || (set(MSTATUS_MXR) && pte & PTE_X != 0),
+            AccessType::Store => pte & PTE_W != 0,
+        };
+        mode_allowed && access_allowed
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{AccessType, CSRFile, PMPADDR0, PMPCFG0, Privilege, RISCV, Trap, Xlen};
+
+pub const PMP_ENTRIES: usize = 64; // pmp0cfg..pmp63cfg and pmpaddr0..pmpaddr63
+
+// pmpcfg entry fields, one byte per entry
+pub const PMP_R: u8 = 1 << 0; // readable
+pub const PMP_W: u8 = 1 << 1; // writable
+pub const PMP_X: u8 = 1 << 2; // executable
+pub const PMP_A: u8 = 0b11 << 3; // address-matching mode
+pub const PMP_L: u8 = 1 << 7; // locked, and enforced in M-mode too
+const PMP_WRITABLE: u8 = PMP_R | PMP_W | PMP_X | PMP_A | PMP_L; // bits 6:5 are reserved
+
+// address-matching modes in the A field
+pub const PMP_OFF: u8 = 0b00 << 3; // the entry is disabled
+pub const PMP_TOR: u8 = 0b01 << 3; // top of range: from the previous pmpaddr up to this one
+pub const PMP_NA4: u8 = 0b10 << 3; // naturally aligned four-byte region
+pub const PMP_NAPOT: u8 = 0b11 << 3; // naturally aligned power-of-two region of 8 bytes or more
+
+impl AccessType {
+    /// The PMP permission bit the access needs
+    fn pmp_permission(self) -> u8 {
+        match self {
+            AccessType::Fetch => PMP_X,
+            AccessType::Load => PMP_R,
+            AccessType::Store => PMP_W,
+        }
+    }
+}
+
+impl<X: Xlen> CSRFile<X> {
+    /// The pmpcfg register holding the configuration of `entry`, and the byte it sits in.
+    /// RV64 packs eight entries in each even-numbered register.
+    fn pmpcfg_location(entry: usize) -> (usize, usize) {
+        let per_register: usize = X::XLEN as usize / 8;
+        let register: usize = PMPCFG0 + entry / per_register * (per_register / 4);
+        (register, entry % per_register)
+    }
+
+    /// The configuration byte of PMP entry `entry`
+    pub fn pmp_cfg(&self, entry: usize) -> u8 {
+        let (register, byte) = Self::pmpcfg_location(entry);
+        (self.get(register).to_u64() >> (8 * byte)) as u8
+    }
+
+    /// The address register of PMP entry `entry`, which holds bits 33:2 (RV32) or 55:2 (RV64)
+    /// of a physical address
+    pub fn pmp_addr(&self, entry: usize) -> u64 {
+        self.get(PMPADDR0 + entry).to_u64()
+    }
+
+    /// Writes pmpcfg register `addr`, leaving the bytes of locked entries unchanged
+    pub fn write_pmpcfg(&mut self, addr: usize, value: X) {
+        let entries: usize = X::XLEN as usize / 8;
+        let first: usize = (addr - PMPCFG0) * 4;
+        let mut result: u64 = 0;
+        for byte in 0..entries {
+            let old: u8 = self.pmp_cfg(first + byte);
+            let mut cfg: u8 = (value.to_u64() >> (8 * byte)) as u8 & PMP_WRITABLE;
+            if cfg & (PMP_R | PMP_W) == PMP_W {
+                cfg &= !PMP_W; // WARL: write-only is reserved
+            }
+            let cfg: u8 = if old & PMP_L != 0 { old } else { cfg };
+            result |= (cfg as u64) << (8 * byte);
+        }
+        self.set(addr, X::from_u64(result));
+    }
+
+    /// Writes pmpaddr register `addr`, unless its entry is locked or it is the bottom of a
+    /// locked TOR range
+    pub fn write_pmpaddr(&mut self, addr: usize, value: X) {
+        let entry: usize = addr - PMPADDR0;
+        let locked_tor: bool = entry + 1 < PMP_ENTRIES && {
+            let next: u8 = self.pmp_cfg(entry + 1);
+            next & PMP_L != 0 && next & PMP_A == PMP_TOR
+        };
+        if self.pmp_cfg(entry) & PMP_L != 0 || locked_tor {
+            return;
+        }
+        let bits: u32 = if X::XLEN == 32 { 32 } else { 54 };
+        self.set(addr, X::from_u64(value.to_u64() & ((1 << bits) - 1)));
+    }
+
+    /// The physical addresses PMP entry `entry` matches, as a start and an exclusive end,
+    /// or None when it is off
+    pub fn pmp_range(&self, entry: usize) -> Option<(u64, u64)> {
+        let pmpaddr: u64 = self.pmp_addr(entry);
+        match self.pmp_cfg(entry) & PMP_A {
+            PMP_TOR => {
+                let bottom: u64 = if entry == 0 {
+                    0
+                } else {
+                    self.pmp_addr(entry - 1) << 2
+                };
+                Some((bottom, pmpaddr << 2))
+            }
+            PMP_NA4 => Some((pmpaddr << 2, (pmpaddr << 2) + 4)),
+            PMP_NAPOT => {
+                // the trailing ones encode the size: 0 for 8 bytes, 1 for 16, ...
+                let ones: u32 = pmpaddr.trailing_ones();
+                let start: u64 = (pmpaddr & !((1 << ones) - 1)) << 2;
+                Some((start, start + (1 << (ones + 3))))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether PMP lets `privilege` make `access` to the `size` bytes at physical `addr`.
+    /// The lowest-numbered entry matching any of the bytes decides, and it must match all of
+    /// them. M-mode is only restricted by locked entries, and S-mode and U-mode may only make
+    /// accesses some entry allows, so firmware must grant them memory before dropping to them.
+    pub fn pmp_allows(
+        &self,
+        addr: u64,
+        size: u64,
+        access: AccessType,
+        privilege: Privilege,
+    ) -> bool {
+        let end: u64 = addr + size;
+        for entry in 0..PMP_ENTRIES {
+            let Some((start, limit)) = self.pmp_range(entry) else {
+                continue;
+            };
+            if start >= limit || end <= start || addr >= limit {
+                continue; // no byte matches
+            }
+            if addr < start || end > limit {
+                return false; // the access straddles the edge of the region
+            }
+            let cfg: u8 = self.pmp_cfg(entry);
+            if privilege == Privilege::Machine && cfg & PMP_L == 0 {
+                return true;
+            }
+            return cfg & access.pmp_permission() != 0;
+        }
+        privilege == Privilege::Machine
+    }
+}
+
+impl<X: Xlen> RISCV<X> {
+    /// Raises the access fault of `access` unless PMP lets the effective privilege mode reach
+    /// the `size` bytes at `physical`, translated from the virtual `address`
+    pub fn check_pmp(
+        &self,
+        physical: usize,
+        size: usize,
+        address: X,
+        access: AccessType,
+    ) -> Result<(), Trap<X>> {
+        let privilege: Privilege = self.effective_privilege(access);
+        if self
+            .csr
+            .pmp_allows(physical as u64, size as u64, access, privilege)
+        {
+            Ok(())
+        } else {
+            Err(Trap::new(access.access_fault(), address))
+        }
+    }
+}
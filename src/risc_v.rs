@@ -1,21 +1,42 @@
 use std::{
+    cmp::Ordering,
     ops::{Index, IndexMut},
-    usize,
 };
 
-use crate::sign_extend_u32;
+use crate::{
+    AccessType, CSRFile, ConfigError, DOUBLE, ENVCFG_CBCFE, ENVCFG_CBIE, ENVCFG_CBZE, EcallAction,
+    EcallHandler, ElementWidths, Exception, FRM, FloatFormat, HALF, HPM_EVENT_ATOMIC,
+    HPM_EVENT_BRANCH, HPM_EVENT_BRANCH_TAKEN, HPM_EVENT_EXCEPTION, HPM_EVENT_FP, HPM_EVENT_JUMP,
+    HPM_EVENT_LOAD, HPM_EVENT_STORE, Halt, Interrupt, MCAUSE, MEDELEG, MEPC, MIDELEG, MIE, MIP,
+    MISA, MISA_C, MSTATUS, MSTATUS_MIE, MSTATUS_MPIE, MSTATUS_MPP, MSTATUS_MPRV, MSTATUS_SIE,
+    MSTATUS_SPIE, MSTATUS_SPP, MSTATUS_TSR, MSTATUS_TVM, MSTATUS_TW, MTVAL, MTVEC, MTVEC_MODE,
+    MTVEC_VECTORED, MemoryError, PAGE_SIZE, Privilege, RoundingMode, SATP, SCAUSE, SEPC, SINGLE,
+    STVAL, STVEC, Tlb, Trap, VECTOR_REGISTER_COUNT, VL, VLENB, VSTART, VTYPE, VType,
+    VectorAddressing, VectorOperand, VectorRegisters, Xlen, aes32_decrypt, aes32_encrypt,
+    asid_mask, brev8, group_aligned, sign_extend_element, sign_extend_u32, truncate_element, unzip,
+    xperm, zip,
+};
 
 pub type Byte = u8; // Represents a byte in memory
 pub type HalfWord = u16; // Represents 2 bytes in memory
 pub type Word = u32; // Represents 4 bytes in memory (one instruction)
+pub type DoubleWord = u64; // Represents 8 bytes in memory (one double-precision value)
 
-const XLEN: usize = 32; // # of registers ( mem_size = 2^(xlen-1) )
-const MEM_SIZE: usize = 0x1000000;
+const REGISTER_COUNT: usize = 32; // # of integer and floating-point registers
+const EMBEDDED_REGISTER_COUNT: usize = 16; // # of integer registers in RV32E / RV64E
+pub const MEM_SIZE: usize = 0x1000000; // bytes of physical memory
+pub const DEFAULT_CACHE_BLOCK_SIZE: usize = 64; // bytes covered by one CBO instruction
 
 pub struct Memory {
     mem: Vec<Byte>, //[Byte; MEM_SIZE], // 16MB of addresable memory
 }
 
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Memory {
     pub fn new() -> Self {
         Memory {
@@ -23,46 +44,213 @@ impl Memory {
         }
     }
 
+    /// Checks that an access of `size` bytes at `addr` is naturally aligned and in bounds
+    fn check_access(addr: usize, size: usize) -> Result<(), MemoryError> {
+        if !addr.is_multiple_of(size) {
+            return Err(MemoryError::Misaligned);
+        }
+        if addr > MEM_SIZE - size {
+            return Err(MemoryError::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Whether the `size` bytes at `addr` all lie inside memory
+    pub fn contains(addr: usize, size: usize) -> bool {
+        addr <= MEM_SIZE - size
+    }
+
     /// Reads a full word from memory at aligned address
-    pub fn fetch_word(&self, addr: usize) -> Word {
-        assert!(addr % 4 == 0); // the address needs to be aligned to 32 bits
-        assert!(
-            addr <= MEM_SIZE - 4,
-            "Tried accessing memory out of bounds {}",
-            addr
-        ); // ensure we don't go out of bounds
+    pub fn fetch_word(&self, addr: usize) -> Result<Word, MemoryError> {
+        Memory::check_access(addr, 4)?;
 
         // little-endian
-        u32::from_le_bytes([self[addr], self[addr + 1], self[addr + 2], self[addr + 3]])
+        Ok(u32::from_le_bytes([
+            self.mem[addr],
+            self.mem[addr + 1],
+            self.mem[addr + 2],
+            self.mem[addr + 3],
+        ]))
     }
 
-    pub fn store_word(&mut self, addr: usize, value: Word) {
-        assert!(addr % 4 == 0); // the address needs to be aligned to 32 bits
-        assert!(addr <= MEM_SIZE - 4, "Tried accessing memory out of bounds"); // ensure we don't go out of bounds
+    pub fn store_word(&mut self, addr: usize, value: Word) -> Result<(), MemoryError> {
+        Memory::check_access(addr, 4)?;
 
         let bytes: [Byte; 4] = value.to_le_bytes(); // convert the word to bytes (little-endian)
-        self.mem[addr] = bytes[0];
-        self.mem[addr + 1] = bytes[1];
-        self.mem[addr + 2] = bytes[2];
-        self.mem[addr + 3] = bytes[3];
+        self.mem[addr..addr + 4].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Reads a double word from memory at aligned address
+    pub fn fetch_doubleword(&self, addr: usize) -> Result<DoubleWord, MemoryError> {
+        Memory::check_access(addr, 8)?;
+
+        let mut bytes: [Byte; 8] = [0; 8];
+        bytes.copy_from_slice(&self.mem[addr..addr + 8]);
+        Ok(u64::from_le_bytes(bytes)) // little-endian
+    }
+
+    pub fn store_doubleword(&mut self, addr: usize, value: DoubleWord) -> Result<(), MemoryError> {
+        Memory::check_access(addr, 8)?;
+
+        let bytes: [Byte; 8] = value.to_le_bytes(); // convert the double word to bytes (little-endian)
+        self.mem[addr..addr + 8].copy_from_slice(&bytes);
+        Ok(())
     }
 
     /// Reads a half word from memory at aligned address
-    pub fn fetch_halfword(&self, addr: usize) -> HalfWord {
-        assert!(addr % 2 == 0); // the address needs to be aligned to 16 bits
-        assert!(addr <= MEM_SIZE - 2, "Tried accessing memory out of bounds"); // ensure we don't go out of bounds
+    pub fn fetch_halfword(&self, addr: usize) -> Result<HalfWord, MemoryError> {
+        Memory::check_access(addr, 2)?;
 
         // little-endian
-        u16::from_le_bytes([self[addr], self[addr + 1]])
+        Ok(u16::from_le_bytes([self.mem[addr], self.mem[addr + 1]]))
+    }
+
+    pub fn store_halfword(&mut self, addr: usize, value: HalfWord) -> Result<(), MemoryError> {
+        Memory::check_access(addr, 2)?;
+
+        let bytes: [Byte; 2] = value.to_le_bytes(); // convert the half word to bytes (little-endian)
+        self.mem[addr..addr + 2].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    /// Atomically applies `op` to the aligned word at `addr`, returning its original value
+    pub fn atomic_word(
+        &mut self,
+        addr: usize,
+        op: AmoOp,
+        operand: Word,
+    ) -> Result<Word, MemoryError> {
+        let memory_value: Word = self.fetch_word(addr)?;
+        self.store_word(addr, op.apply(memory_value, operand))?;
+        Ok(memory_value)
+    }
+
+    /// Atomically applies `op` to the aligned double word at `addr`, returning its original value
+    pub fn atomic_doubleword(
+        &mut self,
+        addr: usize,
+        op: AmoOp,
+        operand: DoubleWord,
+    ) -> Result<DoubleWord, MemoryError> {
+        let memory_value: DoubleWord = self.fetch_doubleword(addr)?;
+        self.store_doubleword(addr, op.apply_doubleword(memory_value, operand))?;
+        Ok(memory_value)
+    }
+
+    /// Reads a single byte from memory
+    pub fn fetch_byte(&self, addr: usize) -> Result<Byte, MemoryError> {
+        Memory::check_access(addr, 1)?;
+
+        Ok(self.mem[addr])
+    }
+
+    pub fn store_byte(&mut self, addr: usize, value: Byte) -> Result<(), MemoryError> {
+        Memory::check_access(addr, 1)?;
+
+        self.mem[addr] = value;
+        Ok(())
+    }
+
+    /// Reads `size` bytes (1, 2, 4 or 8), zero-extended
+    pub fn fetch_sized(&self, addr: usize, size: usize) -> Result<u64, MemoryError> {
+        match size {
+            1 => self.fetch_byte(addr).map(u64::from),
+            2 => self.fetch_halfword(addr).map(u64::from),
+            4 => self.fetch_word(addr).map(u64::from),
+            _ => self.fetch_doubleword(addr),
+        }
     }
 
-    pub fn store_halfword(&mut self, addr: usize, value: HalfWord) {
-        assert!(addr % 2 == 0); // the address needs to be aligned to 16 bits
-        assert!(addr <= MEM_SIZE - 2, "Tried accessing memory out of bounds"); // ensure we don't go out of bounds
+    /// Zeroes the aligned `size`-byte block at `addr` (CBO.ZERO)
+    pub fn zero_block(&mut self, addr: usize, size: usize) -> Result<(), MemoryError> {
+        Memory::check_access(addr, size)?;
 
-        let bytes: [Byte; 2] = value.to_le_bytes(); // convert the word to bytes (little-endian)
-        self.mem[addr] = bytes[0];
-        self.mem[addr + 1] = bytes[1];
+        self.mem[addr..addr + size].fill(0);
+        Ok(())
+    }
+
+    /// Reads `size` bytes one at a time, zero-extended, whatever the alignment of `addr`
+    pub fn fetch_unaligned(&self, addr: usize, size: usize) -> Result<u64, MemoryError> {
+        if addr > MEM_SIZE - size {
+            return Err(MemoryError::OutOfBounds);
+        }
+
+        let mut bytes: [Byte; 8] = [0; 8];
+        bytes[..size].copy_from_slice(&self.mem[addr..addr + size]);
+        Ok(u64::from_le_bytes(bytes)) // little-endian
+    }
+
+    /// Writes the low `size` bytes of `value` one at a time, whatever the alignment of `addr`.
+    /// Nothing is written when any byte is out of bounds.
+    pub fn store_unaligned(
+        &mut self,
+        addr: usize,
+        size: usize,
+        value: u64,
+    ) -> Result<(), MemoryError> {
+        if addr > MEM_SIZE - size {
+            return Err(MemoryError::OutOfBounds);
+        }
+
+        self.mem[addr..addr + size].copy_from_slice(&value.to_le_bytes()[..size]);
+        Ok(())
+    }
+
+    /// Writes the low `size` bytes (1, 2, 4 or 8) of `value`
+    pub fn store_sized(&mut self, addr: usize, size: usize, value: u64) -> Result<(), MemoryError> {
+        match size {
+            1 => self.store_byte(addr, value as Byte),
+            2 => self.store_halfword(addr, value as HalfWord),
+            4 => self.store_word(addr, value as Word),
+            _ => self.store_doubleword(addr, value),
+        }
+    }
+}
+
+/// Read-modify-write operations performed by the AMO instructions
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AmoOp {
+    Swap,
+    Add,
+    Xor,
+    And,
+    Or,
+    Min,  // signed minimum
+    Max,  // signed maximum
+    MinU, // unsigned minimum
+    MaxU, // unsigned maximum
+}
+
+impl AmoOp {
+    /// Combines the value in memory with the register operand
+    pub fn apply(&self, memory_value: Word, operand: Word) -> Word {
+        match self {
+            AmoOp::Swap => operand,
+            AmoOp::Add => memory_value.wrapping_add(operand),
+            AmoOp::Xor => memory_value ^ operand,
+            AmoOp::And => memory_value & operand,
+            AmoOp::Or => memory_value | operand,
+            AmoOp::Min => (memory_value as i32).min(operand as i32) as u32,
+            AmoOp::Max => (memory_value as i32).max(operand as i32) as u32,
+            AmoOp::MinU => memory_value.min(operand),
+            AmoOp::MaxU => memory_value.max(operand),
+        }
+    }
+
+    /// Combines the double word in memory with the register operand (the RV64 .D forms)
+    pub fn apply_doubleword(&self, memory_value: DoubleWord, operand: DoubleWord) -> DoubleWord {
+        match self {
+            AmoOp::Swap => operand,
+            AmoOp::Add => memory_value.wrapping_add(operand),
+            AmoOp::Xor => memory_value ^ operand,
+            AmoOp::And => memory_value & operand,
+            AmoOp::Or => memory_value | operand,
+            AmoOp::Min => (memory_value as i64).min(operand as i64) as u64,
+            AmoOp::Max => (memory_value as i64).max(operand as i64) as u64,
+            AmoOp::MinU => memory_value.min(operand),
+            AmoOp::MaxU => memory_value.max(operand),
+        }
     }
 }
 
@@ -85,26 +273,39 @@ impl IndexMut<usize> for Memory {
 /// OPCODE always occupies the lowest 7 bits of an instruction
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum OPCODE {
-    OPIMM,  // Integer Register-Immediate Instructions
-    LUI,    // Load Upper Immediate
-    AUIPC,  // Add upper immediate to PC
-    OPRR,   // Integer Register-Register Operations
-    JAL,    // Jump and link
-    JALR,   // Jump and link register
-    BRANCH, // Conditional Branches
-    LOAD,   // Loads values from memory
-    STORE,  // Stores values to memory
-    FENCE,  // Memory and I/O fence
-    SYSTEM, // Environment call and breakpoints
+    OPIMM,   // Integer Register-Immediate Instructions
+    OPIMM32, // 32-bit Register-Immediate Instructions (RV64 only)
+    LUI,     // Load Upper Immediate
+    AUIPC,   // Add upper immediate to PC
+    OPRR,    // Integer Register-Register Operations
+    OP32,    // 32-bit Register-Register Operations (RV64 only)
+    JAL,     // Jump and link
+    JALR,    // Jump and link register
+    BRANCH,  // Conditional Branches
+    LOAD,    // Loads values from memory
+    STORE,   // Stores values to memory
+    FENCE,   // Memory and I/O fence
+    SYSTEM,  // Environment call and breakpoints
+    AMO,     // Atomic memory operations
+    LOADFP,  // Floating-point loads
+    STOREFP, // Floating-point stores
+    OPFP,    // Floating-point computational instructions
+    MADD,    // Fused multiply-add
+    MSUB,    // Fused multiply-subtract
+    NMSUB,   // Negated fused multiply-subtract
+    NMADD,   // Negated fused multiply-add
+    OPV,     // Vector arithmetic and configuration
 }
 
 impl OPCODE {
     pub fn value(&self) -> usize {
         match self {
             OPCODE::OPIMM => 0b0010011,
+            OPCODE::OPIMM32 => 0b0011011,
             OPCODE::LUI => 0b0110111,
             OPCODE::AUIPC => 0b0010111,
             OPCODE::OPRR => 0b0110011,
+            OPCODE::OP32 => 0b0111011,
             OPCODE::JAL => 0b1101111,
             OPCODE::JALR => 0b1100111,
             OPCODE::BRANCH => 0b1100011,
@@ -112,6 +313,15 @@ impl OPCODE {
             OPCODE::STORE => 0b0100011,
             OPCODE::FENCE => 0b0001111,
             OPCODE::SYSTEM => 0b1110011,
+            OPCODE::AMO => 0b0101111,
+            OPCODE::LOADFP => 0b0000111,
+            OPCODE::STOREFP => 0b0100111,
+            OPCODE::OPFP => 0b1010011,
+            OPCODE::MADD => 0b1000011,
+            OPCODE::MSUB => 0b1000111,
+            OPCODE::NMSUB => 0b1001011,
+            OPCODE::NMADD => 0b1001111,
+            OPCODE::OPV => 0b1010111,
         }
     }
 
@@ -119,9 +329,11 @@ impl OPCODE {
         use OPCODE::*;
         match value {
             x if x == OPIMM.value() => Some(OPIMM),
+            x if x == OPIMM32.value() => Some(OPIMM32),
             x if x == LUI.value() => Some(LUI),
             x if x == AUIPC.value() => Some(AUIPC),
             x if x == OPRR.value() => Some(OPRR),
+            x if x == OP32.value() => Some(OP32),
             x if x == JAL.value() => Some(JAL),
             x if x == JALR.value() => Some(JALR),
             x if x == BRANCH.value() => Some(BRANCH),
@@ -129,6 +341,15 @@ impl OPCODE {
             x if x == STORE.value() => Some(STORE),
             x if x == FENCE.value() => Some(FENCE),
             x if x == SYSTEM.value() => Some(SYSTEM),
+            x if x == AMO.value() => Some(AMO),
+            x if x == LOADFP.value() => Some(LOADFP),
+            x if x == STOREFP.value() => Some(STOREFP),
+            x if x == OPFP.value() => Some(OPFP),
+            x if x == MADD.value() => Some(MADD),
+            x if x == MSUB.value() => Some(MSUB),
+            x if x == NMSUB.value() => Some(NMSUB),
+            x if x == NMADD.value() => Some(NMADD),
+            x if x == OPV.value() => Some(OPV),
             _ => None,
         }
     }
@@ -187,17 +408,24 @@ pub enum EncodingVariant {
         rd: usize,        // destination register
         opcode: OPCODE,   // opcode
     }, // jump operations
+    R4Type {
+        rs3: usize,     // third source register
+        funct2: usize,  // floating-point format
+        rs2: usize,     // second source register
+        rs1: usize,     // first source register
+        funct3: usize,  // rounding mode
+        rd: usize,      // destination register
+        opcode: OPCODE, // opcode
+    }, // fused multiply-add operations
 }
 
 impl EncodingVariant {
-    pub fn get_encoding(instruction: Word) -> EncodingVariant {
-        let opcode: OPCODE = OPCODE::get_opcode(instruction).expect(&format!(
-            "unrecognized opcode in instruction {:?}",
-            instruction
-        ));
-
-        match opcode {
-            OPCODE::OPIMM => EncodingVariant::IType {
+    pub fn get_encoding(instruction: Word) -> Result<EncodingVariant, Exception> {
+        let opcode: OPCODE =
+            OPCODE::get_opcode(instruction).ok_or(Exception::IllegalInstruction)?;
+
+        let encoding: EncodingVariant = match opcode {
+            OPCODE::OPIMM | OPCODE::OPIMM32 => EncodingVariant::IType {
                 imm: ((instruction >> 20) & 0b111111111111) as usize,
                 rs1: ((instruction >> 15) & 0b11111) as usize,
                 funct3: ((instruction >> 12) & 0b111) as usize,
@@ -214,7 +442,7 @@ impl EncodingVariant {
                 rd: ((instruction >> 7) & 0b11111) as usize,
                 opcode,
             },
-            OPCODE::OPRR => EncodingVariant::RType {
+            OPCODE::OPRR | OPCODE::OP32 => EncodingVariant::RType {
                 funct7: ((instruction >> 25) & 0b1111111) as usize,
                 rs2: ((instruction >> 20) & 0b11111) as usize,
                 rs1: ((instruction >> 15) & 0b11111) as usize,
@@ -262,56 +490,2134 @@ impl EncodingVariant {
                 imm_4_0: ((instruction >> 7) & 0b11111) as usize,
                 opcode,
             },
-            OPCODE::FENCE => todo!(),
-            OPCODE::SYSTEM => todo!(),
-        }
+            OPCODE::SYSTEM => EncodingVariant::IType {
+                imm: ((instruction >> 20) & 0b111111111111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::AMO => EncodingVariant::RType {
+                funct7: ((instruction >> 25) & 0b1111111) as usize, // funct5 | aq | rl
+                rs2: ((instruction >> 20) & 0b11111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::LOADFP => EncodingVariant::IType {
+                imm: ((instruction >> 20) & 0b111111111111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::STOREFP => EncodingVariant::SType {
+                imm_11_5: ((instruction >> 25) & 0b1111111) as usize,
+                rs2: ((instruction >> 20) & 0b11111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                imm_4_0: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::OPV => EncodingVariant::RType {
+                funct7: ((instruction >> 25) & 0b1111111) as usize, // funct6 | vm
+                rs2: ((instruction >> 20) & 0b11111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::OPFP => EncodingVariant::RType {
+                funct7: ((instruction >> 25) & 0b1111111) as usize, // funct5 | fmt
+                rs2: ((instruction >> 20) & 0b11111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+            OPCODE::MADD | OPCODE::MSUB | OPCODE::NMSUB | OPCODE::NMADD => {
+                EncodingVariant::R4Type {
+                    rs3: ((instruction >> 27) & 0b11111) as usize,
+                    funct2: ((instruction >> 25) & 0b11) as usize,
+                    rs2: ((instruction >> 20) & 0b11111) as usize,
+                    rs1: ((instruction >> 15) & 0b11111) as usize,
+                    funct3: ((instruction >> 12) & 0b111) as usize,
+                    rd: ((instruction >> 7) & 0b11111) as usize,
+                    opcode,
+                }
+            }
+            OPCODE::FENCE => EncodingVariant::IType {
+                imm: ((instruction >> 20) & 0b111111111111) as usize,
+                rs1: ((instruction >> 15) & 0b11111) as usize,
+                funct3: ((instruction >> 12) & 0b111) as usize,
+                rd: ((instruction >> 7) & 0b11111) as usize,
+                opcode,
+            },
+        };
+        Ok(encoding)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Instruction {
     // OPIMM
-    ADDI { imm: i32, rs1: usize, rd: usize },
-    SLTI { imm: i32, rs1: usize, rd: usize },
-    STLIU { imm: u32, rs1: usize, rd: usize },
-    ANDI { imm: u32, rs1: usize, rd: usize },
-    ORI { imm: u32, rs1: usize, rd: usize },
-    XORI { imm: u32, rs1: usize, rd: usize },
-    SLLI { shamt: u32, rs1: usize, rd: usize },
-    SRLI { shamt: u32, rs1: usize, rd: usize },
-    SRAI { shamt: u32, rs1: usize, rd: usize },
+    ADDI {
+        imm: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    SLTI {
+        imm: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    STLIU {
+        imm: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    ANDI {
+        imm: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    ORI {
+        imm: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    XORI {
+        imm: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    SLLI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    SRLI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    SRAI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
     // LUI \ AUIPC
-    LUI { imm: u32, rd: usize },
-    AUIPC { imm: u32, rd: usize },
+    LUI {
+        imm: u32,
+        rd: usize,
+    },
+    AUIPC {
+        imm: u32,
+        rd: usize,
+    },
     // OPRR
-    ADD { rs1: usize, rs2: usize, rd: usize },
-    SUB { rs1: usize, rs2: usize, rd: usize },
-    SLT { rs1: usize, rs2: usize, rd: usize },
-    SLTU { rs1: usize, rs2: usize, rd: usize },
-    AND { rs1: usize, rs2: usize, rd: usize },
-    OR { rs1: usize, rs2: usize, rd: usize },
-    XOR { rs1: usize, rs2: usize, rd: usize },
-    SLL { rs1: usize, rs2: usize, rd: usize },
-    SRL { rs1: usize, rs2: usize, rd: usize },
-    SRA { rs1: usize, rs2: usize, rd: usize },
+    ADD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SUB {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SLT {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SLTU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AND {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    OR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    XOR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SLL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SRL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SRA {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPIMM32 \ OP32 (RV64I)
+    ADDIW {
+        imm: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    SLLIW {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    SRLIW {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    SRAIW {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    ADDW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SUBW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SLLW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SRLW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SRAW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPRR (M extension)
+    MUL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MULH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MULHSU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MULHU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    DIV {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    DIVU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    REM {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    REMU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OP32 (RV64M)
+    MULW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    DIVW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    DIVUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    REMW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    REMUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPRR (Zba)
+    SH1ADD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SH2ADD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SH3ADD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OP32 \ OPIMM32 (RV64 Zba)
+    ADDUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SH1ADDUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SH2ADDUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SH3ADDUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SLLIUW {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    // OPRR \ OPIMM (Zbb)
+    ANDN {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    ORN {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    XNOR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    CLZ {
+        rs1: usize,
+        rd: usize,
+    },
+    CTZ {
+        rs1: usize,
+        rd: usize,
+    },
+    CPOP {
+        rs1: usize,
+        rd: usize,
+    },
+    MIN {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MINU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MAX {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    MAXU {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SEXTB {
+        rs1: usize,
+        rd: usize,
+    },
+    SEXTH {
+        rs1: usize,
+        rd: usize,
+    },
+    ZEXTH {
+        rs1: usize,
+        rd: usize,
+    },
+    ROL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    ROR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    RORI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    REV8 {
+        rs1: usize,
+        rd: usize,
+    },
+    ORCB {
+        rs1: usize,
+        rd: usize,
+    },
+    // OP32 \ OPIMM32 (RV64 Zbb)
+    CLZW {
+        rs1: usize,
+        rd: usize,
+    },
+    CTZW {
+        rs1: usize,
+        rd: usize,
+    },
+    CPOPW {
+        rs1: usize,
+        rd: usize,
+    },
+    ROLW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    RORW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    RORIW {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    // OPRR \ OPIMM (Zbs)
+    BSET {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    BSETI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    BCLR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    BCLRI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    BINV {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    BINVI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    BEXT {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    BEXTI {
+        shamt: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    // OPRR (Zbc)
+    CLMUL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    CLMULH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    CLMULR {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPRR \ OPIMM (Zbkb)
+    PACK {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    PACKH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    PACKW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    BREV8 {
+        rs1: usize,
+        rd: usize,
+    },
+    ZIP {
+        rs1: usize,
+        rd: usize,
+    },
+    UNZIP {
+        rs1: usize,
+        rd: usize,
+    },
+    // OPRR (Zbkx)
+    XPERM4 {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    XPERM8 {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPRR (Zkne \ Zknd), bs selects the byte of rs2
+    AES32ESI {
+        bs: u32,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AES32ESMI {
+        bs: u32,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AES32DSI {
+        bs: u32,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AES32DSMI {
+        bs: u32,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPIMM \ OPRR (Zknh), the SHA-512 instructions work on register pairs on RV32
+    SHA256SIG0 {
+        rs1: usize,
+        rd: usize,
+    },
+    SHA256SIG1 {
+        rs1: usize,
+        rd: usize,
+    },
+    SHA256SUM0 {
+        rs1: usize,
+        rd: usize,
+    },
+    SHA256SUM1 {
+        rs1: usize,
+        rd: usize,
+    },
+    SHA512SIG0L {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SHA512SIG0H {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SHA512SIG1L {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SHA512SIG1H {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SHA512SUM0R {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    SHA512SUM1R {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OPRR (Zicond)
+    CZEROEQZ {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    CZERONEZ {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
     // JAL \ JALR
-    JAL { offset: i32, rd: usize },
-    JALR { offset: i32, rs1: usize, rd: usize },
+    JAL {
+        offset: i32,
+        rd: usize,
+    },
+    JALR {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
     // BRANCH
-    BEQ { offset: i32, rs1: usize, rs2: usize },
-    BNE { offset: i32, rs1: usize, rs2: usize },
-    BLT { offset: i32, rs1: usize, rs2: usize },
-    BLTU { offset: i32, rs1: usize, rs2: usize },
-    BGE { offset: i32, rs1: usize, rs2: usize },
-    BGEU { offset: i32, rs1: usize, rs2: usize },
-    LW { offset: i32, rs1: usize, rd: usize },
-    LH { offset: i32, rs1: usize, rd: usize },
-    LHU { offset: i32, rs1: usize, rd: usize },
-    LB { offset: i32, rs1: usize, rd: usize },
-    LBU { offset: i32, rs1: usize, rd: usize },
-    SW { offset: i32, rs1: usize, rs2: usize },
-    SH { offset: i32, rs1: usize, rs2: usize },
-    SB { offset: i32, rs1: usize, rs2: usize },
+    BEQ {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    BNE {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    BLT {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    BLTU {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    BGE {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    BGEU {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    LW {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    LH {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    LHU {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    LB {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    LBU {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    SW {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    SH {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    SB {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    // LOAD \ STORE (RV64I)
+    LD {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    LWU {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    SD {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    // SYSTEM (Zicsr)
+    CSRRW {
+        csr: usize,
+        rs1: usize,
+        rd: usize,
+    },
+    CSRRS {
+        csr: usize,
+        rs1: usize,
+        rd: usize,
+    },
+    CSRRC {
+        csr: usize,
+        rs1: usize,
+        rd: usize,
+    },
+    CSRRWI {
+        csr: usize,
+        uimm: u32,
+        rd: usize,
+    },
+    CSRRSI {
+        csr: usize,
+        uimm: u32,
+        rd: usize,
+    },
+    CSRRCI {
+        csr: usize,
+        uimm: u32,
+        rd: usize,
+    },
+    // SYSTEM (privileged)
+    ECALL,
+    EBREAK,
+    SRET,
+    MRET,
+    WFI,
+    SFENCEVMA {
+        rs1: usize,
+        rs2: usize,
+    },
+    // MISC-MEM
+    FENCE {
+        fm: u32,
+        pred: u32,
+        succ: u32,
+    },
+    FENCEI,
+    // MISC-MEM (Zicbom \ Zicboz), rs1 holds an address inside the cache block
+    CBOINVAL {
+        rs1: usize,
+    },
+    CBOCLEAN {
+        rs1: usize,
+    },
+    CBOFLUSH {
+        rs1: usize,
+    },
+    CBOZERO {
+        rs1: usize,
+    },
+    // Hints (Zicbop), encoded as ORI forms that write x0
+    PREFETCHI {
+        offset: i32,
+        rs1: usize,
+    },
+    PREFETCHR {
+        offset: i32,
+        rs1: usize,
+    },
+    PREFETCHW {
+        offset: i32,
+        rs1: usize,
+    },
+    // Hints (Zihintpause \ Zihintntl), encoded as FENCE / ADD forms that write nothing
+    PAUSE,
+    NTLP1,
+    NTLPALL,
+    NTLS1,
+    NTLALL,
+    // AMO
+    LRW {
+        rs1: usize,
+        rd: usize,
+    },
+    SCW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOSWAPW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOADDW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOXORW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOANDW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOORW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMINW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMAXW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMINUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMAXUW {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // AMO (RV64A)
+    LRD {
+        rs1: usize,
+        rd: usize,
+    },
+    SCD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOSWAPD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOADDD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOXORD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOANDD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOORD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMIND {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMAXD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMINUD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    AMOMAXUD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // F
+    FLW {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    FSW {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    FMADDS {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMSUBS {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMSUBS {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMADDS {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FADDS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSUBS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMULS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FDIVS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSQRTS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSGNJS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJNS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJXS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMINS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCVTWS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTWUS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVXW {
+        rs1: usize,
+        rd: usize,
+    },
+    FEQS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLES {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCLASSS {
+        rs1: usize,
+        rd: usize,
+    },
+    FCVTSW {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTSWU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVWX {
+        rs1: usize,
+        rd: usize,
+    },
+    // F (RV64F)
+    FCVTLS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTLUS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTSL {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTSLU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    // D
+    FLD {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    FSD {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    FMADDD {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMSUBD {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMSUBD {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMADDD {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FADDD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSUBD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMULD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FDIVD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSQRTD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSGNJD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJND {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJXD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMIND {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCVTSD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTDS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FEQD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLED {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCLASSD {
+        rs1: usize,
+        rd: usize,
+    },
+    FCVTWD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTWUD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTDW {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTDWU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    // D (RV64D)
+    FCVTLD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTLUD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVXD {
+        rs1: usize,
+        rd: usize,
+    },
+    FCVTDL {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTDLU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVDX {
+        rs1: usize,
+        rd: usize,
+    },
+    // Zfh
+    FLH {
+        offset: i32,
+        rs1: usize,
+        rd: usize,
+    },
+    FSH {
+        offset: i32,
+        rs1: usize,
+        rs2: usize,
+    },
+    FMADDH {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMSUBH {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMSUBH {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FNMADDH {
+        rs1: usize,
+        rs2: usize,
+        rs3: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FADDH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSUBH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMULH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FDIVH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSQRTH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FSGNJH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJNH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FSGNJXH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMINH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCVTSH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTHS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTDH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTHD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTWH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTWUH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVXH {
+        rs1: usize,
+        rd: usize,
+    },
+    FEQH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLEH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FCLASSH {
+        rs1: usize,
+        rd: usize,
+    },
+    FCVTHW {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTHWU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FMVHX {
+        rs1: usize,
+        rd: usize,
+    },
+    // Zfh (RV64)
+    FCVTLH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTLUH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTHL {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTHLU {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    // Zfa, index selects one of the 32 FLI constants
+    FLIS {
+        index: u32,
+        rd: usize,
+    },
+    FLID {
+        index: u32,
+        rd: usize,
+    },
+    FLIH {
+        index: u32,
+        rd: usize,
+    },
+    FMINMS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXMS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMINMD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXMD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMINMH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FMAXMH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FROUNDS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FROUNDNXS {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FROUNDD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FROUNDNXD {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FROUNDH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FROUNDNXH {
+        rs1: usize,
+        rd: usize,
+        rm: u32,
+    },
+    FCVTMODWD {
+        rs1: usize,
+        rd: usize,
+    },
+    FLEQS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTQS {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLEQD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTQD {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLEQH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    FLTQH {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // OP-V (V): configuration
+    VSETVLI {
+        vtypei: u32,
+        rs1: usize,
+        rd: usize,
+    },
+    VSETIVLI {
+        vtypei: u32,
+        uimm: u32,
+        rd: usize,
+    },
+    VSETVL {
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    },
+    // LOAD-FP \ STORE-FP (V), eew is the element (or index) width in bits, nf the number of fields
+    VLE {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vd: usize,
+    },
+    VLEFF {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vd: usize,
+    },
+    VLSE {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        rs2: usize,
+        vd: usize,
+    },
+    VLUXEI {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vs2: usize,
+        vd: usize,
+    },
+    VLOXEI {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vs2: usize,
+        vd: usize,
+    },
+    VLM {
+        rs1: usize,
+        vd: usize,
+    },
+    VLRE {
+        eew: u32,
+        nf: u32,
+        rs1: usize,
+        vd: usize,
+    },
+    VSE {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vs3: usize,
+    },
+    VSSE {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        rs2: usize,
+        vs3: usize,
+    },
+    VSUXEI {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vs2: usize,
+        vs3: usize,
+    },
+    VSOXEI {
+        eew: u32,
+        nf: u32,
+        vm: bool,
+        rs1: usize,
+        vs2: usize,
+        vs3: usize,
+    },
+    VSM {
+        rs1: usize,
+        vs3: usize,
+    },
+    VSR {
+        nf: u32,
+        rs1: usize,
+        vs3: usize,
+    },
+    // OP-V (V): integer arithmetic, vm is clear when the instruction is masked by v0
+    VADD {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VSUB {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VRSUB {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWADDU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWADD {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWSUBU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWSUB {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWADDUW {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWADDW {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWSUBUW {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWSUBW {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VZEXT {
+        factor: u32,
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VSEXT {
+        factor: u32,
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VADC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+    },
+    VMADC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VSBC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+    },
+    VMSBC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VAND {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VOR {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VXOR {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VSLL {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VSRL {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VSRA {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VNSRL {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VNSRA {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSEQ {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSNE {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSLTU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSLT {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSLEU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSLE {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSGTU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMSGT {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMINU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMIN {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMAXU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMAX {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMUL {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMULH {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMULHU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMULHSU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VDIVU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VDIV {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VREMU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VREM {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMULU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMUL {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMULSU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMACC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VNMSAC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMADD {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VNMSUB {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMACCU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMACC {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMACCSU {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VWMACCUS {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+        vm: bool,
+    },
+    VMERGE {
+        vd: usize,
+        vs2: usize,
+        operand: VectorOperand,
+    },
+    VMV {
+        vd: usize,
+        operand: VectorOperand,
+    },
+    // OP-V (V): reductions, vd[0] = vs1[0] op vs2[*]
+    VREDSUM {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDAND {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDXOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDMINU {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDMIN {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDMAXU {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VREDMAX {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VWREDSUMU {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    VWREDSUM {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+        vm: bool,
+    },
+    // OP-V (V): mask instructions
+    VMAND {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMNAND {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMANDN {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMXOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMNOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMORN {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VMXNOR {
+        vd: usize,
+        vs2: usize,
+        vs1: usize,
+    },
+    VCPOP {
+        vs2: usize,
+        rd: usize,
+        vm: bool,
+    },
+    VFIRST {
+        vs2: usize,
+        rd: usize,
+        vm: bool,
+    },
+    VMSBF {
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VMSIF {
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VMSOF {
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VIOTA {
+        vd: usize,
+        vs2: usize,
+        vm: bool,
+    },
+    VID {
+        vd: usize,
+        vm: bool,
+    },
+    // OP-V (V): moves between scalar and vector registers, and whole register moves
+    VMVXS {
+        vs2: usize,
+        rd: usize,
+    },
+    VMVSX {
+        rs1: usize,
+        vd: usize,
+    },
+    VMVNRR {
+        nr: u32,
+        vd: usize,
+        vs2: usize,
+    },
     // TODO: implement these as we go along
 }
 
@@ -326,6 +2632,11 @@ impl Instruction {
     const SRLI_FUNCT3: usize = 0b101;
     const SRAI_FUNCT3: usize = 0b101;
 
+    const ADDIW_FUNCT3: usize = 0b000;
+    const SLLIW_FUNCT3: usize = 0b001;
+    const SRLIW_FUNCT3: usize = 0b101;
+    const SRAIW_FUNCT3: usize = 0b101;
+
     const ADD_FUNCT3: usize = 0b000;
     const ADD_FUNCT7: usize = 0b0000000;
     const SUB_FUNCT3: usize = 0b000;
@@ -347,6 +2658,101 @@ impl Instruction {
     const SRA_FUNCT3: usize = 0b101;
     const SRA_FUNCT7: usize = 0b0100000;
 
+    const ADDW_FUNCT3: usize = 0b000;
+    const ADDW_FUNCT7: usize = 0b0000000;
+    const SUBW_FUNCT3: usize = 0b000;
+    const SUBW_FUNCT7: usize = 0b0100000;
+    const SLLW_FUNCT3: usize = 0b001;
+    const SLLW_FUNCT7: usize = 0b0000000;
+    const SRLW_FUNCT3: usize = 0b101;
+    const SRLW_FUNCT7: usize = 0b0000000;
+    const SRAW_FUNCT3: usize = 0b101;
+    const SRAW_FUNCT7: usize = 0b0100000;
+
+    const MULDIV_FUNCT7: usize = 0b0000001;
+    const MUL_FUNCT3: usize = 0b000;
+    const MULH_FUNCT3: usize = 0b001;
+    const MULHSU_FUNCT3: usize = 0b010;
+    const MULHU_FUNCT3: usize = 0b011;
+    const DIV_FUNCT3: usize = 0b100;
+    const DIVU_FUNCT3: usize = 0b101;
+    const REM_FUNCT3: usize = 0b110;
+    const REMU_FUNCT3: usize = 0b111;
+
+    // Bit-manipulation operations, selected by funct7 (or imm[11:5]) and then funct3
+    const SHADD_FUNCT7: usize = 0b0010000; // funct3 selects SH1ADD / SH2ADD / SH3ADD
+    const SH1ADD_FUNCT3: usize = 0b010;
+    const SH2ADD_FUNCT3: usize = 0b100;
+    const SH3ADD_FUNCT3: usize = 0b110;
+    const ADDUW_FUNCT7: usize = 0b0000100; // also PACK / PACKH / PACKW
+    const ADDUW_FUNCT3: usize = 0b000;
+    const PACK_FUNCT3: usize = 0b100; // ZEXT.H is PACK (PACKW on RV64) with rs2 = 0
+    const PACKH_FUNCT3: usize = 0b111;
+    const SLLIUW_FUNCT6: usize = 0b000010;
+    const INVERTED_FUNCT7: usize = 0b0100000; // ANDN / ORN / XNOR use the funct3 of AND / OR / XOR
+    const MINMAX_CLMUL_FUNCT7: usize = 0b0000101;
+    const CLMUL_FUNCT3: usize = 0b001;
+    const CLMULR_FUNCT3: usize = 0b010;
+    const CLMULH_FUNCT3: usize = 0b011;
+    const MIN_FUNCT3: usize = 0b100;
+    const MINU_FUNCT3: usize = 0b101;
+    const MAX_FUNCT3: usize = 0b110;
+    const MAXU_FUNCT3: usize = 0b111;
+    const ROTATE_FUNCT7: usize = 0b0110000; // also the unary CLZ group in OPIMM, rs2 selects
+    const ROL_FUNCT3: usize = 0b001;
+    const ROR_FUNCT3: usize = 0b101;
+    const CLZ_RS2: usize = 0b00000;
+    const CTZ_RS2: usize = 0b00001;
+    const CPOP_RS2: usize = 0b00010;
+    const SEXTB_RS2: usize = 0b00100;
+    const SEXTH_RS2: usize = 0b00101;
+    #[allow(clippy::unusual_byte_groupings)]
+    const REV8_RV32_IMM: usize = 0b0110100_11000;
+    #[allow(clippy::unusual_byte_groupings)]
+    const REV8_RV64_IMM: usize = 0b0110101_11000;
+    #[allow(clippy::unusual_byte_groupings)]
+    const ORCB_IMM: usize = 0b0010100_00111;
+    const BSET_FUNCT7: usize = 0b0010100;
+    const BCLR_FUNCT7: usize = 0b0100100; // also BEXT with funct3 101
+    const BINV_FUNCT7: usize = 0b0110100;
+    const BIT_FUNCT3: usize = 0b001; // BSET / BCLR / BINV
+    const BEXT_FUNCT3: usize = 0b101;
+    #[allow(clippy::unusual_byte_groupings)]
+    const BREV8_IMM: usize = 0b0110100_00111;
+    #[allow(clippy::unusual_byte_groupings)]
+    const ZIP_IMM: usize = 0b0000100_01111; // ZIP with funct3 001, UNZIP with 101
+    const XPERM_FUNCT7: usize = 0b0010100;
+    const XPERM4_FUNCT3: usize = 0b010;
+    const XPERM8_FUNCT3: usize = 0b100;
+
+    // Scalar cryptography, AES in the low five bits of funct7 (bs sits above), SHA-2 in funct7
+    const CRYPTO_FUNCT3: usize = 0b000;
+    const AES32ESI_FUNCT5: usize = 0b10001;
+    const AES32ESMI_FUNCT5: usize = 0b10011;
+    const AES32DSI_FUNCT5: usize = 0b10101;
+    const AES32DSMI_FUNCT5: usize = 0b10111;
+    const SHA256_FUNCT7: usize = 0b0001000; // OPIMM, rs2 selects the function
+    const SHA256SUM0_RS2: usize = 0b00000;
+    const SHA256SUM1_RS2: usize = 0b00001;
+    const SHA256SIG0_RS2: usize = 0b00010;
+    const SHA256SIG1_RS2: usize = 0b00011;
+    const SHA512SUM0R_FUNCT7: usize = 0b0101000;
+    const SHA512SUM1R_FUNCT7: usize = 0b0101001;
+    const SHA512SIG0L_FUNCT7: usize = 0b0101010;
+    const SHA512SIG1L_FUNCT7: usize = 0b0101011;
+    const SHA512SIG0H_FUNCT7: usize = 0b0101110;
+    const SHA512SIG1H_FUNCT7: usize = 0b0101111;
+
+    const CZERO_FUNCT7: usize = 0b0000111;
+    const CZEROEQZ_FUNCT3: usize = 0b101;
+    const CZERONEZ_FUNCT3: usize = 0b111;
+
+    // Zihintntl hints, add x0, x0, rs2
+    const NTL_P1_RS2: usize = 2; // innermost private cache
+    const NTL_PALL_RS2: usize = 3; // all private caches
+    const NTL_S1_RS2: usize = 4; // innermost shared cache
+    const NTL_ALL_RS2: usize = 5; // all caches
+
     const JALR_FUNCT3: usize = 0b000;
 
     const BEQ_FUNCT3: usize = 0b000;
@@ -361,10 +2767,209 @@ impl Instruction {
     const LHU_FUNCT3: usize = 0b101;
     const LB_FUNCT3: usize = 0b000;
     const LBU_FUNCT3: usize = 0b100;
+    const LD_FUNCT3: usize = 0b011;
+    const LWU_FUNCT3: usize = 0b110;
 
     const SW_FUNCT3: usize = 0b010;
     const SH_FUNCT3: usize = 0b001;
     const SB_FUNCT3: usize = 0b000;
+    const SD_FUNCT3: usize = 0b011;
+
+    const CSRRW_FUNCT3: usize = 0b001;
+    const CSRRS_FUNCT3: usize = 0b010;
+    const CSRRC_FUNCT3: usize = 0b011;
+    const CSRRWI_FUNCT3: usize = 0b101;
+    const CSRRSI_FUNCT3: usize = 0b110;
+    const CSRRCI_FUNCT3: usize = 0b111;
+
+    const AMO_W_FUNCT3: usize = 0b010;
+    const AMO_D_FUNCT3: usize = 0b011;
+    const LR_FUNCT5: usize = 0b00010;
+    const SC_FUNCT5: usize = 0b00011;
+    const AMOSWAP_FUNCT5: usize = 0b00001;
+    const AMOADD_FUNCT5: usize = 0b00000;
+    const AMOXOR_FUNCT5: usize = 0b00100;
+    const AMOAND_FUNCT5: usize = 0b01100;
+    const AMOOR_FUNCT5: usize = 0b01000;
+    const AMOMIN_FUNCT5: usize = 0b10000;
+    const AMOMAX_FUNCT5: usize = 0b10100;
+    const AMOMINU_FUNCT5: usize = 0b11000;
+    const AMOMAXU_FUNCT5: usize = 0b11100;
+
+    const FLW_FUNCT3: usize = 0b010; // width field of LOAD-FP / STORE-FP
+    const FSW_FUNCT3: usize = 0b010;
+    const FLD_FUNCT3: usize = 0b011;
+    const FSD_FUNCT3: usize = 0b011;
+    const FLH_FUNCT3: usize = 0b001;
+    const FSH_FUNCT3: usize = 0b001;
+
+    // Floating-point formats in the fmt / funct2 field
+    const FMT_S: usize = 0b00;
+    const FMT_D: usize = 0b01;
+    const FMT_H: usize = 0b10;
+
+    // OP-FP operations in the upper five bits of funct7
+    const FADD_FUNCT5: usize = 0b00000;
+    const FSUB_FUNCT5: usize = 0b00001;
+    const FMUL_FUNCT5: usize = 0b00010;
+    const FDIV_FUNCT5: usize = 0b00011;
+    const FSQRT_FUNCT5: usize = 0b01011;
+    const FCVT_FP_FUNCT5: usize = 0b01000; // between formats, rs2 holds the source format
+    const FSGNJ_FUNCT5: usize = 0b00100; // funct3 selects J / JN / JX
+    const FMINMAX_FUNCT5: usize = 0b00101; // funct3 selects MIN / MAX
+    const FCVT_INT_FUNCT5: usize = 0b11000; // float to integer, rs2 selects the integer type
+    const FCVT_FROM_INT_FUNCT5: usize = 0b11010; // integer to float
+    const FCMP_FUNCT5: usize = 0b10100; // funct3 selects LE / LT / EQ
+    const FMV_X_FUNCT5: usize = 0b11100; // funct3 selects FMV.X / FCLASS
+    const FMV_FROM_X_FUNCT5: usize = 0b11110;
+
+    // Zfa operations sharing the OP-FP funct5 values above, told apart by rs2 or funct3
+    const FLI_RS2: usize = 0b00001; // FMV_FROM_X with rs1 selecting the constant
+    const FROUND_RS2: usize = 0b00100; // FCVT_FP
+    const FROUNDNX_RS2: usize = 0b00101;
+    const FCVTMOD_RS2: usize = 0b01000; // FCVT_INT, rounding towards zero only
+
+    // Vector loads and stores share LOAD-FP / STORE-FP, the width field selects the element width
+    const VECTOR_WIDTH_8_FUNCT3: usize = 0b000;
+    const VECTOR_WIDTH_16_FUNCT3: usize = 0b101;
+    const VECTOR_WIDTH_32_FUNCT3: usize = 0b110;
+    const VECTOR_WIDTH_64_FUNCT3: usize = 0b111;
+    const VECTOR_MOP_UNIT: usize = 0b00; // mop field: addressing mode
+    const VECTOR_MOP_INDEXED_UNORDERED: usize = 0b01;
+    const VECTOR_MOP_STRIDED: usize = 0b10;
+    const VECTOR_MOP_INDEXED_ORDERED: usize = 0b11;
+    const VECTOR_UNIT: usize = 0b00000; // lumop / sumop field of the unit-stride accesses
+    const VECTOR_UNIT_WHOLE: usize = 0b01000;
+    const VECTOR_UNIT_MASK: usize = 0b01011;
+    const VECTOR_UNIT_FAULT_FIRST: usize = 0b10000;
+
+    // OP-V operand categories in funct3
+    const OPIVV_FUNCT3: usize = 0b000; // integer, vector-vector
+    const OPMVV_FUNCT3: usize = 0b010; // multiply / mask / reduction, vector-vector
+    const OPIVI_FUNCT3: usize = 0b011; // integer, vector-immediate
+    const OPIVX_FUNCT3: usize = 0b100; // integer, vector-scalar
+    const OPMVX_FUNCT3: usize = 0b110; // multiply, vector-scalar
+    const OPCFG_FUNCT3: usize = 0b111; // vsetvli / vsetivli / vsetvl
+
+    // OP-V operations in funct6, OPI forms
+    const VADD_FUNCT6: usize = 0b000000;
+    const VSUB_FUNCT6: usize = 0b000010;
+    const VRSUB_FUNCT6: usize = 0b000011;
+    const VMINU_FUNCT6: usize = 0b000100;
+    const VMIN_FUNCT6: usize = 0b000101;
+    const VMAXU_FUNCT6: usize = 0b000110;
+    const VMAX_FUNCT6: usize = 0b000111;
+    const VAND_FUNCT6: usize = 0b001001;
+    const VOR_FUNCT6: usize = 0b001010;
+    const VXOR_FUNCT6: usize = 0b001011;
+    const VADC_FUNCT6: usize = 0b010000;
+    const VMADC_FUNCT6: usize = 0b010001;
+    const VSBC_FUNCT6: usize = 0b010010;
+    const VMSBC_FUNCT6: usize = 0b010011;
+    const VMERGE_FUNCT6: usize = 0b010111; // VMV.V when unmasked
+    const VMSEQ_FUNCT6: usize = 0b011000;
+    const VMSNE_FUNCT6: usize = 0b011001;
+    const VMSLTU_FUNCT6: usize = 0b011010;
+    const VMSLT_FUNCT6: usize = 0b011011;
+    const VMSLEU_FUNCT6: usize = 0b011100;
+    const VMSLE_FUNCT6: usize = 0b011101;
+    const VMSGTU_FUNCT6: usize = 0b011110;
+    const VMSGT_FUNCT6: usize = 0b011111;
+    const VSLL_FUNCT6: usize = 0b100101;
+    const VMVNRR_FUNCT6: usize = 0b100111; // simm5 holds the register count - 1
+    const VSRL_FUNCT6: usize = 0b101000;
+    const VSRA_FUNCT6: usize = 0b101001;
+    const VNSRL_FUNCT6: usize = 0b101100;
+    const VNSRA_FUNCT6: usize = 0b101101;
+    const VWREDSUMU_FUNCT6: usize = 0b110000;
+    const VWREDSUM_FUNCT6: usize = 0b110001;
+
+    // OP-V operations in funct6, OPM forms
+    const VREDSUM_FUNCT6: usize = 0b000000;
+    const VREDAND_FUNCT6: usize = 0b000001;
+    const VREDOR_FUNCT6: usize = 0b000010;
+    const VREDXOR_FUNCT6: usize = 0b000011;
+    const VREDMINU_FUNCT6: usize = 0b000100;
+    const VREDMIN_FUNCT6: usize = 0b000101;
+    const VREDMAXU_FUNCT6: usize = 0b000110;
+    const VREDMAX_FUNCT6: usize = 0b000111;
+    const VWXUNARY0_FUNCT6: usize = 0b010000; // VMV.X.S / VCPOP / VFIRST, vs1 selects (VMV.S.X for .vx)
+    const VXUNARY0_FUNCT6: usize = 0b010010; // VZEXT / VSEXT, vs1 selects
+    const VMUNARY0_FUNCT6: usize = 0b010100; // VMSBF / VMSOF / VMSIF / VIOTA / VID, vs1 selects
+    const VMANDN_FUNCT6: usize = 0b011000;
+    const VMAND_FUNCT6: usize = 0b011001;
+    const VMOR_FUNCT6: usize = 0b011010;
+    const VMXOR_FUNCT6: usize = 0b011011;
+    const VMORN_FUNCT6: usize = 0b011100;
+    const VMNAND_FUNCT6: usize = 0b011101;
+    const VMNOR_FUNCT6: usize = 0b011110;
+    const VMXNOR_FUNCT6: usize = 0b011111;
+    const VDIVU_FUNCT6: usize = 0b100000;
+    const VDIV_FUNCT6: usize = 0b100001;
+    const VREMU_FUNCT6: usize = 0b100010;
+    const VREM_FUNCT6: usize = 0b100011;
+    const VMULHU_FUNCT6: usize = 0b100100;
+    const VMUL_FUNCT6: usize = 0b100101;
+    const VMULHSU_FUNCT6: usize = 0b100110;
+    const VMULH_FUNCT6: usize = 0b100111;
+    const VMADD_FUNCT6: usize = 0b101001;
+    const VNMSUB_FUNCT6: usize = 0b101011;
+    const VMACC_FUNCT6: usize = 0b101101;
+    const VNMSAC_FUNCT6: usize = 0b101111;
+    const VWADDU_FUNCT6: usize = 0b110000;
+    const VWADD_FUNCT6: usize = 0b110001;
+    const VWSUBU_FUNCT6: usize = 0b110010;
+    const VWSUB_FUNCT6: usize = 0b110011;
+    const VWADDUW_FUNCT6: usize = 0b110100;
+    const VWADDW_FUNCT6: usize = 0b110101;
+    const VWSUBUW_FUNCT6: usize = 0b110110;
+    const VWSUBW_FUNCT6: usize = 0b110111;
+    const VWMULU_FUNCT6: usize = 0b111000;
+    const VWMULSU_FUNCT6: usize = 0b111010;
+    const VWMUL_FUNCT6: usize = 0b111011;
+    const VWMACCU_FUNCT6: usize = 0b111100;
+    const VWMACC_FUNCT6: usize = 0b111101;
+    const VWMACCUS_FUNCT6: usize = 0b111110;
+    const VWMACCSU_FUNCT6: usize = 0b111111;
+    const VMV_X_S_VS1: usize = 0b00000;
+    const VCPOP_VS1: usize = 0b10000;
+    const VFIRST_VS1: usize = 0b10001;
+    const VMSBF_VS1: usize = 0b00001;
+    const VMSOF_VS1: usize = 0b00010;
+    const VMSIF_VS1: usize = 0b00011;
+    const VIOTA_VS1: usize = 0b10000;
+    const VID_VS1: usize = 0b10001;
+
+    const FENCE_FUNCT3: usize = 0b000;
+    const FENCEI_FUNCT3: usize = 0b001;
+    const CBO_FUNCT3: usize = 0b010;
+    const CBO_INVAL_FUNCT12: usize = 0b000000000000;
+    const CBO_CLEAN_FUNCT12: usize = 0b000000000001;
+    const CBO_FLUSH_FUNCT12: usize = 0b000000000010;
+    const CBO_ZERO_FUNCT12: usize = 0b000000000100;
+
+    // PREFETCH.* select the operation in imm[4:0], the offset is imm[11:5] << 5
+    const PREFETCH_I: usize = 0b00000;
+    const PREFETCH_R: usize = 0b00001;
+    const PREFETCH_W: usize = 0b00011;
+
+    // FENCE predecessor / successor sets
+    pub const FENCE_I: u32 = 0b1000; // device input
+    pub const FENCE_O: u32 = 0b0100; // device output
+    pub const FENCE_R: u32 = 0b0010; // memory reads
+    pub const FENCE_W: u32 = 0b0001; // memory writes
+    pub const FENCE_TSO_FM: u32 = 0b1000; // fence mode of FENCE.TSO
+
+    const PRIV_FUNCT3: usize = 0b000;
+    const ECALL_FUNCT12: usize = 0b000000000000;
+    const EBREAK_FUNCT12: usize = 0b000000000001;
+    #[allow(clippy::unusual_byte_groupings)]
+    const SRET_FUNCT12: usize = 0b0001000_00010;
+    #[allow(clippy::unusual_byte_groupings)]
+    const MRET_FUNCT12: usize = 0b0011000_00010;
+    #[allow(clippy::unusual_byte_groupings)]
+    const WFI_FUNCT12: usize = 0b0001000_00101;
+    const SFENCE_VMA_FUNCT7: usize = 0b0001001;
 
     const OPIMM_BITS: u32 = 12;
     const JAL_BITS: u32 = 21;
@@ -373,8 +2978,20 @@ impl Instruction {
     const LOAD_BITS: u32 = 12;
     const STORE_BITS: u32 = 12;
 
-    pub fn parse_instruction(encoding: EncodingVariant) -> Instruction {
-        match encoding {
+    /// Decodes a 32-bit instruction for an RV32 hart
+    pub fn parse_instruction(encoding: EncodingVariant) -> Result<Instruction, Exception> {
+        Instruction::parse_instruction_xlen(encoding, 32)
+    }
+
+    /// Decodes a 32-bit instruction for a hart whose registers are `xlen` bits wide.
+    /// The RV64-only encodings are illegal when `xlen` is 32.
+    pub fn parse_instruction_xlen(
+        encoding: EncodingVariant,
+        xlen: u32,
+    ) -> Result<Instruction, Exception> {
+        let rv64: bool = xlen == 64;
+        let shamt_mask: usize = xlen as usize - 1; // 5-bit shift amounts on RV32, 6-bit on RV64
+        let instruction: Instruction = match encoding {
             EncodingVariant::IType {
                 imm,
                 rs1,
@@ -386,59 +3003,106 @@ impl Instruction {
                     let addi_imm: i32 = sign_extend_u32(imm, Instruction::OPIMM_BITS);
                     Instruction::ADDI {
                         imm: addi_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
                     }
                 } else if opcode == OPCODE::OPIMM && funct3 == Instruction::SLTI_FUNCT3 {
                     let slti_imm: i32 = sign_extend_u32(imm, Instruction::OPIMM_BITS);
                     Instruction::SLTI {
                         imm: slti_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
                     }
                 } else if opcode == OPCODE::OPIMM && funct3 == Instruction::SLTIU_FUNCT3 {
                     let sltiu_imm: u32 = sign_extend_u32(imm, Instruction::OPIMM_BITS) as u32;
                     Instruction::STLIU {
                         imm: sltiu_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
                     }
                 } else if opcode == OPCODE::OPIMM && funct3 == Instruction::ANDI_FUNCT3 {
                     let andi_imm: u32 = sign_extend_u32(imm, Instruction::OPIMM_BITS) as u32;
                     Instruction::ANDI {
                         imm: andi_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
+                    }
+                } else if opcode == OPCODE::OPIMM
+                    && funct3 == Instruction::ORI_FUNCT3
+                    && rd == 0
+                    && matches!(
+                        imm & 0b11111,
+                        Instruction::PREFETCH_I | Instruction::PREFETCH_R | Instruction::PREFETCH_W
+                    )
+                {
+                    let offset: i32 = sign_extend_u32(imm & !0b11111, Instruction::OPIMM_BITS);
+                    match imm & 0b11111 {
+                        Instruction::PREFETCH_I => Instruction::PREFETCHI { offset, rs1 },
+                        Instruction::PREFETCH_R => Instruction::PREFETCHR { offset, rs1 },
+                        _ => Instruction::PREFETCHW { offset, rs1 },
                     }
                 } else if opcode == OPCODE::OPIMM && funct3 == Instruction::ORI_FUNCT3 {
                     let ori_imm: u32 = sign_extend_u32(imm, Instruction::OPIMM_BITS) as u32;
                     Instruction::ORI {
                         imm: ori_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
                     }
                 } else if opcode == OPCODE::OPIMM && funct3 == Instruction::XORI_FUNCT3 {
                     let xori_imm: u32 = sign_extend_u32(imm, Instruction::OPIMM_BITS) as u32;
                     Instruction::XORI {
                         imm: xori_imm,
-                        rs1: rs1,
-                        rd: rd,
+                        rs1,
+                        rd,
                     }
-                } else if opcode == OPCODE::OPIMM && funct3 == Instruction::SLLI_FUNCT3 {
-                    let shamt: u32 = (imm & 0b11111) as u32; // shift amount is in lower 5 bits
+                } else if opcode == OPCODE::OPIMM
+                    && funct3 == Instruction::SLLI_FUNCT3
+                    && (imm & !shamt_mask) == 0
+                {
+                    let shamt: u32 = (imm & shamt_mask) as u32;
                     Instruction::SLLI { shamt, rs1, rd }
                 } else if opcode == OPCODE::OPIMM
                     && funct3 == Instruction::SRLI_FUNCT3
-                    && (imm >> 5) == 0
+                    && (imm & !shamt_mask) == 0
                 {
-                    let shamt: u32 = (imm & 0b11111) as u32; // shift amount is in lower 5 bits
+                    let shamt: u32 = (imm & shamt_mask) as u32;
                     Instruction::SRLI { shamt, rs1, rd }
                 } else if opcode == OPCODE::OPIMM
                     && funct3 == Instruction::SRAI_FUNCT3
-                    && (imm >> 5) == 0b0100000
+                    && (imm & !shamt_mask) >> 5 == 0b0100000
                 {
-                    let shamt: u32 = (imm & 0b11111) as u32; // shift amount is in lower 5 bits
+                    let shamt: u32 = (imm & shamt_mask) as u32;
                     Instruction::SRAI { shamt, rs1, rd }
+                } else if rv64 && opcode == OPCODE::OPIMM32 && funct3 == Instruction::ADDIW_FUNCT3 {
+                    let addiw_imm: i32 = sign_extend_u32(imm, Instruction::OPIMM_BITS);
+                    Instruction::ADDIW {
+                        imm: addiw_imm,
+                        rs1,
+                        rd,
+                    }
+                } else if rv64
+                    && opcode == OPCODE::OPIMM32
+                    && funct3 == Instruction::SLLIW_FUNCT3
+                    && (imm >> 5) == 0
+                {
+                    let shamt: u32 = (imm & 0b11111) as u32; // word shifts keep 5-bit amounts
+                    Instruction::SLLIW { shamt, rs1, rd }
+                } else if rv64
+                    && opcode == OPCODE::OPIMM32
+                    && funct3 == Instruction::SRLIW_FUNCT3
+                    && (imm >> 5) == 0
+                {
+                    let shamt: u32 = (imm & 0b11111) as u32;
+                    Instruction::SRLIW { shamt, rs1, rd }
+                } else if rv64
+                    && opcode == OPCODE::OPIMM32
+                    && funct3 == Instruction::SRAIW_FUNCT3
+                    && (imm >> 5) == 0b0100000
+                {
+                    let shamt: u32 = (imm & 0b11111) as u32;
+                    Instruction::SRAIW { shamt, rs1, rd }
+                } else if opcode == OPCODE::OPIMM || opcode == OPCODE::OPIMM32 {
+                    Instruction::parse_op_imm_bitmanip(imm, rs1, funct3, rd, opcode, xlen)?
                 } else if opcode == OPCODE::JALR && funct3 == Instruction::JALR_FUNCT3 {
                     let jalr_imm: i32 = sign_extend_u32(imm, Instruction::JALR_BITS);
                     Instruction::JALR {
@@ -461,8 +3125,103 @@ impl Instruction {
                 } else if opcode == OPCODE::LOAD && funct3 == Instruction::LBU_FUNCT3 {
                     let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
                     Instruction::LBU { offset, rs1, rd }
+                } else if rv64 && opcode == OPCODE::LOAD && funct3 == Instruction::LD_FUNCT3 {
+                    let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
+                    Instruction::LD { offset, rs1, rd }
+                } else if rv64 && opcode == OPCODE::LOAD && funct3 == Instruction::LWU_FUNCT3 {
+                    let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
+                    Instruction::LWU { offset, rs1, rd }
+                } else if opcode == OPCODE::FENCE && funct3 == Instruction::FENCE_FUNCT3 {
+                    // rs1 and rd are reserved and ignored
+                    let fm: u32 = (imm >> 8) as u32;
+                    let pred: u32 = ((imm >> 4) & 0b1111) as u32;
+                    let succ: u32 = (imm & 0b1111) as u32;
+                    if fm == 0 && pred == Instruction::FENCE_W && succ == 0 && rs1 == 0 && rd == 0 {
+                        Instruction::PAUSE // FENCE W, 0 never orders anything
+                    } else {
+                        Instruction::FENCE { fm, pred, succ }
+                    }
+                } else if opcode == OPCODE::LOADFP && funct3 == Instruction::FLW_FUNCT3 {
+                    let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
+                    Instruction::FLW { offset, rs1, rd }
+                } else if opcode == OPCODE::LOADFP && funct3 == Instruction::FLD_FUNCT3 {
+                    let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
+                    Instruction::FLD { offset, rs1, rd }
+                } else if opcode == OPCODE::LOADFP && funct3 == Instruction::FLH_FUNCT3 {
+                    let offset: i32 = sign_extend_u32(imm, Instruction::LOAD_BITS);
+                    Instruction::FLH { offset, rs1, rd }
+                } else if opcode == OPCODE::LOADFP && Instruction::vector_eew(funct3).is_some() {
+                    Instruction::parse_vector_load(imm, rs1, funct3, rd)?
+                } else if opcode == OPCODE::FENCE && funct3 == Instruction::FENCEI_FUNCT3 {
+                    // imm, rs1 and rd are reserved and ignored
+                    Instruction::FENCEI
+                } else if opcode == OPCODE::FENCE && funct3 == Instruction::CBO_FUNCT3 && rd == 0 {
+                    match imm {
+                        Instruction::CBO_INVAL_FUNCT12 => Instruction::CBOINVAL { rs1 },
+                        Instruction::CBO_CLEAN_FUNCT12 => Instruction::CBOCLEAN { rs1 },
+                        Instruction::CBO_FLUSH_FUNCT12 => Instruction::CBOFLUSH { rs1 },
+                        Instruction::CBO_ZERO_FUNCT12 => Instruction::CBOZERO { rs1 },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm == Instruction::ECALL_FUNCT12
+                    && rs1 == 0
+                    && rd == 0
+                {
+                    Instruction::ECALL
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm == Instruction::EBREAK_FUNCT12
+                    && rs1 == 0
+                    && rd == 0
+                {
+                    Instruction::EBREAK
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm == Instruction::SRET_FUNCT12
+                    && rs1 == 0
+                    && rd == 0
+                {
+                    Instruction::SRET
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm >> 5 == Instruction::SFENCE_VMA_FUNCT7
+                    && rd == 0
+                {
+                    let rs2: usize = imm & 0b11111; // the low bits of funct12 hold rs2
+                    Instruction::SFENCEVMA { rs1, rs2 }
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm == Instruction::MRET_FUNCT12
+                    && rs1 == 0
+                    && rd == 0
+                {
+                    Instruction::MRET
+                } else if opcode == OPCODE::SYSTEM
+                    && funct3 == Instruction::PRIV_FUNCT3
+                    && imm == Instruction::WFI_FUNCT12
+                    && rs1 == 0
+                    && rd == 0
+                {
+                    Instruction::WFI
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRW_FUNCT3 {
+                    Instruction::CSRRW { csr: imm, rs1, rd }
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRS_FUNCT3 {
+                    Instruction::CSRRS { csr: imm, rs1, rd }
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRC_FUNCT3 {
+                    Instruction::CSRRC { csr: imm, rs1, rd }
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRWI_FUNCT3 {
+                    let uimm: u32 = rs1 as u32; // the rs1 field holds a 5-bit zero-extended immediate
+                    Instruction::CSRRWI { csr: imm, uimm, rd }
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRSI_FUNCT3 {
+                    let uimm: u32 = rs1 as u32;
+                    Instruction::CSRRSI { csr: imm, uimm, rd }
+                } else if opcode == OPCODE::SYSTEM && funct3 == Instruction::CSRRCI_FUNCT3 {
+                    let uimm: u32 = rs1 as u32;
+                    Instruction::CSRRCI { csr: imm, uimm, rd }
                 } else {
-                    panic!("unrecognized IType instruction")
+                    return Err(Exception::IllegalInstruction);
                 }
             }
             EncodingVariant::UType {
@@ -477,7 +3236,7 @@ impl Instruction {
                     let imm: u32 = (imm_31_12 as u32) << 12;
                     Instruction::AUIPC { imm, rd }
                 } else {
-                    panic!("unrecognized UType instruction")
+                    return Err(Exception::IllegalInstruction);
                 }
             }
             EncodingVariant::RType {
@@ -492,7 +3251,10 @@ impl Instruction {
                     && funct3 == Instruction::ADD_FUNCT3
                     && funct7 == Instruction::ADD_FUNCT7
                 {
-                    Instruction::ADD { rs1, rs2, rd }
+                    match Instruction::ntl_hint(rs2) {
+                        Some(hint) if rs1 == 0 && rd == 0 => hint,
+                        _ => Instruction::ADD { rs1, rs2, rd },
+                    }
                 } else if opcode == OPCODE::OPRR
                     && funct3 == Instruction::SUB_FUNCT3
                     && funct7 == Instruction::SUB_FUNCT7
@@ -538,8 +3300,104 @@ impl Instruction {
                     && funct7 == Instruction::SRA_FUNCT7
                 {
                     Instruction::SRA { rs1, rs2, rd }
+                } else if opcode == OPCODE::OPRR && funct7 == Instruction::MULDIV_FUNCT7 {
+                    match funct3 {
+                        Instruction::MUL_FUNCT3 => Instruction::MUL { rs1, rs2, rd },
+                        Instruction::MULH_FUNCT3 => Instruction::MULH { rs1, rs2, rd },
+                        Instruction::MULHSU_FUNCT3 => Instruction::MULHSU { rs1, rs2, rd },
+                        Instruction::MULHU_FUNCT3 => Instruction::MULHU { rs1, rs2, rd },
+                        Instruction::DIV_FUNCT3 => Instruction::DIV { rs1, rs2, rd },
+                        Instruction::DIVU_FUNCT3 => Instruction::DIVU { rs1, rs2, rd },
+                        Instruction::REM_FUNCT3 => Instruction::REM { rs1, rs2, rd },
+                        Instruction::REMU_FUNCT3 => Instruction::REMU { rs1, rs2, rd },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
+                } else if rv64
+                    && opcode == OPCODE::OP32
+                    && funct3 == Instruction::ADDW_FUNCT3
+                    && funct7 == Instruction::ADDW_FUNCT7
+                {
+                    Instruction::ADDW { rs1, rs2, rd }
+                } else if rv64
+                    && opcode == OPCODE::OP32
+                    && funct3 == Instruction::SUBW_FUNCT3
+                    && funct7 == Instruction::SUBW_FUNCT7
+                {
+                    Instruction::SUBW { rs1, rs2, rd }
+                } else if rv64
+                    && opcode == OPCODE::OP32
+                    && funct3 == Instruction::SLLW_FUNCT3
+                    && funct7 == Instruction::SLLW_FUNCT7
+                {
+                    Instruction::SLLW { rs1, rs2, rd }
+                } else if rv64
+                    && opcode == OPCODE::OP32
+                    && funct3 == Instruction::SRLW_FUNCT3
+                    && funct7 == Instruction::SRLW_FUNCT7
+                {
+                    Instruction::SRLW { rs1, rs2, rd }
+                } else if rv64
+                    && opcode == OPCODE::OP32
+                    && funct3 == Instruction::SRAW_FUNCT3
+                    && funct7 == Instruction::SRAW_FUNCT7
+                {
+                    Instruction::SRAW { rs1, rs2, rd }
+                } else if rv64 && opcode == OPCODE::OP32 && funct7 == Instruction::MULDIV_FUNCT7 {
+                    match funct3 {
+                        Instruction::MUL_FUNCT3 => Instruction::MULW { rs1, rs2, rd },
+                        Instruction::DIV_FUNCT3 => Instruction::DIVW { rs1, rs2, rd },
+                        Instruction::DIVU_FUNCT3 => Instruction::DIVUW { rs1, rs2, rd },
+                        Instruction::REM_FUNCT3 => Instruction::REMW { rs1, rs2, rd },
+                        Instruction::REMU_FUNCT3 => Instruction::REMUW { rs1, rs2, rd },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
+                } else if opcode == OPCODE::OPRR && funct7 == Instruction::CZERO_FUNCT7 {
+                    match funct3 {
+                        Instruction::CZEROEQZ_FUNCT3 => Instruction::CZEROEQZ { rs1, rs2, rd },
+                        Instruction::CZERONEZ_FUNCT3 => Instruction::CZERONEZ { rs1, rs2, rd },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
+                } else if opcode == OPCODE::OPRR || opcode == OPCODE::OP32 {
+                    Instruction::parse_op_bitmanip(funct7, rs2, rs1, funct3, rd, opcode, xlen)?
+                } else if opcode == OPCODE::OPFP {
+                    Instruction::parse_op_fp(funct7, rs2, rs1, funct3, rd, xlen)?
+                } else if opcode == OPCODE::OPV {
+                    Instruction::parse_op_vector(funct7, rs2, rs1, funct3, rd)?
+                } else if opcode == OPCODE::AMO && funct3 == Instruction::AMO_W_FUNCT3 {
+                    // aq/rl (low bits of funct7) need no extra work on a single in-order hart
+                    let funct5: usize = funct7 >> 2;
+                    match funct5 {
+                        Instruction::LR_FUNCT5 if rs2 == 0 => Instruction::LRW { rs1, rd },
+                        Instruction::SC_FUNCT5 => Instruction::SCW { rs1, rs2, rd },
+                        Instruction::AMOSWAP_FUNCT5 => Instruction::AMOSWAPW { rs1, rs2, rd },
+                        Instruction::AMOADD_FUNCT5 => Instruction::AMOADDW { rs1, rs2, rd },
+                        Instruction::AMOXOR_FUNCT5 => Instruction::AMOXORW { rs1, rs2, rd },
+                        Instruction::AMOAND_FUNCT5 => Instruction::AMOANDW { rs1, rs2, rd },
+                        Instruction::AMOOR_FUNCT5 => Instruction::AMOORW { rs1, rs2, rd },
+                        Instruction::AMOMIN_FUNCT5 => Instruction::AMOMINW { rs1, rs2, rd },
+                        Instruction::AMOMAX_FUNCT5 => Instruction::AMOMAXW { rs1, rs2, rd },
+                        Instruction::AMOMINU_FUNCT5 => Instruction::AMOMINUW { rs1, rs2, rd },
+                        Instruction::AMOMAXU_FUNCT5 => Instruction::AMOMAXUW { rs1, rs2, rd },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
+                } else if rv64 && opcode == OPCODE::AMO && funct3 == Instruction::AMO_D_FUNCT3 {
+                    let funct5: usize = funct7 >> 2;
+                    match funct5 {
+                        Instruction::LR_FUNCT5 if rs2 == 0 => Instruction::LRD { rs1, rd },
+                        Instruction::SC_FUNCT5 => Instruction::SCD { rs1, rs2, rd },
+                        Instruction::AMOSWAP_FUNCT5 => Instruction::AMOSWAPD { rs1, rs2, rd },
+                        Instruction::AMOADD_FUNCT5 => Instruction::AMOADDD { rs1, rs2, rd },
+                        Instruction::AMOXOR_FUNCT5 => Instruction::AMOXORD { rs1, rs2, rd },
+                        Instruction::AMOAND_FUNCT5 => Instruction::AMOANDD { rs1, rs2, rd },
+                        Instruction::AMOOR_FUNCT5 => Instruction::AMOORD { rs1, rs2, rd },
+                        Instruction::AMOMIN_FUNCT5 => Instruction::AMOMIND { rs1, rs2, rd },
+                        Instruction::AMOMAX_FUNCT5 => Instruction::AMOMAXD { rs1, rs2, rd },
+                        Instruction::AMOMINU_FUNCT5 => Instruction::AMOMINUD { rs1, rs2, rd },
+                        Instruction::AMOMAXU_FUNCT5 => Instruction::AMOMAXUD { rs1, rs2, rd },
+                        _ => return Err(Exception::IllegalInstruction),
+                    }
                 } else {
-                    todo!()
+                    return Err(Exception::IllegalInstruction);
                 }
             }
             EncodingVariant::JType {
@@ -557,7 +3415,7 @@ impl Instruction {
                     );
                     Instruction::JAL { offset, rd }
                 } else {
-                    panic!("unrecognized JType instruction")
+                    return Err(Exception::IllegalInstruction);
                 }
             }
             EncodingVariant::BType {
@@ -587,7 +3445,7 @@ impl Instruction {
                 } else if opcode == OPCODE::BRANCH && funct3 == Instruction::BGEU_FUNCT3 {
                     Instruction::BGEU { offset, rs1, rs2 }
                 } else {
-                    panic!("unrecognized BType instruction")
+                    return Err(Exception::IllegalInstruction);
                 }
             }
             EncodingVariant::SType {
@@ -606,317 +3464,5763 @@ impl Instruction {
                     Instruction::SH { offset, rs1, rs2 }
                 } else if opcode == OPCODE::STORE && funct3 == Instruction::SB_FUNCT3 {
                     Instruction::SB { offset, rs1, rs2 }
+                } else if rv64 && opcode == OPCODE::STORE && funct3 == Instruction::SD_FUNCT3 {
+                    Instruction::SD { offset, rs1, rs2 }
+                } else if opcode == OPCODE::STOREFP && funct3 == Instruction::FSW_FUNCT3 {
+                    Instruction::FSW { offset, rs1, rs2 }
+                } else if opcode == OPCODE::STOREFP && funct3 == Instruction::FSD_FUNCT3 {
+                    Instruction::FSD { offset, rs1, rs2 }
+                } else if opcode == OPCODE::STOREFP && funct3 == Instruction::FSH_FUNCT3 {
+                    Instruction::FSH { offset, rs1, rs2 }
+                } else if opcode == OPCODE::STOREFP && Instruction::vector_eew(funct3).is_some() {
+                    Instruction::parse_vector_store(imm_11_5, rs2, rs1, funct3, imm_4_0)?
                 } else {
-                    panic!("unrecognized SType instruction")
+                    return Err(Exception::IllegalInstruction);
                 }
-            } // _ => todo!("only  instructions are implemented so far"),
-        }
-    }
-}
-
-pub struct RISCV {
-    pub reg: [Word; XLEN],     // 32 registers which are 32 bits wide
-    pub pc: Word,              // Program counter (holds current instruction address)
-    current_instruction: Word, // holds the current instruction being executed
-}
-
-impl RISCV {
-    pub fn reset() -> Self {
-        RISCV {
-            reg: [0; XLEN], // Resets registers to 0x00000
-            pc: 0,          // Start executing code from 0x00000
-            current_instruction: 0,
-        }
+            }
+            EncodingVariant::R4Type {
+                rs3,
+                funct2,
+                rs2,
+                rs1,
+                funct3,
+                rd,
+                opcode,
+            } => {
+                let rm: u32 = funct3 as u32;
+                match (opcode, funct2) {
+                    (OPCODE::MADD, Instruction::FMT_S) => Instruction::FMADDS {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::MADD, Instruction::FMT_D) => Instruction::FMADDD {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::MADD, Instruction::FMT_H) => Instruction::FMADDH {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::MSUB, Instruction::FMT_S) => Instruction::FMSUBS {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::MSUB, Instruction::FMT_D) => Instruction::FMSUBD {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::MSUB, Instruction::FMT_H) => Instruction::FMSUBH {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMSUB, Instruction::FMT_S) => Instruction::FNMSUBS {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMSUB, Instruction::FMT_D) => Instruction::FNMSUBD {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMSUB, Instruction::FMT_H) => Instruction::FNMSUBH {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMADD, Instruction::FMT_S) => Instruction::FNMADDS {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMADD, Instruction::FMT_D) => Instruction::FNMADDD {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    (OPCODE::NMADD, Instruction::FMT_H) => Instruction::FNMADDH {
+                        rs1,
+                        rs2,
+                        rs3,
+                        rd,
+                        rm,
+                    },
+                    _ => return Err(Exception::IllegalInstruction),
+                }
+            }
+        };
+        Ok(instruction)
     }
 
-    pub fn new_() -> Self {
-        RISCV {
-            reg: [0; XLEN], // Resets registers to 0x00000
-            pc: 0x1000,     // Start executing code from 0x1000
-            current_instruction: 0,
+    /// The Zihintntl hint encoded as `add x0, x0, rs2` (or `c.add x0, rs2`), if any
+    pub fn ntl_hint(rs2: usize) -> Option<Instruction> {
+        match rs2 {
+            Instruction::NTL_P1_RS2 => Some(Instruction::NTLP1),
+            Instruction::NTL_PALL_RS2 => Some(Instruction::NTLPALL),
+            Instruction::NTL_S1_RS2 => Some(Instruction::NTLS1),
+            Instruction::NTL_ALL_RS2 => Some(Instruction::NTLALL),
+            _ => None,
         }
     }
 
-    pub fn clock_cycle(&mut self, mem: &mut Memory) {
-        self.fetch_instruction(mem);
-        self.execute(mem);
-        self.increment_pc();
-    }
-
-    /// Fetch the full instruction word that pc is pointing to (incrementing it)
-    pub fn fetch_instruction(&mut self, mem: &Memory) -> Word {
-        self.current_instruction = mem.fetch_word(self.pc as usize);
-
-        self.current_instruction
-    }
-
-    pub fn increment_pc(&mut self) {
-        self.pc = self.pc.wrapping_add(4); // increment pc by 4 (size of instruction word)
-    }
-
-    pub fn execute(&mut self, mem: &mut Memory) {
-        let encoding: EncodingVariant = EncodingVariant::get_encoding(self.current_instruction);
-        let parsed_instruction: Instruction = Instruction::parse_instruction(encoding);
-        match parsed_instruction {
-            Instruction::ADDI { imm, rs1, rd } => {
-                if rd != 0 {
-                    self.reg[rd] = self.reg[rs1].wrapping_add(imm as u32);
+    /// Decodes the bit-manipulation and SHA-256 immediate instructions, which reuse the shift
+    /// encodings of OPIMM and OPIMM32 with other values in imm[11:5]
+    fn parse_op_imm_bitmanip(
+        imm: usize,
+        rs1: usize,
+        funct3: usize,
+        rd: usize,
+        opcode: OPCODE,
+        xlen: u32,
+    ) -> Result<Instruction, Exception> {
+        let rv64: bool = xlen == 64;
+        let shamt_mask: usize = xlen as usize - 1;
+        let shamt: u32 = (imm & shamt_mask) as u32;
+        let funct7: usize = (imm & !shamt_mask) >> 5; // shamt[5] is part of funct7 on RV32
+        let rev8_imm: usize = if rv64 {
+            Instruction::REV8_RV64_IMM
+        } else {
+            Instruction::REV8_RV32_IMM
+        };
+        let instruction: Instruction = match (opcode, funct3) {
+            (OPCODE::OPIMM, Instruction::SLLI_FUNCT3) if imm >> 5 == Instruction::ROTATE_FUNCT7 => {
+                match imm & 0b11111 {
+                    Instruction::CLZ_RS2 => Instruction::CLZ { rs1, rd },
+                    Instruction::CTZ_RS2 => Instruction::CTZ { rs1, rd },
+                    Instruction::CPOP_RS2 => Instruction::CPOP { rs1, rd },
+                    Instruction::SEXTB_RS2 => Instruction::SEXTB { rs1, rd },
+                    Instruction::SEXTH_RS2 => Instruction::SEXTH { rs1, rd },
+                    _ => return Err(Exception::IllegalInstruction),
                 }
             }
-            Instruction::SLTI { imm, rs1, rd } => {
-                if rd != 0 {
-                    let rs1_value: i32 = self.reg[rs1] as i32;
-                    self.reg[rd] = if rs1_value < imm { 1 } else { 0 };
+            (OPCODE::OPIMM, Instruction::SLLI_FUNCT3) if imm >> 5 == Instruction::SHA256_FUNCT7 => {
+                match imm & 0b11111 {
+                    Instruction::SHA256SUM0_RS2 => Instruction::SHA256SUM0 { rs1, rd },
+                    Instruction::SHA256SUM1_RS2 => Instruction::SHA256SUM1 { rs1, rd },
+                    Instruction::SHA256SIG0_RS2 => Instruction::SHA256SIG0 { rs1, rd },
+                    Instruction::SHA256SIG1_RS2 => Instruction::SHA256SIG1 { rs1, rd },
+                    _ => return Err(Exception::IllegalInstruction),
                 }
             }
-            Instruction::STLIU { imm, rs1, rd } => {
-                if rd != 0 {
-                    let rs1_value: u32 = self.reg[rs1];
-                    self.reg[rd] = if rs1_value < imm { 1 } else { 0 }
-                }
+            (OPCODE::OPIMM, Instruction::SLLI_FUNCT3) if !rv64 && imm == Instruction::ZIP_IMM => {
+                Instruction::ZIP { rs1, rd }
             }
-            Instruction::ANDI { imm, rs1, rd } => {
-                if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] & imm;
-                }
+            (OPCODE::OPIMM, Instruction::SLLI_FUNCT3) => match funct7 {
+                Instruction::BSET_FUNCT7 => Instruction::BSETI { shamt, rs1, rd },
+                Instruction::BCLR_FUNCT7 => Instruction::BCLRI { shamt, rs1, rd },
+                Instruction::BINV_FUNCT7 => Instruction::BINVI { shamt, rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (OPCODE::OPIMM, Instruction::SRLI_FUNCT3) if imm == rev8_imm => {
+                Instruction::REV8 { rs1, rd }
             }
-            Instruction::ORI { imm, rs1, rd } => {
-                if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] | imm;
+            (OPCODE::OPIMM, Instruction::SRLI_FUNCT3) if imm == Instruction::ORCB_IMM => {
+                Instruction::ORCB { rs1, rd }
+            }
+            (OPCODE::OPIMM, Instruction::SRLI_FUNCT3) if imm == Instruction::BREV8_IMM => {
+                Instruction::BREV8 { rs1, rd }
+            }
+            (OPCODE::OPIMM, Instruction::SRLI_FUNCT3) if !rv64 && imm == Instruction::ZIP_IMM => {
+                Instruction::UNZIP { rs1, rd }
+            }
+            (OPCODE::OPIMM, Instruction::SRLI_FUNCT3) => match funct7 {
+                Instruction::ROTATE_FUNCT7 => Instruction::RORI { shamt, rs1, rd },
+                Instruction::BCLR_FUNCT7 => Instruction::BEXTI { shamt, rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (OPCODE::OPIMM32, Instruction::SLLIW_FUNCT3)
+                if rv64 && imm >> 5 == Instruction::ROTATE_FUNCT7 =>
+            {
+                match imm & 0b11111 {
+                    Instruction::CLZ_RS2 => Instruction::CLZW { rs1, rd },
+                    Instruction::CTZ_RS2 => Instruction::CTZW { rs1, rd },
+                    Instruction::CPOP_RS2 => Instruction::CPOPW { rs1, rd },
+                    _ => return Err(Exception::IllegalInstruction),
+                }
+            }
+            (OPCODE::OPIMM32, Instruction::SLLIW_FUNCT3)
+                if rv64 && imm >> 6 == Instruction::SLLIUW_FUNCT6 =>
+            {
+                Instruction::SLLIUW { shamt, rs1, rd } // 6-bit shift amount
+            }
+            (OPCODE::OPIMM32, Instruction::SRLIW_FUNCT3)
+                if rv64 && imm >> 5 == Instruction::ROTATE_FUNCT7 =>
+            {
+                let shamt: u32 = (imm & 0b11111) as u32; // word rotates keep 5-bit amounts
+                Instruction::RORIW { shamt, rs1, rd }
+            }
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+
+    /// Decodes the bit-manipulation and scalar cryptography register-register instructions
+    /// in OPRR and OP32
+    fn parse_op_bitmanip(
+        funct7: usize,
+        rs2: usize,
+        rs1: usize,
+        funct3: usize,
+        rd: usize,
+        opcode: OPCODE,
+        xlen: u32,
+    ) -> Result<Instruction, Exception> {
+        let rv64: bool = xlen == 64;
+        let instruction: Instruction = match (opcode, funct7, funct3) {
+            (OPCODE::OPRR, Instruction::SHADD_FUNCT7, Instruction::SH1ADD_FUNCT3) => {
+                Instruction::SH1ADD { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::SHADD_FUNCT7, Instruction::SH2ADD_FUNCT3) => {
+                Instruction::SH2ADD { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::SHADD_FUNCT7, Instruction::SH3ADD_FUNCT3) => {
+                Instruction::SH3ADD { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::INVERTED_FUNCT7, Instruction::AND_FUNCT3) => {
+                Instruction::ANDN { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::INVERTED_FUNCT7, Instruction::OR_FUNCT3) => {
+                Instruction::ORN { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::INVERTED_FUNCT7, Instruction::XOR_FUNCT3) => {
+                Instruction::XNOR { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::MINMAX_CLMUL_FUNCT7, _) => match funct3 {
+                Instruction::CLMUL_FUNCT3 => Instruction::CLMUL { rs1, rs2, rd },
+                Instruction::CLMULR_FUNCT3 => Instruction::CLMULR { rs1, rs2, rd },
+                Instruction::CLMULH_FUNCT3 => Instruction::CLMULH { rs1, rs2, rd },
+                Instruction::MIN_FUNCT3 => Instruction::MIN { rs1, rs2, rd },
+                Instruction::MINU_FUNCT3 => Instruction::MINU { rs1, rs2, rd },
+                Instruction::MAX_FUNCT3 => Instruction::MAX { rs1, rs2, rd },
+                Instruction::MAXU_FUNCT3 => Instruction::MAXU { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (OPCODE::OPRR, Instruction::ROTATE_FUNCT7, Instruction::ROL_FUNCT3) => {
+                Instruction::ROL { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::ROTATE_FUNCT7, Instruction::ROR_FUNCT3) => {
+                Instruction::ROR { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::BSET_FUNCT7, Instruction::BIT_FUNCT3) => {
+                Instruction::BSET { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::BCLR_FUNCT7, Instruction::BIT_FUNCT3) => {
+                Instruction::BCLR { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::BINV_FUNCT7, Instruction::BIT_FUNCT3) => {
+                Instruction::BINV { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::BCLR_FUNCT7, Instruction::BEXT_FUNCT3) => {
+                Instruction::BEXT { rs1, rs2, rd }
+            }
+            // ZEXT.H lives in OPRR on RV32 and in OP32 on RV64
+            (OPCODE::OPRR, Instruction::ADDUW_FUNCT7, Instruction::PACK_FUNCT3)
+                if !rv64 && rs2 == 0 =>
+            {
+                Instruction::ZEXTH { rs1, rd }
+            }
+            (OPCODE::OP32, Instruction::ADDUW_FUNCT7, Instruction::PACK_FUNCT3)
+                if rv64 && rs2 == 0 =>
+            {
+                Instruction::ZEXTH { rs1, rd }
+            }
+            (OPCODE::OPRR, Instruction::ADDUW_FUNCT7, Instruction::PACK_FUNCT3) => {
+                Instruction::PACK { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::ADDUW_FUNCT7, Instruction::PACKH_FUNCT3) => {
+                Instruction::PACKH { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::ADDUW_FUNCT7, Instruction::PACK_FUNCT3) if rv64 => {
+                Instruction::PACKW { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::XPERM_FUNCT7, Instruction::XPERM4_FUNCT3) => {
+                Instruction::XPERM4 { rs1, rs2, rd }
+            }
+            (OPCODE::OPRR, Instruction::XPERM_FUNCT7, Instruction::XPERM8_FUNCT3) => {
+                Instruction::XPERM8 { rs1, rs2, rd }
+            }
+            // the AES and SHA-512 instructions below are the RV32 forms
+            (OPCODE::OPRR, _, Instruction::CRYPTO_FUNCT3) if !rv64 => {
+                let bs: u32 = (funct7 >> 5) as u32;
+                match funct7 {
+                    Instruction::SHA512SUM0R_FUNCT7 => Instruction::SHA512SUM0R { rs1, rs2, rd },
+                    Instruction::SHA512SUM1R_FUNCT7 => Instruction::SHA512SUM1R { rs1, rs2, rd },
+                    Instruction::SHA512SIG0L_FUNCT7 => Instruction::SHA512SIG0L { rs1, rs2, rd },
+                    Instruction::SHA512SIG1L_FUNCT7 => Instruction::SHA512SIG1L { rs1, rs2, rd },
+                    Instruction::SHA512SIG0H_FUNCT7 => Instruction::SHA512SIG0H { rs1, rs2, rd },
+                    Instruction::SHA512SIG1H_FUNCT7 => Instruction::SHA512SIG1H { rs1, rs2, rd },
+                    _ => match funct7 & 0b11111 {
+                        Instruction::AES32ESI_FUNCT5 => Instruction::AES32ESI { bs, rs1, rs2, rd },
+                        Instruction::AES32ESMI_FUNCT5 => {
+                            Instruction::AES32ESMI { bs, rs1, rs2, rd }
+                        }
+                        Instruction::AES32DSI_FUNCT5 => Instruction::AES32DSI { bs, rs1, rs2, rd },
+                        Instruction::AES32DSMI_FUNCT5 => {
+                            Instruction::AES32DSMI { bs, rs1, rs2, rd }
+                        }
+                        _ => return Err(Exception::IllegalInstruction),
+                    },
+                }
+            }
+            (OPCODE::OP32, Instruction::ADDUW_FUNCT7, Instruction::ADDUW_FUNCT3) if rv64 => {
+                Instruction::ADDUW { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::SHADD_FUNCT7, Instruction::SH1ADD_FUNCT3) if rv64 => {
+                Instruction::SH1ADDUW { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::SHADD_FUNCT7, Instruction::SH2ADD_FUNCT3) if rv64 => {
+                Instruction::SH2ADDUW { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::SHADD_FUNCT7, Instruction::SH3ADD_FUNCT3) if rv64 => {
+                Instruction::SH3ADDUW { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::ROTATE_FUNCT7, Instruction::ROL_FUNCT3) if rv64 => {
+                Instruction::ROLW { rs1, rs2, rd }
+            }
+            (OPCODE::OP32, Instruction::ROTATE_FUNCT7, Instruction::ROR_FUNCT3) if rv64 => {
+                Instruction::RORW { rs1, rs2, rd }
+            }
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+
+    /// Decodes the OP-FP major opcode, where funct7 holds the operation and the format
+    fn parse_op_fp(
+        funct7: usize,
+        rs2: usize,
+        rs1: usize,
+        funct3: usize,
+        rd: usize,
+        xlen: u32,
+    ) -> Result<Instruction, Exception> {
+        let rv64: bool = xlen == 64; // the L / LU conversions and FMV.X.D / FMV.D.X
+        let funct5: usize = funct7 >> 2;
+        let fmt: usize = funct7 & 0b11;
+        let rm: u32 = funct3 as u32; // rounding mode of the operations that round
+        let instruction: Instruction = match (funct5, fmt) {
+            (Instruction::FADD_FUNCT5, Instruction::FMT_S) => {
+                Instruction::FADDS { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSUB_FUNCT5, Instruction::FMT_S) => {
+                Instruction::FSUBS { rs1, rs2, rd, rm }
+            }
+            (Instruction::FMUL_FUNCT5, Instruction::FMT_S) => {
+                Instruction::FMULS { rs1, rs2, rd, rm }
+            }
+            (Instruction::FDIV_FUNCT5, Instruction::FMT_S) => {
+                Instruction::FDIVS { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSQRT_FUNCT5, Instruction::FMT_S) if rs2 == 0 => {
+                Instruction::FSQRTS { rs1, rd, rm }
+            }
+            (Instruction::FSGNJ_FUNCT5, Instruction::FMT_S) => match funct3 {
+                0b000 => Instruction::FSGNJS { rs1, rs2, rd },
+                0b001 => Instruction::FSGNJNS { rs1, rs2, rd },
+                0b010 => Instruction::FSGNJXS { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMINMAX_FUNCT5, Instruction::FMT_S) => match funct3 {
+                0b000 => Instruction::FMINS { rs1, rs2, rd },
+                0b001 => Instruction::FMAXS { rs1, rs2, rd },
+                0b010 => Instruction::FMINMS { rs1, rs2, rd },
+                0b011 => Instruction::FMAXMS { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCVT_INT_FUNCT5, Instruction::FMT_S) => match rs2 {
+                0b00000 => Instruction::FCVTWS { rs1, rd, rm },
+                0b00001 => Instruction::FCVTWUS { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTLS { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTLUS { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCVT_FROM_INT_FUNCT5, Instruction::FMT_S) => match rs2 {
+                0b00000 => Instruction::FCVTSW { rs1, rd, rm },
+                0b00001 => Instruction::FCVTSWU { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTSL { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTSLU { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCMP_FUNCT5, Instruction::FMT_S) => match funct3 {
+                0b000 => Instruction::FLES { rs1, rs2, rd },
+                0b001 => Instruction::FLTS { rs1, rs2, rd },
+                0b010 => Instruction::FEQS { rs1, rs2, rd },
+                0b100 => Instruction::FLEQS { rs1, rs2, rd },
+                0b101 => Instruction::FLTQS { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_X_FUNCT5, Instruction::FMT_S) if rs2 == 0 => match funct3 {
+                0b000 => Instruction::FMVXW { rs1, rd },
+                0b001 => Instruction::FCLASSS { rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_FROM_X_FUNCT5, Instruction::FMT_S) if rs2 == 0 && funct3 == 0 => {
+                Instruction::FMVWX { rs1, rd }
+            }
+            (Instruction::FMV_FROM_X_FUNCT5, Instruction::FMT_S)
+                if rs2 == Instruction::FLI_RS2 && funct3 == 0 =>
+            {
+                Instruction::FLIS {
+                    index: rs1 as u32,
+                    rd,
+                }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_S) if rs2 == Instruction::FROUND_RS2 => {
+                Instruction::FROUNDS { rs1, rd, rm }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_S)
+                if rs2 == Instruction::FROUNDNX_RS2 =>
+            {
+                Instruction::FROUNDNXS { rs1, rd, rm }
+            }
+            (Instruction::FADD_FUNCT5, Instruction::FMT_D) => {
+                Instruction::FADDD { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSUB_FUNCT5, Instruction::FMT_D) => {
+                Instruction::FSUBD { rs1, rs2, rd, rm }
+            }
+            (Instruction::FMUL_FUNCT5, Instruction::FMT_D) => {
+                Instruction::FMULD { rs1, rs2, rd, rm }
+            }
+            (Instruction::FDIV_FUNCT5, Instruction::FMT_D) => {
+                Instruction::FDIVD { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSQRT_FUNCT5, Instruction::FMT_D) if rs2 == 0 => {
+                Instruction::FSQRTD { rs1, rd, rm }
+            }
+            (Instruction::FSGNJ_FUNCT5, Instruction::FMT_D) => match funct3 {
+                0b000 => Instruction::FSGNJD { rs1, rs2, rd },
+                0b001 => Instruction::FSGNJND { rs1, rs2, rd },
+                0b010 => Instruction::FSGNJXD { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMINMAX_FUNCT5, Instruction::FMT_D) => match funct3 {
+                0b000 => Instruction::FMIND { rs1, rs2, rd },
+                0b001 => Instruction::FMAXD { rs1, rs2, rd },
+                0b010 => Instruction::FMINMD { rs1, rs2, rd },
+                0b011 => Instruction::FMAXMD { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            // FCVT.S.D and FCVT.D.S: fmt is the destination and rs2 the source format
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_S) if rs2 == Instruction::FMT_D => {
+                Instruction::FCVTSD { rs1, rd, rm }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_D) if rs2 == Instruction::FMT_S => {
+                Instruction::FCVTDS { rs1, rd, rm }
+            }
+            (Instruction::FCVT_INT_FUNCT5, Instruction::FMT_D) => match rs2 {
+                0b00000 => Instruction::FCVTWD { rs1, rd, rm },
+                0b00001 => Instruction::FCVTWUD { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTLD { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTLUD { rs1, rd, rm },
+                // Zfa: always rounds towards zero, which rm must also say
+                Instruction::FCVTMOD_RS2 if rm == 0b001 => Instruction::FCVTMODWD { rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCVT_FROM_INT_FUNCT5, Instruction::FMT_D) => match rs2 {
+                0b00000 => Instruction::FCVTDW { rs1, rd, rm },
+                0b00001 => Instruction::FCVTDWU { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTDL { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTDLU { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCMP_FUNCT5, Instruction::FMT_D) => match funct3 {
+                0b000 => Instruction::FLED { rs1, rs2, rd },
+                0b001 => Instruction::FLTD { rs1, rs2, rd },
+                0b010 => Instruction::FEQD { rs1, rs2, rd },
+                0b100 => Instruction::FLEQD { rs1, rs2, rd },
+                0b101 => Instruction::FLTQD { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_X_FUNCT5, Instruction::FMT_D) if rs2 == 0 => match funct3 {
+                0b000 if rv64 => Instruction::FMVXD { rs1, rd },
+                0b001 => Instruction::FCLASSD { rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_FROM_X_FUNCT5, Instruction::FMT_D)
+                if rv64 && rs2 == 0 && funct3 == 0 =>
+            {
+                Instruction::FMVDX { rs1, rd }
+            }
+            (Instruction::FMV_FROM_X_FUNCT5, Instruction::FMT_D)
+                if rs2 == Instruction::FLI_RS2 && funct3 == 0 =>
+            {
+                Instruction::FLID {
+                    index: rs1 as u32,
+                    rd,
+                }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_D) if rs2 == Instruction::FROUND_RS2 => {
+                Instruction::FROUNDD { rs1, rd, rm }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_D)
+                if rs2 == Instruction::FROUNDNX_RS2 =>
+            {
+                Instruction::FROUNDNXD { rs1, rd, rm }
+            }
+            (Instruction::FADD_FUNCT5, Instruction::FMT_H) => {
+                Instruction::FADDH { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSUB_FUNCT5, Instruction::FMT_H) => {
+                Instruction::FSUBH { rs1, rs2, rd, rm }
+            }
+            (Instruction::FMUL_FUNCT5, Instruction::FMT_H) => {
+                Instruction::FMULH { rs1, rs2, rd, rm }
+            }
+            (Instruction::FDIV_FUNCT5, Instruction::FMT_H) => {
+                Instruction::FDIVH { rs1, rs2, rd, rm }
+            }
+            (Instruction::FSQRT_FUNCT5, Instruction::FMT_H) if rs2 == 0 => {
+                Instruction::FSQRTH { rs1, rd, rm }
+            }
+            (Instruction::FSGNJ_FUNCT5, Instruction::FMT_H) => match funct3 {
+                0b000 => Instruction::FSGNJH { rs1, rs2, rd },
+                0b001 => Instruction::FSGNJNH { rs1, rs2, rd },
+                0b010 => Instruction::FSGNJXH { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMINMAX_FUNCT5, Instruction::FMT_H) => match funct3 {
+                0b000 => Instruction::FMINH { rs1, rs2, rd },
+                0b001 => Instruction::FMAXH { rs1, rs2, rd },
+                0b010 => Instruction::FMINMH { rs1, rs2, rd },
+                0b011 => Instruction::FMAXMH { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            // conversions between half and the wider formats
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_S) if rs2 == Instruction::FMT_H => {
+                Instruction::FCVTSH { rs1, rd, rm }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_D) if rs2 == Instruction::FMT_H => {
+                Instruction::FCVTDH { rs1, rd, rm }
+            }
+            (Instruction::FCVT_FP_FUNCT5, Instruction::FMT_H) => match rs2 {
+                Instruction::FMT_S => Instruction::FCVTHS { rs1, rd, rm },
+                Instruction::FMT_D => Instruction::FCVTHD { rs1, rd, rm },
+                Instruction::FROUND_RS2 => Instruction::FROUNDH { rs1, rd, rm },
+                Instruction::FROUNDNX_RS2 => Instruction::FROUNDNXH { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCVT_INT_FUNCT5, Instruction::FMT_H) => match rs2 {
+                0b00000 => Instruction::FCVTWH { rs1, rd, rm },
+                0b00001 => Instruction::FCVTWUH { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTLH { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTLUH { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCVT_FROM_INT_FUNCT5, Instruction::FMT_H) => match rs2 {
+                0b00000 => Instruction::FCVTHW { rs1, rd, rm },
+                0b00001 => Instruction::FCVTHWU { rs1, rd, rm },
+                0b00010 if rv64 => Instruction::FCVTHL { rs1, rd, rm },
+                0b00011 if rv64 => Instruction::FCVTHLU { rs1, rd, rm },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FCMP_FUNCT5, Instruction::FMT_H) => match funct3 {
+                0b000 => Instruction::FLEH { rs1, rs2, rd },
+                0b001 => Instruction::FLTH { rs1, rs2, rd },
+                0b010 => Instruction::FEQH { rs1, rs2, rd },
+                0b100 => Instruction::FLEQH { rs1, rs2, rd },
+                0b101 => Instruction::FLTQH { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_X_FUNCT5, Instruction::FMT_H) if rs2 == 0 => match funct3 {
+                0b000 => Instruction::FMVXH { rs1, rd },
+                0b001 => Instruction::FCLASSH { rs1, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            (Instruction::FMV_FROM_X_FUNCT5, Instruction::FMT_H) if funct3 == 0 => match rs2 {
+                0 => Instruction::FMVHX { rs1, rd },
+                Instruction::FLI_RS2 => Instruction::FLIH {
+                    index: rs1 as u32,
+                    rd,
+                },
+                _ => return Err(Exception::IllegalInstruction),
+            },
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+
+    /// Element width in bits selected by the width field of a vector load or store
+    fn vector_eew(funct3: usize) -> Option<u32> {
+        match funct3 {
+            Instruction::VECTOR_WIDTH_8_FUNCT3 => Some(8),
+            Instruction::VECTOR_WIDTH_16_FUNCT3 => Some(16),
+            Instruction::VECTOR_WIDTH_32_FUNCT3 => Some(32),
+            Instruction::VECTOR_WIDTH_64_FUNCT3 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// Splits imm[11:0] of a vector load or store into nf (as a field count), mew, mop, vm and
+    /// the lumop / sumop / rs2 / vs2 field
+    fn vector_access_fields(imm: usize) -> (u32, usize, usize, bool, usize) {
+        let nf: u32 = (imm >> 9) as u32 + 1;
+        let mew: usize = (imm >> 8) & 0b1;
+        let mop: usize = (imm >> 6) & 0b11;
+        let vm: bool = (imm >> 5) & 0b1 == 1;
+        (nf, mew, mop, vm, imm & 0b11111)
+    }
+
+    /// Decodes the vector loads, which use the LOAD-FP widths that no scalar format takes
+    fn parse_vector_load(
+        imm: usize,
+        rs1: usize,
+        funct3: usize,
+        vd: usize,
+    ) -> Result<Instruction, Exception> {
+        let eew: u32 = Instruction::vector_eew(funct3).ok_or(Exception::IllegalInstruction)?;
+        let (nf, mew, mop, vm, rs2) = Instruction::vector_access_fields(imm);
+        if mew != 0 {
+            return Err(Exception::IllegalInstruction); // reserved for elements wider than 64 bits
+        }
+        let instruction: Instruction = match (mop, rs2) {
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT) => Instruction::VLE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vd,
+            },
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT_FAULT_FIRST) => {
+                Instruction::VLEFF {
+                    eew,
+                    nf,
+                    vm,
+                    rs1,
+                    vd,
+                }
+            }
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT_WHOLE)
+                if vm && nf.is_power_of_two() =>
+            {
+                Instruction::VLRE { eew, nf, rs1, vd }
+            }
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT_MASK)
+                if vm && nf == 1 && eew == 8 =>
+            {
+                Instruction::VLM { rs1, vd }
+            }
+            (Instruction::VECTOR_MOP_STRIDED, _) => Instruction::VLSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                rs2,
+                vd,
+            },
+            (Instruction::VECTOR_MOP_INDEXED_UNORDERED, vs2) => Instruction::VLUXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vd,
+            },
+            (Instruction::VECTOR_MOP_INDEXED_ORDERED, vs2) => Instruction::VLOXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vd,
+            },
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+
+    /// Decodes the vector stores, which use the STORE-FP widths that no scalar format takes.
+    /// imm[11:5] holds nf | mew | mop | vm and the imm[4:0] field names the source vs3.
+    fn parse_vector_store(
+        imm_11_5: usize,
+        rs2: usize,
+        rs1: usize,
+        funct3: usize,
+        vs3: usize,
+    ) -> Result<Instruction, Exception> {
+        let eew: u32 = Instruction::vector_eew(funct3).ok_or(Exception::IllegalInstruction)?;
+        let (nf, mew, mop, vm, rs2) = Instruction::vector_access_fields((imm_11_5 << 5) | rs2);
+        if mew != 0 {
+            return Err(Exception::IllegalInstruction);
+        }
+        let instruction: Instruction = match (mop, rs2) {
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT) => Instruction::VSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs3,
+            },
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT_WHOLE)
+                if vm && nf.is_power_of_two() && eew == 8 =>
+            {
+                Instruction::VSR { nf, rs1, vs3 }
+            }
+            (Instruction::VECTOR_MOP_UNIT, Instruction::VECTOR_UNIT_MASK)
+                if vm && nf == 1 && eew == 8 =>
+            {
+                Instruction::VSM { rs1, vs3 }
+            }
+            (Instruction::VECTOR_MOP_STRIDED, _) => Instruction::VSSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                rs2,
+                vs3,
+            },
+            (Instruction::VECTOR_MOP_INDEXED_UNORDERED, vs2) => Instruction::VSUXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vs3,
+            },
+            (Instruction::VECTOR_MOP_INDEXED_ORDERED, vs2) => Instruction::VSOXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vs3,
+            },
+            _ => return Err(Exception::IllegalInstruction),
+        };
+        Ok(instruction)
+    }
+
+    /// Decodes the OP-V major opcode, where funct3 selects the operand category and funct7
+    /// holds funct6 | vm. Only the integer categories are supported.
+    fn parse_op_vector(
+        funct7: usize,
+        rs2: usize,
+        rs1: usize,
+        funct3: usize,
+        rd: usize,
+    ) -> Result<Instruction, Exception> {
+        if funct3 == Instruction::OPCFG_FUNCT3 {
+            // bit 31 clear: vsetvli; bits 31:30 set: vsetivli; otherwise vsetvl
+            let instruction: Instruction = match funct7 >> 5 {
+                0b00 | 0b01 => Instruction::VSETVLI {
+                    vtypei: (((funct7 & 0b0111111) << 5) | rs2) as u32,
+                    rs1,
+                    rd,
+                },
+                0b11 => Instruction::VSETIVLI {
+                    vtypei: (((funct7 & 0b0011111) << 5) | rs2) as u32,
+                    uimm: rs1 as u32,
+                    rd,
+                },
+                _ if funct7 & 0b0111111 == 0 => Instruction::VSETVL { rs1, rs2, rd },
+                _ => return Err(Exception::IllegalInstruction),
+            };
+            return Ok(instruction);
+        }
+
+        let funct6: usize = funct7 >> 1;
+        let vm: bool = funct7 & 0b1 == 1;
+        let (vd, vs2, vs1) = (rd, rs2, rs1);
+        let shift: bool = matches!(
+            funct6,
+            Instruction::VSLL_FUNCT6
+                | Instruction::VSRL_FUNCT6
+                | Instruction::VSRA_FUNCT6
+                | Instruction::VNSRL_FUNCT6
+                | Instruction::VNSRA_FUNCT6
+        );
+        let operand: VectorOperand = match funct3 {
+            Instruction::OPIVV_FUNCT3 | Instruction::OPMVV_FUNCT3 => VectorOperand::Vector(vs1),
+            Instruction::OPIVX_FUNCT3 | Instruction::OPMVX_FUNCT3 => VectorOperand::Scalar(rs1),
+            Instruction::OPIVI_FUNCT3 if shift => VectorOperand::Immediate(rs1 as i32), // uimm5
+            Instruction::OPIVI_FUNCT3 => VectorOperand::Immediate(sign_extend_u32(rs1, 5)),
+            _ => return Err(Exception::IllegalInstruction), // floating-point operands
+        };
+        let vector_vector: bool =
+            funct3 == Instruction::OPIVV_FUNCT3 || funct3 == Instruction::OPMVV_FUNCT3;
+        let immediate: bool = funct3 == Instruction::OPIVI_FUNCT3;
+
+        let instruction: Instruction = if matches!(
+            funct3,
+            Instruction::OPIVV_FUNCT3 | Instruction::OPIVI_FUNCT3 | Instruction::OPIVX_FUNCT3
+        ) {
+            match funct6 {
+                Instruction::VADD_FUNCT6 => Instruction::VADD {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VSUB_FUNCT6 if !immediate => Instruction::VSUB {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VRSUB_FUNCT6 if !vector_vector => Instruction::VRSUB {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMINU_FUNCT6 if !immediate => Instruction::VMINU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMIN_FUNCT6 if !immediate => Instruction::VMIN {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMAXU_FUNCT6 if !immediate => Instruction::VMAXU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMAX_FUNCT6 if !immediate => Instruction::VMAX {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VAND_FUNCT6 => Instruction::VAND {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VOR_FUNCT6 => Instruction::VOR {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VXOR_FUNCT6 => Instruction::VXOR {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VADC_FUNCT6 if !vm => Instruction::VADC { vd, vs2, operand },
+                Instruction::VMADC_FUNCT6 => Instruction::VMADC {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VSBC_FUNCT6 if !vm && !immediate => {
+                    Instruction::VSBC { vd, vs2, operand }
+                }
+                Instruction::VMSBC_FUNCT6 if !immediate => Instruction::VMSBC {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMERGE_FUNCT6 if !vm => Instruction::VMERGE { vd, vs2, operand },
+                Instruction::VMERGE_FUNCT6 if vs2 == 0 => Instruction::VMV { vd, operand },
+                Instruction::VMSEQ_FUNCT6 => Instruction::VMSEQ {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSNE_FUNCT6 => Instruction::VMSNE {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSLTU_FUNCT6 if !immediate => Instruction::VMSLTU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSLT_FUNCT6 if !immediate => Instruction::VMSLT {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSLEU_FUNCT6 => Instruction::VMSLEU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSLE_FUNCT6 => Instruction::VMSLE {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSGTU_FUNCT6 if !vector_vector => Instruction::VMSGTU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMSGT_FUNCT6 if !vector_vector => Instruction::VMSGT {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VSLL_FUNCT6 => Instruction::VSLL {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VSRL_FUNCT6 => Instruction::VSRL {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VSRA_FUNCT6 => Instruction::VSRA {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VNSRL_FUNCT6 => Instruction::VNSRL {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VNSRA_FUNCT6 => Instruction::VNSRA {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMVNRR_FUNCT6 if immediate && vm && matches!(rs1, 0 | 1 | 3 | 7) => {
+                    Instruction::VMVNRR {
+                        nr: rs1 as u32 + 1,
+                        vd,
+                        vs2,
+                    }
+                }
+                Instruction::VWREDSUMU_FUNCT6 if vector_vector => {
+                    Instruction::VWREDSUMU { vd, vs2, vs1, vm }
+                }
+                Instruction::VWREDSUM_FUNCT6 if vector_vector => {
+                    Instruction::VWREDSUM { vd, vs2, vs1, vm }
+                }
+                _ => return Err(Exception::IllegalInstruction),
+            }
+        } else {
+            match funct6 {
+                Instruction::VREDSUM_FUNCT6 if vector_vector => {
+                    Instruction::VREDSUM { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDAND_FUNCT6 if vector_vector => {
+                    Instruction::VREDAND { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDOR_FUNCT6 if vector_vector => {
+                    Instruction::VREDOR { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDXOR_FUNCT6 if vector_vector => {
+                    Instruction::VREDXOR { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDMINU_FUNCT6 if vector_vector => {
+                    Instruction::VREDMINU { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDMIN_FUNCT6 if vector_vector => {
+                    Instruction::VREDMIN { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDMAXU_FUNCT6 if vector_vector => {
+                    Instruction::VREDMAXU { vd, vs2, vs1, vm }
+                }
+                Instruction::VREDMAX_FUNCT6 if vector_vector => {
+                    Instruction::VREDMAX { vd, vs2, vs1, vm }
+                }
+                Instruction::VWXUNARY0_FUNCT6 if vector_vector => match vs1 {
+                    Instruction::VMV_X_S_VS1 if vm => Instruction::VMVXS { vs2, rd },
+                    Instruction::VCPOP_VS1 => Instruction::VCPOP { vs2, rd, vm },
+                    Instruction::VFIRST_VS1 => Instruction::VFIRST { vs2, rd, vm },
+                    _ => return Err(Exception::IllegalInstruction),
+                },
+                Instruction::VWXUNARY0_FUNCT6 if vm && vs2 == 0 => Instruction::VMVSX { rs1, vd },
+                Instruction::VXUNARY0_FUNCT6 if vector_vector => {
+                    // vs1 holds the extension factor and the signedness in its low bit
+                    let factor: u32 = match vs1 >> 1 {
+                        0b001 => 8,
+                        0b010 => 4,
+                        0b011 => 2,
+                        _ => return Err(Exception::IllegalInstruction),
+                    };
+                    if vs1 & 0b1 == 1 {
+                        Instruction::VSEXT {
+                            factor,
+                            vd,
+                            vs2,
+                            vm,
+                        }
+                    } else {
+                        Instruction::VZEXT {
+                            factor,
+                            vd,
+                            vs2,
+                            vm,
+                        }
+                    }
+                }
+                Instruction::VMUNARY0_FUNCT6 if vector_vector => match vs1 {
+                    Instruction::VMSBF_VS1 => Instruction::VMSBF { vd, vs2, vm },
+                    Instruction::VMSOF_VS1 => Instruction::VMSOF { vd, vs2, vm },
+                    Instruction::VMSIF_VS1 => Instruction::VMSIF { vd, vs2, vm },
+                    Instruction::VIOTA_VS1 => Instruction::VIOTA { vd, vs2, vm },
+                    Instruction::VID_VS1 if vs2 == 0 => Instruction::VID { vd, vm },
+                    _ => return Err(Exception::IllegalInstruction),
+                },
+                Instruction::VMANDN_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMANDN { vd, vs2, vs1 }
+                }
+                Instruction::VMAND_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMAND { vd, vs2, vs1 }
+                }
+                Instruction::VMOR_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMOR { vd, vs2, vs1 }
+                }
+                Instruction::VMXOR_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMXOR { vd, vs2, vs1 }
+                }
+                Instruction::VMORN_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMORN { vd, vs2, vs1 }
+                }
+                Instruction::VMNAND_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMNAND { vd, vs2, vs1 }
+                }
+                Instruction::VMNOR_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMNOR { vd, vs2, vs1 }
+                }
+                Instruction::VMXNOR_FUNCT6 if vector_vector && vm => {
+                    Instruction::VMXNOR { vd, vs2, vs1 }
+                }
+                Instruction::VDIVU_FUNCT6 => Instruction::VDIVU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VDIV_FUNCT6 => Instruction::VDIV {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VREMU_FUNCT6 => Instruction::VREMU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VREM_FUNCT6 => Instruction::VREM {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMULHU_FUNCT6 => Instruction::VMULHU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMUL_FUNCT6 => Instruction::VMUL {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMULHSU_FUNCT6 => Instruction::VMULHSU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMULH_FUNCT6 => Instruction::VMULH {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMADD_FUNCT6 => Instruction::VMADD {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VNMSUB_FUNCT6 => Instruction::VNMSUB {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VMACC_FUNCT6 => Instruction::VMACC {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VNMSAC_FUNCT6 => Instruction::VNMSAC {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWADDU_FUNCT6 => Instruction::VWADDU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWADD_FUNCT6 => Instruction::VWADD {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWSUBU_FUNCT6 => Instruction::VWSUBU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWSUB_FUNCT6 => Instruction::VWSUB {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWADDUW_FUNCT6 => Instruction::VWADDUW {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWADDW_FUNCT6 => Instruction::VWADDW {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWSUBUW_FUNCT6 => Instruction::VWSUBUW {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWSUBW_FUNCT6 => Instruction::VWSUBW {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMULU_FUNCT6 => Instruction::VWMULU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMULSU_FUNCT6 => Instruction::VWMULSU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMUL_FUNCT6 => Instruction::VWMUL {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMACCU_FUNCT6 => Instruction::VWMACCU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMACC_FUNCT6 => Instruction::VWMACC {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMACCUS_FUNCT6 if !vector_vector => Instruction::VWMACCUS {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                Instruction::VWMACCSU_FUNCT6 => Instruction::VWMACCSU {
+                    vd,
+                    vs2,
+                    operand,
+                    vm,
+                },
+                _ => return Err(Exception::IllegalInstruction),
+            }
+        };
+        Ok(instruction)
+    }
+
+    /// The HPM_EVENT_* bits the instruction causes when it retires, `taken` tells whether
+    /// it changed the control flow
+    pub fn hpm_events(&self, taken: bool) -> Word {
+        let event: Word = match self {
+            Instruction::LW { .. }
+            | Instruction::LH { .. }
+            | Instruction::LHU { .. }
+            | Instruction::LB { .. }
+            | Instruction::LBU { .. }
+            | Instruction::LD { .. }
+            | Instruction::LWU { .. }
+            | Instruction::FLW { .. }
+            | Instruction::FLD { .. }
+            | Instruction::FLH { .. }
+            | Instruction::LRW { .. }
+            | Instruction::LRD { .. }
+            | Instruction::VLE { .. }
+            | Instruction::VLEFF { .. }
+            | Instruction::VLSE { .. }
+            | Instruction::VLUXEI { .. }
+            | Instruction::VLOXEI { .. }
+            | Instruction::VLM { .. }
+            | Instruction::VLRE { .. } => HPM_EVENT_LOAD,
+            Instruction::SW { .. }
+            | Instruction::SH { .. }
+            | Instruction::SB { .. }
+            | Instruction::SD { .. }
+            | Instruction::FSW { .. }
+            | Instruction::FSD { .. }
+            | Instruction::FSH { .. }
+            | Instruction::CBOZERO { .. }
+            | Instruction::SCW { .. }
+            | Instruction::SCD { .. }
+            | Instruction::VSE { .. }
+            | Instruction::VSSE { .. }
+            | Instruction::VSUXEI { .. }
+            | Instruction::VSOXEI { .. }
+            | Instruction::VSM { .. }
+            | Instruction::VSR { .. } => HPM_EVENT_STORE,
+            Instruction::AMOSWAPW { .. }
+            | Instruction::AMOADDW { .. }
+            | Instruction::AMOXORW { .. }
+            | Instruction::AMOANDW { .. }
+            | Instruction::AMOORW { .. }
+            | Instruction::AMOMINW { .. }
+            | Instruction::AMOMAXW { .. }
+            | Instruction::AMOMINUW { .. }
+            | Instruction::AMOMAXUW { .. }
+            | Instruction::AMOSWAPD { .. }
+            | Instruction::AMOADDD { .. }
+            | Instruction::AMOXORD { .. }
+            | Instruction::AMOANDD { .. }
+            | Instruction::AMOORD { .. }
+            | Instruction::AMOMIND { .. }
+            | Instruction::AMOMAXD { .. }
+            | Instruction::AMOMINUD { .. }
+            | Instruction::AMOMAXUD { .. } => HPM_EVENT_ATOMIC,
+            Instruction::BEQ { .. }
+            | Instruction::BNE { .. }
+            | Instruction::BLT { .. }
+            | Instruction::BLTU { .. }
+            | Instruction::BGE { .. }
+            | Instruction::BGEU { .. } => {
+                let taken_event: Word = if taken {
+                    1 << HPM_EVENT_BRANCH_TAKEN
+                } else {
+                    0
+                };
+                return (1 << HPM_EVENT_BRANCH) | taken_event;
+            }
+            Instruction::JAL { .. } | Instruction::JALR { .. } => HPM_EVENT_JUMP,
+            _ if self.uses_fpu() => HPM_EVENT_FP,
+            _ => return 0,
+        };
+        let fp_event: Word = if self.uses_fpu() {
+            1 << HPM_EVENT_FP // floating-point loads and stores
+        } else {
+            0
+        };
+        (1 << event) | fp_event
+    }
+
+    /// Whether the instruction needs the FPU, which mstatus.FS can turn off
+    fn uses_fpu(&self) -> bool {
+        matches!(
+            self,
+            Instruction::FLW { .. }
+                | Instruction::FSW { .. }
+                | Instruction::FMADDS { .. }
+                | Instruction::FMSUBS { .. }
+                | Instruction::FNMSUBS { .. }
+                | Instruction::FNMADDS { .. }
+                | Instruction::FADDS { .. }
+                | Instruction::FSUBS { .. }
+                | Instruction::FMULS { .. }
+                | Instruction::FDIVS { .. }
+                | Instruction::FSQRTS { .. }
+                | Instruction::FSGNJS { .. }
+                | Instruction::FSGNJNS { .. }
+                | Instruction::FSGNJXS { .. }
+                | Instruction::FMINS { .. }
+                | Instruction::FMAXS { .. }
+                | Instruction::FCVTWS { .. }
+                | Instruction::FCVTWUS { .. }
+                | Instruction::FMVXW { .. }
+                | Instruction::FEQS { .. }
+                | Instruction::FLTS { .. }
+                | Instruction::FLES { .. }
+                | Instruction::FCLASSS { .. }
+                | Instruction::FCVTSW { .. }
+                | Instruction::FCVTSWU { .. }
+                | Instruction::FMVWX { .. }
+                | Instruction::FLD { .. }
+                | Instruction::FSD { .. }
+                | Instruction::FMADDD { .. }
+                | Instruction::FMSUBD { .. }
+                | Instruction::FNMSUBD { .. }
+                | Instruction::FNMADDD { .. }
+                | Instruction::FADDD { .. }
+                | Instruction::FSUBD { .. }
+                | Instruction::FMULD { .. }
+                | Instruction::FDIVD { .. }
+                | Instruction::FSQRTD { .. }
+                | Instruction::FSGNJD { .. }
+                | Instruction::FSGNJND { .. }
+                | Instruction::FSGNJXD { .. }
+                | Instruction::FMIND { .. }
+                | Instruction::FMAXD { .. }
+                | Instruction::FCVTSD { .. }
+                | Instruction::FCVTDS { .. }
+                | Instruction::FEQD { .. }
+                | Instruction::FLTD { .. }
+                | Instruction::FLED { .. }
+                | Instruction::FCLASSD { .. }
+                | Instruction::FCVTWD { .. }
+                | Instruction::FCVTWUD { .. }
+                | Instruction::FCVTDW { .. }
+                | Instruction::FCVTDWU { .. }
+                | Instruction::FCVTLS { .. }
+                | Instruction::FCVTLUS { .. }
+                | Instruction::FCVTSL { .. }
+                | Instruction::FCVTSLU { .. }
+                | Instruction::FCVTLD { .. }
+                | Instruction::FCVTLUD { .. }
+                | Instruction::FMVXD { .. }
+                | Instruction::FCVTDL { .. }
+                | Instruction::FCVTDLU { .. }
+                | Instruction::FMVDX { .. }
+                | Instruction::FLH { .. }
+                | Instruction::FSH { .. }
+                | Instruction::FMADDH { .. }
+                | Instruction::FMSUBH { .. }
+                | Instruction::FNMSUBH { .. }
+                | Instruction::FNMADDH { .. }
+                | Instruction::FADDH { .. }
+                | Instruction::FSUBH { .. }
+                | Instruction::FMULH { .. }
+                | Instruction::FDIVH { .. }
+                | Instruction::FSQRTH { .. }
+                | Instruction::FSGNJH { .. }
+                | Instruction::FSGNJNH { .. }
+                | Instruction::FSGNJXH { .. }
+                | Instruction::FMINH { .. }
+                | Instruction::FMAXH { .. }
+                | Instruction::FCVTSH { .. }
+                | Instruction::FCVTHS { .. }
+                | Instruction::FCVTDH { .. }
+                | Instruction::FCVTHD { .. }
+                | Instruction::FCVTWH { .. }
+                | Instruction::FCVTWUH { .. }
+                | Instruction::FMVXH { .. }
+                | Instruction::FEQH { .. }
+                | Instruction::FLTH { .. }
+                | Instruction::FLEH { .. }
+                | Instruction::FCLASSH { .. }
+                | Instruction::FCVTHW { .. }
+                | Instruction::FCVTHWU { .. }
+                | Instruction::FMVHX { .. }
+                | Instruction::FCVTLH { .. }
+                | Instruction::FCVTLUH { .. }
+                | Instruction::FCVTHL { .. }
+                | Instruction::FCVTHLU { .. }
+                | Instruction::FLIS { .. }
+                | Instruction::FLID { .. }
+                | Instruction::FLIH { .. }
+                | Instruction::FMINMS { .. }
+                | Instruction::FMAXMS { .. }
+                | Instruction::FMINMD { .. }
+                | Instruction::FMAXMD { .. }
+                | Instruction::FMINMH { .. }
+                | Instruction::FMAXMH { .. }
+                | Instruction::FROUNDS { .. }
+                | Instruction::FROUNDNXS { .. }
+                | Instruction::FROUNDD { .. }
+                | Instruction::FROUNDNXD { .. }
+                | Instruction::FROUNDH { .. }
+                | Instruction::FROUNDNXH { .. }
+                | Instruction::FCVTMODWD { .. }
+                | Instruction::FLEQS { .. }
+                | Instruction::FLTQS { .. }
+                | Instruction::FLEQD { .. }
+                | Instruction::FLTQD { .. }
+                | Instruction::FLEQH { .. }
+                | Instruction::FLTQH { .. }
+        )
+    }
+
+    /// Whether the instruction belongs to the vector extension, which mstatus.VS can turn off
+    fn uses_vector(&self) -> bool {
+        matches!(
+            self,
+            Instruction::VSETVLI { .. }
+                | Instruction::VSETIVLI { .. }
+                | Instruction::VSETVL { .. }
+                | Instruction::VLE { .. }
+                | Instruction::VLEFF { .. }
+                | Instruction::VLSE { .. }
+                | Instruction::VLUXEI { .. }
+                | Instruction::VLOXEI { .. }
+                | Instruction::VLM { .. }
+                | Instruction::VLRE { .. }
+                | Instruction::VSE { .. }
+                | Instruction::VSSE { .. }
+                | Instruction::VSUXEI { .. }
+                | Instruction::VSOXEI { .. }
+                | Instruction::VSM { .. }
+                | Instruction::VSR { .. }
+                | Instruction::VADD { .. }
+                | Instruction::VSUB { .. }
+                | Instruction::VRSUB { .. }
+                | Instruction::VWADDU { .. }
+                | Instruction::VWADD { .. }
+                | Instruction::VWSUBU { .. }
+                | Instruction::VWSUB { .. }
+                | Instruction::VWADDUW { .. }
+                | Instruction::VWADDW { .. }
+                | Instruction::VWSUBUW { .. }
+                | Instruction::VWSUBW { .. }
+                | Instruction::VZEXT { .. }
+                | Instruction::VSEXT { .. }
+                | Instruction::VADC { .. }
+                | Instruction::VMADC { .. }
+                | Instruction::VSBC { .. }
+                | Instruction::VMSBC { .. }
+                | Instruction::VAND { .. }
+                | Instruction::VOR { .. }
+                | Instruction::VXOR { .. }
+                | Instruction::VSLL { .. }
+                | Instruction::VSRL { .. }
+                | Instruction::VSRA { .. }
+                | Instruction::VNSRL { .. }
+                | Instruction::VNSRA { .. }
+                | Instruction::VMSEQ { .. }
+                | Instruction::VMSNE { .. }
+                | Instruction::VMSLTU { .. }
+                | Instruction::VMSLT { .. }
+                | Instruction::VMSLEU { .. }
+                | Instruction::VMSLE { .. }
+                | Instruction::VMSGTU { .. }
+                | Instruction::VMSGT { .. }
+                | Instruction::VMINU { .. }
+                | Instruction::VMIN { .. }
+                | Instruction::VMAXU { .. }
+                | Instruction::VMAX { .. }
+                | Instruction::VMUL { .. }
+                | Instruction::VMULH { .. }
+                | Instruction::VMULHU { .. }
+                | Instruction::VMULHSU { .. }
+                | Instruction::VDIVU { .. }
+                | Instruction::VDIV { .. }
+                | Instruction::VREMU { .. }
+                | Instruction::VREM { .. }
+                | Instruction::VWMULU { .. }
+                | Instruction::VWMUL { .. }
+                | Instruction::VWMULSU { .. }
+                | Instruction::VMACC { .. }
+                | Instruction::VNMSAC { .. }
+                | Instruction::VMADD { .. }
+                | Instruction::VNMSUB { .. }
+                | Instruction::VWMACCU { .. }
+                | Instruction::VWMACC { .. }
+                | Instruction::VWMACCSU { .. }
+                | Instruction::VWMACCUS { .. }
+                | Instruction::VMERGE { .. }
+                | Instruction::VMV { .. }
+                | Instruction::VREDSUM { .. }
+                | Instruction::VREDAND { .. }
+                | Instruction::VREDOR { .. }
+                | Instruction::VREDXOR { .. }
+                | Instruction::VREDMINU { .. }
+                | Instruction::VREDMIN { .. }
+                | Instruction::VREDMAXU { .. }
+                | Instruction::VREDMAX { .. }
+                | Instruction::VWREDSUMU { .. }
+                | Instruction::VWREDSUM { .. }
+                | Instruction::VMAND { .. }
+                | Instruction::VMNAND { .. }
+                | Instruction::VMANDN { .. }
+                | Instruction::VMXOR { .. }
+                | Instruction::VMOR { .. }
+                | Instruction::VMNOR { .. }
+                | Instruction::VMORN { .. }
+                | Instruction::VMXNOR { .. }
+                | Instruction::VCPOP { .. }
+                | Instruction::VFIRST { .. }
+                | Instruction::VMSBF { .. }
+                | Instruction::VMSIF { .. }
+                | Instruction::VMSOF { .. }
+                | Instruction::VIOTA { .. }
+                | Instruction::VID { .. }
+                | Instruction::VMVXS { .. }
+                | Instruction::VMVSX { .. }
+                | Instruction::VMVNRR { .. }
+        )
+    }
+
+    /// Second source of the vector arithmetic instructions that take one
+    fn vector_operand(&self) -> Option<VectorOperand> {
+        match *self {
+            Instruction::VADD { operand, .. }
+            | Instruction::VSUB { operand, .. }
+            | Instruction::VRSUB { operand, .. }
+            | Instruction::VWADDU { operand, .. }
+            | Instruction::VWADD { operand, .. }
+            | Instruction::VWSUBU { operand, .. }
+            | Instruction::VWSUB { operand, .. }
+            | Instruction::VWADDUW { operand, .. }
+            | Instruction::VWADDW { operand, .. }
+            | Instruction::VWSUBUW { operand, .. }
+            | Instruction::VWSUBW { operand, .. }
+            | Instruction::VADC { operand, .. }
+            | Instruction::VMADC { operand, .. }
+            | Instruction::VSBC { operand, .. }
+            | Instruction::VMSBC { operand, .. }
+            | Instruction::VAND { operand, .. }
+            | Instruction::VOR { operand, .. }
+            | Instruction::VXOR { operand, .. }
+            | Instruction::VSLL { operand, .. }
+            | Instruction::VSRL { operand, .. }
+            | Instruction::VSRA { operand, .. }
+            | Instruction::VNSRL { operand, .. }
+            | Instruction::VNSRA { operand, .. }
+            | Instruction::VMSEQ { operand, .. }
+            | Instruction::VMSNE { operand, .. }
+            | Instruction::VMSLTU { operand, .. }
+            | Instruction::VMSLT { operand, .. }
+            | Instruction::VMSLEU { operand, .. }
+            | Instruction::VMSLE { operand, .. }
+            | Instruction::VMSGTU { operand, .. }
+            | Instruction::VMSGT { operand, .. }
+            | Instruction::VMINU { operand, .. }
+            | Instruction::VMIN { operand, .. }
+            | Instruction::VMAXU { operand, .. }
+            | Instruction::VMAX { operand, .. }
+            | Instruction::VMUL { operand, .. }
+            | Instruction::VMULH { operand, .. }
+            | Instruction::VMULHU { operand, .. }
+            | Instruction::VMULHSU { operand, .. }
+            | Instruction::VDIVU { operand, .. }
+            | Instruction::VDIV { operand, .. }
+            | Instruction::VREMU { operand, .. }
+            | Instruction::VREM { operand, .. }
+            | Instruction::VWMULU { operand, .. }
+            | Instruction::VWMUL { operand, .. }
+            | Instruction::VWMULSU { operand, .. }
+            | Instruction::VMACC { operand, .. }
+            | Instruction::VNMSAC { operand, .. }
+            | Instruction::VMADD { operand, .. }
+            | Instruction::VNMSUB { operand, .. }
+            | Instruction::VWMACCU { operand, .. }
+            | Instruction::VWMACC { operand, .. }
+            | Instruction::VWMACCSU { operand, .. }
+            | Instruction::VWMACCUS { operand, .. }
+            | Instruction::VMERGE { operand, .. }
+            | Instruction::VMV { operand, .. } => Some(operand),
+            _ => None,
+        }
+    }
+
+    /// Integer registers the instruction reads or writes, padded with x0.
+    /// Floating-point register operands are not included.
+    fn integer_registers(&self) -> [usize; 3] {
+        match *self {
+            Instruction::ADD { rs1, rs2, rd }
+            | Instruction::SUB { rs1, rs2, rd }
+            | Instruction::SLT { rs1, rs2, rd }
+            | Instruction::SLTU { rs1, rs2, rd }
+            | Instruction::AND { rs1, rs2, rd }
+            | Instruction::OR { rs1, rs2, rd }
+            | Instruction::XOR { rs1, rs2, rd }
+            | Instruction::SLL { rs1, rs2, rd }
+            | Instruction::SRL { rs1, rs2, rd }
+            | Instruction::SRA { rs1, rs2, rd }
+            | Instruction::ADDW { rs1, rs2, rd }
+            | Instruction::SUBW { rs1, rs2, rd }
+            | Instruction::SLLW { rs1, rs2, rd }
+            | Instruction::SRLW { rs1, rs2, rd }
+            | Instruction::SRAW { rs1, rs2, rd }
+            | Instruction::SH1ADD { rs1, rs2, rd }
+            | Instruction::SH2ADD { rs1, rs2, rd }
+            | Instruction::SH3ADD { rs1, rs2, rd }
+            | Instruction::ADDUW { rs1, rs2, rd }
+            | Instruction::SH1ADDUW { rs1, rs2, rd }
+            | Instruction::SH2ADDUW { rs1, rs2, rd }
+            | Instruction::SH3ADDUW { rs1, rs2, rd }
+            | Instruction::ANDN { rs1, rs2, rd }
+            | Instruction::ORN { rs1, rs2, rd }
+            | Instruction::XNOR { rs1, rs2, rd }
+            | Instruction::MIN { rs1, rs2, rd }
+            | Instruction::MINU { rs1, rs2, rd }
+            | Instruction::MAX { rs1, rs2, rd }
+            | Instruction::MAXU { rs1, rs2, rd }
+            | Instruction::ROL { rs1, rs2, rd }
+            | Instruction::ROR { rs1, rs2, rd }
+            | Instruction::ROLW { rs1, rs2, rd }
+            | Instruction::RORW { rs1, rs2, rd }
+            | Instruction::BSET { rs1, rs2, rd }
+            | Instruction::BCLR { rs1, rs2, rd }
+            | Instruction::BINV { rs1, rs2, rd }
+            | Instruction::BEXT { rs1, rs2, rd }
+            | Instruction::CLMUL { rs1, rs2, rd }
+            | Instruction::CLMULH { rs1, rs2, rd }
+            | Instruction::CLMULR { rs1, rs2, rd }
+            | Instruction::PACK { rs1, rs2, rd }
+            | Instruction::PACKH { rs1, rs2, rd }
+            | Instruction::PACKW { rs1, rs2, rd }
+            | Instruction::XPERM4 { rs1, rs2, rd }
+            | Instruction::XPERM8 { rs1, rs2, rd }
+            | Instruction::AES32ESI { rs1, rs2, rd, .. }
+            | Instruction::AES32ESMI { rs1, rs2, rd, .. }
+            | Instruction::AES32DSI { rs1, rs2, rd, .. }
+            | Instruction::AES32DSMI { rs1, rs2, rd, .. }
+            | Instruction::SHA512SIG0L { rs1, rs2, rd }
+            | Instruction::SHA512SIG0H { rs1, rs2, rd }
+            | Instruction::SHA512SIG1L { rs1, rs2, rd }
+            | Instruction::SHA512SIG1H { rs1, rs2, rd }
+            | Instruction::SHA512SUM0R { rs1, rs2, rd }
+            | Instruction::SHA512SUM1R { rs1, rs2, rd }
+            | Instruction::CZEROEQZ { rs1, rs2, rd }
+            | Instruction::CZERONEZ { rs1, rs2, rd }
+            | Instruction::MUL { rs1, rs2, rd }
+            | Instruction::MULH { rs1, rs2, rd }
+            | Instruction::MULHSU { rs1, rs2, rd }
+            | Instruction::MULHU { rs1, rs2, rd }
+            | Instruction::DIV { rs1, rs2, rd }
+            | Instruction::DIVU { rs1, rs2, rd }
+            | Instruction::REM { rs1, rs2, rd }
+            | Instruction::REMU { rs1, rs2, rd }
+            | Instruction::MULW { rs1, rs2, rd }
+            | Instruction::DIVW { rs1, rs2, rd }
+            | Instruction::DIVUW { rs1, rs2, rd }
+            | Instruction::REMW { rs1, rs2, rd }
+            | Instruction::REMUW { rs1, rs2, rd }
+            | Instruction::SCW { rs1, rs2, rd }
+            | Instruction::AMOSWAPW { rs1, rs2, rd }
+            | Instruction::AMOADDW { rs1, rs2, rd }
+            | Instruction::AMOXORW { rs1, rs2, rd }
+            | Instruction::AMOANDW { rs1, rs2, rd }
+            | Instruction::AMOORW { rs1, rs2, rd }
+            | Instruction::AMOMINW { rs1, rs2, rd }
+            | Instruction::AMOMAXW { rs1, rs2, rd }
+            | Instruction::AMOMINUW { rs1, rs2, rd }
+            | Instruction::AMOMAXUW { rs1, rs2, rd }
+            | Instruction::SCD { rs1, rs2, rd }
+            | Instruction::AMOSWAPD { rs1, rs2, rd }
+            | Instruction::AMOADDD { rs1, rs2, rd }
+            | Instruction::AMOXORD { rs1, rs2, rd }
+            | Instruction::AMOANDD { rs1, rs2, rd }
+            | Instruction::AMOORD { rs1, rs2, rd }
+            | Instruction::AMOMIND { rs1, rs2, rd }
+            | Instruction::AMOMAXD { rs1, rs2, rd }
+            | Instruction::AMOMINUD { rs1, rs2, rd }
+            | Instruction::AMOMAXUD { rs1, rs2, rd } => [rs1, rs2, rd],
+            Instruction::BEQ { rs1, rs2, .. }
+            | Instruction::BNE { rs1, rs2, .. }
+            | Instruction::BLT { rs1, rs2, .. }
+            | Instruction::BLTU { rs1, rs2, .. }
+            | Instruction::BGE { rs1, rs2, .. }
+            | Instruction::BGEU { rs1, rs2, .. }
+            | Instruction::SW { rs1, rs2, .. }
+            | Instruction::SH { rs1, rs2, .. }
+            | Instruction::SB { rs1, rs2, .. }
+            | Instruction::SD { rs1, rs2, .. }
+            | Instruction::SFENCEVMA { rs1, rs2 } => [rs1, rs2, 0],
+            Instruction::ADDI { rs1, rd, .. }
+            | Instruction::SLTI { rs1, rd, .. }
+            | Instruction::STLIU { rs1, rd, .. }
+            | Instruction::ANDI { rs1, rd, .. }
+            | Instruction::ORI { rs1, rd, .. }
+            | Instruction::XORI { rs1, rd, .. }
+            | Instruction::SLLI { rs1, rd, .. }
+            | Instruction::SRLI { rs1, rd, .. }
+            | Instruction::SRAI { rs1, rd, .. }
+            | Instruction::ADDIW { rs1, rd, .. }
+            | Instruction::SLLIW { rs1, rd, .. }
+            | Instruction::SRLIW { rs1, rd, .. }
+            | Instruction::SRAIW { rs1, rd, .. }
+            | Instruction::SLLIUW { rs1, rd, .. }
+            | Instruction::CLZ { rs1, rd }
+            | Instruction::CTZ { rs1, rd }
+            | Instruction::CPOP { rs1, rd }
+            | Instruction::CLZW { rs1, rd }
+            | Instruction::CTZW { rs1, rd }
+            | Instruction::CPOPW { rs1, rd }
+            | Instruction::SEXTB { rs1, rd }
+            | Instruction::SEXTH { rs1, rd }
+            | Instruction::ZEXTH { rs1, rd }
+            | Instruction::RORI { rs1, rd, .. }
+            | Instruction::RORIW { rs1, rd, .. }
+            | Instruction::REV8 { rs1, rd }
+            | Instruction::ORCB { rs1, rd }
+            | Instruction::BSETI { rs1, rd, .. }
+            | Instruction::BCLRI { rs1, rd, .. }
+            | Instruction::BINVI { rs1, rd, .. }
+            | Instruction::BEXTI { rs1, rd, .. }
+            | Instruction::BREV8 { rs1, rd }
+            | Instruction::ZIP { rs1, rd }
+            | Instruction::UNZIP { rs1, rd }
+            | Instruction::SHA256SIG0 { rs1, rd }
+            | Instruction::SHA256SIG1 { rs1, rd }
+            | Instruction::SHA256SUM0 { rs1, rd }
+            | Instruction::SHA256SUM1 { rs1, rd }
+            | Instruction::JALR { rs1, rd, .. }
+            | Instruction::LW { rs1, rd, .. }
+            | Instruction::LH { rs1, rd, .. }
+            | Instruction::LHU { rs1, rd, .. }
+            | Instruction::LB { rs1, rd, .. }
+            | Instruction::LBU { rs1, rd, .. }
+            | Instruction::LD { rs1, rd, .. }
+            | Instruction::LWU { rs1, rd, .. }
+            | Instruction::CSRRW { rs1, rd, .. }
+            | Instruction::CSRRS { rs1, rd, .. }
+            | Instruction::CSRRC { rs1, rd, .. }
+            | Instruction::LRW { rs1, rd, .. }
+            | Instruction::LRD { rs1, rd, .. } => [rs1, rd, 0],
+            Instruction::LUI { rd, .. }
+            | Instruction::AUIPC { rd, .. }
+            | Instruction::JAL { rd, .. }
+            | Instruction::CSRRWI { rd, .. }
+            | Instruction::CSRRSI { rd, .. }
+            | Instruction::CSRRCI { rd, .. }
+            | Instruction::FCVTWS { rd, .. }
+            | Instruction::FCVTWUS { rd, .. }
+            | Instruction::FMVXW { rd, .. }
+            | Instruction::FEQS { rd, .. }
+            | Instruction::FLTS { rd, .. }
+            | Instruction::FLES { rd, .. }
+            | Instruction::FCLASSS { rd, .. }
+            | Instruction::FCVTLS { rd, .. }
+            | Instruction::FCVTLUS { rd, .. }
+            | Instruction::FEQD { rd, .. }
+            | Instruction::FLTD { rd, .. }
+            | Instruction::FLED { rd, .. }
+            | Instruction::FCLASSD { rd, .. }
+            | Instruction::FCVTWD { rd, .. }
+            | Instruction::FCVTWUD { rd, .. }
+            | Instruction::FCVTLD { rd, .. }
+            | Instruction::FCVTLUD { rd, .. }
+            | Instruction::FMVXD { rd, .. }
+            | Instruction::FCVTWH { rd, .. }
+            | Instruction::FCVTWUH { rd, .. }
+            | Instruction::FMVXH { rd, .. }
+            | Instruction::FEQH { rd, .. }
+            | Instruction::FLTH { rd, .. }
+            | Instruction::FLEH { rd, .. }
+            | Instruction::FCLASSH { rd, .. }
+            | Instruction::FCVTLH { rd, .. }
+            | Instruction::FCVTLUH { rd, .. }
+            | Instruction::FCVTMODWD { rd, .. }
+            | Instruction::FLEQS { rd, .. }
+            | Instruction::FLTQS { rd, .. }
+            | Instruction::FLEQD { rd, .. }
+            | Instruction::FLTQD { rd, .. }
+            | Instruction::FLEQH { rd, .. }
+            | Instruction::FLTQH { rd, .. } => [rd, 0, 0],
+            Instruction::FLW { rs1, .. }
+            | Instruction::FSW { rs1, .. }
+            | Instruction::FLD { rs1, .. }
+            | Instruction::FSD { rs1, .. }
+            | Instruction::FCVTSW { rs1, .. }
+            | Instruction::FCVTSWU { rs1, .. }
+            | Instruction::FMVWX { rs1, .. }
+            | Instruction::FCVTSL { rs1, .. }
+            | Instruction::FCVTSLU { rs1, .. }
+            | Instruction::FCVTDW { rs1, .. }
+            | Instruction::FCVTDWU { rs1, .. }
+            | Instruction::FCVTDL { rs1, .. }
+            | Instruction::FCVTDLU { rs1, .. }
+            | Instruction::FMVDX { rs1, .. }
+            | Instruction::CBOINVAL { rs1 }
+            | Instruction::CBOCLEAN { rs1 }
+            | Instruction::CBOFLUSH { rs1 }
+            | Instruction::CBOZERO { rs1 }
+            | Instruction::PREFETCHI { rs1, .. }
+            | Instruction::PREFETCHR { rs1, .. }
+            | Instruction::PREFETCHW { rs1, .. }
+            | Instruction::FLH { rs1, .. }
+            | Instruction::FSH { rs1, .. }
+            | Instruction::FCVTHW { rs1, .. }
+            | Instruction::FCVTHWU { rs1, .. }
+            | Instruction::FMVHX { rs1, .. }
+            | Instruction::FCVTHL { rs1, .. }
+            | Instruction::FCVTHLU { rs1, .. } => [rs1, 0, 0],
+            Instruction::VSETVLI { rs1, rd, .. } => [rd, rs1, 0],
+            Instruction::VSETIVLI { rd, .. }
+            | Instruction::VMVXS { rd, .. }
+            | Instruction::VCPOP { rd, .. }
+            | Instruction::VFIRST { rd, .. } => [rd, 0, 0],
+            Instruction::VSETVL { rs1, rs2, rd } => [rd, rs1, rs2],
+            Instruction::VLSE { rs1, rs2, .. } | Instruction::VSSE { rs1, rs2, .. } => {
+                [rs1, rs2, 0]
+            }
+            Instruction::VLE { rs1, .. }
+            | Instruction::VLEFF { rs1, .. }
+            | Instruction::VLUXEI { rs1, .. }
+            | Instruction::VLOXEI { rs1, .. }
+            | Instruction::VLM { rs1, .. }
+            | Instruction::VLRE { rs1, .. }
+            | Instruction::VSE { rs1, .. }
+            | Instruction::VSUXEI { rs1, .. }
+            | Instruction::VSOXEI { rs1, .. }
+            | Instruction::VSM { rs1, .. }
+            | Instruction::VSR { rs1, .. }
+            | Instruction::VMVSX { rs1, .. } => [rs1, 0, 0],
+            _ => match self.vector_operand() {
+                Some(VectorOperand::Scalar(rs1)) => [rs1, 0, 0],
+                _ => [0; 3],
+            },
+        }
+    }
+}
+
+const DECODE_CACHE_SIZE: usize = 1024; // entries in the direct-mapped decoded instruction cache
+
+/// A decoded instruction remembered for the address it was fetched from
+#[derive(Clone, Copy)]
+struct DecodedInstruction<X: Xlen> {
+    pc: X,                    // address the instruction was fetched from
+    raw: Word,                // instruction bits it was decoded from
+    instruction: Instruction, // decoded form
+}
+
+/// What the hart does with a load or store that is not naturally aligned
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MisalignedAccess {
+    Trap,                     // raise the address-misaligned exception
+    Emulate { penalty: u64 }, // perform the access byte by byte, taking `penalty` extra cycles
+}
+
+/// A single hart whose integer registers are `X` wide: `u32` for RV32, `u64` for RV64
+pub struct RISCV<X: Xlen = Word> {
+    pub reg: [X; REGISTER_COUNT], // 32 registers which are XLEN bits wide
+    pub freg: [DoubleWord; REGISTER_COUNT], // 32 floating-point registers, 64 bits wide for D
+    pub vreg: VectorRegisters,    // 32 vector registers, VLEN bits wide
+    pub pc: X,                    // Program counter (holds current instruction address)
+    pub csr: CSRFile<X>,          // Control and status registers
+    pub halt: Option<Halt>,       // set when the hart stopped executing (exit or breakpoint)
+    pub reservation: Option<X>,   // address reserved by LR, consumed by SC
+    cache_block_size: usize,      // bytes in a cache block, a power of two
+    pub misaligned_access: MisalignedAccess, // how misaligned loads and stores are handled
+    pub privilege: Privilege,     // current privilege mode
+    pub dtlb: Tlb,                // translations for loads and stores, and fetches when unified
+    pub itlb: Option<Tlb>,        // translations for fetches, None when they share the data TLB
+    current_instruction: Word,    // holds the current instruction being executed
+    instruction_length: Word,     // size in bytes of the current instruction (2 or 4)
+    jumped: bool,                 // whether the current instruction changed the control flow
+    decode_cache: Vec<Option<DecodedInstruction<X>>>, // decoded instructions indexed by pc, flushed by FENCE.I
+    ecall_handler: Option<Box<dyn EcallHandler<X>>>,  // host services for ECALL / EBREAK
+}
+
+pub type RISCV32 = RISCV<u32>; // RV32 hart
+pub type RISCV64 = RISCV<u64>; // RV64 hart
+
+/// Constructors of a hart of any XLEN, as in `RISCV64::reset()`. `RISCV<Word>` also has them
+/// as inherent functions, so `RISCV::reset()` builds an RV32 hart without naming the type.
+pub trait Reset: Sized {
+    /// A hart in M-mode with the pc and every register at 0
+    fn reset() -> Self;
+
+    /// A hart with the embedded base ISA, which only has the integer registers x0-x15
+    fn reset_embedded() -> Self;
+
+    /// A hart whose vector registers are `vlen` bits wide, holding elements of up to `elen`
+    /// bits (32 or 64)
+    fn reset_with_vlen(vlen: usize, elen: usize) -> Result<Self, ConfigError>;
+
+    /// A hart whose TLBs hold `entries` entries in sets of `ways`, with separate instruction
+    /// and data TLBs when `split`, or one unified TLB otherwise
+    fn reset_with_tlb(entries: usize, ways: usize, split: bool) -> Result<Self, ConfigError>;
+
+    /// A hart that starts executing from 0x1000
+    fn new_() -> Self;
+}
+
+impl<X: Xlen> Reset for RISCV<X> {
+    fn reset() -> Self {
+        RISCV {
+            reg: [X::default(); REGISTER_COUNT], // Resets registers to 0x00000
+            freg: [0; REGISTER_COUNT],
+            vreg: VectorRegisters::default(),
+            pc: X::default(), // Start executing code from 0x00000
+            csr: CSRFile::new(),
+            halt: None,
+            reservation: None,
+            cache_block_size: DEFAULT_CACHE_BLOCK_SIZE,
+            misaligned_access: MisalignedAccess::Trap,
+            privilege: Privilege::Machine,
+            dtlb: Tlb::default(),
+            itlb: Some(Tlb::default()),
+            current_instruction: 0,
+            instruction_length: 4,
+            jumped: false,
+            decode_cache: vec![None; DECODE_CACHE_SIZE],
+            ecall_handler: None,
+        }
+    }
+
+    fn reset_embedded() -> Self {
+        RISCV {
+            csr: CSRFile::new_embedded(),
+            ..Self::reset()
+        }
+    }
+
+    fn reset_with_vlen(vlen: usize, elen: usize) -> Result<Self, ConfigError> {
+        let mut hart: RISCV<X> = Self::reset();
+        hart.vreg = VectorRegisters::new(vlen, elen)?;
+        hart.csr.set(VLENB, X::from_u64(hart.vreg.vlenb() as u64));
+        Ok(hart)
+    }
+
+    fn reset_with_tlb(entries: usize, ways: usize, split: bool) -> Result<Self, ConfigError> {
+        let tlb: Tlb = Tlb::new(entries, ways)?;
+        Ok(RISCV {
+            itlb: split.then(|| tlb.clone()),
+            dtlb: tlb,
+            ..Self::reset()
+        })
+    }
+
+    fn new_() -> Self {
+        RISCV {
+            pc: X::from(0x1000), // Start executing code from 0x1000
+            ..Self::reset()
+        }
+    }
+}
+
+// The default XLEN does not drive inference, so the RV32 constructors are also inherent
+impl RISCV<Word> {
+    pub fn reset() -> Self {
+        <Self as Reset>::reset()
+    }
+
+    pub fn reset_embedded() -> Self {
+        <Self as Reset>::reset_embedded()
+    }
+
+    pub fn reset_with_vlen(vlen: usize, elen: usize) -> Result<Self, ConfigError> {
+        <Self as Reset>::reset_with_vlen(vlen, elen)
+    }
+
+    pub fn reset_with_tlb(entries: usize, ways: usize, split: bool) -> Result<Self, ConfigError> {
+        <Self as Reset>::reset_with_tlb(entries, ways, split)
+    }
+
+    pub fn new_() -> Self {
+        <Self as Reset>::new_()
+    }
+}
+
+impl<X: Xlen> RISCV<X> {
+    /// Runs a single fetch-execute cycle, returning the trap raised by the instruction if any.
+    /// On a trap the pc is left pointing at the faulting instruction.
+    pub fn clock_cycle(&mut self, mem: &mut Memory) -> Result<(), Trap<X>> {
+        if self.halt.is_some() {
+            return Ok(()); // a halted hart does not execute until the host resumes it
+        }
+        let result: Result<(Instruction, bool), Trap<X>> = self.fetch_and_execute(mem);
+        let events: Word = match result {
+            Ok((instruction, taken)) => instruction.hpm_events(taken),
+            Err(_) => 1 << HPM_EVENT_EXCEPTION,
+        };
+        self.csr.tick_counters(result.is_ok(), events);
+        result.map(|_| ())
+    }
+
+    fn fetch_and_execute(&mut self, mem: &mut Memory) -> Result<(Instruction, bool), Trap<X>> {
+        self.fetch_instruction(mem)?;
+        let executed: (Instruction, bool) = self.execute_instruction(mem)?;
+        self.increment_pc();
+        Ok(executed)
+    }
+
+    /// Runs a single cycle the way the hardware does: a pending and enabled interrupt is taken
+    /// before fetching, and any trap raised by the instruction is taken through mtvec
+    /// instead of being reported to the host.
+    pub fn step(&mut self, mem: &mut Memory) {
+        if self.halt.is_some() {
+            return;
+        }
+        if let Some(interrupt) = self.pending_interrupt() {
+            self.take_interrupt(interrupt);
+            self.csr.tick_counters(false, 0); // the cycle spent entering the handler
+            return;
+        }
+        if let Err(trap) = self.clock_cycle(mem) {
+            self.take_trap(trap);
+        }
+    }
+
+    /// Lets a halted hart run again. After a breakpoint the pc steps over the EBREAK it stopped
+    /// on, so execution continues with the following instruction.
+    pub fn resume(&mut self) {
+        if self.halt.take() == Some(Halt::Breakpoint) {
+            self.increment_pc();
+        }
+    }
+
+    /// Enters the trap handler for a synchronous exception, in S-mode when medeleg
+    /// delegates it and M-mode otherwise
+    pub fn take_trap(&mut self, trap: Trap<X>) {
+        self.reservation = None; // a trap breaks any LR/SC sequence in progress
+        let code: Word = trap.cause.code();
+        let target: Privilege = self.trap_target(MEDELEG, code);
+        self.enter_trap_handler(target, X::from(code), trap.tval);
+        self.pc = self.trap_vector(target) & !X::from(MTVEC_MODE); // exceptions always go to BASE
+    }
+
+    /// Enters the trap handler for an interrupt, in S-mode when mideleg delegates it and
+    /// M-mode otherwise
+    pub fn take_interrupt(&mut self, interrupt: Interrupt) {
+        let target: Privilege = self.trap_target(MIDELEG, interrupt.code());
+        let interrupt_bit: X = X::from_u64(1 << (X::XLEN - 1)); // top bit of mcause / scause
+        self.enter_trap_handler(
+            target,
+            interrupt_bit | X::from(interrupt.code()),
+            X::default(),
+        );
+
+        let tvec: X = self.trap_vector(target);
+        let base: X = tvec & !X::from(MTVEC_MODE);
+        self.pc = if tvec & X::from(MTVEC_MODE) == X::from(MTVEC_VECTORED) {
+            base.wrapping_add(X::from(4 * interrupt.code()))
+        } else {
+            base
+        };
+    }
+
+    /// The mode that handles trap `code` given the delegation register `deleg`. Traps are
+    /// never delegated to a mode less privileged than the one they are raised in.
+    fn trap_target(&self, deleg: usize, code: Word) -> Privilege {
+        let delegated: bool = self.csr.get(deleg).to_u64() & (1 << code) != 0;
+        if delegated && self.privilege <= Privilege::Supervisor {
+            Privilege::Supervisor
+        } else {
+            Privilege::Machine
+        }
+    }
+
+    /// mtvec or stvec
+    fn trap_vector(&self, target: Privilege) -> X {
+        if target == Privilege::Machine {
+            self.csr.get(MTVEC)
+        } else {
+            self.csr.get(STVEC)
+        }
+    }
+
+    /// Saves the trap state into the xepc/xcause/xtval CSRs of `target`, disables its
+    /// interrupts and switches to that mode
+    fn enter_trap_handler(&mut self, target: Privilege, cause: X, tval: X) {
+        let mstatus: X = self.csr.get(MSTATUS);
+        let mstatus: X = if target == Privilege::Machine {
+            self.csr.set(MEPC, self.pc);
+            self.csr.set(MCAUSE, cause);
+            self.csr.set(MTVAL, tval);
+            // MPIE <- MIE, MIE <- 0, MPP <- the mode the trap was taken from
+            let mpie: Word = if mstatus & X::from(MSTATUS_MIE) != X::default() {
+                MSTATUS_MPIE
+            } else {
+                0
+            };
+            (mstatus & !X::from(MSTATUS_MIE | MSTATUS_MPIE | MSTATUS_MPP))
+                | X::from(mpie | self.privilege.mpp())
+        } else {
+            self.csr.set(SEPC, self.pc);
+            self.csr.set(SCAUSE, cause);
+            self.csr.set(STVAL, tval);
+            // SPIE <- SIE, SIE <- 0, SPP <- the mode the trap was taken from (U or S)
+            let spie: Word = if mstatus & X::from(MSTATUS_SIE) != X::default() {
+                MSTATUS_SPIE
+            } else {
+                0
+            };
+            let spp: Word = if self.privilege == Privilege::User {
+                0
+            } else {
+                MSTATUS_SPP
+            };
+            (mstatus & !X::from(MSTATUS_SIE | MSTATUS_SPIE | MSTATUS_SPP)) | X::from(spie | spp)
+        };
+        self.csr.set(MSTATUS, mstatus);
+        self.privilege = target;
+    }
+
+    /// Bytes in the cache block a CBO instruction operates on
+    pub fn cache_block_size(&self) -> usize {
+        self.cache_block_size
+    }
+
+    /// Sets the cache block size, a power of two no larger than a page so that a block is
+    /// never split between pages. Other sizes are rejected and leave the size unchanged.
+    pub fn set_cache_block_size(&mut self, size: usize) -> Result<(), ConfigError> {
+        if !size.is_power_of_two() || size > PAGE_SIZE {
+            return Err(ConfigError::CacheBlockSize);
+        }
+        self.cache_block_size = size;
+        Ok(())
+    }
+
+    /// Installs the host handler that services ECALL and EBREAK
+    pub fn set_ecall_handler(&mut self, handler: Box<dyn EcallHandler<X>>) {
+        self.ecall_handler = Some(handler);
+    }
+
+    /// Hands an ECALL or EBREAK to the installed host handler.
+    /// Without a handler the exception is raised in the guest.
+    fn call_host(&mut self, mem: &mut Memory, ebreak: bool) -> EcallAction {
+        let Some(mut handler) = self.ecall_handler.take() else {
+            return EcallAction::Trap;
+        };
+        let action: EcallAction = if ebreak {
+            handler.ebreak(self, mem)
+        } else {
+            handler.ecall(self, mem)
+        };
+        self.ecall_handler = Some(handler);
+        action
+    }
+
+    /// Raises or clears an interrupt line, as a platform device would
+    pub fn set_interrupt_pending(&mut self, interrupt: Interrupt, pending: bool) {
+        let mip: X = self.csr.get(MIP);
+        let mip: X = if pending {
+            mip | X::from(interrupt.mask())
+        } else {
+            mip & !X::from(interrupt.mask())
+        };
+        self.csr.set(MIP, mip);
+    }
+
+    /// The highest-priority interrupt that is pending, enabled and globally enabled, if any.
+    /// Interrupts for a mode above the current one are always enabled, those for a mode below
+    /// it never are.
+    fn pending_interrupt(&self) -> Option<Interrupt> {
+        let mstatus: X = self.csr.get(MSTATUS);
+        let pending: Word = (self.csr.get(MIP) & self.csr.get(MIE)).to_u64() as Word;
+        let delegated: Word = self.csr.get(MIDELEG).to_u64() as Word;
+
+        let machine_enabled: bool =
+            self.privilege < Privilege::Machine || mstatus & X::from(MSTATUS_MIE) != X::default();
+        let supervisor_enabled: bool = self.privilege < Privilege::Supervisor
+            || (self.privilege == Privilege::Supervisor
+                && mstatus & X::from(MSTATUS_SIE) != X::default());
+        let mut enabled: Word = 0;
+        if machine_enabled {
+            enabled |= pending & !delegated;
+        }
+        if supervisor_enabled {
+            enabled |= pending & delegated;
+        }
+        // priority order: external, software, timer, machine-level before supervisor-level
+        [
+            Interrupt::MachineExternal,
+            Interrupt::MachineSoftware,
+            Interrupt::MachineTimer,
+            Interrupt::SupervisorExternal,
+            Interrupt::SupervisorSoftware,
+            Interrupt::SupervisorTimer,
+        ]
+        .into_iter()
+        .find(|interrupt| enabled & interrupt.mask() != 0)
+    }
+
+    /// Fetch the instruction that pc is pointing to, one 16-bit parcel at a time.
+    /// Compressed instructions are only recognised while the C extension is enabled.
+    pub fn fetch_instruction(&mut self, mem: &Memory) -> Result<Word, Trap<X>> {
+        let pc: X = self.pc;
+        if !pc.is_multiple_of(self.csr.ialign()) {
+            return Err(Trap::new(Exception::InstructionAddressMisaligned, pc));
+        }
+
+        let low_address: usize = self.translate(mem, pc, 2, AccessType::Fetch)?;
+        let low: Word = mem
+            .fetch_halfword(low_address)
+            .map_err(|err| Trap::fetch(err, pc))? as Word;
+        if self.csr.ialign() == 2 && Instruction::is_compressed(low) {
+            self.current_instruction = low;
+            self.instruction_length = 2;
+        } else {
+            let high_address: X = pc.wrapping_add(X::from(2));
+            // the second parcel needs its own translation when it starts the next page
+            let high_physical: usize = if high_address.to_usize().is_multiple_of(PAGE_SIZE) {
+                self.translate(mem, high_address, 2, AccessType::Fetch)?
+            } else {
+                self.check_pmp(low_address + 2, 2, high_address, AccessType::Fetch)?;
+                low_address + 2
+            };
+            let high: Word =
+                mem.fetch_halfword(high_physical)
+                    .map_err(|err| Trap::fetch(err, high_address))? as Word;
+            self.current_instruction = low | (high << 16);
+            self.instruction_length = 4;
+        }
+
+        Ok(self.current_instruction)
+    }
+
+    pub fn increment_pc(&mut self) {
+        self.pc = self.pc.wrapping_add(X::from(self.instruction_length)); // move past the current instruction
+    }
+
+    /// The address of the instruction following the current one, as linked by JAL / JALR
+    fn next_pc(&self) -> X {
+        self.pc.wrapping_add(X::from(self.instruction_length))
+    }
+
+    /// Continues execution at `target_address` once the current instruction completes
+    fn jump(&mut self, target_address: X) {
+        self.jumped = true;
+        // subtract the instruction length because pc will be incremented after execute
+        self.pc = target_address.wrapping_sub(X::from(self.instruction_length));
+    }
+
+    /// Checks that a jump or branch target is aligned to IALIGN (16 bits with C, else 32)
+    fn check_jump_target(&self, target_address: X) -> Result<(), Trap<X>> {
+        if !target_address.is_multiple_of(self.csr.ialign()) {
+            return Err(Trap::new(
+                Exception::InstructionAddressMisaligned,
+                target_address,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Shift amount held in rs2: its low 5 bits on RV32 and 6 bits on RV64
+    fn shift_amount(&self, rs2: usize) -> u32 {
+        (self.reg[rs2].to_u64() & (X::XLEN as u64 - 1)) as u32
+    }
+
+    /// Carry-less product of rs1 and rs2, 2*XLEN-1 bits wide
+    fn carryless_product(&self, rs1: usize, rs2: usize) -> u128 {
+        let (rs1_value, rs2_value): (u64, u64) = (self.reg[rs1].to_u64(), self.reg[rs2].to_u64());
+        (0..X::XLEN)
+            .filter(|bit| (rs2_value >> bit) & 1 != 0)
+            .fold(0, |product, bit| product ^ ((rs1_value as u128) << bit))
+    }
+
+    /// The low 32 bits of rs1 and rs2, the operands of the RV64 *W instructions
+    fn word_operands(&self, rs1: usize, rs2: usize) -> (Word, Word) {
+        (
+            self.reg[rs1].to_u64() as Word,
+            self.reg[rs2].to_u64() as Word,
+        )
+    }
+
+    /// Executes an AMO: rd receives the original memory word and `op` combines it with rs2.
+    /// Faults are always reported as store/AMO exceptions.
+    fn amo(
+        &mut self,
+        mem: &mut Memory,
+        op: AmoOp,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    ) -> Result<(), Trap<X>> {
+        let address: X = self.reg[rs1];
+        let addr: usize = self.atomic_address(mem, address, 4, AccessType::Store)?;
+        let memory_value: Word = mem
+            .atomic_word(addr, op, self.reg[rs2].to_u64() as Word)
+            .map_err(|err| Trap::store(err, address))?;
+        if rd != 0 {
+            self.reg[rd] = X::from_signed_word(memory_value); // sign-extended on RV64
+        }
+        Ok(())
+    }
+
+    /// Executes a doubleword AMO (RV64 only), otherwise like `amo`
+    fn amo_doubleword(
+        &mut self,
+        mem: &mut Memory,
+        op: AmoOp,
+        rs1: usize,
+        rs2: usize,
+        rd: usize,
+    ) -> Result<(), Trap<X>> {
+        let address: X = self.reg[rs1];
+        let addr: usize = self.atomic_address(mem, address, 8, AccessType::Store)?;
+        let memory_value: DoubleWord = mem
+            .atomic_doubleword(addr, op, self.reg[rs2].to_u64())
+            .map_err(|err| Trap::store(err, address))?;
+        if rd != 0 {
+            self.reg[rd] = X::from_u64(memory_value);
+        }
+        Ok(())
+    }
+
+    /// Whether mstatus.TVM makes satp and SFENCE.VMA illegal in the current mode
+    fn trapped_vm(&self) -> bool {
+        self.privilege == Privilege::Supervisor
+            && self.csr.get(MSTATUS) & X::from(MSTATUS_TVM) != X::default()
+    }
+
+    /// Atomically reads and updates a CSR for the Zicsr instructions.
+    /// The old value is only read (with its side effects) when `read` is set, and the value
+    /// produced by `update` is only written when `write` is set.
+    fn csr_read_write(
+        &mut self,
+        csr: usize,
+        rd: usize,
+        read: bool,
+        write: bool,
+        update: impl FnOnce(X) -> X,
+    ) -> Result<(), Exception> {
+        if !CSRFile::<X>::exists(csr) || (write && CSRFile::<X>::is_read_only(csr)) {
+            return Err(Exception::IllegalInstruction);
+        }
+        // bits 9:8 of the address hold the lowest privilege that may access the CSR
+        let required: usize = (csr >> 8) & 0b11;
+        if required > self.privilege as usize
+            || !self.csr.counter_enabled(csr, self.privilege)
+            || (csr == SATP && self.trapped_vm())
+        {
+            return Err(Exception::IllegalInstruction);
+        }
+
+        let old_value: X = if read {
+            self.csr.read(csr)?
+        } else {
+            X::default()
+        };
+        if write {
+            let mut value: X = update(old_value);
+            if csr == MISA {
+                // C cannot be turned off while the next instruction is only 16-bit aligned
+                if !self.next_pc().is_multiple_of(4) {
+                    value = value | X::from(MISA_C);
+                }
+                self.flush_decode_cache(); // cached decodings depend on whether C is enabled
+            }
+            self.csr.write(csr, value)?;
+        }
+        if rd != 0 {
+            self.reg[rd] = old_value;
+        }
+        Ok(())
+    }
+
+    /// Resolves the rm field of an instruction, where 0b111 selects the dynamic mode in frm.
+    /// Reserved rounding modes make the instruction illegal.
+    fn rounding_mode(&self, rm: u32) -> Result<RoundingMode, Exception> {
+        const DYNAMIC: u32 = 0b111;
+        let rm: Word = if rm == DYNAMIC {
+            self.csr.read(FRM)?.to_u64() as Word
+        } else {
+            rm
+        };
+        RoundingMode::from_value(rm).ok_or(Exception::IllegalInstruction)
+    }
+
+    /// Reads an operand of `format` from the floating-point registers
+    fn read_fp(&self, format: FloatFormat, reg: usize) -> u64 {
+        format.unbox(self.freg[reg])
+    }
+
+    /// Writes a result of `format`, NaN-boxed, and accrues the exception flags it raised
+    fn write_fp(&mut self, format: FloatFormat, rd: usize, value: u64, flags: Word) {
+        self.freg[rd] = format.nan_box(value);
+        self.csr.accrue_fflags(flags);
+        self.csr.set_fp_dirty();
+    }
+
+    /// Writes the integer result of a floating-point instruction and accrues its flags
+    fn write_fp_integer(&mut self, rd: usize, value: X, flags: Word) {
+        if rd != 0 {
+            self.reg[rd] = value;
+        }
+        self.csr.accrue_fflags(flags);
+    }
+
+    /// FADD / FSUB / FMUL / FDIV: `rd = rs1 op rs2`
+    fn fp_arithmetic(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rs2, rd): (usize, usize, usize),
+        rm: u32,
+        op: fn(&FloatFormat, u64, u64, RoundingMode, &mut Word) -> u64,
+    ) -> Result<(), Exception> {
+        let rm: RoundingMode = self.rounding_mode(rm)?;
+        let mut flags: Word = 0;
+        let result: u64 = op(
+            &format,
+            self.read_fp(format, rs1),
+            self.read_fp(format, rs2),
+            rm,
+            &mut flags,
+        );
+        self.write_fp(format, rd, result, flags);
+        Ok(())
+    }
+
+    /// FMADD / FMSUB / FNMSUB / FNMADD: `rd = (+-rs1 * rs2) +- rs3` with a single rounding
+    fn fp_fused(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rs2, rs3, rd): (usize, usize, usize, usize),
+        rm: u32,
+        (negate_product, negate_addend): (bool, bool),
+    ) -> Result<(), Exception> {
+        let rm: RoundingMode = self.rounding_mode(rm)?;
+        let (a, b, c) = (
+            self.read_fp(format, rs1),
+            self.read_fp(format, rs2),
+            self.read_fp(format, rs3),
+        );
+        // negating a NaN is harmless since NaN results are canonical anyway
+        let a: u64 = format.with_sign(a, format.sign(a) != negate_product);
+        let c: u64 = format.with_sign(c, format.sign(c) != negate_addend);
+        let mut flags: Word = 0;
+        let result: u64 = format.mul_add(a, b, c, rm, &mut flags);
+        self.write_fp(format, rd, result, flags);
+        Ok(())
+    }
+
+    /// FSGNJ / FSGNJN / FSGNJX: rs1 with a sign computed from the signs of rs1 and rs2
+    fn fp_sign_injection(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rs2, rd): (usize, usize, usize),
+        sign: fn(bool, bool) -> bool,
+    ) {
+        let (a, b) = (self.read_fp(format, rs1), self.read_fp(format, rs2));
+        let result: u64 = format.with_sign(a, sign(format.sign(a), format.sign(b)));
+        self.write_fp(format, rd, result, 0);
+    }
+
+    /// FMIN / FMAX / FMINM / FMAXM
+    fn fp_min_max(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rs2, rd): (usize, usize, usize),
+        max: bool,
+        op: fn(&FloatFormat, u64, u64, bool, &mut Word) -> u64,
+    ) {
+        let mut flags: Word = 0;
+        let result: u64 = op(
+            &format,
+            self.read_fp(format, rs1),
+            self.read_fp(format, rs2),
+            max,
+            &mut flags,
+        );
+        self.write_fp(format, rd, result, flags);
+    }
+
+    /// FEQ / FLT / FLE: writes 1 to rd when rs1 and rs2 are ordered as one of `accepted`
+    fn fp_compare(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rs2, rd): (usize, usize, usize),
+        signaling: bool,
+        accepted: &[Ordering],
+    ) {
+        let mut flags: Word = 0;
+        let ordering: Option<Ordering> = format.compare(
+            self.read_fp(format, rs1),
+            self.read_fp(format, rs2),
+            signaling,
+            &mut flags,
+        );
+        let result: bool = ordering.is_some_and(|ordering| accepted.contains(&ordering));
+        self.write_fp_integer(rd, X::from(result as Word), flags);
+    }
+
+    /// FCVT.W / FCVT.WU / FCVT.L / FCVT.LU: float to a `width`-bit integer.
+    /// Like every 32-bit result, the W forms are sign-extended on RV64.
+    fn fp_to_int(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rd): (usize, usize),
+        rm: u32,
+        (signed, width): (bool, u32),
+    ) -> Result<(), Exception> {
+        let rm: RoundingMode = self.rounding_mode(rm)?;
+        let mut flags: Word = 0;
+        let result: u64 = format.to_int(self.read_fp(format, rs1), signed, width, rm, &mut flags);
+        let shift: u32 = 64 - width;
+        self.write_fp_integer(rd, X::from_i64(((result << shift) as i64) >> shift), flags);
+        Ok(())
+    }
+
+    /// FCVT.<fmt>.W / FCVT.<fmt>.WU / FCVT.<fmt>.L / FCVT.<fmt>.LU: `width`-bit integer to float
+    fn fp_from_int(
+        &mut self,
+        format: FloatFormat,
+        (rs1, rd): (usize, usize),
+        rm: u32,
+        (signed, width): (bool, u32),
+    ) -> Result<(), Exception> {
+        let rm: RoundingMode = self.rounding_mode(rm)?;
+        let mut flags: Word = 0;
+        let result: u64 = format.from_int(self.reg[rs1].to_u64(), signed, width, rm, &mut flags);
+        self.write_fp(format, rd, result, flags);
+        Ok(())
+    }
+
+    /// FSQRT and FCVT between floating-point formats: `rd = op(rs1)` rounded into `to`
+    fn fp_unary(
+        &mut self,
+        (from, to): (FloatFormat, FloatFormat),
+        (rs1, rd): (usize, usize),
+        rm: u32,
+        op: impl Fn(u64, RoundingMode, &mut Word) -> u64,
+    ) -> Result<(), Exception> {
+        let rm: RoundingMode = self.rounding_mode(rm)?;
+        let mut flags: Word = 0;
+        let result: u64 = op(self.read_fp(from, rs1), rm, &mut flags);
+        self.write_fp(to, rd, result, flags);
+        Ok(())
+    }
+
+    /// AVL requested by vsetvli / vsetvl: x[rs1], VLMAX when rs1 is x0 and rd is not, or the
+    /// current vl when both are x0
+    fn vector_avl(&self, rs1: usize, rd: usize) -> Option<u64> {
+        if rs1 != 0 {
+            Some(self.reg[rs1].to_u64())
+        } else if rd != 0 {
+            Some(u64::MAX)
+        } else {
+            None
+        }
+    }
+
+    /// Sets vtype and vl = min(AVL, VLMAX), or vill and vl = 0 when `vtype` is not supported
+    fn vset(&mut self, vtype: u64, avl: Option<u64>, rd: usize) {
+        let vl: u64 = match VType::decode(vtype, self.vreg.elen()) {
+            Some(decoded) => {
+                let vlmax: u64 = decoded.vlmax(self.vreg.vlen()) as u64;
+                self.csr.set(VTYPE, X::from_u64(vtype));
+                avl.unwrap_or(self.csr.get(VL).to_u64()).min(vlmax)
+            }
+            None => {
+                self.csr.set(VTYPE, X::from_u64(1 << (X::XLEN - 1)));
+                0
+            }
+        };
+        self.csr.set(VL, X::from_u64(vl));
+        if rd != 0 {
+            self.reg[rd] = X::from_u64(vl);
+        }
+    }
+
+    /// The current vtype, vl and vstart. Instructions that depend on vtype are illegal while
+    /// vill is set.
+    fn vector_state(&self) -> Result<(VType, usize, usize), Exception> {
+        let vtype: VType = VType::decode(self.csr.get(VTYPE).to_u64(), self.vreg.elen())
+            .ok_or(Exception::IllegalInstruction)?;
+        Ok((
+            vtype,
+            self.csr.get(VL).to_usize(),
+            self.csr.get(VSTART).to_usize(),
+        ))
+    }
+
+    /// Checks that `reg` starts a legal group of elements 2^`scale` times SEW wide
+    fn check_vector_group(&self, vtype: VType, reg: usize, scale: i32) -> Result<(), Exception> {
+        let emul_log2: i32 = vtype.lmul_log2 + scale;
+        if emul_log2 > 3 || vtype.sew << scale > self.vreg.elen() || !group_aligned(reg, emul_log2)
+        {
+            return Err(Exception::IllegalInstruction);
+        }
+        Ok(())
+    }
+
+    /// Element `index` of the second source of a .vv / .vx / .vi instruction, truncated to SEW
+    fn vector_operand(&self, operand: VectorOperand, index: usize, sew: usize) -> u64 {
+        match operand {
+            VectorOperand::Vector(vs1) => self.vreg.element(vs1, index, sew),
+            VectorOperand::Scalar(rs1) => truncate_element(self.reg[rs1].to_i64() as u64, sew),
+            VectorOperand::Immediate(imm) => truncate_element(imm as i64 as u64, sew),
+        }
+    }
+
+    /// Whether element `index` is active: every element is when vm is set, otherwise v0 decides
+    fn vector_active(&self, vm: bool, index: usize) -> bool {
+        vm || self.vreg.mask_bit(0, index)
+    }
+
+    /// Runs `op(vs2, operand, vd, sew)` on the active body elements. The arguments are
+    /// zero-extended from their element widths and the result is truncated to the destination
+    /// width. Inactive and tail elements are left undisturbed.
+    fn vector_elementwise(
+        &mut self,
+        (vd, vs2, operand, vm): (usize, usize, VectorOperand, bool),
+        widths: ElementWidths,
+        op: impl Fn(u64, u64, u64, usize) -> u64,
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        let (vd_scale, vs2_scale) = widths.scales();
+        self.check_vector_group(vtype, vd, vd_scale)?;
+        self.check_vector_group(vtype, vs2, vs2_scale)?;
+        if let VectorOperand::Vector(vs1) = operand {
+            self.check_vector_group(vtype, vs1, 0)?;
+        }
+        if !vm && vd == 0 {
+            return Err(Exception::IllegalInstruction); // the mask would be overwritten
+        }
+        let sew: usize = vtype.sew;
+        let results: Vec<(usize, u64)> = (vstart..vl)
+            .filter(|&i| self.vector_active(vm, i))
+            .map(|i| {
+                let a: u64 = self.vreg.element(vs2, i, sew << vs2_scale);
+                let b: u64 = self.vector_operand(operand, i, sew);
+                let d: u64 = self.vreg.element(vd, i, sew << vd_scale);
+                (i, op(a, b, d, sew))
+            })
+            .collect();
+        for (i, value) in results {
+            self.vreg.set_element(vd, i, sew << vd_scale, value);
+        }
+        Ok(())
+    }
+
+    /// Writes `op(vs2, operand, carry, sew)` to the mask register vd for the active body
+    /// elements. With `carry` set, v0 supplies a carry-in instead of masking (vmadc / vmsbc).
+    fn vector_compare(
+        &mut self,
+        (vd, vs2, operand, vm): (usize, usize, VectorOperand, bool),
+        carry: bool,
+        op: impl Fn(u64, u64, bool, usize) -> bool,
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        self.check_vector_group(vtype, vs2, 0)?;
+        if let VectorOperand::Vector(vs1) = operand {
+            self.check_vector_group(vtype, vs1, 0)?;
+        }
+        let sew: usize = vtype.sew;
+        let results: Vec<(usize, bool)> = (vstart..vl)
+            .filter(|&i| carry || self.vector_active(vm, i))
+            .map(|i| {
+                let a: u64 = self.vreg.element(vs2, i, sew);
+                let b: u64 = self.vector_operand(operand, i, sew);
+                (i, op(a, b, carry && self.vreg.mask_bit(0, i), sew))
+            })
+            .collect();
+        for (i, value) in results {
+            self.vreg.set_mask_bit(vd, i, value);
+        }
+        Ok(())
+    }
+
+    /// Writes `op(vs2, operand, v0, sew)` to every body element of vd (vadc / vsbc / vmerge)
+    fn vector_carry(
+        &mut self,
+        (vd, vs2, operand): (usize, usize, VectorOperand),
+        op: impl Fn(u64, u64, bool, usize) -> u64,
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        self.check_vector_group(vtype, vd, 0)?;
+        self.check_vector_group(vtype, vs2, 0)?;
+        if let VectorOperand::Vector(vs1) = operand {
+            self.check_vector_group(vtype, vs1, 0)?;
+        }
+        if vd == 0 {
+            return Err(Exception::IllegalInstruction); // v0 holds the carries
+        }
+        let sew: usize = vtype.sew;
+        for i in vstart..vl {
+            let a: u64 = self.vreg.element(vs2, i, sew);
+            let b: u64 = self.vector_operand(operand, i, sew);
+            let value: u64 = op(a, b, self.vreg.mask_bit(0, i), sew);
+            self.vreg.set_element(vd, i, sew, value);
+        }
+        Ok(())
+    }
+
+    /// vzext / vsext: widens the vs2 elements SEW / `factor` bits wide to SEW
+    fn vector_extend(
+        &mut self,
+        (vd, vs2, vm): (usize, usize, bool),
+        factor: u32,
+        signed: bool,
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        let factor_log2: i32 = factor.trailing_zeros() as i32;
+        let source_width: usize = vtype.sew >> factor_log2;
+        let source_emul_log2: i32 = vtype.lmul_log2 - factor_log2;
+        if source_width < 8 || source_emul_log2 < -3 || !group_aligned(vs2, source_emul_log2) {
+            return Err(Exception::IllegalInstruction);
+        }
+        self.check_vector_group(vtype, vd, 0)?;
+        if !vm && vd == 0 {
+            return Err(Exception::IllegalInstruction);
+        }
+        let results: Vec<(usize, u64)> = (vstart..vl)
+            .filter(|&i| self.vector_active(vm, i))
+            .map(|i| {
+                let value: u64 = self.vreg.element(vs2, i, source_width);
+                if signed {
+                    (i, sign_extend_element(value, source_width))
+                } else {
+                    (i, value)
+                }
+            })
+            .collect();
+        for (i, value) in results {
+            self.vreg.set_element(vd, i, vtype.sew, value);
+        }
+        Ok(())
+    }
+
+    /// Folds the active elements of vs2 into vs1[0] with `op(accumulator, element, sew)` and
+    /// writes the result to vd[0]. Widening reductions accumulate at 2 * SEW.
+    fn vector_reduction(
+        &mut self,
+        (vd, vs2, vs1, vm): (usize, usize, usize, bool),
+        widening: bool,
+        op: impl Fn(u64, u64, usize) -> u64,
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        if vstart != 0 {
+            return Err(Exception::IllegalInstruction);
+        }
+        self.check_vector_group(vtype, vs2, 0)?;
+        let width: usize = vtype.sew << widening as usize;
+        if width > self.vreg.elen() {
+            return Err(Exception::IllegalInstruction);
+        }
+        if vl == 0 {
+            return Ok(()); // vd is left unchanged
+        }
+        let result: u64 = (0..vl)
+            .filter(|&i| self.vector_active(vm, i))
+            .fold(self.vreg.element(vs1, 0, width), |accumulator, i| {
+                op(accumulator, self.vreg.element(vs2, i, vtype.sew), vtype.sew)
+            });
+        self.vreg.set_element(vd, 0, width, result);
+        Ok(())
+    }
+
+    /// Combines the body bits of the masks in vs2 and vs1 with `op`
+    fn vector_mask_logical(
+        &mut self,
+        (vd, vs2, vs1): (usize, usize, usize),
+        op: impl Fn(bool, bool) -> bool,
+    ) -> Result<(), Exception> {
+        let (_, vl, vstart) = self.vector_state()?;
+        for i in vstart..vl {
+            let value: bool = op(self.vreg.mask_bit(vs2, i), self.vreg.mask_bit(vs1, i));
+            self.vreg.set_mask_bit(vd, i, value);
+        }
+        Ok(())
+    }
+
+    /// Indices of the active elements whose bit is set in the mask vs2 (vcpop / vfirst)
+    fn vector_mask_indices(&self, vs2: usize, vm: bool) -> Result<Vec<usize>, Exception> {
+        let (_, vl, vstart) = self.vector_state()?;
+        if vstart != 0 {
+            return Err(Exception::IllegalInstruction);
+        }
+        Ok((0..vl)
+            .filter(|&i| self.vector_active(vm, i) && self.vreg.mask_bit(vs2, i))
+            .collect())
+    }
+
+    /// vmsbf / vmsif / vmsof: sets each active bit of vd to `op(before_first, is_first)`, where
+    /// the first is the lowest active set bit of vs2
+    fn vector_set_first(
+        &mut self,
+        (vd, vs2, vm): (usize, usize, bool),
+        op: impl Fn(bool, bool) -> bool,
+    ) -> Result<(), Exception> {
+        let (_, vl, vstart) = self.vector_state()?;
+        if vstart != 0 || vd == vs2 || (!vm && vd == 0) {
+            return Err(Exception::IllegalInstruction);
+        }
+        let mut found: bool = false;
+        for i in 0..vl {
+            if !self.vector_active(vm, i) {
+                continue;
+            }
+            let bit: bool = self.vreg.mask_bit(vs2, i);
+            self.vreg
+                .set_mask_bit(vd, i, op(!found && !bit, !found && bit));
+            found |= bit;
+        }
+        Ok(())
+    }
+
+    /// viota writes the number of active set bits of the mask vs2 below each element,
+    /// vid (`vs2` of None) writes the element indices
+    fn vector_iota(
+        &mut self,
+        (vd, vs2, vm): (usize, Option<usize>, bool),
+    ) -> Result<(), Exception> {
+        let (vtype, vl, vstart) = self.vector_state()?;
+        self.check_vector_group(vtype, vd, 0)?;
+        if !vm && vd == 0 {
+            return Err(Exception::IllegalInstruction);
+        }
+        let Some(vs2) = vs2 else {
+            for i in vstart..vl {
+                if !self.vector_active(vm, i) {
+                    continue;
+                }
+                self.vreg.set_element(vd, i, vtype.sew, i as u64);
+            }
+            return Ok(());
+        };
+        let group: usize = 1 << vtype.lmul_log2.max(0);
+        if vstart != 0 || (vd..vd + group).contains(&vs2) {
+            return Err(Exception::IllegalInstruction);
+        }
+        let mut count: u64 = 0;
+        for i in 0..vl {
+            if !self.vector_active(vm, i) {
+                continue;
+            }
+            let bit: bool = self.vreg.mask_bit(vs2, i);
+            self.vreg.set_element(vd, i, vtype.sew, count);
+            count += bit as u64;
+        }
+        Ok(())
+    }
+
+    /// Element width, number of elements, fields and registers per field of a vector load or
+    /// store. `eew` is the index width of the indexed forms, whose data is SEW wide.
+    fn vector_access_layout(
+        &self,
+        addressing: VectorAddressing,
+        eew: usize,
+        nf: usize,
+        vd: usize,
+    ) -> Result<(usize, usize, usize, usize), Exception> {
+        match addressing {
+            VectorAddressing::WholeRegister => {
+                // whole registers are moved regardless of vtype and vl
+                if !vd.is_multiple_of(nf) {
+                    return Err(Exception::IllegalInstruction);
+                }
+                return Ok((eew, nf * self.vreg.vlen() / eew, 1, nf));
+            }
+            VectorAddressing::Mask => {
+                let (_, vl, _) = self.vector_state()?;
+                return Ok((8, vl.div_ceil(8), 1, 1));
+            }
+            _ => {}
+        }
+        let (vtype, vl, _) = self.vector_state()?;
+        // EMUL = EEW / SEW * LMUL
+        let scaled_log2: i32 =
+            vtype.lmul_log2 + eew.trailing_zeros() as i32 - vtype.sew.trailing_zeros() as i32;
+        if !(-3..=3).contains(&scaled_log2) || eew > self.vreg.elen() {
+            return Err(Exception::IllegalInstruction);
+        }
+        let (width, emul_log2) = match addressing {
+            VectorAddressing::Indexed(vs2) => {
+                if !group_aligned(vs2, scaled_log2) {
+                    return Err(Exception::IllegalInstruction);
+                }
+                (vtype.sew, vtype.lmul_log2)
+            }
+            _ => (eew, scaled_log2),
+        };
+        let registers: usize = 1 << emul_log2.max(0);
+        if nf * registers > 8
+            || vd + nf * registers > VECTOR_REGISTER_COUNT
+            || !group_aligned(vd, emul_log2)
+        {
+            return Err(Exception::IllegalInstruction);
+        }
+        Ok((width, vl, nf, registers))
+    }
+
+    /// Byte offset from x[rs1] of field `field` of element `index`
+    fn vector_element_offset(
+        &self,
+        addressing: VectorAddressing,
+        eew: usize,
+        index: usize,
+        field: usize,
+        width: usize,
+        fields: usize,
+    ) -> u64 {
+        let field_offset: u64 = (field * width / 8) as u64;
+        match addressing {
+            VectorAddressing::Strided(rs2) => (self.reg[rs2].to_i64() as u64)
+                .wrapping_mul(index as u64)
+                .wrapping_add(field_offset),
+            VectorAddressing::Indexed(vs2) => self
+                .vreg
+                .element(vs2, index, eew)
+                .wrapping_add(field_offset),
+            _ => (index * fields * width / 8) as u64 + field_offset,
+        }
+    }
+
+    /// Vector loads. A fault stops the load with vstart at the faulting element, except that
+    /// a fault-only-first load shrinks vl instead when the element is not the first.
+    fn vector_load(
+        &mut self,
+        mem: &Memory,
+        addressing: VectorAddressing,
+        (eew, nf, vm): (usize, usize, bool),
+        (rs1, vd): (usize, usize),
+        fault_first: bool,
+    ) -> Result<(), Trap<X>> {
+        let instruction: X = X::from(self.current_instruction);
+        let (width, evl, fields, registers) = self
+            .vector_access_layout(addressing, eew, nf, vd)
+            .map_err(|cause| Trap::new(cause, instruction))?;
+        if !vm && vd == 0 {
+            return Err(Trap::new(Exception::IllegalInstruction, instruction));
+        }
+        let base: X = self.reg[rs1];
+        for i in self.csr.get(VSTART).to_usize()..evl {
+            if !self.vector_active(vm, i) {
+                continue;
+            }
+            for field in 0..fields {
+                let offset: u64 =
+                    self.vector_element_offset(addressing, eew, i, field, width, fields);
+                let address: X = base.wrapping_add(X::from_u64(offset));
+                match self.load(mem, address, width / 8) {
+                    Ok(value) => self
+                        .vreg
+                        .set_element(vd + field * registers, i, width, value),
+                    Err(_) if fault_first && i != 0 => {
+                        self.csr.set(VL, X::from_u64(i as u64));
+                        return Ok(());
+                    }
+                    Err(trap) => {
+                        self.csr.set(VSTART, X::from_u64(i as u64));
+                        self.csr.set_vector_dirty();
+                        return Err(trap);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Vector stores. A fault stops the store with vstart at the faulting element.
+    fn vector_store(
+        &mut self,
+        mem: &mut Memory,
+        addressing: VectorAddressing,
+        (eew, nf, vm): (usize, usize, bool),
+        (rs1, vs3): (usize, usize),
+    ) -> Result<(), Trap<X>> {
+        let instruction: X = X::from(self.current_instruction);
+        let (width, evl, fields, registers) = self
+            .vector_access_layout(addressing, eew, nf, vs3)
+            .map_err(|cause| Trap::new(cause, instruction))?;
+        let base: X = self.reg[rs1];
+        for i in self.csr.get(VSTART).to_usize()..evl {
+            if !self.vector_active(vm, i) {
+                continue;
+            }
+            for field in 0..fields {
+                let offset: u64 =
+                    self.vector_element_offset(addressing, eew, i, field, width, fields);
+                let address: X = base.wrapping_add(X::from_u64(offset));
+                let value: u64 = self.vreg.element(vs3 + field * registers, i, width);
+                if let Err(trap) = self.store(mem, address, width / 8, value) {
+                    self.csr.set(VSTART, X::from_u64(i as u64));
+                    self.csr.set_vector_dirty();
+                    return Err(trap);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the current instruction, reusing the cached decoding for this pc when the
+    /// instruction bits have not changed
+    fn decode(&mut self) -> Result<Instruction, Exception> {
+        let index: usize = (self.pc.to_usize() >> 1) % DECODE_CACHE_SIZE;
+        if let Some(cached) = self.decode_cache[index]
+            && cached.pc == self.pc
+            && cached.raw == self.current_instruction
+        {
+            return Ok(cached.instruction);
+        }
+
+        let instruction: Instruction = if self.instruction_length == 2 {
+            Instruction::parse_compressed_xlen(self.current_instruction as HalfWord, X::XLEN)?
+        } else {
+            let encoding: EncodingVariant =
+                EncodingVariant::get_encoding(self.current_instruction)?;
+            Instruction::parse_instruction_xlen(encoding, X::XLEN)?
+        };
+        self.decode_cache[index] = Some(DecodedInstruction {
+            pc: self.pc,
+            raw: self.current_instruction,
+            instruction,
+        });
+        Ok(instruction)
+    }
+
+    /// Loads `size` bytes (1, 2, 4 or 8) at `address`, zero-extended. A misaligned address is
+    /// split into byte accesses when `misaligned_access` allows it.
+    fn load(&mut self, mem: &Memory, address: X, size: usize) -> Result<u64, Trap<X>> {
+        if address.to_usize().is_multiple_of(size) {
+            let addr: usize = self.translate(mem, address, size, AccessType::Load)?;
+            return mem
+                .fetch_sized(addr, size)
+                .map_err(|err| Trap::load(err, address));
+        }
+        let MisalignedAccess::Emulate { penalty } = self.misaligned_access else {
+            return Err(Trap::new(Exception::LoadAddressMisaligned, address));
+        };
+        self.csr.stall(penalty);
+        let mut value: u64 = 0;
+        let mut shift: usize = 0;
+        let parts: [(usize, usize); 2] =
+            self.translate_split(mem, address, size, AccessType::Load)?;
+        for (addr, length) in parts.into_iter().filter(|&(_, length)| length > 0) {
+            let part: u64 = mem
+                .fetch_unaligned(addr, length)
+                .map_err(|err| Trap::load(err, address))?;
+            value |= part << shift;
+            shift += 8 * length;
+        }
+        Ok(value)
+    }
+
+    /// Stores the low `size` bytes (1, 2, 4 or 8) of `value` at `address`, like `load`
+    fn store(
+        &mut self,
+        mem: &mut Memory,
+        address: X,
+        size: usize,
+        value: u64,
+    ) -> Result<(), Trap<X>> {
+        if address.to_usize().is_multiple_of(size) {
+            let addr: usize = self.translate(mem, address, size, AccessType::Store)?;
+            return mem
+                .store_sized(addr, size, value)
+                .map_err(|err| Trap::store(err, address));
+        }
+        let MisalignedAccess::Emulate { penalty } = self.misaligned_access else {
+            return Err(Trap::new(Exception::StoreAddressMisaligned, address));
+        };
+        self.csr.stall(penalty);
+        let parts: [(usize, usize); 2] =
+            self.translate_split(mem, address, size, AccessType::Store)?;
+        // nothing is written unless both parts exist
+        if parts
+            .iter()
+            .any(|&(addr, length)| !Memory::contains(addr, length))
+        {
+            return Err(Trap::new(Exception::StoreAccessFault, address));
+        }
+        let mut shift: usize = 0;
+        for (addr, length) in parts.into_iter().filter(|&(_, length)| length > 0) {
+            mem.store_unaligned(addr, length, value >> shift)
+                .map_err(|err| Trap::store(err, address))?;
+            shift += 8 * length;
+        }
+        Ok(())
+    }
+
+    /// Translates the `size` bytes at a misaligned `address`, which may straddle a page
+    /// boundary, into the physical address and length of the part in each page. The second
+    /// part is empty when the access fits in one page.
+    fn translate_split(
+        &mut self,
+        mem: &Memory,
+        address: X,
+        size: usize,
+        access: AccessType,
+    ) -> Result<[(usize, usize); 2], Trap<X>> {
+        let in_page: usize = PAGE_SIZE - address.to_usize() % PAGE_SIZE;
+        if size <= in_page {
+            let low: usize = self.translate(mem, address, size, access)?;
+            return Ok([(low, size), (0, 0)]);
+        }
+        let low: usize = self.translate(mem, address, in_page, access)?;
+        let high_address: X = address.wrapping_add(X::from(in_page as Word));
+        let high: usize = self.translate(mem, high_address, size - in_page, access)?;
+        Ok([(low, in_page), (high, size - in_page)])
+    }
+
+    /// Checks that an LR, SC or AMO address is naturally aligned and translates it
+    fn atomic_address(
+        &mut self,
+        mem: &Memory,
+        address: X,
+        size: usize,
+        access: AccessType,
+    ) -> Result<usize, Trap<X>> {
+        if !address.to_usize().is_multiple_of(size) {
+            let cause: Exception = if access == AccessType::Load {
+                Exception::LoadAddressMisaligned
+            } else {
+                Exception::StoreAddressMisaligned
+            };
+            return Err(Trap::new(cause, address));
+        }
+        self.translate(mem, address, size, access)
+    }
+
+    /// Translates the cache block holding `address` for a CBO, which PMP must allow as a whole.
+    /// Faults report `address` itself.
+    fn translate_block(
+        &mut self,
+        mem: &Memory,
+        address: X,
+        access: AccessType,
+    ) -> Result<usize, Trap<X>> {
+        let block_address: X = address & !X::from((self.cache_block_size - 1) as Word);
+        self.translate(mem, block_address, self.cache_block_size, access)
+            .map_err(|trap| Trap::new(trap.cause, address))
+    }
+
+    /// Drops every cached decoded instruction (FENCE.I)
+    pub fn flush_decode_cache(&mut self) {
+        self.decode_cache.fill(None);
+    }
+
+    pub fn execute(&mut self, mem: &mut Memory) -> Result<(), Trap<X>> {
+        self.execute_instruction(mem).map(|_| ())
+    }
+
+    /// Executes the current instruction, returning its decoded form once it completes and
+    /// whether it jumped or took its branch, even to the next instruction
+    fn execute_instruction(&mut self, mem: &mut Memory) -> Result<(Instruction, bool), Trap<X>> {
+        self.jumped = false;
+        let instruction: X = X::from(self.current_instruction);
+        let illegal = move |cause: Exception| Trap::new(cause, instruction);
+        let parsed_instruction: Instruction = self.decode().map_err(illegal)?;
+        if parsed_instruction.uses_fpu() && !self.csr.fp_enabled() {
+            return Err(illegal(Exception::IllegalInstruction)); // mstatus.FS is Off
+        }
+        if parsed_instruction.uses_vector() && !self.csr.vector_enabled() {
+            return Err(illegal(Exception::IllegalInstruction)); // mstatus.VS is Off
+        }
+        if self.csr.is_embedded()
+            && parsed_instruction
+                .integer_registers()
+                .iter()
+                .any(|&reg| reg >= EMBEDDED_REGISTER_COUNT)
+        {
+            return Err(illegal(Exception::IllegalInstruction)); // x16-x31 do not exist
+        }
+        match parsed_instruction {
+            Instruction::ADDI { imm, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].wrapping_add_signed(imm);
+                }
+            }
+            Instruction::SLTI { imm, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    self.reg[rd] = X::from(if rs1_value < imm as i64 { 1 } else { 0 });
+                }
+            }
+            Instruction::STLIU { imm, rs1, rd } => {
+                if rd != 0 {
+                    // the immediate is sign-extended first, then compared as unsigned
+                    let rs1_value: X = self.reg[rs1];
+                    self.reg[rd] = X::from(if rs1_value < X::from_signed_word(imm) {
+                        1
+                    } else {
+                        0
+                    })
+                }
+            }
+            Instruction::ANDI { imm, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] & X::from_signed_word(imm);
+                }
+            }
+            Instruction::ORI { imm, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] | X::from_signed_word(imm);
+                }
+            }
+            Instruction::XORI { imm, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] ^ X::from_signed_word(imm);
+                }
+            }
+            Instruction::SLLI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] << shamt;
+                }
+            }
+            Instruction::SRLI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] >> shamt;
+                }
+            }
+            Instruction::SRAI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    self.reg[rd] = X::from_i64(rs1_value >> shamt);
+                }
+            }
+            Instruction::LUI { imm, rd } => {
+                if rd != 0 {
+                    // x0 is hardwired to 0
+                    self.reg[rd] = X::from_signed_word(imm);
+                }
+            }
+            Instruction::AUIPC { imm, rd } => {
+                if rd != 0 {
+                    // x0 is hardwired to 0
+                    self.reg[rd] = self.pc.wrapping_add(X::from_signed_word(imm));
+                }
+            }
+            Instruction::ADD { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SUB { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].wrapping_sub(self.reg[rs2]);
+                }
+            }
+            Instruction::SLT { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    let rs2_value: i64 = self.reg[rs2].to_i64();
+                    self.reg[rd] = X::from(if rs1_value < rs2_value { 1 } else { 0 });
+                }
+            }
+            Instruction::SLTU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: X = self.reg[rs1];
+                    let rs2_value: X = self.reg[rs2];
+                    self.reg[rd] = X::from(if rs1_value < rs2_value { 1 } else { 0 })
+                }
+            }
+            Instruction::AND { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] & self.reg[rs2];
+                }
+            }
+            Instruction::OR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] | self.reg[rs2];
+                }
+            }
+            Instruction::XOR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] ^ self.reg[rs2];
+                }
+            }
+            Instruction::SLL { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let shamt: u32 = self.shift_amount(rs2);
+                    let rs1_value: X = self.reg[rs1];
+                    self.reg[rd] = rs1_value << shamt;
+                }
+            }
+            Instruction::SRL { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let shamt: u32 = self.shift_amount(rs2);
+                    let rs1_value: X = self.reg[rs1];
+                    self.reg[rd] = rs1_value >> shamt;
+                }
+            }
+            Instruction::SRA { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let shamt: u32 = self.shift_amount(rs2);
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    self.reg[rd] = X::from_i64(rs1_value >> shamt);
+                }
+            }
+            Instruction::ADDIW { imm, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(rs1_value.wrapping_add_signed(imm));
+                }
+            }
+            Instruction::SLLIW { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(rs1_value << shamt);
+                }
+            }
+            Instruction::SRLIW { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(rs1_value >> shamt);
+                }
+            }
+            Instruction::SRAIW { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: i32 = self.reg[rs1].to_u64() as i32;
+                    self.reg[rd] = X::from_signed_word((rs1_value >> shamt) as Word);
+                }
+            }
+            Instruction::ADDW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word(rs1_value.wrapping_add(rs2_value));
+                }
+            }
+            Instruction::SUBW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word(rs1_value.wrapping_sub(rs2_value));
+                }
+            }
+            Instruction::SLLW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let shamt: u32 = rs2_value & 0b11111; // shift amount is in lower 5 bits
+                    self.reg[rd] = X::from_signed_word(rs1_value << shamt);
+                }
+            }
+            Instruction::SRLW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let shamt: u32 = rs2_value & 0b11111; // shift amount is in lower 5 bits
+                    self.reg[rd] = X::from_signed_word(rs1_value >> shamt);
+                }
+            }
+            Instruction::SRAW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let shamt: u32 = rs2_value & 0b11111; // shift amount is in lower 5 bits
+                    self.reg[rd] = X::from_signed_word(((rs1_value as i32) >> shamt) as Word);
+                }
+            }
+            Instruction::MUL { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let product: u64 = self.reg[rs1].to_u64().wrapping_mul(self.reg[rs2].to_u64());
+                    self.reg[rd] = X::from_u64(product); // lower XLEN bits of the product
+                }
+            }
+            Instruction::MULH { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: i128 = self.reg[rs1].to_i64() as i128;
+                    let rs2_value: i128 = self.reg[rs2].to_i64() as i128;
+                    // upper XLEN bits of the product
+                    self.reg[rd] = X::from_i64(((rs1_value * rs2_value) >> X::XLEN) as i64);
+                }
+            }
+            Instruction::MULHSU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: i128 = self.reg[rs1].to_i64() as i128; // signed
+                    let rs2_value: i128 = self.reg[rs2].to_u64() as i128; // unsigned
+                    self.reg[rd] = X::from_i64(((rs1_value * rs2_value) >> X::XLEN) as i64);
+                }
+            }
+            Instruction::MULHU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: u128 = self.reg[rs1].to_u64() as u128;
+                    let rs2_value: u128 = self.reg[rs2].to_u64() as u128;
+                    self.reg[rd] = X::from_u64(((rs1_value * rs2_value) >> X::XLEN) as u64);
+                }
+            }
+            Instruction::DIV { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    let rs2_value: i64 = self.reg[rs2].to_i64();
+                    // division by zero gives -1, overflow (MIN / -1) gives MIN
+                    self.reg[rd] = X::from_i64(if rs2_value == 0 {
+                        -1
+                    } else {
+                        rs1_value.wrapping_div(rs2_value)
+                    });
+                }
+            }
+            Instruction::DIVU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: u64 = self.reg[rs1].to_u64();
+                    let rs2_value: u64 = self.reg[rs2].to_u64();
+                    // division by zero gives all ones
+                    self.reg[rd] =
+                        X::from_u64(rs1_value.checked_div(rs2_value).unwrap_or(u64::MAX));
+                }
+            }
+            Instruction::REM { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: i64 = self.reg[rs1].to_i64();
+                    let rs2_value: i64 = self.reg[rs2].to_i64();
+                    // remainder by zero gives the dividend, overflow (MIN % -1) gives 0
+                    self.reg[rd] = X::from_i64(if rs2_value == 0 {
+                        rs1_value
+                    } else {
+                        rs1_value.wrapping_rem(rs2_value)
+                    });
+                }
+            }
+            Instruction::REMU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: u64 = self.reg[rs1].to_u64();
+                    let rs2_value: u64 = self.reg[rs2].to_u64();
+                    // remainder by zero gives the dividend
+                    self.reg[rd] =
+                        X::from_u64(rs1_value.checked_rem(rs2_value).unwrap_or(rs1_value));
+                }
+            }
+            Instruction::MULW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word(rs1_value.wrapping_mul(rs2_value));
+                }
+            }
+            Instruction::DIVW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let (rs1_value, rs2_value): (i32, i32) = (rs1_value as i32, rs2_value as i32);
+                    self.reg[rd] = X::from_signed_word(if rs2_value == 0 {
+                        u32::MAX
+                    } else {
+                        rs1_value.wrapping_div(rs2_value) as u32
+                    });
+                }
+            }
+            Instruction::DIVUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let quotient: Word = rs1_value.checked_div(rs2_value).unwrap_or(u32::MAX);
+                    self.reg[rd] = X::from_signed_word(quotient);
+                }
+            }
+            Instruction::REMW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let (rs1_value, rs2_value): (i32, i32) = (rs1_value as i32, rs2_value as i32);
+                    self.reg[rd] = X::from_signed_word(if rs2_value == 0 {
+                        rs1_value as u32
+                    } else {
+                        rs1_value.wrapping_rem(rs2_value) as u32
+                    });
+                }
+            }
+            Instruction::REMUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    let remainder: Word = rs1_value.checked_rem(rs2_value).unwrap_or(rs1_value);
+                    self.reg[rd] = X::from_signed_word(remainder);
+                }
+            }
+            Instruction::SH1ADD { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = (self.reg[rs1] << 1).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SH2ADD { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = (self.reg[rs1] << 2).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SH3ADD { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = (self.reg[rs1] << 3).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::ADDUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(rs1_value).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SH1ADDUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = (X::from(rs1_value) << 1).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SH2ADDUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = (X::from(rs1_value) << 2).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SH3ADDUW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = (X::from(rs1_value) << 3).wrapping_add(self.reg[rs2]);
+                }
+            }
+            Instruction::SLLIUW { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(rs1_value) << shamt; // zero-extended before the shift
+                }
+            }
+            Instruction::ANDN { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] & !self.reg[rs2];
+                }
+            }
+            Instruction::ORN { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] | !self.reg[rs2];
+                }
+            }
+            Instruction::XNOR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = !(self.reg[rs1] ^ self.reg[rs2]);
+                }
+            }
+            Instruction::CLZ { rs1, rd } => {
+                if rd != 0 {
+                    // the value is zero-extended to 64 bits, so skip the extra leading zeros
+                    let zeros: u32 = self.reg[rs1].to_u64().leading_zeros() - (64 - X::XLEN);
+                    self.reg[rd] = X::from(zeros);
+                }
+            }
+            Instruction::CTZ { rs1, rd } => {
+                if rd != 0 {
+                    let zeros: u32 = self.reg[rs1].to_u64().trailing_zeros().min(X::XLEN);
+                    self.reg[rd] = X::from(zeros);
+                }
+            }
+            Instruction::CPOP { rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = X::from(self.reg[rs1].to_u64().count_ones());
+                }
+            }
+            Instruction::CLZW { rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(rs1_value.leading_zeros());
+                }
+            }
+            Instruction::CTZW { rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(rs1_value.trailing_zeros());
+                }
+            }
+            Instruction::CPOPW { rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(rs1_value.count_ones());
+                }
+            }
+            Instruction::MIN { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (i64, i64) =
+                        (self.reg[rs1].to_i64(), self.reg[rs2].to_i64());
+                    self.reg[rd] = X::from_i64(rs1_value.min(rs2_value));
+                }
+            }
+            Instruction::MINU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].min(self.reg[rs2]);
+                }
+            }
+            Instruction::MAX { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (i64, i64) =
+                        (self.reg[rs1].to_i64(), self.reg[rs2].to_i64());
+                    self.reg[rd] = X::from_i64(rs1_value.max(rs2_value));
+                }
+            }
+            Instruction::MAXU { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].max(self.reg[rs2]);
+                }
+            }
+            Instruction::SEXTB { rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = X::from_i64(self.reg[rs1].to_u64() as i8 as i64);
+                }
+            }
+            Instruction::SEXTH { rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = X::from_i64(self.reg[rs1].to_u64() as i16 as i64);
+                }
+            }
+            Instruction::ZEXTH { rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = X::from(self.reg[rs1].to_u64() as u16 as Word);
+                }
+            }
+            Instruction::ROL { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].rotate_left(self.shift_amount(rs2));
+                }
+            }
+            Instruction::ROR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].rotate_right(self.shift_amount(rs2));
+                }
+            }
+            Instruction::RORI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1].rotate_right(shamt);
+                }
+            }
+            Instruction::ROLW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word(rs1_value.rotate_left(rs2_value & 0b11111));
+                }
+            }
+            Instruction::RORW { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word(rs1_value.rotate_right(rs2_value & 0b11111));
+                }
+            }
+            Instruction::RORIW { shamt, rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(rs1_value.rotate_right(shamt));
+                }
+            }
+            Instruction::REV8 { rs1, rd } => {
+                if rd != 0 {
+                    // reverse all eight bytes, then drop the ones above XLEN
+                    let reversed: u64 = self.reg[rs1].to_u64().swap_bytes() >> (64 - X::XLEN);
+                    self.reg[rd] = X::from_u64(reversed);
+                }
+            }
+            Instruction::ORCB { rs1, rd } => {
+                if rd != 0 {
+                    let rs1_value: u64 = self.reg[rs1].to_u64();
+                    // every non-zero byte becomes 0xFF
+                    let combined: u64 = (0..8)
+                        .map(|byte| byte * 8)
+                        .filter(|shift| (rs1_value >> shift) & 0xFF != 0)
+                        .fold(0, |result, shift| result | (0xFF << shift));
+                    self.reg[rd] = X::from_u64(combined);
+                }
+            }
+            Instruction::BSET { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] | (X::from(1) << self.shift_amount(rs2));
+                }
+            }
+            Instruction::BSETI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] | (X::from(1) << shamt);
+                }
+            }
+            Instruction::BCLR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] & !(X::from(1) << self.shift_amount(rs2));
+                }
+            }
+            Instruction::BCLRI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] & !(X::from(1) << shamt);
+                }
+            }
+            Instruction::BINV { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] ^ (X::from(1) << self.shift_amount(rs2));
+                }
+            }
+            Instruction::BINVI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = self.reg[rs1] ^ (X::from(1) << shamt);
+                }
+            }
+            Instruction::BEXT { rs1, rs2, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = (self.reg[rs1] >> self.shift_amount(rs2)) & X::from(1);
+                }
+            }
+            Instruction::BEXTI { shamt, rs1, rd } => {
+                if rd != 0 {
+                    self.reg[rd] = (self.reg[rs1] >> shamt) & X::from(1);
+                }
+            }
+            Instruction::CLMUL { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let product: u128 = self.carryless_product(rs1, rs2);
+                    self.reg[rd] = X::from_u64(product as u64); // lower XLEN bits
+                }
+            }
+            Instruction::CLMULH { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let product: u128 = self.carryless_product(rs1, rs2);
+                    self.reg[rd] = X::from_u64((product >> X::XLEN) as u64); // upper XLEN bits
+                }
+            }
+            Instruction::CLMULR { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let product: u128 = self.carryless_product(rs1, rs2);
+                    // bits 2*XLEN-2 down to XLEN-1, the bit-reversed product of the reversed operands
+                    self.reg[rd] = X::from_u64((product >> (X::XLEN - 1)) as u64);
                 }
             }
-            Instruction::XORI { imm, rs1, rd } => {
+            Instruction::PACK { rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] ^ imm;
+                    // the lower halves of rs1 and rs2, rs2 on top
+                    let half: u32 = X::XLEN / 2;
+                    let lower: X = (self.reg[rs1] << half) >> half;
+                    self.reg[rd] = (self.reg[rs2] << half) | lower;
                 }
             }
-            Instruction::SLLI { shamt, rs1, rd } => {
+            Instruction::PACKH { rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] << shamt;
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(((rs2_value & 0xFF) << 8) | (rs1_value & 0xFF));
                 }
             }
-            Instruction::SRLI { shamt, rs1, rd } => {
+            Instruction::PACKW { rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] >> shamt;
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from_signed_word((rs2_value << 16) | (rs1_value & 0xFFFF));
                 }
             }
-            Instruction::SRAI { shamt, rs1, rd } => {
+            Instruction::BREV8 { rs1, rd } => {
                 if rd != 0 {
-                    let rs1_value: i32 = self.reg[rs1] as i32;
-                    self.reg[rd] = (rs1_value >> shamt) as u32;
+                    self.reg[rd] = X::from_u64(brev8(self.reg[rs1].to_u64()));
                 }
             }
-            Instruction::LUI { imm, rd } => {
+            Instruction::ZIP { rs1, rd } => {
                 if rd != 0 {
-                    // x0 is hardwired to 0
-                    self.reg[rd] = imm;
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(zip(rs1_value));
                 }
             }
-            Instruction::AUIPC { imm, rd } => {
+            Instruction::UNZIP { rs1, rd } => {
                 if rd != 0 {
-                    // x0 is hardwired to 0
-                    self.reg[rd] = self.pc.wrapping_add(imm);
+                    let rs1_value: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from(unzip(rs1_value));
                 }
             }
-            Instruction::ADD { rs1, rs2, rd } => {
+            Instruction::XPERM4 { rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1].wrapping_add(self.reg[rs2]);
+                    let (table, indices): (u64, u64) =
+                        (self.reg[rs1].to_u64(), self.reg[rs2].to_u64());
+                    self.reg[rd] = X::from_u64(xperm(table, indices, 4, X::XLEN));
                 }
             }
-            Instruction::SUB { rs1, rs2, rd } => {
+            Instruction::XPERM8 { rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1].wrapping_sub(self.reg[rs2]);
+                    let (table, indices): (u64, u64) =
+                        (self.reg[rs1].to_u64(), self.reg[rs2].to_u64());
+                    self.reg[rd] = X::from_u64(xperm(table, indices, 8, X::XLEN));
                 }
             }
-            Instruction::SLT { rs1, rs2, rd } => {
+            Instruction::AES32ESI { bs, rs1, rs2, rd } => {
                 if rd != 0 {
-                    let rs1_value: i32 = self.reg[rs1] as i32;
-                    let rs2_value: i32 = self.reg[rs2] as i32;
-                    self.reg[rd] = if rs1_value < rs2_value { 1 } else { 0 };
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(aes32_encrypt(rs1_value, rs2_value, bs, false));
                 }
             }
-            Instruction::SLTU { rs1, rs2, rd } => {
+            Instruction::AES32ESMI { bs, rs1, rs2, rd } => {
                 if rd != 0 {
-                    let rs1_value: u32 = self.reg[rs1];
-                    let rs2_value: u32 = self.reg[rs2];
-                    self.reg[rd] = if rs1_value < rs2_value { 1 } else { 0 }
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(aes32_encrypt(rs1_value, rs2_value, bs, true));
                 }
             }
-            Instruction::AND { rs1, rs2, rd } => {
+            Instruction::AES32DSI { bs, rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] & self.reg[rs2];
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(aes32_decrypt(rs1_value, rs2_value, bs, false));
                 }
             }
-            Instruction::OR { rs1, rs2, rd } => {
+            Instruction::AES32DSMI { bs, rs1, rs2, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] | self.reg[rs2];
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(aes32_decrypt(rs1_value, rs2_value, bs, true));
                 }
             }
-            Instruction::XOR { rs1, rs2, rd } => {
+            // the SHA-256 functions work on the low word and sign-extend it on RV64
+            Instruction::SHA256SIG0 { rs1, rd } => {
                 if rd != 0 {
-                    self.reg[rd] = self.reg[rs1] ^ self.reg[rs2];
+                    let x: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] =
+                        X::from_signed_word(x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3));
                 }
             }
-            Instruction::SLL { rs1, rs2, rd } => {
+            Instruction::SHA256SIG1 { rs1, rd } => {
                 if rd != 0 {
-                    let shamt: u32 = (self.reg[rs2] & 0b11111) as u32; // shift amount is in lower 5 bits
-                    let rs1_value: u32 = self.reg[rs1];
-                    self.reg[rd] = (rs1_value << shamt) as u32;
+                    let x: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] =
+                        X::from_signed_word(x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10));
                 }
             }
-            Instruction::SRL { rs1, rs2, rd } => {
+            Instruction::SHA256SUM0 { rs1, rd } => {
                 if rd != 0 {
-                    let shamt: u32 = (self.reg[rs2] & 0b11111) as u32; // shift amount is in lower 5 bits
-                    let rs1_value: u32 = self.reg[rs1];
-                    self.reg[rd] = (rs1_value >> shamt) as u32;
+                    let x: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(
+                        x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22),
+                    );
                 }
             }
-            Instruction::SRA { rs1, rs2, rd } => {
+            Instruction::SHA256SUM1 { rs1, rd } => {
+                if rd != 0 {
+                    let x: Word = self.reg[rs1].to_u64() as Word;
+                    self.reg[rd] = X::from_signed_word(
+                        x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25),
+                    );
+                }
+            }
+            // RV32 SHA-512: one half of the 64-bit result from the two halves of the input
+            Instruction::SHA512SIG0L { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (low, high): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (low >> 1)
+                            ^ (low >> 7)
+                            ^ (low >> 8)
+                            ^ (high << 31)
+                            ^ (high << 25)
+                            ^ (high << 24),
+                    );
+                }
+            }
+            Instruction::SHA512SIG0H { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (high, low): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (high >> 1) ^ (high >> 7) ^ (high >> 8) ^ (low << 31) ^ (low << 24),
+                    );
+                }
+            }
+            Instruction::SHA512SIG1L { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (low, high): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (low << 3)
+                            ^ (low >> 6)
+                            ^ (low >> 19)
+                            ^ (high >> 29)
+                            ^ (high << 26)
+                            ^ (high << 13),
+                    );
+                }
+            }
+            Instruction::SHA512SIG1H { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (high, low): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (high << 3) ^ (high >> 6) ^ (high >> 19) ^ (low >> 29) ^ (low << 13),
+                    );
+                }
+            }
+            Instruction::SHA512SUM0R { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (rs1_value << 25)
+                            ^ (rs1_value << 30)
+                            ^ (rs1_value >> 28)
+                            ^ (rs2_value >> 7)
+                            ^ (rs2_value >> 2)
+                            ^ (rs2_value << 4),
+                    );
+                }
+            }
+            Instruction::SHA512SUM1R { rs1, rs2, rd } => {
                 if rd != 0 {
-                    let shamt: u32 = (self.reg[rs2] & 0b11111) as u32; // shift amount is in lower 5 bits
-                    let rs1_value: i32 = self.reg[rs1] as i32;
-                    self.reg[rd] = (rs1_value >> shamt) as u32;
+                    let (rs1_value, rs2_value): (Word, Word) = self.word_operands(rs1, rs2);
+                    self.reg[rd] = X::from(
+                        (rs1_value << 23)
+                            ^ (rs1_value >> 14)
+                            ^ (rs1_value >> 18)
+                            ^ (rs2_value >> 9)
+                            ^ (rs2_value << 18)
+                            ^ (rs2_value << 14),
+                    );
                 }
             }
             Instruction::JAL { offset, rd } => {
-                assert!(offset % 2 == 0, "JAL offset must be aligned to 2 bytes");
+                let target_address: X = self.pc.wrapping_add_signed(offset);
+                self.check_jump_target(target_address)?;
                 if rd != 0 {
-                    self.reg[rd] = self.pc.wrapping_add(4);
+                    self.reg[rd] = self.next_pc();
                 }
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
-                assert!(
-                    target_address % 4 == 0,
-                    "JAL target address must be aligned to 4 bytes"
-                );
-                self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                self.jump(target_address);
             }
             Instruction::JALR { offset, rs1, rd } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset) & !X::from(1); // set LSB to 0
+                self.check_jump_target(target_address)?;
                 if rd != 0 {
-                    self.reg[rd] = self.pc.wrapping_add(4);
+                    self.reg[rd] = self.next_pc();
                 }
-                let target_address: u32 = self.reg[rs1].wrapping_add_signed(offset) & !1; // set LSB to 0
-                assert!(
-                    target_address % 4 == 0,
-                    "JALR target address must be aligned to 4 bytes"
-                );
-                self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                self.jump(target_address);
             }
             Instruction::BEQ { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
                 if self.reg[rs1] == self.reg[rs2] {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BEQ target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::BNE { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
                 if self.reg[rs1] != self.reg[rs2] {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BNE target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::BLT { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
-                if (self.reg[rs1] as i32) < (self.reg[rs2] as i32) {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BLT target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                if self.reg[rs1].to_i64() < self.reg[rs2].to_i64() {
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::BLTU { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
                 if self.reg[rs1] < self.reg[rs2] {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BLTU target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::BGE { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
-                if (self.reg[rs1] as i32) >= (self.reg[rs2] as i32) {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BGE target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                if self.reg[rs1].to_i64() >= self.reg[rs2].to_i64() {
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::BGEU { offset, rs1, rs2 } => {
-                let target_address: u32 = self.pc.wrapping_add_signed(offset);
+                let target_address: X = self.pc.wrapping_add_signed(offset);
 
                 if self.reg[rs1] >= self.reg[rs2] {
-                    assert!(
-                        target_address % 4 == 0,
-                        "BGEU target address must be aligned to 4 bytes"
-                    );
-                    self.pc = target_address.wrapping_sub(4); // subtract 4 because pc will be incremented after execute
+                    self.check_jump_target(target_address)?;
+                    self.jump(target_address);
                 }
             }
             Instruction::LW { offset, rs1, rd } => {
-                let effective_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let loaded_word: Word = mem.fetch_word(effective_address as usize);
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_word: Word = self.load(mem, effective_address, 4)? as Word;
                 if rd != 0 {
-                    self.reg[rd] = loaded_word;
+                    self.reg[rd] = X::from_signed_word(loaded_word);
                 }
             }
             Instruction::LH { offset, rs1, rd } => {
-                let effective_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let loaded_halfword: HalfWord = mem.fetch_halfword(effective_address as usize);
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_halfword: HalfWord = self.load(mem, effective_address, 2)? as HalfWord;
                 if rd != 0 {
-                    self.reg[rd] = sign_extend_u32(loaded_halfword as usize, 16) as u32;
+                    self.reg[rd] =
+                        X::from_i64(sign_extend_u32(loaded_halfword as usize, 16) as i64);
                 }
             }
             Instruction::LHU { offset, rs1, rd } => {
-                let effective_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let loaded_halfword: HalfWord = mem.fetch_halfword(effective_address as usize);
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_halfword: HalfWord = self.load(mem, effective_address, 2)? as HalfWord;
                 if rd != 0 {
-                    self.reg[rd] = loaded_halfword as u32;
+                    self.reg[rd] = X::from(loaded_halfword as Word);
                 }
             }
             Instruction::LB { offset, rs1, rd } => {
-                let effective_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let loaded_byte: Byte = mem[effective_address as usize];
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_byte: Byte = self.load(mem, effective_address, 1)? as Byte;
                 if rd != 0 {
-                    self.reg[rd] = sign_extend_u32(loaded_byte as usize, 8) as u32;
+                    self.reg[rd] = X::from_i64(sign_extend_u32(loaded_byte as usize, 8) as i64);
                 }
             }
             Instruction::LBU { offset, rs1, rd } => {
-                let effective_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let loaded_byte: Byte = mem[effective_address as usize];
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_byte: Byte = self.load(mem, effective_address, 1)? as Byte;
+                if rd != 0 {
+                    self.reg[rd] = X::from(loaded_byte as Word);
+                }
+            }
+            Instruction::LD { offset, rs1, rd } => {
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_doubleword: DoubleWord = self.load(mem, effective_address, 8)?;
+                if rd != 0 {
+                    self.reg[rd] = X::from_u64(loaded_doubleword);
+                }
+            }
+            Instruction::LWU { offset, rs1, rd } => {
+                let effective_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_word: Word = self.load(mem, effective_address, 4)? as Word;
                 if rd != 0 {
-                    self.reg[rd] = loaded_byte as u32;
+                    self.reg[rd] = X::from(loaded_word); // zero-extended
                 }
             }
             Instruction::SW { offset, rs1, rs2 } => {
-                let target_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let value: Word = self.reg[rs2];
-                mem.store_word(target_address as usize, value);
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let value: Word = self.reg[rs2].to_u64() as Word;
+                self.store(mem, target_address, 4, value as u64)?;
             }
             Instruction::SH { offset, rs1, rs2 } => {
-                let target_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let value: HalfWord = self.reg[rs2] as HalfWord;
-                mem.store_halfword(target_address as usize, value);
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let value: HalfWord = self.reg[rs2].to_u64() as HalfWord;
+                self.store(mem, target_address, 2, value as u64)?;
             }
             Instruction::SB { offset, rs1, rs2 } => {
-                let target_address: Word = self.reg[rs1].wrapping_add_signed(offset);
-                let value: Byte = self.reg[rs2] as Byte;
-                mem[target_address as usize] = value;
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let value: Byte = self.reg[rs2].to_u64() as Byte;
+                self.store(mem, target_address, 1, value as u64)?;
+            }
+            Instruction::SD { offset, rs1, rs2 } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let value: DoubleWord = self.reg[rs2].to_u64();
+                self.store(mem, target_address, 8, value)?;
+            }
+            Instruction::ECALL => match self.call_host(mem, false) {
+                EcallAction::Continue => {}
+                EcallAction::Exit(code) => self.halt = Some(Halt::Exit(code)),
+                EcallAction::Break => {
+                    self.halt = Some(Halt::Breakpoint);
+                    self.jump(self.pc); // stay on the instruction until the debugger resumes
+                }
+                EcallAction::Trap => {
+                    let cause: Exception = match self.privilege {
+                        Privilege::User => Exception::EnvironmentCallFromUMode,
+                        Privilege::Supervisor => Exception::EnvironmentCallFromSMode,
+                        Privilege::Machine => Exception::EnvironmentCallFromMMode,
+                    };
+                    return Err(Trap::new(cause, X::default()));
+                }
+            },
+            Instruction::EBREAK => match self.call_host(mem, true) {
+                EcallAction::Continue => {}
+                EcallAction::Exit(code) => self.halt = Some(Halt::Exit(code)),
+                EcallAction::Break => {
+                    self.halt = Some(Halt::Breakpoint);
+                    self.jump(self.pc); // stay on the instruction until the debugger resumes
+                }
+                EcallAction::Trap => return Err(Trap::new(Exception::Breakpoint, self.pc)),
+            },
+            Instruction::SRET => {
+                let mstatus: X = self.csr.get(MSTATUS);
+                if self.privilege < Privilege::Supervisor
+                    || (self.privilege == Privilege::Supervisor
+                        && mstatus & X::from(MSTATUS_TSR) != X::default())
+                {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                let sie: Word = if mstatus & X::from(MSTATUS_SPIE) != X::default() {
+                    MSTATUS_SIE
+                } else {
+                    0
+                };
+                self.privilege = if mstatus & X::from(MSTATUS_SPP) != X::default() {
+                    Privilege::Supervisor
+                } else {
+                    Privilege::User
+                };
+                // SIE <- SPIE, SPIE <- 1, SPP <- U; leaving M-mode also clears MPRV
+                self.csr.set(
+                    MSTATUS,
+                    (mstatus & !X::from(MSTATUS_SIE | MSTATUS_SPP | MSTATUS_MPRV))
+                        | X::from(sie | MSTATUS_SPIE),
+                );
+                self.jump(self.csr.get(SEPC) & !X::from(self.csr.ialign() - 1));
+            }
+            Instruction::MRET => {
+                if self.privilege < Privilege::Machine {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                let mstatus: X = self.csr.get(MSTATUS);
+                let mie: Word = if mstatus & X::from(MSTATUS_MPIE) != X::default() {
+                    MSTATUS_MIE
+                } else {
+                    0
+                };
+                self.privilege = Privilege::from_mstatus(mstatus);
+                let mprv: Word = if self.privilege == Privilege::Machine {
+                    0
+                } else {
+                    MSTATUS_MPRV // leaving M-mode clears MPRV
+                };
+                // MIE <- MPIE, MPIE <- 1, MPP <- U (the least-privileged supported mode)
+                self.csr.set(
+                    MSTATUS,
+                    (mstatus & !X::from(MSTATUS_MIE | MSTATUS_MPP | mprv))
+                        | X::from(mie | MSTATUS_MPIE),
+                );
+                self.jump(self.csr.get(MEPC) & !X::from(self.csr.ialign() - 1));
+            }
+            Instruction::SFENCEVMA { rs1, rs2 } => {
+                if self.privilege == Privilege::User || self.trapped_vm() {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                // x0 selects every address or every address space
+                let vaddr: Option<u64> = (rs1 != 0).then(|| self.reg[rs1].to_u64());
+                let asid: Option<u64> =
+                    (rs2 != 0).then(|| self.reg[rs2].to_u64() & asid_mask::<X>());
+                self.sfence_vma(vaddr, asid);
+            }
+            Instruction::LRW { rs1, rd, .. } => {
+                let address: X = self.reg[rs1];
+                let addr: usize = self.atomic_address(mem, address, 4, AccessType::Load)?;
+                let loaded_word: Word = mem
+                    .fetch_word(addr)
+                    .map_err(|err| Trap::load(err, address))?;
+                self.reservation = Some(address);
+                if rd != 0 {
+                    self.reg[rd] = X::from_signed_word(loaded_word);
+                }
+            }
+            Instruction::SCW { rs1, rs2, rd, .. } => {
+                let address: X = self.reg[rs1];
+                let addr: usize = self.atomic_address(mem, address, 4, AccessType::Store)?;
+                // the reservation is consumed whether or not the store succeeds
+                let reserved: bool = self.reservation.take() == Some(address);
+                if reserved {
+                    mem.store_word(addr, self.reg[rs2].to_u64() as Word)
+                        .map_err(|err| Trap::store(err, address))?;
+                }
+                if rd != 0 {
+                    self.reg[rd] = X::from(if reserved { 0 } else { 1 }); // 0 on success
+                }
+            }
+            Instruction::AMOSWAPW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Swap, rs1, rs2, rd)?;
+            }
+            Instruction::AMOADDW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Add, rs1, rs2, rd)?;
+            }
+            Instruction::AMOXORW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Xor, rs1, rs2, rd)?;
+            }
+            Instruction::AMOANDW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::And, rs1, rs2, rd)?;
+            }
+            Instruction::AMOORW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Or, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMINW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Min, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMAXW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::Max, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMINUW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::MinU, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMAXUW { rs1, rs2, rd, .. } => {
+                self.amo(mem, AmoOp::MaxU, rs1, rs2, rd)?;
+            }
+            Instruction::LRD { rs1, rd, .. } => {
+                let address: X = self.reg[rs1];
+                let addr: usize = self.atomic_address(mem, address, 8, AccessType::Load)?;
+                let loaded_doubleword: DoubleWord = mem
+                    .fetch_doubleword(addr)
+                    .map_err(|err| Trap::load(err, address))?;
+                self.reservation = Some(address);
+                if rd != 0 {
+                    self.reg[rd] = X::from_u64(loaded_doubleword);
+                }
+            }
+            Instruction::SCD { rs1, rs2, rd, .. } => {
+                let address: X = self.reg[rs1];
+                let addr: usize = self.atomic_address(mem, address, 8, AccessType::Store)?;
+                let reserved: bool = self.reservation.take() == Some(address);
+                if reserved {
+                    mem.store_doubleword(addr, self.reg[rs2].to_u64())
+                        .map_err(|err| Trap::store(err, address))?;
+                }
+                if rd != 0 {
+                    self.reg[rd] = X::from(if reserved { 0 } else { 1 }); // 0 on success
+                }
+            }
+            Instruction::AMOSWAPD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Swap, rs1, rs2, rd)?;
+            }
+            Instruction::AMOADDD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Add, rs1, rs2, rd)?;
+            }
+            Instruction::AMOXORD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Xor, rs1, rs2, rd)?;
+            }
+            Instruction::AMOANDD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::And, rs1, rs2, rd)?;
+            }
+            Instruction::AMOORD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Or, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMIND { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Min, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMAXD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::Max, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMINUD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::MinU, rs1, rs2, rd)?;
+            }
+            Instruction::AMOMAXUD { rs1, rs2, rd, .. } => {
+                self.amo_doubleword(mem, AmoOp::MaxU, rs1, rs2, rd)?;
+            }
+            Instruction::FLW { offset, rs1, rd } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_word: Word = self.load(mem, target_address, 4)? as Word;
+                self.write_fp(SINGLE, rd, loaded_word as u64, 0);
+            }
+            Instruction::FSW { offset, rs1, rs2 } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                self.store(mem, target_address, 4, self.freg[rs2])?; // low bits, boxed or not
+            }
+            Instruction::FMADDS {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(SINGLE, (rs1, rs2, rs3, rd), rm, (false, false))
+                .map_err(illegal)?,
+            Instruction::FMSUBS {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(SINGLE, (rs1, rs2, rs3, rd), rm, (false, true))
+                .map_err(illegal)?,
+            Instruction::FNMSUBS {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(SINGLE, (rs1, rs2, rs3, rd), rm, (true, false))
+                .map_err(illegal)?,
+            Instruction::FNMADDS {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(SINGLE, (rs1, rs2, rs3, rd), rm, (true, true))
+                .map_err(illegal)?,
+            Instruction::FADDS { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(SINGLE, (rs1, rs2, rd), rm, FloatFormat::add)
+                .map_err(illegal)?,
+            Instruction::FSUBS { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(SINGLE, (rs1, rs2, rd), rm, FloatFormat::sub)
+                .map_err(illegal)?,
+            Instruction::FMULS { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(SINGLE, (rs1, rs2, rd), rm, FloatFormat::mul)
+                .map_err(illegal)?,
+            Instruction::FDIVS { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(SINGLE, (rs1, rs2, rd), rm, FloatFormat::div)
+                .map_err(illegal)?,
+            Instruction::FSQRTS { rs1, rd, rm } => self
+                .fp_unary((SINGLE, SINGLE), (rs1, rd), rm, |a, rm, flags| {
+                    SINGLE.sqrt(a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FSGNJS { rs1, rs2, rd } => {
+                self.fp_sign_injection(SINGLE, (rs1, rs2, rd), |_, sign_b| sign_b)
+            }
+            Instruction::FSGNJNS { rs1, rs2, rd } => {
+                self.fp_sign_injection(SINGLE, (rs1, rs2, rd), |_, sign_b| !sign_b)
+            }
+            Instruction::FSGNJXS { rs1, rs2, rd } => {
+                self.fp_sign_injection(SINGLE, (rs1, rs2, rd), |sign_a, sign_b| sign_a != sign_b)
+            }
+            Instruction::FMINS { rs1, rs2, rd } => {
+                self.fp_min_max(SINGLE, (rs1, rs2, rd), false, FloatFormat::min_max)
+            }
+            Instruction::FMAXS { rs1, rs2, rd } => {
+                self.fp_min_max(SINGLE, (rs1, rs2, rd), true, FloatFormat::min_max)
+            }
+            Instruction::FCVTWS { rs1, rd, rm } => self
+                .fp_to_int(SINGLE, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTWUS { rs1, rd, rm } => self
+                .fp_to_int(SINGLE, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FMVXW { rs1, rd } => {
+                // raw low bits, no conversion, sign-extended on RV64
+                self.write_fp_integer(rd, X::from_signed_word(self.freg[rs1] as Word), 0);
+            }
+            Instruction::FEQS { rs1, rs2, rd } => {
+                self.fp_compare(SINGLE, (rs1, rs2, rd), false, &[Ordering::Equal])
+            }
+            Instruction::FLTS { rs1, rs2, rd } => {
+                self.fp_compare(SINGLE, (rs1, rs2, rd), true, &[Ordering::Less])
+            }
+            Instruction::FLES { rs1, rs2, rd } => self.fp_compare(
+                SINGLE,
+                (rs1, rs2, rd),
+                true,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FCLASSS { rs1, rd } => {
+                let class: Word = SINGLE.classify(self.read_fp(SINGLE, rs1));
+                self.write_fp_integer(rd, X::from(class), 0);
+            }
+            Instruction::FCVTSW { rs1, rd, rm } => self
+                .fp_from_int(SINGLE, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTSWU { rs1, rd, rm } => self
+                .fp_from_int(SINGLE, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTLS { rs1, rd, rm } => self
+                .fp_to_int(SINGLE, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTLUS { rs1, rd, rm } => self
+                .fp_to_int(SINGLE, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTSL { rs1, rd, rm } => self
+                .fp_from_int(SINGLE, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTSLU { rs1, rd, rm } => self
+                .fp_from_int(SINGLE, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FMVWX { rs1, rd } => {
+                let bits: Word = self.reg[rs1].to_u64() as Word;
+                self.write_fp(SINGLE, rd, bits as u64, 0); // raw low bits, no conversion
+            }
+            Instruction::FLD { offset, rs1, rd } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_value: DoubleWord = self.load(mem, target_address, 8)?;
+                self.write_fp(DOUBLE, rd, loaded_value, 0);
+            }
+            Instruction::FSD { offset, rs1, rs2 } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                self.store(mem, target_address, 8, self.freg[rs2])?;
+            }
+            Instruction::FMADDD {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(DOUBLE, (rs1, rs2, rs3, rd), rm, (false, false))
+                .map_err(illegal)?,
+            Instruction::FMSUBD {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(DOUBLE, (rs1, rs2, rs3, rd), rm, (false, true))
+                .map_err(illegal)?,
+            Instruction::FNMSUBD {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(DOUBLE, (rs1, rs2, rs3, rd), rm, (true, false))
+                .map_err(illegal)?,
+            Instruction::FNMADDD {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(DOUBLE, (rs1, rs2, rs3, rd), rm, (true, true))
+                .map_err(illegal)?,
+            Instruction::FADDD { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(DOUBLE, (rs1, rs2, rd), rm, FloatFormat::add)
+                .map_err(illegal)?,
+            Instruction::FSUBD { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(DOUBLE, (rs1, rs2, rd), rm, FloatFormat::sub)
+                .map_err(illegal)?,
+            Instruction::FMULD { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(DOUBLE, (rs1, rs2, rd), rm, FloatFormat::mul)
+                .map_err(illegal)?,
+            Instruction::FDIVD { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(DOUBLE, (rs1, rs2, rd), rm, FloatFormat::div)
+                .map_err(illegal)?,
+            Instruction::FSQRTD { rs1, rd, rm } => self
+                .fp_unary((DOUBLE, DOUBLE), (rs1, rd), rm, |a, rm, flags| {
+                    DOUBLE.sqrt(a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FSGNJD { rs1, rs2, rd } => {
+                self.fp_sign_injection(DOUBLE, (rs1, rs2, rd), |_, sign_b| sign_b)
+            }
+            Instruction::FSGNJND { rs1, rs2, rd } => {
+                self.fp_sign_injection(DOUBLE, (rs1, rs2, rd), |_, sign_b| !sign_b)
+            }
+            Instruction::FSGNJXD { rs1, rs2, rd } => {
+                self.fp_sign_injection(DOUBLE, (rs1, rs2, rd), |sign_a, sign_b| sign_a != sign_b)
+            }
+            Instruction::FMIND { rs1, rs2, rd } => {
+                self.fp_min_max(DOUBLE, (rs1, rs2, rd), false, FloatFormat::min_max)
+            }
+            Instruction::FMAXD { rs1, rs2, rd } => {
+                self.fp_min_max(DOUBLE, (rs1, rs2, rd), true, FloatFormat::min_max)
+            }
+            Instruction::FCVTSD { rs1, rd, rm } => self
+                .fp_unary((DOUBLE, SINGLE), (rs1, rd), rm, |a, rm, flags| {
+                    SINGLE.convert_from(DOUBLE, a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTDS { rs1, rd, rm } => self
+                .fp_unary((SINGLE, DOUBLE), (rs1, rd), rm, |a, rm, flags| {
+                    DOUBLE.convert_from(SINGLE, a, rm, flags) // always exact
+                })
+                .map_err(illegal)?,
+            Instruction::FEQD { rs1, rs2, rd } => {
+                self.fp_compare(DOUBLE, (rs1, rs2, rd), false, &[Ordering::Equal])
+            }
+            Instruction::FLTD { rs1, rs2, rd } => {
+                self.fp_compare(DOUBLE, (rs1, rs2, rd), true, &[Ordering::Less])
+            }
+            Instruction::FLED { rs1, rs2, rd } => self.fp_compare(
+                DOUBLE,
+                (rs1, rs2, rd),
+                true,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FCLASSD { rs1, rd } => {
+                let class: Word = DOUBLE.classify(self.read_fp(DOUBLE, rs1));
+                self.write_fp_integer(rd, X::from(class), 0);
+            }
+            Instruction::FCVTWD { rs1, rd, rm } => self
+                .fp_to_int(DOUBLE, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTWUD { rs1, rd, rm } => self
+                .fp_to_int(DOUBLE, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTDW { rs1, rd, rm } => self
+                .fp_from_int(DOUBLE, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTDWU { rs1, rd, rm } => self
+                .fp_from_int(DOUBLE, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTLD { rs1, rd, rm } => self
+                .fp_to_int(DOUBLE, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTLUD { rs1, rd, rm } => self
+                .fp_to_int(DOUBLE, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FMVXD { rs1, rd } => {
+                self.write_fp_integer(rd, X::from_u64(self.freg[rs1]), 0); // raw bits, no conversion
+            }
+            Instruction::FCVTDL { rs1, rd, rm } => self
+                .fp_from_int(DOUBLE, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTDLU { rs1, rd, rm } => self
+                .fp_from_int(DOUBLE, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FMVDX { rs1, rd } => {
+                self.write_fp(DOUBLE, rd, self.reg[rs1].to_u64(), 0); // raw bits, no conversion
+            }
+            Instruction::FLH { offset, rs1, rd } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                let loaded_halfword: HalfWord = self.load(mem, target_address, 2)? as HalfWord;
+                self.write_fp(HALF, rd, loaded_halfword as u64, 0);
+            }
+            Instruction::FSH { offset, rs1, rs2 } => {
+                let target_address: X = self.reg[rs1].wrapping_add_signed(offset);
+                self.store(mem, target_address, 2, self.freg[rs2])?; // low bits
+            }
+            Instruction::FMADDH {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(HALF, (rs1, rs2, rs3, rd), rm, (false, false))
+                .map_err(illegal)?,
+            Instruction::FMSUBH {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(HALF, (rs1, rs2, rs3, rd), rm, (false, true))
+                .map_err(illegal)?,
+            Instruction::FNMSUBH {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(HALF, (rs1, rs2, rs3, rd), rm, (true, false))
+                .map_err(illegal)?,
+            Instruction::FNMADDH {
+                rs1,
+                rs2,
+                rs3,
+                rd,
+                rm,
+            } => self
+                .fp_fused(HALF, (rs1, rs2, rs3, rd), rm, (true, true))
+                .map_err(illegal)?,
+            Instruction::FADDH { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(HALF, (rs1, rs2, rd), rm, FloatFormat::add)
+                .map_err(illegal)?,
+            Instruction::FSUBH { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(HALF, (rs1, rs2, rd), rm, FloatFormat::sub)
+                .map_err(illegal)?,
+            Instruction::FMULH { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(HALF, (rs1, rs2, rd), rm, FloatFormat::mul)
+                .map_err(illegal)?,
+            Instruction::FDIVH { rs1, rs2, rd, rm } => self
+                .fp_arithmetic(HALF, (rs1, rs2, rd), rm, FloatFormat::div)
+                .map_err(illegal)?,
+            Instruction::FSQRTH { rs1, rd, rm } => self
+                .fp_unary((HALF, HALF), (rs1, rd), rm, |a, rm, flags| {
+                    HALF.sqrt(a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FSGNJH { rs1, rs2, rd } => {
+                self.fp_sign_injection(HALF, (rs1, rs2, rd), |_, sign_b| sign_b)
+            }
+            Instruction::FSGNJNH { rs1, rs2, rd } => {
+                self.fp_sign_injection(HALF, (rs1, rs2, rd), |_, sign_b| !sign_b)
+            }
+            Instruction::FSGNJXH { rs1, rs2, rd } => {
+                self.fp_sign_injection(HALF, (rs1, rs2, rd), |sign_a, sign_b| sign_a != sign_b)
+            }
+            Instruction::FMINH { rs1, rs2, rd } => {
+                self.fp_min_max(HALF, (rs1, rs2, rd), false, FloatFormat::min_max)
+            }
+            Instruction::FMAXH { rs1, rs2, rd } => {
+                self.fp_min_max(HALF, (rs1, rs2, rd), true, FloatFormat::min_max)
+            }
+            Instruction::FCVTSH { rs1, rd, rm } => self
+                .fp_unary((HALF, SINGLE), (rs1, rd), rm, |a, rm, flags| {
+                    SINGLE.convert_from(HALF, a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTHS { rs1, rd, rm } => self
+                .fp_unary((SINGLE, HALF), (rs1, rd), rm, |a, rm, flags| {
+                    HALF.convert_from(SINGLE, a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTDH { rs1, rd, rm } => self
+                .fp_unary((HALF, DOUBLE), (rs1, rd), rm, |a, rm, flags| {
+                    DOUBLE.convert_from(HALF, a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTHD { rs1, rd, rm } => self
+                .fp_unary((DOUBLE, HALF), (rs1, rd), rm, |a, rm, flags| {
+                    HALF.convert_from(DOUBLE, a, rm, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTWH { rs1, rd, rm } => self
+                .fp_to_int(HALF, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTWUH { rs1, rd, rm } => self
+                .fp_to_int(HALF, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTLH { rs1, rd, rm } => self
+                .fp_to_int(HALF, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTLUH { rs1, rd, rm } => self
+                .fp_to_int(HALF, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FMVXH { rs1, rd } => {
+                // raw low bits, no conversion, sign-extended to XLEN
+                let bits: i16 = self.freg[rs1] as i16;
+                self.write_fp_integer(rd, X::from_i64(bits as i64), 0);
+            }
+            Instruction::FEQH { rs1, rs2, rd } => {
+                self.fp_compare(HALF, (rs1, rs2, rd), false, &[Ordering::Equal])
+            }
+            Instruction::FLTH { rs1, rs2, rd } => {
+                self.fp_compare(HALF, (rs1, rs2, rd), true, &[Ordering::Less])
+            }
+            Instruction::FLEH { rs1, rs2, rd } => self.fp_compare(
+                HALF,
+                (rs1, rs2, rd),
+                true,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FCLASSH { rs1, rd } => {
+                let class: Word = HALF.classify(self.read_fp(HALF, rs1));
+                self.write_fp_integer(rd, X::from(class), 0);
+            }
+            Instruction::FCVTHW { rs1, rd, rm } => self
+                .fp_from_int(HALF, (rs1, rd), rm, (true, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTHWU { rs1, rd, rm } => self
+                .fp_from_int(HALF, (rs1, rd), rm, (false, 32))
+                .map_err(illegal)?,
+            Instruction::FCVTHL { rs1, rd, rm } => self
+                .fp_from_int(HALF, (rs1, rd), rm, (true, 64))
+                .map_err(illegal)?,
+            Instruction::FCVTHLU { rs1, rd, rm } => self
+                .fp_from_int(HALF, (rs1, rd), rm, (false, 64))
+                .map_err(illegal)?,
+            Instruction::FMVHX { rs1, rd } => {
+                let bits: HalfWord = self.reg[rs1].to_u64() as HalfWord;
+                self.write_fp(HALF, rd, bits as u64, 0); // raw low bits, no conversion
+            }
+            Instruction::FLIS { index, rd } => self.write_fp(SINGLE, rd, SINGLE.fli(index), 0),
+            Instruction::FLID { index, rd } => self.write_fp(DOUBLE, rd, DOUBLE.fli(index), 0),
+            Instruction::FLIH { index, rd } => self.write_fp(HALF, rd, HALF.fli(index), 0),
+            Instruction::FMINMS { rs1, rs2, rd } => {
+                self.fp_min_max(SINGLE, (rs1, rs2, rd), false, FloatFormat::minimum_maximum)
+            }
+            Instruction::FMAXMS { rs1, rs2, rd } => {
+                self.fp_min_max(SINGLE, (rs1, rs2, rd), true, FloatFormat::minimum_maximum)
+            }
+            Instruction::FMINMD { rs1, rs2, rd } => {
+                self.fp_min_max(DOUBLE, (rs1, rs2, rd), false, FloatFormat::minimum_maximum)
+            }
+            Instruction::FMAXMD { rs1, rs2, rd } => {
+                self.fp_min_max(DOUBLE, (rs1, rs2, rd), true, FloatFormat::minimum_maximum)
+            }
+            Instruction::FMINMH { rs1, rs2, rd } => {
+                self.fp_min_max(HALF, (rs1, rs2, rd), false, FloatFormat::minimum_maximum)
+            }
+            Instruction::FMAXMH { rs1, rs2, rd } => {
+                self.fp_min_max(HALF, (rs1, rs2, rd), true, FloatFormat::minimum_maximum)
+            }
+            Instruction::FROUNDS { rs1, rd, rm } => self
+                .fp_unary((SINGLE, SINGLE), (rs1, rd), rm, |a, rm, flags| {
+                    SINGLE.round_to_integral(a, rm, false, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FROUNDNXS { rs1, rd, rm } => self
+                .fp_unary((SINGLE, SINGLE), (rs1, rd), rm, |a, rm, flags| {
+                    SINGLE.round_to_integral(a, rm, true, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FROUNDD { rs1, rd, rm } => self
+                .fp_unary((DOUBLE, DOUBLE), (rs1, rd), rm, |a, rm, flags| {
+                    DOUBLE.round_to_integral(a, rm, false, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FROUNDNXD { rs1, rd, rm } => self
+                .fp_unary((DOUBLE, DOUBLE), (rs1, rd), rm, |a, rm, flags| {
+                    DOUBLE.round_to_integral(a, rm, true, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FROUNDH { rs1, rd, rm } => self
+                .fp_unary((HALF, HALF), (rs1, rd), rm, |a, rm, flags| {
+                    HALF.round_to_integral(a, rm, false, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FROUNDNXH { rs1, rd, rm } => self
+                .fp_unary((HALF, HALF), (rs1, rd), rm, |a, rm, flags| {
+                    HALF.round_to_integral(a, rm, true, flags)
+                })
+                .map_err(illegal)?,
+            Instruction::FCVTMODWD { rs1, rd } => {
+                let mut flags: Word = 0;
+                let result: Word =
+                    DOUBLE.to_int_modular(self.read_fp(DOUBLE, rs1), 32, &mut flags) as Word;
+                self.write_fp_integer(rd, X::from_signed_word(result), flags);
+            }
+            Instruction::FLEQS { rs1, rs2, rd } => self.fp_compare(
+                SINGLE,
+                (rs1, rs2, rd),
+                false,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FLTQS { rs1, rs2, rd } => {
+                self.fp_compare(SINGLE, (rs1, rs2, rd), false, &[Ordering::Less])
+            }
+            Instruction::FLEQD { rs1, rs2, rd } => self.fp_compare(
+                DOUBLE,
+                (rs1, rs2, rd),
+                false,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FLTQD { rs1, rs2, rd } => {
+                self.fp_compare(DOUBLE, (rs1, rs2, rd), false, &[Ordering::Less])
+            }
+            Instruction::FLEQH { rs1, rs2, rd } => self.fp_compare(
+                HALF,
+                (rs1, rs2, rd),
+                false,
+                &[Ordering::Less, Ordering::Equal],
+            ),
+            Instruction::FLTQH { rs1, rs2, rd } => {
+                self.fp_compare(HALF, (rs1, rs2, rd), false, &[Ordering::Less])
+            }
+            Instruction::FENCE { .. } => {
+                // a single in-order hart performs every access in program order,
+                // so FENCE and FENCE.TSO have nothing to wait for
+            }
+            Instruction::FENCEI => self.flush_decode_cache(),
+            Instruction::WFI => {
+                if self.privilege == Privilege::User
+                    || (self.privilege == Privilege::Supervisor
+                        && self.csr.get(MSTATUS) & X::from(MSTATUS_TW) != X::default())
+                {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                // a hint: interrupts are taken between any two instructions anyway, so the
+                // hart simply carries on as if one had woken it up
+            }
+            Instruction::CBOINVAL { rs1 }
+            | Instruction::CBOCLEAN { rs1 }
+            | Instruction::CBOFLUSH { rs1 } => {
+                let field: Word = if matches!(parsed_instruction, Instruction::CBOINVAL { .. }) {
+                    ENVCFG_CBIE
+                } else {
+                    ENVCFG_CBCFE
+                };
+                if !self.csr.cbo_enabled(field, self.privilege) {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                // memory is always coherent without a data cache, so there is nothing to write
+                // back or discard, but the block must still exist
+                let address: X = self.reg[rs1];
+                // allowed wherever a load or a store is, faulting like a store otherwise
+                let block: usize = self
+                    .translate_block(mem, address, AccessType::Load)
+                    .or_else(|_| self.translate_block(mem, address, AccessType::Store))?;
+                Memory::check_access(block, self.cache_block_size)
+                    .map_err(|err| Trap::store(err, address))?;
+            }
+            Instruction::CBOZERO { rs1 } => {
+                if !self.csr.cbo_enabled(ENVCFG_CBZE, self.privilege) {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                let address: X = self.reg[rs1];
+                let block: usize = self.translate_block(mem, address, AccessType::Store)?;
+                mem.zero_block(block, self.cache_block_size)
+                    .map_err(|err| Trap::store(err, address))?;
+            }
+            Instruction::PREFETCHI { .. }
+            | Instruction::PREFETCHR { .. }
+            | Instruction::PREFETCHW { .. } => {
+                // there are no caches to prefetch into, and prefetches never trap
+            }
+            Instruction::PAUSE => {} // a single hart has nobody to yield to
+            Instruction::NTLP1
+            | Instruction::NTLPALL
+            | Instruction::NTLS1
+            | Instruction::NTLALL => {
+                // there are no caches for the locality hint to steer
+            }
+            Instruction::CZEROEQZ { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let zero: bool = self.reg[rs2] == X::default();
+                    self.reg[rd] = if zero { X::default() } else { self.reg[rs1] };
+                }
+            }
+            Instruction::CZERONEZ { rs1, rs2, rd } => {
+                if rd != 0 {
+                    let zero: bool = self.reg[rs2] == X::default();
+                    self.reg[rd] = if zero { self.reg[rs1] } else { X::default() };
+                }
+            }
+            Instruction::VSETVLI { vtypei, rs1, rd } => {
+                let avl: Option<u64> = self.vector_avl(rs1, rd);
+                self.vset(vtypei as u64, avl, rd);
+            }
+            Instruction::VSETIVLI { vtypei, uimm, rd } => {
+                self.vset(vtypei as u64, Some(uimm as u64), rd);
+            }
+            Instruction::VSETVL { rs1, rs2, rd } => {
+                let avl: Option<u64> = self.vector_avl(rs1, rd);
+                self.vset(self.reg[rs2].to_u64(), avl, rd);
+            }
+            Instruction::VLE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vd,
+            } => self.vector_load(
+                mem,
+                VectorAddressing::UnitStride,
+                (eew as usize, nf as usize, vm),
+                (rs1, vd),
+                false,
+            )?,
+            Instruction::VLEFF {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vd,
+            } => self.vector_load(
+                mem,
+                VectorAddressing::UnitStride,
+                (eew as usize, nf as usize, vm),
+                (rs1, vd),
+                true,
+            )?,
+            Instruction::VLSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                rs2,
+                vd,
+            } => self.vector_load(
+                mem,
+                VectorAddressing::Strided(rs2),
+                (eew as usize, nf as usize, vm),
+                (rs1, vd),
+                false,
+            )?,
+            Instruction::VLUXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vd,
+            }
+            | Instruction::VLOXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vd,
+            } => self.vector_load(
+                mem,
+                VectorAddressing::Indexed(vs2),
+                (eew as usize, nf as usize, vm),
+                (rs1, vd),
+                false,
+            )?,
+            Instruction::VLM { rs1, vd } => {
+                self.vector_load(mem, VectorAddressing::Mask, (8, 1, true), (rs1, vd), false)?
+            }
+            Instruction::VLRE { eew, nf, rs1, vd } => self.vector_load(
+                mem,
+                VectorAddressing::WholeRegister,
+                (eew as usize, nf as usize, true),
+                (rs1, vd),
+                false,
+            )?,
+            Instruction::VSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs3,
+            } => self.vector_store(
+                mem,
+                VectorAddressing::UnitStride,
+                (eew as usize, nf as usize, vm),
+                (rs1, vs3),
+            )?,
+            Instruction::VSSE {
+                eew,
+                nf,
+                vm,
+                rs1,
+                rs2,
+                vs3,
+            } => self.vector_store(
+                mem,
+                VectorAddressing::Strided(rs2),
+                (eew as usize, nf as usize, vm),
+                (rs1, vs3),
+            )?,
+            Instruction::VSUXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vs3,
+            }
+            | Instruction::VSOXEI {
+                eew,
+                nf,
+                vm,
+                rs1,
+                vs2,
+                vs3,
+            } => self.vector_store(
+                mem,
+                VectorAddressing::Indexed(vs2),
+                (eew as usize, nf as usize, vm),
+                (rs1, vs3),
+            )?,
+            Instruction::VSM { rs1, vs3 } => {
+                self.vector_store(mem, VectorAddressing::Mask, (8, 1, true), (rs1, vs3))?
+            }
+            Instruction::VSR { nf, rs1, vs3 } => self.vector_store(
+                mem,
+                VectorAddressing::WholeRegister,
+                (8, nf as usize, true),
+                (rs1, vs3),
+            )?,
+            Instruction::VADD {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.wrapping_add(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VSUB {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.wrapping_sub(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VRSUB {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| b.wrapping_sub(a),
+                )
+                .map_err(illegal)?,
+            Instruction::VWADDU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, _| a + b,
+                )
+                .map_err(illegal)?,
+            Instruction::VWADD {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, sew| {
+                        sign_extend_element(a, sew).wrapping_add(sign_extend_element(b, sew))
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWSUBU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, _| a.wrapping_sub(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VWSUB {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, sew| {
+                        sign_extend_element(a, sew).wrapping_sub(sign_extend_element(b, sew))
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWADDUW {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::WideningWide,
+                    |a, b, _, _| a.wrapping_add(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VWADDW {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::WideningWide,
+                    |a, b, _, sew| a.wrapping_add(sign_extend_element(b, sew)),
+                )
+                .map_err(illegal)?,
+            Instruction::VWSUBUW {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::WideningWide,
+                    |a, b, _, _| a.wrapping_sub(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VWSUBW {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::WideningWide,
+                    |a, b, _, sew| a.wrapping_sub(sign_extend_element(b, sew)),
+                )
+                .map_err(illegal)?,
+            Instruction::VZEXT {
+                factor,
+                vd,
+                vs2,
+                vm,
+            } => self
+                .vector_extend((vd, vs2, vm), factor, false)
+                .map_err(illegal)?,
+            Instruction::VSEXT {
+                factor,
+                vd,
+                vs2,
+                vm,
+            } => self
+                .vector_extend((vd, vs2, vm), factor, true)
+                .map_err(illegal)?,
+            Instruction::VADC { vd, vs2, operand } => self
+                .vector_carry((vd, vs2, operand), |a, b, carry, _| {
+                    a.wrapping_add(b).wrapping_add(carry as u64)
+                })
+                .map_err(illegal)?,
+            Instruction::VSBC { vd, vs2, operand } => self
+                .vector_carry((vd, vs2, operand), |a, b, borrow, _| {
+                    a.wrapping_sub(b).wrapping_sub(borrow as u64)
+                })
+                .map_err(illegal)?,
+            Instruction::VMERGE { vd, vs2, operand } => self
+                .vector_carry((vd, vs2, operand), |a, b, mask, _| if mask { b } else { a })
+                .map_err(illegal)?,
+            Instruction::VMV { vd, operand } => self
+                .vector_elementwise(
+                    (vd, 0, operand, true),
+                    ElementWidths::Single,
+                    |_, b, _, _| b,
+                )
+                .map_err(illegal)?,
+            Instruction::VMADC {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, true), !vm, |a, b, carry, sew| {
+                    // the carry out of the SEW-bit sum
+                    (a as u128 + b as u128 + carry as u128) >> sew != 0
+                })
+                .map_err(illegal)?,
+            Instruction::VMSBC {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, true), !vm, |a, b, borrow, _| {
+                    (a as u128) < b as u128 + borrow as u128
+                })
+                .map_err(illegal)?,
+            Instruction::VAND {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a & b,
+                )
+                .map_err(illegal)?,
+            Instruction::VOR {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a | b,
+                )
+                .map_err(illegal)?,
+            Instruction::VXOR {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a ^ b,
+                )
+                .map_err(illegal)?,
+            Instruction::VSLL {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| a << (b as usize & (sew - 1)),
+                )
+                .map_err(illegal)?,
+            Instruction::VSRL {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| a >> (b as usize & (sew - 1)),
+                )
+                .map_err(illegal)?,
+            Instruction::VSRA {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        (sign_extend_element(a, sew) as i64 >> (b as usize & (sew - 1))) as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VNSRL {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Narrowing,
+                    |a, b, _, sew| a >> (b as usize & (2 * sew - 1)),
+                )
+                .map_err(illegal)?,
+            Instruction::VNSRA {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Narrowing,
+                    |a, b, _, sew| {
+                        (sign_extend_element(a, 2 * sew) as i64 >> (b as usize & (2 * sew - 1)))
+                            as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VMSEQ {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, _| a == b)
+                .map_err(illegal)?,
+            Instruction::VMSNE {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, _| a != b)
+                .map_err(illegal)?,
+            Instruction::VMSLTU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, _| a < b)
+                .map_err(illegal)?,
+            Instruction::VMSLT {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, sew| {
+                    (sign_extend_element(a, sew) as i64) < sign_extend_element(b, sew) as i64
+                })
+                .map_err(illegal)?,
+            Instruction::VMSLEU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, _| a <= b)
+                .map_err(illegal)?,
+            Instruction::VMSLE {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, sew| {
+                    sign_extend_element(a, sew) as i64 <= sign_extend_element(b, sew) as i64
+                })
+                .map_err(illegal)?,
+            Instruction::VMSGTU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, _| a > b)
+                .map_err(illegal)?,
+            Instruction::VMSGT {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_compare((vd, vs2, operand, vm), false, |a, b, _, sew| {
+                    sign_extend_element(a, sew) as i64 > sign_extend_element(b, sew) as i64
+                })
+                .map_err(illegal)?,
+            Instruction::VMINU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.min(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VMIN {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        (sign_extend_element(a, sew) as i64).min(sign_extend_element(b, sew) as i64)
+                            as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VMAXU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.max(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VMAX {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        (sign_extend_element(a, sew) as i64).max(sign_extend_element(b, sew) as i64)
+                            as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VMUL {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.wrapping_mul(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VMULH {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        let product: i128 = sign_extend_element(a, sew) as i64 as i128
+                            * sign_extend_element(b, sew) as i64 as i128;
+                        (product >> sew) as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VMULHU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| ((a as u128 * b as u128) >> sew) as u64,
+                )
+                .map_err(illegal)?,
+            Instruction::VMULHSU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        // signed vs2 times unsigned operand
+                        let product: i128 = sign_extend_element(a, sew) as i64 as i128 * b as i128;
+                        (product >> sew) as u64
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VDIVU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| {
+                        a.checked_div(b).unwrap_or(u64::MAX) // division by zero gives all ones
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VDIV {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        let (a, b) = (
+                            sign_extend_element(a, sew) as i64,
+                            sign_extend_element(b, sew) as i64,
+                        );
+                        if b == 0 {
+                            u64::MAX
+                        } else {
+                            a.wrapping_div(b) as u64
+                        }
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VREMU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, _| a.checked_rem(b).unwrap_or(a),
+                )
+                .map_err(illegal)?,
+            Instruction::VREM {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, _, sew| {
+                        let (a, b) = (
+                            sign_extend_element(a, sew) as i64,
+                            sign_extend_element(b, sew) as i64,
+                        );
+                        if b == 0 {
+                            a as u64
+                        } else {
+                            a.wrapping_rem(b) as u64
+                        }
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWMULU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, _| a * b,
+                )
+                .map_err(illegal)?,
+            Instruction::VWMUL {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, sew| {
+                        sign_extend_element(a, sew).wrapping_mul(sign_extend_element(b, sew))
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWMULSU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, _, sew| sign_extend_element(a, sew).wrapping_mul(b),
+                )
+                .map_err(illegal)?,
+            Instruction::VMACC {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, d, _| b.wrapping_mul(a).wrapping_add(d),
+                )
+                .map_err(illegal)?,
+            Instruction::VNMSAC {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, d, _| d.wrapping_sub(b.wrapping_mul(a)),
+                )
+                .map_err(illegal)?,
+            Instruction::VMADD {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, d, _| b.wrapping_mul(d).wrapping_add(a),
+                )
+                .map_err(illegal)?,
+            Instruction::VNMSUB {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Single,
+                    |a, b, d, _| a.wrapping_sub(b.wrapping_mul(d)),
+                )
+                .map_err(illegal)?,
+            Instruction::VWMACCU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, d, _| (b * a).wrapping_add(d),
+                )
+                .map_err(illegal)?,
+            Instruction::VWMACC {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, d, sew| {
+                        sign_extend_element(b, sew)
+                            .wrapping_mul(sign_extend_element(a, sew))
+                            .wrapping_add(d)
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWMACCSU {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, d, sew| {
+                        // signed operand times unsigned vs2
+                        sign_extend_element(b, sew).wrapping_mul(a).wrapping_add(d)
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VWMACCUS {
+                vd,
+                vs2,
+                operand,
+                vm,
+            } => self
+                .vector_elementwise(
+                    (vd, vs2, operand, vm),
+                    ElementWidths::Widening,
+                    |a, b, d, sew| {
+                        // unsigned operand times signed vs2
+                        b.wrapping_mul(sign_extend_element(a, sew)).wrapping_add(d)
+                    },
+                )
+                .map_err(illegal)?,
+            Instruction::VREDSUM { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc.wrapping_add(e))
+                .map_err(illegal)?,
+            Instruction::VREDAND { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc & e)
+                .map_err(illegal)?,
+            Instruction::VREDOR { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc | e)
+                .map_err(illegal)?,
+            Instruction::VREDXOR { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc ^ e)
+                .map_err(illegal)?,
+            Instruction::VREDMINU { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc.min(e))
+                .map_err(illegal)?,
+            Instruction::VREDMIN { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, sew| {
+                    (sign_extend_element(acc, sew) as i64).min(sign_extend_element(e, sew) as i64)
+                        as u64
+                })
+                .map_err(illegal)?,
+            Instruction::VREDMAXU { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, _| acc.max(e))
+                .map_err(illegal)?,
+            Instruction::VREDMAX { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), false, |acc, e, sew| {
+                    (sign_extend_element(acc, sew) as i64).max(sign_extend_element(e, sew) as i64)
+                        as u64
+                })
+                .map_err(illegal)?,
+            Instruction::VWREDSUMU { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), true, |acc, e, _| acc.wrapping_add(e))
+                .map_err(illegal)?,
+            Instruction::VWREDSUM { vd, vs2, vs1, vm } => self
+                .vector_reduction((vd, vs2, vs1, vm), true, |acc, e, sew| {
+                    acc.wrapping_add(sign_extend_element(e, sew))
+                })
+                .map_err(illegal)?,
+            Instruction::VMAND { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| a & b)
+                .map_err(illegal)?,
+            Instruction::VMNAND { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| !(a & b))
+                .map_err(illegal)?,
+            Instruction::VMANDN { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| a & !b)
+                .map_err(illegal)?,
+            Instruction::VMXOR { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| a ^ b)
+                .map_err(illegal)?,
+            Instruction::VMOR { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| a | b)
+                .map_err(illegal)?,
+            Instruction::VMNOR { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| !(a | b))
+                .map_err(illegal)?,
+            Instruction::VMORN { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| a | !b)
+                .map_err(illegal)?,
+            Instruction::VMXNOR { vd, vs2, vs1 } => self
+                .vector_mask_logical((vd, vs2, vs1), |a, b| !(a ^ b))
+                .map_err(illegal)?,
+            Instruction::VCPOP { vs2, rd, vm } => {
+                let count: usize = self.vector_mask_indices(vs2, vm).map_err(illegal)?.len();
+                if rd != 0 {
+                    self.reg[rd] = X::from_u64(count as u64);
+                }
+            }
+            Instruction::VFIRST { vs2, rd, vm } => {
+                let indices: Vec<usize> = self.vector_mask_indices(vs2, vm).map_err(illegal)?;
+                if rd != 0 {
+                    self.reg[rd] = X::from_i64(indices.first().map_or(-1, |&i| i as i64));
+                }
+            }
+            Instruction::VMSBF { vd, vs2, vm } => self
+                .vector_set_first((vd, vs2, vm), |before, _| before)
+                .map_err(illegal)?,
+            Instruction::VMSIF { vd, vs2, vm } => self
+                .vector_set_first((vd, vs2, vm), |before, first| before || first)
+                .map_err(illegal)?,
+            Instruction::VMSOF { vd, vs2, vm } => self
+                .vector_set_first((vd, vs2, vm), |_, first| first)
+                .map_err(illegal)?,
+            Instruction::VIOTA { vd, vs2, vm } => {
+                self.vector_iota((vd, Some(vs2), vm)).map_err(illegal)?
+            }
+            Instruction::VID { vd, vm } => self.vector_iota((vd, None, vm)).map_err(illegal)?,
+            Instruction::VMVXS { vs2, rd } => {
+                let (vtype, _, _) = self.vector_state().map_err(illegal)?;
+                if rd != 0 {
+                    let element: u64 = self.vreg.element(vs2, 0, vtype.sew);
+                    self.reg[rd] = X::from_i64(sign_extend_element(element, vtype.sew) as i64);
+                }
+            }
+            Instruction::VMVSX { rs1, vd } => {
+                let (vtype, vl, vstart) = self.vector_state().map_err(illegal)?;
+                if vstart < vl {
+                    let value: u64 = self.reg[rs1].to_i64() as u64;
+                    self.vreg.set_element(vd, 0, vtype.sew, value);
+                }
+            }
+            Instruction::VMVNRR { nr, vd, vs2 } => {
+                let nr: usize = nr as usize;
+                if !vd.is_multiple_of(nr) || !vs2.is_multiple_of(nr) {
+                    return Err(illegal(Exception::IllegalInstruction));
+                }
+                // byte by byte, as VLEN may be narrower than 64 bits
+                for i in 0..nr * self.vreg.vlenb() {
+                    let value: u64 = self.vreg.element(vs2, i, 8);
+                    self.vreg.set_element(vd, i, 8, value);
+                }
+            }
+            Instruction::CSRRW { csr, rs1, rd } => {
+                let rs1_value: X = self.reg[rs1];
+                self.csr_read_write(csr, rd, rd != 0, true, |_| rs1_value)
+                    .map_err(illegal)?;
+            }
+            Instruction::CSRRS { csr, rs1, rd } => {
+                let rs1_value: X = self.reg[rs1];
+                self.csr_read_write(csr, rd, true, rs1 != 0, |old| old | rs1_value)
+                    .map_err(illegal)?;
+            }
+            Instruction::CSRRC { csr, rs1, rd } => {
+                let rs1_value: X = self.reg[rs1];
+                self.csr_read_write(csr, rd, true, rs1 != 0, |old| old & !rs1_value)
+                    .map_err(illegal)?;
+            }
+            Instruction::CSRRWI { csr, uimm, rd } => {
+                self.csr_read_write(csr, rd, rd != 0, true, |_| X::from(uimm))
+                    .map_err(illegal)?;
+            }
+            Instruction::CSRRSI { csr, uimm, rd } => {
+                self.csr_read_write(csr, rd, true, uimm != 0, |old| old | X::from(uimm))
+                    .map_err(illegal)?;
+            }
+            Instruction::CSRRCI { csr, uimm, rd } => {
+                self.csr_read_write(csr, rd, true, uimm != 0, |old| old & !X::from(uimm))
+                    .map_err(illegal)?;
             }
         };
+        if parsed_instruction.uses_vector() {
+            // every vector instruction that completes resets vstart
+            self.csr.set(VSTART, X::default());
+            self.csr.set_vector_dirty();
+        }
+        Ok((parsed_instruction, self.jumped))
     }
 }
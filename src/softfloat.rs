@@ -0,0 +1,747 @@
+use std::cmp::Ordering;
+
+use crate::Word;
+
+// Accrued exception flags, as laid out in fflags
+pub const FFLAGS_NX: Word = 1 << 0; // inexact
+pub const FFLAGS_UF: Word = 1 << 1; // underflow
+pub const FFLAGS_OF: Word = 1 << 2; // overflow
+pub const FFLAGS_DZ: Word = 1 << 3; // divide by zero
+pub const FFLAGS_NV: Word = 1 << 4; // invalid operation
+
+/// IEEE-754 rounding modes, numbered as in the rm field and frm
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)] // the mnemonics used by the spec
+pub enum RoundingMode {
+    RNE, // round to nearest, ties to even
+    RTZ, // round towards zero
+    RDN, // round down (towards -infinity)
+    RUP, // round up (towards +infinity)
+    RMM, // round to nearest, ties to max magnitude
+}
+
+impl RoundingMode {
+    pub fn from_value(value: Word) -> Option<Self> {
+        match value {
+            0b000 => Some(RoundingMode::RNE),
+            0b001 => Some(RoundingMode::RTZ),
+            0b010 => Some(RoundingMode::RDN),
+            0b011 => Some(RoundingMode::RUP),
+            0b100 => Some(RoundingMode::RMM),
+            _ => None,
+        }
+    }
+}
+
+/// Binary interchange format of an IEEE-754 floating-point number.
+/// Values are passed around as raw bit patterns in the low bits of a u64.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FloatFormat {
+    exp_bits: u32,  // width of the biased exponent
+    frac_bits: u32, // width of the stored fraction (precision - 1)
+}
+
+pub const HALF: FloatFormat = FloatFormat {
+    exp_bits: 5,
+    frac_bits: 10,
+};
+
+pub const SINGLE: FloatFormat = FloatFormat {
+    exp_bits: 8,
+    frac_bits: 23,
+};
+
+pub const DOUBLE: FloatFormat = FloatFormat {
+    exp_bits: 11,
+    frac_bits: 52,
+};
+
+/// A floating-point value split into its IEEE-754 classes.
+/// Finite values are exactly `sig * 2^exp`.
+enum Unpacked {
+    NaN,
+    Infinity(bool),
+    Zero(bool),
+    Finite { sign: bool, exp: i32, sig: u128 },
+}
+
+/// Rounds `sig` to a multiple of 2^shift, returning the kept high bits and whether
+/// any discarded bit was set
+fn round_to(sig: u128, shift: i32, sign: bool, rm: RoundingMode) -> (u128, bool) {
+    if shift <= 0 {
+        return (sig << -shift, false);
+    }
+    let shift: u32 = shift as u32;
+    let (kept, rest): (u128, u128) = if shift >= 128 {
+        (0, sig)
+    } else {
+        (sig >> shift, sig & ((1 << shift) - 1))
+    };
+    // how the discarded bits compare to half of the kept lsb
+    let half: Ordering = if shift > 128 {
+        Ordering::Less
+    } else {
+        rest.cmp(&(1 << (shift - 1)))
+    };
+    let inexact: bool = rest != 0;
+    let increment: bool = match rm {
+        RoundingMode::RNE => {
+            half == Ordering::Greater || (half == Ordering::Equal && kept & 1 == 1)
+        }
+        RoundingMode::RTZ => false,
+        RoundingMode::RDN => sign && inexact,
+        RoundingMode::RUP => !sign && inexact,
+        RoundingMode::RMM => half != Ordering::Less,
+    };
+    (kept + increment as u128, inexact)
+}
+
+/// Shifts a significand of at most 126 bits so its most significant bit sits at bit 125,
+/// leaving headroom for additions and plenty of guard bits below the rounding position
+fn normalize(exp: i32, sig: u128) -> (i32, u128) {
+    let shift: u32 = sig.leading_zeros() - 2;
+    (exp - shift as i32, sig << shift)
+}
+
+#[allow(clippy::wrong_self_convention)] // self is the format, the value converted is an argument
+impl FloatFormat {
+    fn bias(&self) -> i32 {
+        (1 << (self.exp_bits - 1)) - 1
+    }
+
+    fn exp_mask(&self) -> u64 {
+        (1 << self.exp_bits) - 1
+    }
+
+    fn frac_mask(&self) -> u64 {
+        (1 << self.frac_bits) - 1
+    }
+
+    fn sign_bit(&self) -> u64 {
+        1 << (self.exp_bits + self.frac_bits)
+    }
+
+    /// Number of significant bits, including the implicit one
+    fn precision(&self) -> i32 {
+        self.frac_bits as i32 + 1
+    }
+
+    /// Exponent of the smallest normal number
+    fn min_exp(&self) -> i32 {
+        1 - self.bias()
+    }
+
+    /// Total width of an encoded value
+    fn width(&self) -> u32 {
+        1 + self.exp_bits + self.frac_bits
+    }
+
+    /// NaN-boxes a value for a 64-bit floating-point register by setting every higher bit
+    pub fn nan_box(&self, bits: u64) -> u64 {
+        if self.width() == 64 {
+            bits
+        } else {
+            bits | (u64::MAX << self.width())
+        }
+    }
+
+    /// Reads a value out of a 64-bit floating-point register.
+    /// Values that are not properly NaN-boxed read as the canonical NaN.
+    pub fn unbox(&self, reg: u64) -> u64 {
+        if self.width() == 64 {
+            reg
+        } else if reg >> self.width() == u64::MAX >> self.width() {
+            reg & !(u64::MAX << self.width())
+        } else {
+            self.canonical_nan()
+        }
+    }
+
+    /// The quiet NaN with a positive sign and an empty payload
+    pub fn canonical_nan(&self) -> u64 {
+        (self.exp_mask() << self.frac_bits) | (1 << (self.frac_bits - 1))
+    }
+
+    fn infinity(&self, sign: bool) -> u64 {
+        self.with_sign(self.exp_mask() << self.frac_bits, sign)
+    }
+
+    fn zero(&self, sign: bool) -> u64 {
+        self.with_sign(0, sign)
+    }
+
+    fn max_finite(&self, sign: bool) -> u64 {
+        self.with_sign(self.infinity(false) - 1, sign)
+    }
+
+    pub fn sign(&self, bits: u64) -> bool {
+        bits & self.sign_bit() != 0
+    }
+
+    /// Replaces the sign bit of `bits`
+    pub fn with_sign(&self, bits: u64, sign: bool) -> u64 {
+        if sign {
+            bits | self.sign_bit()
+        } else {
+            bits & !self.sign_bit()
+        }
+    }
+
+    pub fn is_nan(&self, bits: u64) -> bool {
+        (bits >> self.frac_bits) & self.exp_mask() == self.exp_mask()
+            && bits & self.frac_mask() != 0
+    }
+
+    pub fn is_signaling_nan(&self, bits: u64) -> bool {
+        self.is_nan(bits) && bits & (1 << (self.frac_bits - 1)) == 0
+    }
+
+    fn unpack(&self, bits: u64) -> Unpacked {
+        let sign: bool = self.sign(bits);
+        let biased: u64 = (bits >> self.frac_bits) & self.exp_mask();
+        let frac: u64 = bits & self.frac_mask();
+        let frac_bits: i32 = self.frac_bits as i32;
+        match (biased, frac) {
+            (0, 0) => Unpacked::Zero(sign),
+            (0, _) => Unpacked::Finite {
+                sign,
+                exp: self.min_exp() - frac_bits,
+                sig: frac as u128,
+            },
+            (b, 0) if b == self.exp_mask() => Unpacked::Infinity(sign),
+            (b, _) if b == self.exp_mask() => Unpacked::NaN,
+            (b, _) => Unpacked::Finite {
+                sign,
+                exp: b as i32 - self.bias() - frac_bits,
+                sig: (frac | (1 << self.frac_bits)) as u128,
+            },
+        }
+    }
+
+    /// Rounds the exact value `(-1)^sign * sig * 2^exp` into this format.
+    /// Tininess is detected after rounding, as RISC-V requires.
+    fn round_pack(
+        &self,
+        sign: bool,
+        exp: i32,
+        sig: u128,
+        rm: RoundingMode,
+        flags: &mut Word,
+    ) -> u64 {
+        if sig == 0 {
+            return self.zero(sign);
+        }
+        let precision: i32 = self.precision();
+        let msb_exp: i32 = exp + 127 - sig.leading_zeros() as i32;
+        let min_exp: i32 = self.min_exp();
+
+        // tiny if the result rounded with an unbounded exponent is still below the normal range
+        let tiny: bool = msb_exp < min_exp - 1
+            || (msb_exp == min_exp - 1 && {
+                let (rounded, _) = round_to(sig, msb_exp - (precision - 1) - exp, sign, rm);
+                rounded >> precision == 0
+            });
+
+        let mut lsb_exp: i32 = msb_exp.max(min_exp) - (precision - 1);
+        let (mut kept, inexact) = round_to(sig, lsb_exp - exp, sign, rm);
+        if kept >> precision != 0 {
+            kept >>= 1; // rounding carried into a new leading bit
+            lsb_exp += 1;
+        }
+        if inexact {
+            *flags |= FFLAGS_NX;
+            if tiny {
+                *flags |= FFLAGS_UF;
+            }
+        }
+
+        if kept >> (precision - 1) == 0 {
+            return self.zero(sign) | kept as u64; // subnormal
+        }
+        let biased: i64 = (lsb_exp + precision - 1 + self.bias()) as i64;
+        if biased >= self.exp_mask() as i64 {
+            *flags |= FFLAGS_OF | FFLAGS_NX;
+            let to_infinity: bool = match rm {
+                RoundingMode::RNE | RoundingMode::RMM => true,
+                RoundingMode::RTZ => false,
+                RoundingMode::RDN => sign,
+                RoundingMode::RUP => !sign,
+            };
+            return if to_infinity {
+                self.infinity(sign)
+            } else {
+                self.max_finite(sign)
+            };
+        }
+        self.zero(sign) | ((biased as u64) << self.frac_bits) | (kept as u64 & self.frac_mask())
+    }
+
+    /// NaN result of an arithmetic operation, raising invalid for signaling inputs
+    fn propagate_nan(&self, operands: &[u64], flags: &mut Word) -> u64 {
+        if operands.iter().any(|&bits| self.is_signaling_nan(bits)) {
+            *flags |= FFLAGS_NV;
+        }
+        self.canonical_nan()
+    }
+
+    /// Exact sum of two finite values, rounded once
+    fn add_finite(
+        &self,
+        (sign_a, exp_a, sig_a): (bool, i32, u128),
+        (sign_b, exp_b, sig_b): (bool, i32, u128),
+        rm: RoundingMode,
+        flags: &mut Word,
+    ) -> u64 {
+        let (exp_a, sig_a) = normalize(exp_a, sig_a);
+        let (exp_b, sig_b) = normalize(exp_b, sig_b);
+        let ((sign_big, exp_big, sig_big), (sign_small, exp_small, sig_small)) = if exp_a >= exp_b {
+            ((sign_a, exp_a, sig_a), (sign_b, exp_b, sig_b))
+        } else {
+            ((sign_b, exp_b, sig_b), (sign_a, exp_a, sig_a))
+        };
+
+        // align the smaller operand, folding the bits shifted out into a sticky bit
+        let distance: i32 = exp_big - exp_small;
+        let sig_small: u128 = if distance >= 126 {
+            1
+        } else {
+            let sticky: bool = sig_small & ((1 << distance) - 1) != 0;
+            (sig_small >> distance) | sticky as u128
+        };
+
+        if sign_big == sign_small {
+            return self.round_pack(sign_big, exp_big, sig_big + sig_small, rm, flags);
+        }
+        match sig_big.cmp(&sig_small) {
+            Ordering::Greater => self.round_pack(sign_big, exp_big, sig_big - sig_small, rm, flags),
+            Ordering::Less => self.round_pack(sign_small, exp_big, sig_small - sig_big, rm, flags),
+            Ordering::Equal => self.zero(rm == RoundingMode::RDN), // exact cancellation
+        }
+    }
+
+    pub fn add(&self, a: u64, b: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        match (self.unpack(a), self.unpack(b)) {
+            (Unpacked::NaN, _) | (_, Unpacked::NaN) => self.propagate_nan(&[a, b], flags),
+            (Unpacked::Infinity(sign_a), Unpacked::Infinity(sign_b)) if sign_a != sign_b => {
+                *flags |= FFLAGS_NV; // inf - inf
+                self.canonical_nan()
+            }
+            (Unpacked::Infinity(sign), _) | (_, Unpacked::Infinity(sign)) => self.infinity(sign),
+            (Unpacked::Zero(sign_a), Unpacked::Zero(sign_b)) => {
+                if sign_a == sign_b {
+                    self.zero(sign_a)
+                } else {
+                    self.zero(rm == RoundingMode::RDN)
+                }
+            }
+            (Unpacked::Zero(_), _) => b,
+            (_, Unpacked::Zero(_)) => a,
+            (
+                Unpacked::Finite {
+                    sign: sign_a,
+                    exp: exp_a,
+                    sig: sig_a,
+                },
+                Unpacked::Finite {
+                    sign: sign_b,
+                    exp: exp_b,
+                    sig: sig_b,
+                },
+            ) => self.add_finite((sign_a, exp_a, sig_a), (sign_b, exp_b, sig_b), rm, flags),
+        }
+    }
+
+    pub fn sub(&self, a: u64, b: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        if self.is_nan(b) {
+            return self.propagate_nan(&[a, b], flags);
+        }
+        self.add(a, b ^ self.sign_bit(), rm, flags)
+    }
+
+    pub fn mul(&self, a: u64, b: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        let sign: bool = self.sign(a) != self.sign(b);
+        match (self.unpack(a), self.unpack(b)) {
+            (Unpacked::NaN, _) | (_, Unpacked::NaN) => self.propagate_nan(&[a, b], flags),
+            (Unpacked::Infinity(_), Unpacked::Zero(_))
+            | (Unpacked::Zero(_), Unpacked::Infinity(_)) => {
+                *flags |= FFLAGS_NV; // inf * 0
+                self.canonical_nan()
+            }
+            (Unpacked::Infinity(_), _) | (_, Unpacked::Infinity(_)) => self.infinity(sign),
+            (Unpacked::Zero(_), _) | (_, Unpacked::Zero(_)) => self.zero(sign),
+            (
+                Unpacked::Finite {
+                    exp: exp_a,
+                    sig: sig_a,
+                    ..
+                },
+                Unpacked::Finite {
+                    exp: exp_b,
+                    sig: sig_b,
+                    ..
+                },
+            ) => self.round_pack(sign, exp_a + exp_b, sig_a * sig_b, rm, flags),
+        }
+    }
+
+    pub fn div(&self, a: u64, b: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        let sign: bool = self.sign(a) != self.sign(b);
+        match (self.unpack(a), self.unpack(b)) {
+            (Unpacked::NaN, _) | (_, Unpacked::NaN) => self.propagate_nan(&[a, b], flags),
+            (Unpacked::Infinity(_), Unpacked::Infinity(_))
+            | (Unpacked::Zero(_), Unpacked::Zero(_)) => {
+                *flags |= FFLAGS_NV; // inf / inf and 0 / 0
+                self.canonical_nan()
+            }
+            (Unpacked::Infinity(_), _) => self.infinity(sign),
+            (_, Unpacked::Infinity(_)) => self.zero(sign),
+            (Unpacked::Zero(_), _) => self.zero(sign),
+            (_, Unpacked::Zero(_)) => {
+                *flags |= FFLAGS_DZ;
+                self.infinity(sign)
+            }
+            (
+                Unpacked::Finite {
+                    exp: exp_a,
+                    sig: sig_a,
+                    ..
+                },
+                Unpacked::Finite {
+                    exp: exp_b,
+                    sig: sig_b,
+                    ..
+                },
+            ) => {
+                // a wide dividend leaves plenty of quotient bits below the rounding position
+                let (exp_a, sig_a) = normalize(exp_a, sig_a);
+                let quotient: u128 = sig_a / sig_b;
+                let sticky: bool = sig_a % sig_b != 0;
+                self.round_pack(sign, exp_a - exp_b, quotient | sticky as u128, rm, flags)
+            }
+        }
+    }
+
+    pub fn sqrt(&self, a: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        match self.unpack(a) {
+            Unpacked::NaN => self.propagate_nan(&[a], flags),
+            Unpacked::Zero(_) => a, // sqrt(-0) = -0
+            Unpacked::Infinity(false) => a,
+            Unpacked::Infinity(true) | Unpacked::Finite { sign: true, .. } => {
+                *flags |= FFLAGS_NV;
+                self.canonical_nan()
+            }
+            Unpacked::Finite { exp, sig, .. } => {
+                let (mut exp, mut sig) = normalize(exp, sig);
+                if exp % 2 != 0 {
+                    exp -= 1; // the exponent must be even to halve it
+                    sig <<= 1;
+                }
+                let root: u128 = sig.isqrt();
+                let sticky: bool = root * root != sig;
+                self.round_pack(false, exp / 2, root | sticky as u128, rm, flags)
+            }
+        }
+    }
+
+    /// Fused `a * b + c` with a single rounding
+    pub fn mul_add(&self, a: u64, b: u64, c: u64, rm: RoundingMode, flags: &mut Word) -> u64 {
+        let product_sign: bool = self.sign(a) != self.sign(b);
+        let (factor_a, factor_b) = (self.unpack(a), self.unpack(b));
+        // inf * 0 is invalid even when the addend is a quiet NaN
+        if matches!(
+            (&factor_a, &factor_b),
+            (Unpacked::Infinity(_), Unpacked::Zero(_)) | (Unpacked::Zero(_), Unpacked::Infinity(_))
+        ) {
+            *flags |= FFLAGS_NV;
+            return self.propagate_nan(&[a, b, c], flags);
+        }
+        if self.is_nan(a) || self.is_nan(b) || self.is_nan(c) {
+            return self.propagate_nan(&[a, b, c], flags);
+        }
+        match (factor_a, factor_b) {
+            (Unpacked::Infinity(_), _) | (_, Unpacked::Infinity(_)) => {
+                self.add(self.infinity(product_sign), c, rm, flags)
+            }
+            (Unpacked::Zero(_), _) | (_, Unpacked::Zero(_)) => {
+                self.add(self.zero(product_sign), c, rm, flags)
+            }
+            (
+                Unpacked::Finite {
+                    exp: exp_a,
+                    sig: sig_a,
+                    ..
+                },
+                Unpacked::Finite {
+                    exp: exp_b,
+                    sig: sig_b,
+                    ..
+                },
+            ) => match self.unpack(c) {
+                Unpacked::Finite {
+                    sign: sign_c,
+                    exp: exp_c,
+                    sig: sig_c,
+                } => self.add_finite(
+                    (product_sign, exp_a + exp_b, sig_a * sig_b),
+                    (sign_c, exp_c, sig_c),
+                    rm,
+                    flags,
+                ),
+                _ => self.round_pack(product_sign, exp_a + exp_b, sig_a * sig_b, rm, flags), // c is zero
+            },
+            _ => unreachable!("NaN operands are handled above"),
+        }
+    }
+
+    /// Total order key where -0 sorts below +0
+    fn order_key(&self, bits: u64) -> i128 {
+        let magnitude: i128 = (bits & !self.sign_bit()) as i128;
+        if self.sign(bits) {
+            -magnitude - 1
+        } else {
+            magnitude
+        }
+    }
+
+    /// Compares two values, returning None when they are unordered. Quiet comparisons
+    /// only signal on signaling NaNs, signaling ones on any NaN.
+    pub fn compare(&self, a: u64, b: u64, signaling: bool, flags: &mut Word) -> Option<Ordering> {
+        if self.is_nan(a) || self.is_nan(b) {
+            if signaling || self.is_signaling_nan(a) || self.is_signaling_nan(b) {
+                *flags |= FFLAGS_NV;
+            }
+            return None;
+        }
+        let is_zero = |bits: u64| bits & !self.sign_bit() == 0;
+        if is_zero(a) && is_zero(b) {
+            return Some(Ordering::Equal); // -0 == +0
+        }
+        Some(self.order_key(a).cmp(&self.order_key(b)))
+    }
+
+    /// minimumNumber / maximumNumber: a single NaN operand is ignored and -0 < +0
+    pub fn min_max(&self, a: u64, b: u64, max: bool, flags: &mut Word) -> u64 {
+        if self.is_signaling_nan(a) || self.is_signaling_nan(b) {
+            *flags |= FFLAGS_NV;
+        }
+        match (self.is_nan(a), self.is_nan(b)) {
+            (true, true) => self.canonical_nan(),
+            (true, false) => b,
+            (false, true) => a,
+            (false, false) => {
+                let a_first: bool = self.order_key(a) < self.order_key(b);
+                if a_first != max { a } else { b }
+            }
+        }
+    }
+
+    /// minimum / maximum (FMINM / FMAXM): like `min_max` except that any NaN operand gives NaN
+    pub fn minimum_maximum(&self, a: u64, b: u64, max: bool, flags: &mut Word) -> u64 {
+        if self.is_nan(a) || self.is_nan(b) {
+            return self.propagate_nan(&[a, b], flags);
+        }
+        self.min_max(a, b, max, flags)
+    }
+
+    /// Rounds to an integral value in the same format (FROUND / FROUNDNX). Only `exact`
+    /// raises inexact when the value changes.
+    pub fn round_to_integral(
+        &self,
+        a: u64,
+        rm: RoundingMode,
+        exact: bool,
+        flags: &mut Word,
+    ) -> u64 {
+        match self.unpack(a) {
+            Unpacked::NaN => self.propagate_nan(&[a], flags),
+            Unpacked::Finite { sign, exp, sig } if exp < 0 => {
+                let (magnitude, inexact) = round_to(sig, -exp, sign, rm);
+                if inexact && exact {
+                    *flags |= FFLAGS_NX;
+                }
+                // an integer that fits the source precision packs exactly
+                self.round_pack(sign, 0, magnitude, rm, &mut 0)
+            }
+            _ => a, // infinities, zeros and values that are already integers
+        }
+    }
+
+    /// Converts to a `width`-bit integer rounding towards zero and keeping only the low
+    /// `width` bits of out-of-range values (FCVTMOD.W.D). Infinities and NaNs give 0.
+    pub fn to_int_modular(&self, a: u64, width: u32, flags: &mut Word) -> u64 {
+        let mask: u64 = u64::MAX >> (64 - width);
+        let (sign, magnitude, inexact): (bool, u128, bool) = match self.unpack(a) {
+            Unpacked::NaN | Unpacked::Infinity(_) => {
+                *flags |= FFLAGS_NV;
+                return 0;
+            }
+            Unpacked::Zero(_) => return 0,
+            Unpacked::Finite { exp, .. } if exp >= 64 => {
+                *flags |= FFLAGS_NV;
+                return 0; // every low bit of the integer is zero
+            }
+            Unpacked::Finite { sign, exp, sig } if exp >= 0 => (sign, sig << exp, false),
+            Unpacked::Finite { sign, exp, sig } => {
+                let (magnitude, inexact) = round_to(sig, -exp, sign, RoundingMode::RTZ);
+                (sign, magnitude, inexact)
+            }
+        };
+        let limit: u128 = (1 << (width - 1)) - !sign as u128;
+        if magnitude > limit {
+            *flags |= FFLAGS_NV;
+        } else if inexact {
+            *flags |= FFLAGS_NX;
+        }
+        let value: u64 = magnitude as u64;
+        (if sign { value.wrapping_neg() } else { value }) & mask
+    }
+
+    /// Constant `index` of the FLI table, rounded into this format
+    pub fn fli(&self, index: u32) -> u64 {
+        // entries 2 to 29 as exact doubles: 2^-16, 2^-15, 2^-8, 2^-7, 0.0625 ... 2^15, 2^16
+        const CONSTANTS: [f64; 28] = [
+            1.52587890625e-05,
+            3.0517578125e-05,
+            0.00390625,
+            0.0078125,
+            0.0625,
+            0.125,
+            0.25,
+            0.3125,
+            0.375,
+            0.4375,
+            0.5,
+            0.625,
+            0.75,
+            0.875,
+            1.0,
+            1.25,
+            1.5,
+            1.75,
+            2.0,
+            2.5,
+            3.0,
+            4.0,
+            8.0,
+            16.0,
+            128.0,
+            256.0,
+            32768.0,
+            65536.0,
+        ];
+        match index {
+            0 => self.with_sign(self.fli(16), true), // -1.0
+            1 => 1 << self.frac_bits,                // smallest positive normal
+            30 => self.infinity(false),
+            31 => self.canonical_nan(),
+            // 2^-16 and 2^-15 are subnormal and 2^16 overflows to infinity in half precision
+            _ => self.convert_from(
+                DOUBLE,
+                CONSTANTS[index as usize - 2].to_bits(),
+                RoundingMode::RNE,
+                &mut 0,
+            ),
+        }
+    }
+
+    /// FCLASS result: a one-hot mask of the class of `bits`
+    pub fn classify(&self, bits: u64) -> Word {
+        let subnormal: bool = (bits >> self.frac_bits) & self.exp_mask() == 0;
+        let shift: u32 = match (self.unpack(bits), self.sign(bits)) {
+            (Unpacked::Infinity(true), _) => 0,
+            (Unpacked::Finite { .. }, true) if !subnormal => 1,
+            (Unpacked::Finite { .. }, true) => 2,
+            (Unpacked::Zero(true), _) => 3,
+            (Unpacked::Zero(false), _) => 4,
+            (Unpacked::Finite { .. }, false) if subnormal => 5,
+            (Unpacked::Finite { .. }, false) => 6,
+            (Unpacked::Infinity(false), _) => 7,
+            (Unpacked::NaN, _) if self.is_signaling_nan(bits) => 8,
+            (Unpacked::NaN, _) => 9,
+        };
+        1 << shift
+    }
+
+    /// Converts to a `width`-bit integer, saturating and raising invalid when the
+    /// rounded value is out of range. The result is returned in the low `width` bits.
+    pub fn to_int(
+        &self,
+        a: u64,
+        signed: bool,
+        width: u32,
+        rm: RoundingMode,
+        flags: &mut Word,
+    ) -> u64 {
+        let mask: u64 = u64::MAX >> (64 - width);
+        let max: u64 = if signed { mask >> 1 } else { mask };
+        let min: u64 = if signed { !max & mask } else { 0 }; // bit pattern of the minimum
+        let (sign, magnitude, inexact): (bool, u128, bool) = match self.unpack(a) {
+            Unpacked::NaN => (false, u128::MAX, false),
+            Unpacked::Infinity(sign) => (sign, u128::MAX, false),
+            Unpacked::Zero(_) => return 0,
+            Unpacked::Finite { sign, exp, sig } => {
+                if exp >= 64 {
+                    (sign, u128::MAX, false)
+                } else {
+                    let (magnitude, inexact) = round_to(sig, -exp, sign, rm);
+                    (sign, magnitude, inexact)
+                }
+            }
+        };
+
+        let limit: u128 = if sign {
+            if signed { max as u128 + 1 } else { 0 }
+        } else {
+            max as u128
+        };
+        if magnitude > limit {
+            *flags |= FFLAGS_NV;
+            return if sign && !self.is_nan(a) { min } else { max };
+        }
+        if inexact {
+            *flags |= FFLAGS_NX;
+        }
+        let value: u64 = magnitude as u64;
+        (if sign { value.wrapping_neg() } else { value }) & mask
+    }
+
+    /// Converts the low `width` bits of `value`, read as signed or unsigned, into this format
+    pub fn from_int(
+        &self,
+        value: u64,
+        signed: bool,
+        width: u32,
+        rm: RoundingMode,
+        flags: &mut Word,
+    ) -> u64 {
+        let value: u64 = value & (u64::MAX >> (64 - width));
+        let negative: bool = signed && value >> (width - 1) == 1;
+        let magnitude: u64 = if negative {
+            value.wrapping_neg() & (u64::MAX >> (64 - width))
+        } else {
+            value
+        };
+        self.round_pack(negative, 0, magnitude as u128, rm, flags)
+    }
+
+    /// Converts `a` from format `from` into this format
+    pub fn convert_from(
+        &self,
+        from: FloatFormat,
+        a: u64,
+        rm: RoundingMode,
+        flags: &mut Word,
+    ) -> u64 {
+        match from.unpack(a) {
+            Unpacked::NaN => {
+                if from.is_signaling_nan(a) {
+                    *flags |= FFLAGS_NV;
+                }
+                self.canonical_nan()
+            }
+            Unpacked::Infinity(sign) => self.infinity(sign),
+            Unpacked::Zero(sign) => self.zero(sign),
+            Unpacked::Finite { sign, exp, sig } => self.round_pack(sign, exp, sig, rm, flags),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::ops::Range;
+
+use crate::{ConfigError, PAGE_SIZE};
+
+pub const DEFAULT_TLB_ENTRIES: usize = 32; // entries in each TLB
+pub const DEFAULT_TLB_WAYS: usize = 4; // entries in each set
+
+/// A leaf page-table entry found by a page walk, mapping a page of 2^`page_shift` bytes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Leaf {
+    pub pte: u64,        // the entry itself, for the permission and A/D checks
+    pub base: u64,       // physical address of the start of the page
+    pub page_shift: u32, // 12 for a base page, more for superpages and NAPOT pages
+    pub global: bool,    // G was set on the way to the leaf, so the ASID does not matter
+}
+
+impl Leaf {
+    /// The physical address `vaddr` maps to within this page
+    pub fn physical(&self, vaddr: u64) -> u64 {
+        self.base | (vaddr & ((1 << self.page_shift) - 1))
+    }
+}
+
+/// What a TLB has seen since it was created
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct TlbStats {
+    pub hits: u64,            // lookups that found a translation
+    pub misses: u64,          // lookups that needed a page walk
+    pub flushes: u64,         // SFENCE.VMA operations
+    pub flushed_entries: u64, // entries those operations invalidated
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TlbEntry {
+    leaf: Leaf,
+    vpn: u64,       // virtual address >> leaf.page_shift
+    asid: u64,      // address space of the mapping, ignored when it is global
+    last_used: u64, // lookup clock of the last hit, for LRU replacement
+}
+
+impl TlbEntry {
+    fn translates(&self, vaddr: u64) -> bool {
+        vaddr >> self.leaf.page_shift == self.vpn
+    }
+
+    fn in_address_space(&self, asid: u64) -> bool {
+        self.leaf.global || self.asid == asid
+    }
+}
+
+/// A set-associative TLB with LRU replacement, caching leaf entries by virtual page and
+/// ASID. Superpages are cached in the set of the base page that was accessed.
+#[derive(Debug, Clone)]
+pub struct Tlb {
+    entries: Vec<Option<TlbEntry>>, // the ways of set 0, then those of set 1, ...
+    ways: usize,                    // entries in each set
+    clock: u64,                     // counts lookups, to order the entries by last use
+    stats: TlbStats,
+}
+
+impl Default for Tlb {
+    fn default() -> Self {
+        Self::new(DEFAULT_TLB_ENTRIES, DEFAULT_TLB_WAYS).expect("the default TLB geometry is valid")
+    }
+}
+
+impl Tlb {
+    /// A TLB of `entries` entries in sets of `ways`: 1 way is direct-mapped, `entries` ways
+    /// fully associative. The number of sets must be a power of two.
+    pub fn new(entries: usize, ways: usize) -> Result<Self, ConfigError> {
+        if ways == 0 || !entries.is_multiple_of(ways) || !(entries / ways).is_power_of_two() {
+            return Err(ConfigError::TlbGeometry);
+        }
+        Ok(Tlb {
+            entries: vec![None; entries],
+            ways,
+            clock: 0,
+            stats: TlbStats::default(),
+        })
+    }
+
+    pub fn entries(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn ways(&self) -> usize {
+        self.ways
+    }
+
+    pub fn stats(&self) -> TlbStats {
+        self.stats
+    }
+
+    /// Indices of the entries in the set `vaddr` maps to
+    fn set(&self, vaddr: u64) -> Range<usize> {
+        let sets: usize = self.entries.len() / self.ways;
+        let set: usize = (vaddr / PAGE_SIZE as u64) as usize & (sets - 1);
+        set * self.ways..(set + 1) * self.ways
+    }
+
+    /// The cached translation of `vaddr` in address space `asid`, if any
+    pub fn lookup(&mut self, vaddr: u64, asid: u64) -> Option<Leaf> {
+        self.clock += 1;
+        let clock: u64 = self.clock;
+        let set: Range<usize> = self.set(vaddr);
+        let hit: Option<&mut TlbEntry> = self.entries[set]
+            .iter_mut()
+            .flatten()
+            .find(|entry| entry.translates(vaddr) && entry.in_address_space(asid));
+        match hit {
+            Some(entry) => {
+                entry.last_used = clock;
+                self.stats.hits += 1;
+                Some(entry.leaf)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Caches the translation of `vaddr` in address space `asid`, replacing a free entry of
+    /// its set or else the least recently used one
+    pub fn insert(&mut self, vaddr: u64, asid: u64, leaf: Leaf) {
+        let set: Range<usize> = self.set(vaddr);
+        let ways: &[Option<TlbEntry>] = &self.entries[set.clone()];
+        let victim: usize = ways.iter().position(Option::is_none).unwrap_or_else(|| {
+            (0..ways.len())
+                .min_by_key(|&way| ways[way].map_or(0, |entry| entry.last_used))
+                .unwrap_or(0)
+        });
+        self.entries[set.start + victim] = Some(TlbEntry {
+            leaf,
+            vpn: vaddr >> leaf.page_shift,
+            asid,
+            last_used: self.clock,
+        });
+    }
+
+    /// Invalidates the entries an SFENCE.VMA selects: those translating `vaddr` (or every
+    /// address when None) in address space `asid` (or every one when None). Global entries
+    /// are kept when an ASID is given.
+    pub fn flush(&mut self, vaddr: Option<u64>, asid: Option<u64>) {
+        self.stats.flushes += 1;
+        for slot in &mut self.entries {
+            let Some(entry) = slot else {
+                continue;
+            };
+            let address_matches: bool = vaddr.is_none_or(|vaddr| entry.translates(vaddr));
+            let asid_matches: bool =
+                asid.is_none_or(|asid| !entry.leaf.global && entry.asid == asid);
+            if address_matches && asid_matches {
+                *slot = None;
+                self.stats.flushed_entries += 1;
+            }
+        }
+    }
+}
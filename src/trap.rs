@@ -0,0 +1,125 @@
+use crate::{Word, Xlen};
+
+/// Synchronous exception causes, in the order of their mcause exception codes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Exception {
+    InstructionAddressMisaligned, // jump/branch target not aligned to IALIGN
+    InstructionAccessFault,       // instruction fetch outside of memory
+    IllegalInstruction,           // unknown or unsupported encoding
+    Breakpoint,                   // EBREAK
+    LoadAddressMisaligned,        // load address not naturally aligned
+    LoadAccessFault,              // load outside of memory
+    StoreAddressMisaligned,       // store address not naturally aligned
+    StoreAccessFault,             // store outside of memory
+    EnvironmentCallFromUMode,     // ECALL in user mode
+    EnvironmentCallFromSMode,     // ECALL in supervisor mode
+    EnvironmentCallFromMMode,     // ECALL in machine mode
+    InstructionPageFault,         // fetch from a page that is not executable
+    LoadPageFault,                // load from a page that is not readable
+    StorePageFault,               // store or AMO to a page that is not writable
+}
+
+impl Exception {
+    /// The exception code reported in mcause
+    pub fn code(&self) -> Word {
+        match self {
+            Exception::InstructionAddressMisaligned => 0,
+            Exception::InstructionAccessFault => 1,
+            Exception::IllegalInstruction => 2,
+            Exception::Breakpoint => 3,
+            Exception::LoadAddressMisaligned => 4,
+            Exception::LoadAccessFault => 5,
+            Exception::StoreAddressMisaligned => 6,
+            Exception::StoreAccessFault => 7,
+            Exception::EnvironmentCallFromUMode => 8,
+            Exception::EnvironmentCallFromSMode => 9,
+            Exception::EnvironmentCallFromMMode => 11,
+            Exception::InstructionPageFault => 12,
+            Exception::LoadPageFault => 13,
+            Exception::StorePageFault => 15,
+        }
+    }
+}
+
+/// Asynchronous interrupt causes, in the order of their mcause exception codes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Interrupt {
+    SupervisorSoftware, // inter-processor interrupt for the supervisor
+    MachineSoftware,    // inter-processor interrupt
+    SupervisorTimer,    // supervisor timer, raised by machine-mode software
+    MachineTimer,       // mtime >= mtimecmp
+    SupervisorExternal, // interrupt controller, supervisor context
+    MachineExternal,    // interrupt controller
+}
+
+impl Interrupt {
+    /// The exception code reported in mcause (with the interrupt bit clear)
+    pub fn code(&self) -> Word {
+        match self {
+            Interrupt::SupervisorSoftware => 1,
+            Interrupt::MachineSoftware => 3,
+            Interrupt::SupervisorTimer => 5,
+            Interrupt::MachineTimer => 7,
+            Interrupt::SupervisorExternal => 9,
+            Interrupt::MachineExternal => 11,
+        }
+    }
+
+    /// The bit of this interrupt in mip and mie
+    pub fn mask(&self) -> Word {
+        1 << self.code()
+    }
+}
+
+/// A trap raised while executing an instruction
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Trap<X: Xlen = Word> {
+    pub cause: Exception, // what went wrong
+    pub tval: X,          // faulting address, or the instruction bits for illegal instructions
+}
+
+impl<X: Xlen> Trap<X> {
+    pub fn new(cause: Exception, tval: X) -> Self {
+        Trap { cause, tval }
+    }
+
+    /// Trap raised by a failed instruction fetch at `addr`
+    pub fn fetch(err: MemoryError, addr: X) -> Self {
+        match err {
+            MemoryError::Misaligned => Trap::new(Exception::InstructionAddressMisaligned, addr),
+            MemoryError::OutOfBounds => Trap::new(Exception::InstructionAccessFault, addr),
+        }
+    }
+
+    /// Trap raised by a failed load from `addr`
+    pub fn load(err: MemoryError, addr: X) -> Self {
+        match err {
+            MemoryError::Misaligned => Trap::new(Exception::LoadAddressMisaligned, addr),
+            MemoryError::OutOfBounds => Trap::new(Exception::LoadAccessFault, addr),
+        }
+    }
+
+    /// Trap raised by a failed store to `addr`
+    pub fn store(err: MemoryError, addr: X) -> Self {
+        match err {
+            MemoryError::Misaligned => Trap::new(Exception::StoreAddressMisaligned, addr),
+            MemoryError::OutOfBounds => Trap::new(Exception::StoreAccessFault, addr),
+        }
+    }
+}
+
+/// Errors reported by `Memory` accesses
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MemoryError {
+    Misaligned,  // address is not naturally aligned for the access size
+    OutOfBounds, // address lies outside addressable memory
+}
+
+/// Errors reported when the host configures a hart with unsupported parameters
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConfigError {
+    CacheBlockSize, // a cache block must be a power of two bytes, at most a page
+    TlbGeometry,    // a TLB must hold a power-of-two number of sets of at least one way
+    ElementLength,  // ELEN must be 32 or 64
+    VectorLength,   // VLEN must be a power of two between ELEN and 65536
+}
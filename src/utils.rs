@@ -13,10 +13,12 @@ pub fn sign_extend_u32(value: usize, bits: u32) -> i32 {
 pub fn load_from_file() -> Vec<Word> {
     let contents = fs::read("program.bin");
     match contents {
-        Ok(bytes) => {
-            if bytes.len() % 4 != 0 {
-                panic!("File size is not a multiple of 4 bytes");
+        Ok(mut bytes) => {
+            if bytes.len() % 2 != 0 {
+                panic!("File size is not a multiple of 2 bytes");
             }
+            // a program ending in a compressed instruction is padded to a full word
+            bytes.resize(bytes.len().next_multiple_of(4), 0);
 
             let mut words: Vec<Word> = Vec::new();
             let mut i = 0;
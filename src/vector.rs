@@ -0,0 +1,204 @@
+use crate::{Byte, ConfigError};
+
+pub const DEFAULT_VLEN: usize = 128; // bits in each vector register
+pub const DEFAULT_ELEN: usize = 64; // widest supported element
+pub const VECTOR_REGISTER_COUNT: usize = 32;
+const MAX_VLEN: usize = 1 << 16; // vstart must be able to index every element at SEW = 8
+
+// vtype fields
+pub const VTYPE_VLMUL: u64 = 0b111; // log2 of LMUL as a signed 3-bit value
+pub const VTYPE_VSEW: u64 = 0b111 << 3; // log2 of SEW / 8
+pub const VTYPE_VTA: u64 = 1 << 6; // tail agnostic
+pub const VTYPE_VMA: u64 = 1 << 7; // mask agnostic
+const VTYPE_RESERVED_SHIFT: u32 = 8; // everything above vma must be zero (vill sits at XLEN-1)
+
+/// The 32 vector registers of VLEN bits each. They are stored as one little-endian byte array,
+/// so a register group is simply a run of consecutive registers.
+#[derive(Debug, Clone)]
+pub struct VectorRegisters {
+    bytes: Vec<Byte>, // VECTOR_REGISTER_COUNT * VLEN / 8 bytes
+    vlen: usize,      // bits in each register
+    elen: usize,      // widest element the hart supports
+}
+
+impl Default for VectorRegisters {
+    fn default() -> Self {
+        Self::new(DEFAULT_VLEN, DEFAULT_ELEN).expect("the default VLEN and ELEN are valid")
+    }
+}
+
+impl VectorRegisters {
+    /// Registers of `vlen` bits holding elements of up to `elen` bits, rejecting lengths the
+    /// V extension does not allow
+    pub fn new(vlen: usize, elen: usize) -> Result<Self, ConfigError> {
+        if elen != 32 && elen != 64 {
+            return Err(ConfigError::ElementLength);
+        }
+        if !vlen.is_power_of_two() || vlen < elen || vlen > MAX_VLEN {
+            return Err(ConfigError::VectorLength);
+        }
+        Ok(VectorRegisters {
+            bytes: vec![0; VECTOR_REGISTER_COUNT * vlen / 8],
+            vlen,
+            elen,
+        })
+    }
+
+    pub fn vlen(&self) -> usize {
+        self.vlen
+    }
+
+    pub fn elen(&self) -> usize {
+        self.elen
+    }
+
+    /// Bytes in each register, the value of the vlenb CSR
+    pub fn vlenb(&self) -> usize {
+        self.vlen / 8
+    }
+
+    /// Element `index` of the register group starting at `reg`, zero-extended from `width` bits
+    pub fn element(&self, reg: usize, index: usize, width: usize) -> u64 {
+        let size: usize = width / 8;
+        let start: usize = reg * self.vlenb() + index * size;
+        let mut bytes: [Byte; 8] = [0; 8];
+        bytes[..size].copy_from_slice(&self.bytes[start..start + size]);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Writes the low `width` bits of `value` to element `index` of the group starting at `reg`
+    pub fn set_element(&mut self, reg: usize, index: usize, width: usize, value: u64) {
+        let size: usize = width / 8;
+        let start: usize = reg * self.vlenb() + index * size;
+        self.bytes[start..start + size].copy_from_slice(&value.to_le_bytes()[..size]);
+    }
+
+    /// Bit `index` of the mask held in register `reg`
+    pub fn mask_bit(&self, reg: usize, index: usize) -> bool {
+        self.bytes[reg * self.vlenb() + index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_mask_bit(&mut self, reg: usize, index: usize, value: bool) {
+        let position: usize = reg * self.vlenb() + index / 8;
+        let byte: &mut Byte = &mut self.bytes[position];
+        if value {
+            *byte |= 1 << (index % 8);
+        } else {
+            *byte &= !(1 << (index % 8));
+        }
+    }
+}
+
+/// A legal vtype setting, decoded
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VType {
+    pub sew: usize,          // selected element width in bits
+    pub lmul_log2: i32,      // log2 of the register group multiplier, -3 (1/8) to 3 (8)
+    pub tail_agnostic: bool, // vta, tail elements are left undisturbed either way
+    pub mask_agnostic: bool, // vma, inactive elements are left undisturbed either way
+}
+
+impl VType {
+    /// Decodes a vtype value, returning None when the setting is reserved or wider than
+    /// `elen` allows, which makes vsetvl set vill
+    pub fn decode(vtype: u64, elen: usize) -> Option<VType> {
+        if vtype >> VTYPE_RESERVED_SHIFT != 0 {
+            return None;
+        }
+        let vlmul: u64 = vtype & VTYPE_VLMUL;
+        if vlmul == 0b100 {
+            return None; // reserved LMUL
+        }
+        let lmul_log2: i32 = ((vlmul as i32) << 29) >> 29;
+        let vsew: u64 = (vtype & VTYPE_VSEW) >> 3;
+        if vsew > 3 {
+            return None;
+        }
+        let sew: usize = 8 << vsew;
+        // a fractional group must still hold at least one element: SEW <= LMUL * ELEN
+        if sew > elen || (lmul_log2 < 0 && sew > elen >> -lmul_log2) {
+            return None;
+        }
+        Some(VType {
+            sew,
+            lmul_log2,
+            tail_agnostic: vtype & VTYPE_VTA != 0,
+            mask_agnostic: vtype & VTYPE_VMA != 0,
+        })
+    }
+
+    /// Largest vl for this setting: LMUL * VLEN / SEW
+    pub fn vlmax(&self, vlen: usize) -> usize {
+        scale(vlen, self.lmul_log2) / self.sew
+    }
+}
+
+/// Second source of the .vv / .vx / .vi instruction forms
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VectorOperand {
+    Vector(usize),  // vs1, element by element
+    Scalar(usize),  // x[rs1], truncated to SEW
+    Immediate(i32), // simm5 (or uimm5 for the shifts), truncated to SEW
+}
+
+/// Widths of the destination and vs2 elements relative to SEW. The other source is always
+/// SEW bits wide.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ElementWidths {
+    Single,       // everything is SEW wide
+    Widening,     // 2 * SEW result from SEW sources (vwadd.vv)
+    WideningWide, // 2 * SEW result and vs2 (vwadd.wv)
+    Narrowing,    // SEW result from a 2 * SEW vs2 (vnsrl.wv)
+}
+
+impl ElementWidths {
+    /// log2 of the destination and vs2 widths in units of SEW
+    pub fn scales(&self) -> (i32, i32) {
+        match self {
+            ElementWidths::Single => (0, 0),
+            ElementWidths::Widening => (1, 0),
+            ElementWidths::WideningWide => (1, 1),
+            ElementWidths::Narrowing => (0, 1),
+        }
+    }
+}
+
+/// How the element addresses of a vector load or store are formed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VectorAddressing {
+    UnitStride,     // consecutive elements (segments are packed)
+    Strided(usize), // x[rs2] bytes between elements
+    Indexed(usize), // byte offsets taken from the elements of vs2
+    Mask,           // vlm / vsm: ceil(vl / 8) bytes
+    WholeRegister,  // vl<nf>r / vs<nf>r: nf registers regardless of vtype and vl
+}
+
+/// `value * 2^log2` for a signed power of two
+pub fn scale(value: usize, log2: i32) -> usize {
+    if log2 >= 0 {
+        value << log2
+    } else {
+        value >> -log2
+    }
+}
+
+/// Whether `reg` can start a register group of 2^`lmul_log2` registers. Fractional groups
+/// occupy a single register.
+pub fn group_aligned(reg: usize, lmul_log2: i32) -> bool {
+    lmul_log2 <= 0 || reg.is_multiple_of(1 << lmul_log2)
+}
+
+/// Sign-extends the low `width` bits of an element to 64 bits
+pub fn sign_extend_element(value: u64, width: usize) -> u64 {
+    let shift: usize = 64 - width;
+    (((value << shift) as i64) >> shift) as u64
+}
+
+/// Keeps the low `width` bits of a value
+pub fn truncate_element(value: u64, width: usize) -> u64 {
+    if width >= 64 {
+        value
+    } else {
+        value & ((1 << width) - 1)
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    fmt::{Debug, UpperHex},
+    ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr},
+};
+
+use crate::Word;
+
+/// Width of the integer registers: `u32` for RV32 and `u64` for RV64.
+/// Arithmetic is done on 64-bit values and truncated back to XLEN bits.
+pub trait Xlen:
+    Copy
+    + Default
+    + Debug
+    + UpperHex
+    + Eq
+    + Ord
+    + From<Word>
+    + Into<u64>
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+    + 'static
+{
+    const XLEN: u32; // register width in bits
+
+    /// Truncates a 64-bit value to XLEN bits
+    fn from_u64(value: u64) -> Self;
+
+    /// Zero-extends the register value to 64 bits
+    fn to_u64(self) -> u64 {
+        self.into()
+    }
+
+    /// Sign-extends the register value to 64 bits
+    fn to_i64(self) -> i64;
+
+    /// Truncates a signed 64-bit value to XLEN bits
+    fn from_i64(value: i64) -> Self {
+        Self::from_u64(value as u64)
+    }
+
+    /// Sign-extends a 32-bit value to XLEN bits, as the *W instructions and LW do
+    fn from_signed_word(value: Word) -> Self {
+        Self::from_i64(value as i32 as i64)
+    }
+
+    /// The register value as a memory address
+    fn to_usize(self) -> usize {
+        self.to_u64() as usize
+    }
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        Self::from_u64(self.to_u64().wrapping_add(rhs.to_u64()))
+    }
+
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        Self::from_u64(self.to_u64().wrapping_sub(rhs.to_u64()))
+    }
+
+    fn wrapping_add_signed(self, rhs: i32) -> Self {
+        Self::from_u64(self.to_u64().wrapping_add_signed(rhs as i64))
+    }
+
+    /// Rotates left within XLEN bits, `amount` must be below XLEN
+    fn rotate_left(self, amount: u32) -> Self {
+        let value: u64 = self.to_u64();
+        if amount == 0 {
+            return self;
+        }
+        Self::from_u64((value << amount) | (value >> (Self::XLEN - amount)))
+    }
+
+    /// Rotates right within XLEN bits, `amount` must be below XLEN
+    fn rotate_right(self, amount: u32) -> Self {
+        self.rotate_left((Self::XLEN - amount) % Self::XLEN)
+    }
+
+    fn is_multiple_of(self, rhs: Word) -> bool {
+        self.to_u64().is_multiple_of(rhs as u64)
+    }
+}
+
+impl Xlen for u32 {
+    const XLEN: u32 = 32;
+
+    fn from_u64(value: u64) -> Self {
+        value as u32
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i32 as i64
+    }
+}
+
+impl Xlen for u64 {
+    const XLEN: u32 = 64;
+
+    fn from_u64(value: u64) -> Self {
+        value
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
+}
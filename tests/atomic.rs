@@ -0,0 +1,224 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+// LR.W x1, (x2)
+const LR_INSTRUCTION: Word = 0b00010_0_0_00000_00010_010_00001_0101111;
+// SC.W x3, x4, (x2)
+const SC_INSTRUCTION: Word = 0b00011_0_0_00100_00010_010_00011_0101111;
+
+fn decode(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// lr.w.aqrl decodes regardless of the ordering bits
+#[test]
+fn lr_aqrl_decode() {
+    let lr_aqrl: Word = 0b00010_1_1_00000_00010_010_00001_0101111;
+    assert_eq!(decode(lr_aqrl), Ok(Instruction::LRW { rs1: 2, rd: 1 }));
+}
+
+/// LR.W with a non-zero rs2 field is illegal
+#[test]
+fn lr_with_rs2_is_illegal() {
+    let bad_lr: Word = 0b00010_0_0_00001_00010_010_00001_0101111;
+    assert_eq!(decode(bad_lr), Err(Exception::IllegalInstruction));
+}
+
+/// AMOs only exist with a 32-bit width on RV32
+#[test]
+fn amo_doubleword_is_illegal() {
+    let amoadd_d: Word = 0b00000_0_0_00100_00010_011_00011_0101111;
+    assert_eq!(decode(amoadd_d), Err(Exception::IllegalInstruction));
+}
+
+/* -------------------- LR / SC -------------------- */
+
+/// SC.W succeeds after a matching LR.W and writes 0 to rd
+#[test]
+fn lr_sc_success() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, LR_INSTRUCTION).unwrap();
+    mem.store_word(0x4, SC_INSTRUCTION).unwrap();
+    mem.store_word(0x100, 0x1234).unwrap();
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = 0x5678;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[1], 0x1234);
+    assert_eq!(cpu.reservation, Some(0x100));
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[3], 0);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0x5678);
+    assert_eq!(cpu.reservation, None);
+}
+
+/// SC.W without a reservation fails, writes 1 to rd and leaves memory alone
+#[test]
+fn sc_without_reservation_fails() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, SC_INSTRUCTION).unwrap();
+    mem.store_word(0x100, 0x1234).unwrap();
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = 0x5678;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[3], 1);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0x1234);
+}
+
+/// A second SC.W fails because the first one consumed the reservation
+#[test]
+fn sc_consumes_reservation() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, LR_INSTRUCTION).unwrap();
+    mem.store_word(0x4, SC_INSTRUCTION).unwrap();
+    mem.store_word(0x8, SC_INSTRUCTION).unwrap();
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = 7;
+
+    for _ in 0..3 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(cpu.reg[3], 1);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 7);
+}
+
+/// SC.W to a different address than the reservation fails
+#[test]
+fn sc_other_address_fails() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, SC_INSTRUCTION).unwrap();
+    cpu.reservation = Some(0x200);
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = 7;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[3], 1);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0);
+    assert_eq!(cpu.reservation, None);
+}
+
+/// Taking a trap clears the reservation
+#[test]
+fn trap_clears_reservation() {
+    let mut cpu: RISCV = RISCV::reset();
+
+    cpu.reservation = Some(0x100);
+    cpu.take_trap(Trap::new(Exception::IllegalInstruction, 0));
+
+    assert_eq!(cpu.reservation, None);
+}
+
+/// Misaligned LR.W raises a load exception, misaligned SC.W a store/AMO exception
+#[test]
+fn lr_sc_misaligned_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, LR_INSTRUCTION).unwrap();
+    mem.store_word(0x4, SC_INSTRUCTION).unwrap();
+    cpu.reg[2] = 0x102;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x102))
+    );
+
+    cpu.pc = 0x4;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAddressMisaligned, 0x102))
+    );
+}
+
+/* -------------------- AMOs -------------------- */
+
+/// Runs one AMO with funct5 on memory value `old` and operand `operand`
+fn run_amo(funct5: Word, old: Word, operand: Word) -> (Word, Word) {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // AMO<op>.W x3, x4, (x2)
+    let amo_instruction: Word = (funct5 << 27) | 0b00_00100_00010_010_00011_0101111;
+    mem.store_word(0x0, amo_instruction).unwrap();
+    mem.store_word(0x100, old).unwrap();
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = operand;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    (cpu.reg[3], mem.fetch_word(0x100).unwrap())
+}
+
+/// Every AMO returns the original word and stores the combined value
+#[test]
+fn amo_operations() {
+    assert_eq!(run_amo(0b00001, 5, 9), (5, 9)); // swap
+    assert_eq!(run_amo(0b00000, 0xFFFFFFFF, 2), (0xFFFFFFFF, 1)); // add wraps
+    assert_eq!(run_amo(0b00100, 0b1100, 0b1010), (0b1100, 0b0110)); // xor
+    assert_eq!(run_amo(0b01100, 0b1100, 0b1010), (0b1100, 0b1000)); // and
+    assert_eq!(run_amo(0b01000, 0b1100, 0b1010), (0b1100, 0b1110)); // or
+}
+
+/// Signed and unsigned minimum/maximum
+#[test]
+fn amo_min_max() {
+    let minus_one: Word = -1i32 as Word;
+    assert_eq!(run_amo(0b10000, minus_one, 1), (minus_one, minus_one)); // min
+    assert_eq!(run_amo(0b10100, minus_one, 1), (minus_one, 1)); // max
+    assert_eq!(run_amo(0b11000, minus_one, 1), (minus_one, 1)); // minu
+    assert_eq!(run_amo(0b11100, minus_one, 1), (minus_one, minus_one)); // maxu
+}
+
+/// AMO with rd = x0 still updates memory
+#[test]
+fn amo_rd_x0() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // AMOADD.W x0, x4, (x2)
+    mem.store_word(0x0, 0b00000_0_0_00100_00010_010_00000_0101111)
+        .unwrap();
+    mem.store_word(0x100, 40).unwrap();
+    cpu.reg[2] = 0x100;
+    cpu.reg[4] = 2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[0], 0);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 42);
+}
+
+/// AMO faults are reported as store/AMO exceptions
+#[test]
+fn amo_faults_are_store_faults() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // AMOSWAP.W x3, x4, (x2)
+    mem.store_word(0x0, 0b00001_0_0_00100_00010_010_00011_0101111)
+        .unwrap();
+    cpu.reg[2] = 0x101;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAddressMisaligned, 0x101))
+    );
+
+    cpu.reg[2] = 0xFFFFFFFC;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAccessFault, 0xFFFFFFFC))
+    );
+    assert_eq!(cpu.pc, 0);
+}
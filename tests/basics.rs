@@ -1,5 +1,3 @@
-use std::u32;
-
 use rust_risc_v::*;
 
 #[test]
@@ -56,6 +54,16 @@ fn opcode_values() {
     assert_eq!(OPCODE::STORE.value(), 0b0100011);
     assert_eq!(OPCODE::FENCE.value(), 0b0001111);
     assert_eq!(OPCODE::SYSTEM.value(), 0b1110011);
+    assert_eq!(OPCODE::AMO.value(), 0b0101111);
+    assert_eq!(OPCODE::LOADFP.value(), 0b0000111);
+    assert_eq!(OPCODE::STOREFP.value(), 0b0100111);
+    assert_eq!(OPCODE::OPFP.value(), 0b1010011);
+    assert_eq!(OPCODE::MADD.value(), 0b1000011);
+    assert_eq!(OPCODE::MSUB.value(), 0b1000111);
+    assert_eq!(OPCODE::NMSUB.value(), 0b1001011);
+    assert_eq!(OPCODE::NMADD.value(), 0b1001111);
+    assert_eq!(OPCODE::OPIMM32.value(), 0b0011011);
+    assert_eq!(OPCODE::OP32.value(), 0b0111011);
 }
 
 #[test]
@@ -71,6 +79,16 @@ fn opcode_from_value() {
     assert_eq!(OPCODE::from_value(0b0100011), Some(OPCODE::STORE));
     assert_eq!(OPCODE::from_value(0b0001111), Some(OPCODE::FENCE));
     assert_eq!(OPCODE::from_value(0b1110011), Some(OPCODE::SYSTEM));
+    assert_eq!(OPCODE::from_value(0b0101111), Some(OPCODE::AMO));
+    assert_eq!(OPCODE::from_value(0b0000111), Some(OPCODE::LOADFP));
+    assert_eq!(OPCODE::from_value(0b0100111), Some(OPCODE::STOREFP));
+    assert_eq!(OPCODE::from_value(0b1010011), Some(OPCODE::OPFP));
+    assert_eq!(OPCODE::from_value(0b1000011), Some(OPCODE::MADD));
+    assert_eq!(OPCODE::from_value(0b1000111), Some(OPCODE::MSUB));
+    assert_eq!(OPCODE::from_value(0b1001011), Some(OPCODE::NMSUB));
+    assert_eq!(OPCODE::from_value(0b1001111), Some(OPCODE::NMADD));
+    assert_eq!(OPCODE::from_value(0b0011011), Some(OPCODE::OPIMM32));
+    assert_eq!(OPCODE::from_value(0b0111011), Some(OPCODE::OP32));
     assert_eq!(OPCODE::from_value(0b0000000), None); // Invalid opcode
 }
 
@@ -0,0 +1,404 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/// Executes a single instruction placed at the current pc
+fn run<X: Xlen>(cpu: &mut RISCV<X>, mem: &mut Memory, instruction: Word) -> Result<(), Trap<X>> {
+    mem.store_word(cpu.pc.to_usize(), instruction).unwrap();
+    cpu.clock_cycle(mem)
+}
+
+/// Runs `instruction` on a fresh RV32 hart with x1 and x2 set, returning x3
+fn rv32(instruction: Word, x1: Word, x2: Word) -> Word {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = x1;
+    cpu.reg[2] = x2;
+    run(&mut cpu, &mut mem, instruction).unwrap();
+    cpu.reg[3]
+}
+
+/// Runs `instruction` on a fresh RV64 hart with x1 and x2 set, returning x3
+fn rv64(instruction: Word, x1: u64, x2: u64) -> u64 {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = x1;
+    cpu.reg[2] = x2;
+    run(&mut cpu, &mut mem, instruction).unwrap();
+    cpu.reg[3]
+}
+
+fn decode(instruction: Word, xlen: u32) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction_xlen(EncodingVariant::get_encoding(instruction)?, xlen)
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// Each extension's encodings decode to their instruction
+#[test]
+fn bitmanip_decode() {
+    let (rs1, rs2, rd): (usize, usize, usize) = (1, 2, 3);
+    let cases: [(Word, Instruction); 8] = [
+        (
+            0b0010000_00010_00001_100_00011_0110011,
+            Instruction::SH2ADD { rs1, rs2, rd },
+        ),
+        (
+            0b0100000_00010_00001_111_00011_0110011,
+            Instruction::ANDN { rs1, rs2, rd },
+        ),
+        (
+            0b0110000_00010_00001_001_00011_0010011,
+            Instruction::CPOP { rs1, rd },
+        ),
+        (
+            0b0110000_00100_00001_101_00011_0010011,
+            Instruction::RORI { shamt: 4, rs1, rd },
+        ),
+        (
+            0b0110100_11000_00001_101_00011_0010011,
+            Instruction::REV8 { rs1, rd },
+        ),
+        (
+            0b0100100_00111_00001_101_00011_0010011,
+            Instruction::BEXTI { shamt: 7, rs1, rd },
+        ),
+        (
+            0b0000100_00000_00001_100_00011_0110011,
+            Instruction::ZEXTH { rs1, rd },
+        ),
+        (
+            0b0000101_00010_00001_011_00011_0110011,
+            Instruction::CLMULH { rs1, rs2, rd },
+        ),
+    ];
+    for (instruction, expected) in cases {
+        assert_eq!(decode(instruction, 32), Ok(expected));
+    }
+}
+
+/// Reserved immediates and the RV64-only forms are illegal on RV32
+#[test]
+fn bitmanip_decode_illegal() {
+    let illegal: [Word; 5] = [
+        0b0000001_00001_00001_001_00011_0010011, // slli with imm[11:5] != 0
+        0b0110001_00000_00001_101_00011_0010011, // rori with shamt[5] set
+        0b0110000_00011_00001_001_00011_0010011, // unused unary selector
+        0b0000100_00010_00001_000_00011_0111011, // add.uw
+        0b0110000_00000_00001_001_00011_0011011, // clzw
+    ];
+    for instruction in illegal {
+        assert_eq!(
+            decode(instruction, 32),
+            Err(Exception::IllegalInstruction),
+            "{instruction:#034b}"
+        );
+    }
+
+    // the RV32 zext.h encoding moved to OP32 on RV64, leaving a plain pack with x0
+    assert_eq!(
+        decode(0b0000100_00000_00001_100_00011_0110011, 64),
+        Ok(Instruction::PACK {
+            rs1: 1,
+            rs2: 0,
+            rd: 3
+        })
+    );
+    // rori with shamt[5] set is legal on RV64
+    assert_eq!(
+        decode(0b0110001_00000_00001_101_00011_0010011, 64),
+        Ok(Instruction::RORI {
+            shamt: 32,
+            rs1: 1,
+            rd: 3
+        })
+    );
+}
+
+/* -------------------- Zba -------------------- */
+
+/// sh1add / sh2add / sh3add shift rs1 before adding rs2
+#[test]
+fn zba_shift_add() {
+    assert_eq!(rv32(0b0010000_00010_00001_010_00011_0110011, 5, 100), 110);
+    assert_eq!(rv32(0b0010000_00010_00001_100_00011_0110011, 5, 100), 120);
+    assert_eq!(rv32(0b0010000_00010_00001_110_00011_0110011, 5, 100), 140);
+    // wraps around XLEN
+    assert_eq!(
+        rv32(0b0010000_00010_00001_010_00011_0110011, 0x8000_0001, 1),
+        3
+    );
+}
+
+/// The .uw forms zero-extend the low word of rs1 first
+#[test]
+fn zba_unsigned_word() {
+    // add.uw
+    assert_eq!(
+        rv64(0b0000100_00010_00001_000_00011_0111011, u64::MAX, 1),
+        0x1_0000_0000
+    );
+    // sh1add.uw
+    assert_eq!(
+        rv64(
+            0b0010000_00010_00001_010_00011_0111011,
+            0xFFFF_FFFF_8000_0000,
+            0
+        ),
+        0x1_0000_0000
+    );
+    // sh3add.uw
+    assert_eq!(
+        rv64(
+            0b0010000_00010_00001_110_00011_0111011,
+            0xFFFF_FFFF_0000_0002,
+            1
+        ),
+        17
+    );
+    // slli.uw x3, x1, 36
+    assert_eq!(
+        rv64(
+            0b000010_100100_00001_001_00011_0011011,
+            0xFFFF_FFFF_0000_0001,
+            0
+        ),
+        1 << 36
+    );
+}
+
+/* -------------------- Zbb -------------------- */
+
+/// andn / orn / xnor invert rs2
+#[test]
+fn zbb_inverted_logic() {
+    assert_eq!(
+        rv32(0b0100000_00010_00001_111_00011_0110011, 0b1100, 0b1010),
+        0b0100
+    );
+    assert_eq!(
+        rv32(0b0100000_00010_00001_110_00011_0110011, 0b1100, 0b1010),
+        0xFFFF_FFFD
+    );
+    assert_eq!(
+        rv32(0b0100000_00010_00001_100_00011_0110011, 0b1100, 0b1010),
+        0xFFFF_FFF9
+    );
+}
+
+/// clz / ctz / cpop count within XLEN, the W forms within the low word
+#[test]
+fn zbb_counts() {
+    assert_eq!(
+        rv32(0b0110000_00000_00001_001_00011_0010011, 0x0001_0000, 0),
+        15
+    );
+    assert_eq!(rv32(0b0110000_00000_00001_001_00011_0010011, 0, 0), 32);
+    assert_eq!(
+        rv32(0b0110000_00001_00001_001_00011_0010011, 0x0001_0000, 0),
+        16
+    );
+    assert_eq!(rv32(0b0110000_00001_00001_001_00011_0010011, 0, 0), 32);
+    assert_eq!(rv32(0b0110000_00010_00001_001_00011_0010011, 0xF0F0, 0), 8);
+
+    assert_eq!(rv64(0b0110000_00000_00001_001_00011_0010011, 1, 0), 63);
+    assert_eq!(rv64(0b0110000_00001_00001_001_00011_0010011, 0, 0), 64);
+    // clzw / ctzw / cpopw ignore the upper word
+    assert_eq!(
+        rv64(
+            0b0110000_00000_00001_001_00011_0011011,
+            0xFFFF_FFFF_0000_0001,
+            0
+        ),
+        31
+    );
+    assert_eq!(
+        rv64(
+            0b0110000_00001_00001_001_00011_0011011,
+            0xFFFF_FFFF_0000_0000,
+            0
+        ),
+        32
+    );
+    assert_eq!(
+        rv64(
+            0b0110000_00010_00001_001_00011_0011011,
+            0xFFFF_FFFF_0000_0003,
+            0
+        ),
+        2
+    );
+}
+
+/// min / max compare signed, minu / maxu unsigned
+#[test]
+fn zbb_min_max() {
+    let minus_one: Word = -1i32 as Word;
+    assert_eq!(
+        rv32(0b0000101_00010_00001_100_00011_0110011, minus_one, 1),
+        minus_one
+    );
+    assert_eq!(
+        rv32(0b0000101_00010_00001_101_00011_0110011, minus_one, 1),
+        1
+    );
+    assert_eq!(
+        rv32(0b0000101_00010_00001_110_00011_0110011, minus_one, 1),
+        1
+    );
+    assert_eq!(
+        rv32(0b0000101_00010_00001_111_00011_0110011, minus_one, 1),
+        minus_one
+    );
+    assert_eq!(
+        rv64(0b0000101_00010_00001_100_00011_0110011, u64::MAX, 1),
+        u64::MAX
+    );
+}
+
+/// sext.b / sext.h sign-extend, zext.h zero-extends
+#[test]
+fn zbb_extend() {
+    assert_eq!(
+        rv32(0b0110000_00100_00001_001_00011_0010011, 0x80, 0),
+        0xFFFF_FF80
+    );
+    assert_eq!(
+        rv32(0b0110000_00101_00001_001_00011_0010011, 0x8000, 0),
+        0xFFFF_8000
+    );
+    assert_eq!(
+        rv32(0b0000100_00000_00001_100_00011_0110011, 0xFFFF_1234, 0),
+        0x1234
+    );
+    assert_eq!(rv64(0b0110000_00100_00001_001_00011_0010011, 0x7F, 0), 0x7F);
+    assert_eq!(
+        rv64(0b0000100_00000_00001_100_00011_0111011, u64::MAX, 0),
+        0xFFFF
+    );
+}
+
+/// rol / ror / rori rotate within XLEN, the W forms within the low word
+#[test]
+fn zbb_rotate() {
+    assert_eq!(
+        rv32(0b0110000_00010_00001_001_00011_0110011, 0x8000_0001, 1),
+        3
+    );
+    assert_eq!(
+        rv32(0b0110000_00010_00001_101_00011_0110011, 0x8000_0001, 1),
+        0xC000_0000
+    );
+    // only the low 5 bits of rs2 are used
+    assert_eq!(
+        rv32(0b0110000_00010_00001_001_00011_0110011, 0x8000_0001, 33),
+        3
+    );
+    assert_eq!(
+        rv32(0b0110000_00100_00001_101_00011_0010011, 0x1234_5678, 0),
+        0x8123_4567
+    );
+
+    // rori x3, x1, 36
+    assert_eq!(rv64(0b011000_100100_00001_101_00011_0010011, 1, 0), 1 << 28);
+    // rolw / rorw / roriw sign-extend the rotated word
+    assert_eq!(
+        rv64(0b0110000_00010_00001_001_00011_0111011, 0x8000_0000, 1),
+        1
+    );
+    assert_eq!(
+        rv64(0b0110000_00010_00001_101_00011_0111011, 1, 1),
+        0xFFFF_FFFF_8000_0000
+    );
+    assert_eq!(
+        rv64(0b0110000_00001_00001_101_00011_0011011, 1, 0),
+        0xFFFF_FFFF_8000_0000
+    );
+}
+
+/// rev8 reverses the bytes, orc.b fills every non-zero byte
+#[test]
+fn zbb_bytes() {
+    assert_eq!(
+        rv32(0b0110100_11000_00001_101_00011_0010011, 0x1234_5678, 0),
+        0x7856_3412
+    );
+    assert_eq!(
+        rv64(
+            0b0110101_11000_00001_101_00011_0010011,
+            0x0102_0304_0506_0708,
+            0
+        ),
+        0x0807_0605_0403_0201
+    );
+    assert_eq!(
+        rv32(0b0010100_00111_00001_101_00011_0010011, 0x0012_0300, 0),
+        0x00FF_FF00
+    );
+}
+
+/* -------------------- Zbs -------------------- */
+
+/// Single-bit set / clear / invert / extract with the index in rs2 or the immediate
+#[test]
+fn zbs_single_bit() {
+    assert_eq!(
+        rv32(0b0010100_00010_00001_001_00011_0110011, 0, 31),
+        0x8000_0000
+    );
+    assert_eq!(
+        rv32(0b0100100_00010_00001_001_00011_0110011, 0xFFFF_FFFF, 32),
+        0xFFFF_FFFE
+    );
+    assert_eq!(
+        rv32(0b0110100_00010_00001_001_00011_0110011, 0b101, 2),
+        0b001
+    );
+    assert_eq!(rv32(0b0100100_00010_00001_101_00011_0110011, 0b100, 2), 1);
+
+    assert_eq!(rv32(0b0010100_00101_00001_001_00011_0010011, 0, 0), 1 << 5);
+    assert_eq!(rv32(0b0100100_00000_00001_001_00011_0010011, 0b11, 0), 0b10);
+    assert_eq!(rv32(0b0110100_00001_00001_001_00011_0010011, 0b11, 0), 0b01);
+    assert_eq!(rv32(0b0100100_00001_00001_101_00011_0010011, 0b10, 0), 1);
+
+    // bseti x3, x1, 63
+    assert_eq!(rv64(0b001010_111111_00001_001_00011_0010011, 0, 0), 1 << 63);
+}
+
+/* -------------------- Zbc -------------------- */
+
+/// clmul / clmulh / clmulr return the low, high and reversed parts of the carry-less product
+#[test]
+fn zbc_carryless_multiply() {
+    assert_eq!(
+        rv32(0b0000101_00010_00001_001_00011_0110011, 0b11, 0b11),
+        0b101
+    );
+    assert_eq!(
+        rv32(
+            0b0000101_00010_00001_011_00011_0110011,
+            0x8000_0000,
+            0x8000_0000
+        ),
+        0x4000_0000
+    );
+    assert_eq!(
+        rv32(
+            0b0000101_00010_00001_010_00011_0110011,
+            0x8000_0000,
+            0x8000_0000
+        ),
+        0x8000_0000
+    );
+    assert_eq!(
+        rv64(0b0000101_00010_00001_011_00011_0110011, 1 << 63, 1 << 63),
+        1 << 62
+    );
+}
+
+/// misa advertises the B extension
+#[test]
+fn bitmanip_misa() {
+    let cpu: RISCV = RISCV::reset();
+    assert_ne!(cpu.csr.read(MISA).unwrap() & (1 << 1), 0);
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 /// BEQ instruction fetch
@@ -8,9 +10,9 @@ fn beq_instruction_fetch() {
 
     // BEQ x1, x2, +8  (funct3 = 000, opcode = 1100011)
     let beq_instruction: Word = 0b0_000000_00010_00001_000_0100_0_1100011;
-    mem.store_word(0x0, beq_instruction);
+    mem.store_word(0x0, beq_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, beq_instruction);
 }
 
@@ -22,12 +24,12 @@ fn beq_basic_taken_forward() {
 
     // BEQ x1, x2, +8 ; set x1 == x2 so branch taken
     let beq_instr: Word = 0b0_000000_00010_00001_000_0100_0_1100011; // imm = +8
-    mem.store_word(0x0, beq_instr);
+    mem.store_word(0x0, beq_instr).unwrap();
 
     cpu.reg[1] = 5;
     cpu.reg[2] = 5;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // branch taken -> PC = 0 + 8
     assert_eq!(cpu.pc, 8);
@@ -41,12 +43,12 @@ fn beq_not_taken_pc_increments() {
 
     // BEQ x1, x2, +8 ; x1 != x2 so not taken
     let beq_instr: Word = 0b0_000000_00010_00001_000_0100_0_1100011; // imm = +8
-    mem.store_word(0x0, beq_instr);
+    mem.store_word(0x0, beq_instr).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 2;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // not taken -> PC = 0 + 4
     assert_eq!(cpu.pc, 4);
@@ -63,12 +65,12 @@ fn beq_backward_jump() {
 
     // Encoded imm = -8
     let beq_instr: Word = 0b1_111111_00010_00001_000_1100_1_1100011;
-    mem.store_word(0x10, beq_instr);
+    mem.store_word(0x10, beq_instr).unwrap();
 
     cpu.reg[1] = 7;
     cpu.reg[2] = 7; // equal -> branch taken to 0x08
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -81,9 +83,9 @@ fn beq_x0_x0_always_taken() {
 
     // BEQ x0, x0, +8  -> comparing zero with zero, branch taken to 0x04
     let beq_instr: Word = 0b0_000000_00000_00000_000_0100_0_1100011;
-    mem.store_word(0x0, beq_instr);
+    mem.store_word(0x0, beq_instr).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -96,11 +98,11 @@ fn beq_large_forward_offset() {
 
     // BEQ x1, x1, +1024
     let beq_instr: Word = 0b0_100000_00001_00001_000_0000_0_1100011;
-    mem.store_word(0x0, beq_instr);
+    mem.store_word(0x0, beq_instr).unwrap();
 
     cpu.reg[1] = 0x42; // equal with itself, taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x400); // jumped to +1024
 }
@@ -114,11 +116,11 @@ fn beq_large_backward_offset() {
     // place PC at 0x800, branch -512 -> target 0x600
     cpu.pc = 0x800;
     let beq_instr: Word = 0b1_110000_00001_00001_000_0000_1_1100011;
-    mem.store_word(0x800, beq_instr);
+    mem.store_word(0x800, beq_instr).unwrap();
 
     cpu.reg[1] = 0x7;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x600);
 }
@@ -136,25 +138,25 @@ fn beq_with_lui_interaction() {
     // 0x0C: LUI x6, 0x3
 
     let lui_x2 = 0b00000000000000000001_00010_0110111;
-    mem.store_word(0x00, lui_x2);
+    mem.store_word(0x00, lui_x2).unwrap();
 
     // BEQ x3, x4, +8 ; encoded below
     let beq_x3_x4_plus8: Word = 0b0_000000_00100_00011_000_0100_0_1100011; // rs1=3 rs2=4 imm=+8
-    mem.store_word(0x04, beq_x3_x4_plus8);
+    mem.store_word(0x04, beq_x3_x4_plus8).unwrap();
 
     let lui_x5 = 0b00000000000000000010_00101_0110111;
-    mem.store_word(0x08, lui_x5);
+    mem.store_word(0x08, lui_x5).unwrap();
 
     let lui_x6 = 0b00000000000000000011_00110_0110111;
-    mem.store_word(0x0C, lui_x6);
+    mem.store_word(0x0C, lui_x6).unwrap();
 
     // Make branch taken by setting x3 == x4
     cpu.reg[3] = 9;
     cpu.reg[4] = 9;
 
-    cpu.clock_cycle(&mut mem); // LUI x2
-    cpu.clock_cycle(&mut mem); // BEQ (taken -> jump to 0x0C)
-    cpu.clock_cycle(&mut mem); // LUI x6
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x2
+    cpu.clock_cycle(&mut mem).unwrap(); // BEQ (taken -> jump to 0x0C)
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x6
 
     assert_eq!(cpu.reg[2], 0x1000);
     assert_eq!(cpu.reg[5], 0); // skipped
@@ -174,25 +176,25 @@ fn beq_with_auipc_interaction() {
     // 0x0C: LUI x9, 3
 
     let auipc_x5 = 0b00000000000000000001_00101_0010111;
-    mem.store_word(0x00, auipc_x5);
+    mem.store_word(0x00, auipc_x5).unwrap();
 
     // BEQ x6, x7, +8 ; rs1=6 rs2=7 imm=+8
     let beq_x6_x7_plus8: Word = 0b0_000000_00111_00110_000_0100_0_1100011;
-    mem.store_word(0x04, beq_x6_x7_plus8);
+    mem.store_word(0x04, beq_x6_x7_plus8).unwrap();
 
     let lui_x8 = 0b00000000000000000010_01000_0110111;
-    mem.store_word(0x08, lui_x8);
+    mem.store_word(0x08, lui_x8).unwrap();
 
     let lui_x9 = 0b00000000000000000011_01001_0110111;
-    mem.store_word(0x0C, lui_x9);
+    mem.store_word(0x0C, lui_x9).unwrap();
 
     // Make branch taken
     cpu.reg[6] = 0x12;
     cpu.reg[7] = 0x12;
 
-    cpu.clock_cycle(&mut mem); // AUIPC
-    cpu.clock_cycle(&mut mem); // BEQ taken
-    cpu.clock_cycle(&mut mem); // LUI x9
+    cpu.clock_cycle(&mut mem).unwrap(); // AUIPC
+    cpu.clock_cycle(&mut mem).unwrap(); // BEQ taken
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x9
 
     assert_eq!(cpu.reg[5], 0x1000);
     assert_eq!(cpu.reg[8], 0); // skipped
@@ -200,19 +202,24 @@ fn beq_with_auipc_interaction() {
     assert_eq!(cpu.pc, 0x10);
 }
 
-/// BEQ with an illegal unaligned target should panic (target = PC + 2)
+/// BEQ with an illegal unaligned target should trap (target = PC + 2)
 #[test]
-#[should_panic]
-fn beq_unaligned_target_panics() {
+fn beq_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
-    // BEQ x1, x1, +2 -> unaligned target (0x2), emulator should panic/raise
+    // BEQ x1, x1, +2 -> unaligned target (0x2), emulator should raise
     let beq_unaligned: Word = 0b0_000000_00001_00001_000_0001_0_1100011; // imm = +2
-    mem.store_word(0x0, beq_unaligned);
+    mem.store_word(0x0, beq_unaligned).unwrap();
 
     cpu.reg[1] = 1; // equal -> branch taken to 0x2 (unaligned for 32-bit non-compressed)
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
 
 /* -------------------- BNE tests -------------------- */
@@ -225,9 +232,9 @@ fn bne_instruction_fetch() {
 
     // BNE x3, x4, +8 (funct3 = 001)
     let bne_instruction: Word = 0b0_000000_00100_00011_001_0100_0_1100011;
-    mem.store_word(0x0, bne_instruction);
+    mem.store_word(0x0, bne_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, bne_instruction);
 }
 
@@ -238,12 +245,12 @@ fn bne_basic_taken_forward() {
     let mut mem: Memory = Memory::new();
 
     let bne_instr: Word = 0b0_000000_00100_00011_001_0100_0_1100011; // imm = +8
-    mem.store_word(0x0, bne_instr);
+    mem.store_word(0x0, bne_instr).unwrap();
 
     cpu.reg[3] = 1;
     cpu.reg[4] = 2; // not equal -> taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -255,12 +262,12 @@ fn bne_not_taken_pc_increments() {
     let mut mem: Memory = Memory::new();
 
     let bne_instr: Word = 0b0_000000_00100_00011_001_0100_0_1100011; // imm = +8
-    mem.store_word(0x0, bne_instr);
+    mem.store_word(0x0, bne_instr).unwrap();
 
     cpu.reg[3] = 5;
     cpu.reg[4] = 5; // equal -> not taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
@@ -275,12 +282,12 @@ fn bne_backward_jump() {
     cpu.pc = 0x10;
 
     let bne_instr: Word = 0b1_111111_00100_00011_001_1100_1_1100011; // imm = -8, funct3=001
-    mem.store_word(0x10, bne_instr);
+    mem.store_word(0x10, bne_instr).unwrap();
 
     cpu.reg[3] = 1;
     cpu.reg[4] = 2; // not equal -> branch taken backward
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -293,11 +300,11 @@ fn bne_x0_nonzero_taken() {
 
     // BNE x5, x0, +8 -> if x5 != 0 branch taken
     let bne_instr: Word = 0b0_000000_00000_00101_001_0100_0_1100011; // rs1 = 5, rs2 = 0
-    mem.store_word(0x0, bne_instr);
+    mem.store_word(0x0, bne_instr).unwrap();
 
     cpu.reg[5] = 7; // non-zero -> not equal to x0 -> taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -310,11 +317,11 @@ fn bne_large_forward_offset() {
 
     // BNE x3, x3, +1024 -> x3 == x3 so not taken (since BNE), PC+=4
     let bne_instr: Word = 0b0_100000_00011_00011_001_0000_0_1100011;
-    mem.store_word(0x0, bne_instr);
+    mem.store_word(0x0, bne_instr).unwrap();
 
     cpu.reg[3] = 0x1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // BNE with rs1==rs2 not taken -> PC=4
     assert_eq!(cpu.pc, 4);
@@ -330,11 +337,11 @@ fn bne_large_backward_offset() {
 
     // BNE x3, x3, -512
     let bne_instr: Word = 0b1_110000_00011_00000_001_0000_1_1100011;
-    mem.store_word(0x800, bne_instr);
+    mem.store_word(0x800, bne_instr).unwrap();
 
     cpu.reg[3] = 0x42;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x600);
 }
@@ -352,23 +359,23 @@ fn bne_with_lui_interaction() {
     // 0x0C: LUI x6, 0x3
 
     let lui_x2 = 0b00000000000000000001_00010_0110111;
-    mem.store_word(0x00, lui_x2);
+    mem.store_word(0x00, lui_x2).unwrap();
 
     let bne_x3_x4_plus8: Word = 0b0_000000_00100_00011_001_0100_0_1100011; // rs1=3 rs2=4 imm=+8, funct3=001
-    mem.store_word(0x04, bne_x3_x4_plus8);
+    mem.store_word(0x04, bne_x3_x4_plus8).unwrap();
 
     let lui_x5 = 0b00000000000000000010_00101_0110111;
-    mem.store_word(0x08, lui_x5);
+    mem.store_word(0x08, lui_x5).unwrap();
 
     let lui_x6 = 0b00000000000000000011_00110_0110111;
-    mem.store_word(0x0C, lui_x6);
+    mem.store_word(0x0C, lui_x6).unwrap();
 
     cpu.reg[3] = 1;
     cpu.reg[4] = 2; // not equal -> branch taken
 
-    cpu.clock_cycle(&mut mem); // LUI x2
-    cpu.clock_cycle(&mut mem); // BNE -> taken (to 0x0C)
-    cpu.clock_cycle(&mut mem); // LUI x6
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x2
+    cpu.clock_cycle(&mut mem).unwrap(); // BNE -> taken (to 0x0C)
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x6
 
     assert_eq!(cpu.reg[2], 0x1000);
     assert_eq!(cpu.reg[5], 0); // skipped
@@ -376,19 +383,24 @@ fn bne_with_lui_interaction() {
     assert_eq!(cpu.pc, 0x10);
 }
 
-/// BNE unaligned target -> should panic
+/// BNE unaligned target -> should trap
 #[test]
-#[should_panic]
-fn bne_unaligned_target_panics() {
+fn bne_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
-    // BNE x3, x3, +2 -> target = 0x2 (unaligned), simulator should panic/raise
+    // BNE x3, x3, +2 -> target = 0x2 (unaligned), simulator should raise
     let bne_unaligned: Word = 0b0_000000_00000_00011_001_0001_0_1100011; // imm = +2
-    mem.store_word(0x0, bne_unaligned);
+    mem.store_word(0x0, bne_unaligned).unwrap();
 
     cpu.reg[3] = 1;
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
 
 /// Combined test: BEQ and BNE sequence altering control flow
@@ -410,16 +422,16 @@ fn beq_bne_control_flow_sequence() {
     let addi_x11_0: Word = 0b000000000000_00000_000_01011_0010011; // addi x11,x0,0
     let addi_x11_5: Word = 0b000000000101_00000_000_01011_0010011; // addi x11,x0,5
 
-    mem.store_word(0x00, addi_x10_1);
-    mem.store_word(0x04, beq_x10_x0_plus8);
-    mem.store_word(0x08, bne_x10_x0_plus8);
-    mem.store_word(0x0C, addi_x11_0);
-    mem.store_word(0x10, addi_x11_5);
+    mem.store_word(0x00, addi_x10_1).unwrap();
+    mem.store_word(0x04, beq_x10_x0_plus8).unwrap();
+    mem.store_word(0x08, bne_x10_x0_plus8).unwrap();
+    mem.store_word(0x0C, addi_x11_0).unwrap();
+    mem.store_word(0x10, addi_x11_5).unwrap();
 
-    cpu.clock_cycle(&mut mem); // addi x10 = 1
-    cpu.clock_cycle(&mut mem); // beq -> not taken (x10 != x0) -> PC = 0x08
-    cpu.clock_cycle(&mut mem); // bne -> taken -> PC = 0x10
-    cpu.clock_cycle(&mut mem); // addi x11 = 5
+    cpu.clock_cycle(&mut mem).unwrap(); // addi x10 = 1
+    cpu.clock_cycle(&mut mem).unwrap(); // beq -> not taken (x10 != x0) -> PC = 0x08
+    cpu.clock_cycle(&mut mem).unwrap(); // bne -> taken -> PC = 0x10
+    cpu.clock_cycle(&mut mem).unwrap(); // addi x11 = 5
 
     assert_eq!(cpu.reg[10], 1);
     assert_eq!(cpu.reg[11], 5);
@@ -436,9 +448,9 @@ fn blt_instruction_fetch() {
 
     // BLT x1, x2, +8 (funct3 = 100)
     let blt_instruction: Word = 0b0_000000_00010_00001_100_0100_0_1100011;
-    mem.store_word(0x0, blt_instruction);
+    mem.store_word(0x0, blt_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, blt_instruction);
 }
 
@@ -449,12 +461,12 @@ fn blt_taken_forward() {
     let mut mem: Memory = Memory::new();
 
     let blt_instr: Word = 0b0_000000_00010_00001_100_0100_0_1100011; // imm=+8
-    mem.store_word(0x0, blt_instr);
+    mem.store_word(0x0, blt_instr).unwrap();
 
     cpu.reg[1] = 3;
     cpu.reg[2] = 10; // x1 < x2
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -466,12 +478,12 @@ fn blt_not_taken_pc_increments() {
     let mut mem: Memory = Memory::new();
 
     let blt_instr: Word = 0b0_000000_00010_00001_100_0100_0_1100011;
-    mem.store_word(0x0, blt_instr);
+    mem.store_word(0x0, blt_instr).unwrap();
 
     cpu.reg[1] = 9;
     cpu.reg[2] = 2; // x1 > x2
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
@@ -485,12 +497,12 @@ fn blt_backward_jump() {
     cpu.pc = 0x10;
 
     let blt_instr: Word = 0b1_111111_00010_00001_100_1100_1_1100011; // imm = -8
-    mem.store_word(0x10, blt_instr);
+    mem.store_word(0x10, blt_instr).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 5; // 1 < 5 → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -502,12 +514,12 @@ fn blt_signed_negative_comparison() {
     let mut mem: Memory = Memory::new();
 
     let blt_instr: Word = 0b0_000000_00010_00001_100_0100_0_1100011; // +8
-    mem.store_word(0x0, blt_instr);
+    mem.store_word(0x0, blt_instr).unwrap();
 
     cpu.reg[1] = (-5i32) as Word;
     cpu.reg[2] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -5 < 3 → taken
     assert_eq!(cpu.pc, 8);
@@ -520,31 +532,36 @@ fn blt_equal_not_taken() {
     let mut mem: Memory = Memory::new();
 
     let blt_instr: Word = 0b0_000000_00010_00001_100_0100_0_1100011;
-    mem.store_word(0x0, blt_instr);
+    mem.store_word(0x0, blt_instr).unwrap();
 
     cpu.reg[1] = 7;
     cpu.reg[2] = 7;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
 
-/// BLT unaligned target → panic
+/// BLT unaligned target → trap
 #[test]
-#[should_panic]
-fn blt_unaligned_target_panics() {
+fn blt_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
     // BLT x1, x2, +2 → unaligned
     let blt_instr: Word = 0b0_000000_00010_00001_100_0001_0_1100011;
-    mem.store_word(0x0, blt_instr);
+    mem.store_word(0x0, blt_instr).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 2;
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
 
 /* -------------------- BLTU tests -------------------- */
@@ -557,9 +574,9 @@ fn bltu_instruction_fetch() {
 
     // BLTU x1, x2, +8 (funct3 = 110)
     let bltu_instruction: Word = 0b0_000000_00010_00001_110_0100_0_1100011;
-    mem.store_word(0x0, bltu_instruction);
+    mem.store_word(0x0, bltu_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, bltu_instruction);
 }
 
@@ -570,12 +587,12 @@ fn bltu_taken_forward() {
     let mut mem: Memory = Memory::new();
 
     let bltu_instr: Word = 0b0_000000_00010_00001_110_0100_0_1100011; // imm=+8
-    mem.store_word(0x0, bltu_instr);
+    mem.store_word(0x0, bltu_instr).unwrap();
 
     cpu.reg[1] = 5;
     cpu.reg[2] = 10;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -587,12 +604,12 @@ fn bltu_not_taken_pc_increments() {
     let mut mem: Memory = Memory::new();
 
     let bltu_instr: Word = 0b0_000000_00010_00001_110_0100_0_1100011;
-    mem.store_word(0x0, bltu_instr);
+    mem.store_word(0x0, bltu_instr).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFF;
     cpu.reg[2] = 0x0000_0001; // unsigned: 0xFFFF_FFFF > 0x1 → not taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
@@ -606,12 +623,12 @@ fn bltu_backward_jump() {
     cpu.pc = 0x10;
 
     let bltu_instr: Word = 0b1_111111_00010_00001_110_1100_1_1100011;
-    mem.store_word(0x10, bltu_instr);
+    mem.store_word(0x10, bltu_instr).unwrap();
 
     cpu.reg[1] = 0;
     cpu.reg[2] = 5;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -623,12 +640,12 @@ fn bltu_large_unsigned_values() {
     let mut mem: Memory = Memory::new();
 
     let bltu_instr: Word = 0b0_000000_00010_00001_110_0100_0_1100011;
-    mem.store_word(0x0, bltu_instr);
+    mem.store_word(0x0, bltu_instr).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFE;
     cpu.reg[2] = 0xFFFF_FFFF; // unsigned smaller → branch taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -640,30 +657,35 @@ fn bltu_equal_not_taken() {
     let mut mem: Memory = Memory::new();
 
     let bltu_instr: Word = 0b0_000000_00010_00001_110_0100_0_1100011;
-    mem.store_word(0x0, bltu_instr);
+    mem.store_word(0x0, bltu_instr).unwrap();
 
     cpu.reg[1] = 123;
     cpu.reg[2] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
 
-/// BLTU unaligned target → panic
+/// BLTU unaligned target → trap
 #[test]
-#[should_panic]
-fn bltu_unaligned_target_panics() {
+fn bltu_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
     let bltu_instr: Word = 0b0_000000_00010_00001_110_0001_0_1100011; // imm=+2
-    mem.store_word(0x0, bltu_instr);
+    mem.store_word(0x0, bltu_instr).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 5;
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
 
 /* -------------------- BGE tests -------------------- */
@@ -676,9 +698,9 @@ fn bge_instruction_fetch() {
 
     // BGE x1, x2, +8 (funct3 = 101)
     let bge_instruction: Word = 0b0_000000_00010_00001_101_0100_0_1100011;
-    mem.store_word(0x0, bge_instruction);
+    mem.store_word(0x0, bge_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, bge_instruction);
 }
 
@@ -689,12 +711,12 @@ fn bge_taken_forward() {
     let mut mem: Memory = Memory::new();
 
     let bge_instr: Word = 0b0_000000_00010_00001_101_0100_0_1100011; // imm=+8
-    mem.store_word(0x0, bge_instr);
+    mem.store_word(0x0, bge_instr).unwrap();
 
     cpu.reg[1] = 10;
     cpu.reg[2] = 3; // 10 >= 3 → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -706,12 +728,12 @@ fn bge_not_taken_pc_increments() {
     let mut mem: Memory = Memory::new();
 
     let bge_instr: Word = 0b0_000000_00010_00001_101_0100_0_1100011;
-    mem.store_word(0x0, bge_instr);
+    mem.store_word(0x0, bge_instr).unwrap();
 
     cpu.reg[1] = 2;
     cpu.reg[2] = 9; // 2 < 9 → not taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
@@ -726,12 +748,12 @@ fn bge_backward_jump() {
 
     // BGE x1, x2, -8 → taken if x1 >= x2
     let bge_instr: Word = 0b1_111111_00010_00001_101_1100_1_1100011;
-    mem.store_word(0x10, bge_instr);
+    mem.store_word(0x10, bge_instr).unwrap();
 
     cpu.reg[1] = 5;
     cpu.reg[2] = 5; // equal → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -743,12 +765,12 @@ fn bge_signed_negative_comparison() {
     let mut mem: Memory = Memory::new();
 
     let bge_instr: Word = 0b0_000000_00010_00001_101_0100_0_1100011; // imm=+8
-    mem.store_word(0x0, bge_instr);
+    mem.store_word(0x0, bge_instr).unwrap();
 
     cpu.reg[1] = (-3i32) as Word;
     cpu.reg[2] = (-5i32) as Word; // -3 >= -5 → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -760,31 +782,36 @@ fn bge_equal_taken() {
     let mut mem: Memory = Memory::new();
 
     let bge_instr: Word = 0b0_000000_00010_00001_101_0100_0_1100011;
-    mem.store_word(0x0, bge_instr);
+    mem.store_word(0x0, bge_instr).unwrap();
 
     cpu.reg[1] = 7;
     cpu.reg[2] = 7;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
 
-/// BGE unaligned target → panic
+/// BGE unaligned target → trap
 #[test]
-#[should_panic]
-fn bge_unaligned_target_panics() {
+fn bge_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
     // BGE x1, x2, +2 → unaligned
     let bge_instr: Word = 0b0_000000_00010_00001_101_0001_0_1100011;
-    mem.store_word(0x0, bge_instr);
+    mem.store_word(0x0, bge_instr).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
 
 /* -------------------- BGEU tests -------------------- */
@@ -797,9 +824,9 @@ fn bgeu_instruction_fetch() {
 
     // BGEU x1, x2, +8 (funct3 = 111)
     let bgeu_instruction: Word = 0b0_000000_00010_00001_111_0100_0_1100011;
-    mem.store_word(0x0, bgeu_instruction);
+    mem.store_word(0x0, bgeu_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, bgeu_instruction);
 }
 
@@ -810,12 +837,12 @@ fn bgeu_taken_forward() {
     let mut mem: Memory = Memory::new();
 
     let bgeu_instr: Word = 0b0_000000_00010_00001_111_0100_0_1100011;
-    mem.store_word(0x0, bgeu_instr);
+    mem.store_word(0x0, bgeu_instr).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFF;
     cpu.reg[2] = 0x7FFF_FFFF; // unsigned: 0xFFFF_FFFF > 0x7FFF_FFFF → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -827,12 +854,12 @@ fn bgeu_not_taken_pc_increments() {
     let mut mem: Memory = Memory::new();
 
     let bgeu_instr: Word = 0b0_000000_00010_00001_111_0100_0_1100011;
-    mem.store_word(0x0, bgeu_instr);
+    mem.store_word(0x0, bgeu_instr).unwrap();
 
     cpu.reg[1] = 0x0000_0001;
     cpu.reg[2] = 0xFFFF_FFFF; // unsigned: smaller → not taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 4);
 }
@@ -846,12 +873,12 @@ fn bgeu_backward_jump() {
     cpu.pc = 0x10;
 
     let bgeu_instr: Word = 0b1_111111_00010_00001_111_1100_1_1100011; // imm=-8, funct3=111
-    mem.store_word(0x10, bgeu_instr);
+    mem.store_word(0x10, bgeu_instr).unwrap();
 
     cpu.reg[1] = 5;
     cpu.reg[2] = 3; // unsigned: 5 >= 3 → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
 }
@@ -863,12 +890,12 @@ fn bgeu_equal_taken() {
     let mut mem: Memory = Memory::new();
 
     let bgeu_instr: Word = 0b0_000000_00010_00001_111_0100_0_1100011;
-    mem.store_word(0x0, bgeu_instr);
+    mem.store_word(0x0, bgeu_instr).unwrap();
 
     cpu.reg[1] = 0xDEAD_BEEF;
     cpu.reg[2] = 0xDEAD_BEEF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
@@ -880,28 +907,33 @@ fn bgeu_unsigned_wraparound() {
     let mut mem: Memory = Memory::new();
 
     let bgeu_instr: Word = 0b0_000000_00010_00001_111_0100_0_1100011;
-    mem.store_word(0x0, bgeu_instr);
+    mem.store_word(0x0, bgeu_instr).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFE;
     cpu.reg[2] = 0x0000_0002; // unsigned greater → taken
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 8);
 }
 
-/// BGEU unaligned target → panic
+/// BGEU unaligned target → trap
 #[test]
-#[should_panic]
-fn bgeu_unaligned_target_panics() {
+fn bgeu_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
     let bgeu_instr: Word = 0b0_000000_00010_00001_111_0001_0_1100011; // imm=+2
-    mem.store_word(0x0, bgeu_instr);
+    mem.store_word(0x0, bgeu_instr).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFF;
     cpu.reg[2] = 0x0000_0001;
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
 }
@@ -0,0 +1,352 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/// Stores a 16-bit parcel at `addr`
+fn store_parcel(mem: &mut Memory, addr: usize, parcel: HalfWord) {
+    mem.store_halfword(addr, parcel).unwrap();
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// Register-based compressed instructions expand to their base equivalents
+#[test]
+fn compressed_register_decode() {
+    // c.mv a0, a1
+    assert_eq!(
+        Instruction::parse_compressed(0x852E),
+        Ok(Instruction::ADD {
+            rs1: 0,
+            rs2: 11,
+            rd: 10
+        })
+    );
+    // c.add a0, a1
+    assert_eq!(
+        Instruction::parse_compressed(0x952E),
+        Ok(Instruction::ADD {
+            rs1: 10,
+            rs2: 11,
+            rd: 10
+        })
+    );
+    // c.sub a0, a1
+    assert_eq!(
+        Instruction::parse_compressed(0x8D0D),
+        Ok(Instruction::SUB {
+            rs1: 10,
+            rs2: 11,
+            rd: 10
+        })
+    );
+    // c.jr ra (ret)
+    assert_eq!(
+        Instruction::parse_compressed(0x8082),
+        Ok(Instruction::JALR {
+            offset: 0,
+            rs1: 1,
+            rd: 0
+        })
+    );
+    // c.jalr a0
+    assert_eq!(
+        Instruction::parse_compressed(0x9502),
+        Ok(Instruction::JALR {
+            offset: 0,
+            rs1: 10,
+            rd: 1
+        })
+    );
+    // c.ebreak
+    assert_eq!(
+        Instruction::parse_compressed(0x9002),
+        Ok(Instruction::EBREAK)
+    );
+}
+
+/// Immediate forms are sign- or zero-extended and scaled as the spec requires
+#[test]
+fn compressed_immediate_decode() {
+    // c.li a0, -1
+    assert_eq!(
+        Instruction::parse_compressed(0x557D),
+        Ok(Instruction::ADDI {
+            imm: -1,
+            rs1: 0,
+            rd: 10
+        })
+    );
+    // c.lui a0, 0x1
+    assert_eq!(
+        Instruction::parse_compressed(0x6505),
+        Ok(Instruction::LUI {
+            imm: 0x1000,
+            rd: 10
+        })
+    );
+    // c.addi16sp sp, -16
+    assert_eq!(
+        Instruction::parse_compressed(0x717D),
+        Ok(Instruction::ADDI {
+            imm: -16,
+            rs1: 2,
+            rd: 2
+        })
+    );
+    // c.addi4spn s0, sp, 16
+    assert_eq!(
+        Instruction::parse_compressed(0x0800),
+        Ok(Instruction::ADDI {
+            imm: 16,
+            rs1: 2,
+            rd: 8
+        })
+    );
+    // c.andi a0, -1
+    assert_eq!(
+        Instruction::parse_compressed(0x997D),
+        Ok(Instruction::ANDI {
+            imm: 0xFFFFFFFF,
+            rs1: 10,
+            rd: 10
+        })
+    );
+    // c.slli a0, 2
+    assert_eq!(
+        Instruction::parse_compressed(0x050A),
+        Ok(Instruction::SLLI {
+            shamt: 2,
+            rs1: 10,
+            rd: 10
+        })
+    );
+    // c.srai a0, 1
+    assert_eq!(
+        Instruction::parse_compressed(0x8505),
+        Ok(Instruction::SRAI {
+            shamt: 1,
+            rs1: 10,
+            rd: 10
+        })
+    );
+}
+
+/// Compressed loads and stores scale their offsets
+#[test]
+fn compressed_load_store_decode() {
+    // c.lw a0, 4(a1)
+    assert_eq!(
+        Instruction::parse_compressed(0x41C8),
+        Ok(Instruction::LW {
+            offset: 4,
+            rs1: 11,
+            rd: 10
+        })
+    );
+    // c.lwsp ra, 12(sp)
+    assert_eq!(
+        Instruction::parse_compressed(0x40B2),
+        Ok(Instruction::LW {
+            offset: 12,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // c.swsp ra, 12(sp)
+    assert_eq!(
+        Instruction::parse_compressed(0xC606),
+        Ok(Instruction::SW {
+            offset: 12,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+}
+
+/// Reserved and illegal compressed encodings are rejected
+#[test]
+fn compressed_illegal_decode() {
+    // the all-zero parcel is defined to be illegal
+    assert_eq!(
+        Instruction::parse_compressed(0x0000),
+        Err(Exception::IllegalInstruction)
+    );
+    // c.lwsp with rd = x0 is reserved
+    assert_eq!(
+        Instruction::parse_compressed(0x4032),
+        Err(Exception::IllegalInstruction)
+    );
+    // c.jr with rs1 = x0 is reserved
+    assert_eq!(
+        Instruction::parse_compressed(0x8002),
+        Err(Exception::IllegalInstruction)
+    );
+    // c.slli with shamt[5] = 1 is reserved on RV32
+    assert_eq!(
+        Instruction::parse_compressed(0x1502),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/* -------------------- Execution -------------------- */
+
+/// Compressed instructions advance the pc by 2 and mix freely with 32-bit ones
+#[test]
+fn mixed_length_execution() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: c.li a0, -1
+    // 0x2: ADDI a0, a0, 3 (32-bit, only 16-bit aligned)
+    // 0x6: c.addi a0, 1
+    let addi: Word = 0b000000000011_01010_000_01010_0010011;
+    store_parcel(&mut mem, 0x0, 0x557D);
+    store_parcel(&mut mem, 0x2, addi as HalfWord);
+    store_parcel(&mut mem, 0x4, (addi >> 16) as HalfWord);
+    store_parcel(&mut mem, 0x6, 0x0505);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x2);
+    assert_eq!(cpu.fetch_instruction(&mem).unwrap(), addi);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x6);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x8);
+    assert_eq!(cpu.reg[10], 3);
+}
+
+/// C.JAL links pc + 2 and C.JR returns there
+#[test]
+fn compressed_call_and_return() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: c.jal +8
+    // 0x2: c.li a0, -1
+    // 0x8: c.jr ra
+    store_parcel(&mut mem, 0x0, 0x2021);
+    store_parcel(&mut mem, 0x2, 0x557D);
+    store_parcel(&mut mem, 0x8, 0x8082);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x8);
+    assert_eq!(cpu.reg[1], 0x2);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x2);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[10], 0xFFFFFFFF);
+}
+
+/// Branches and jumps may target 2-byte aligned addresses
+#[test]
+fn compressed_branch_to_halfword_target() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: c.j +6
+    // 0x6: c.beqz a0, +8
+    store_parcel(&mut mem, 0x0, 0xA019);
+    store_parcel(&mut mem, 0x6, 0xC501);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x6);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0xE);
+}
+
+/// A 32-bit JAL at a 2-byte aligned pc links the address after its 4 bytes
+#[test]
+fn jal_links_past_full_instruction() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x2: JAL x1, +6
+    let jal: Word = 0b0_0000000011_0_00000000_00001_1101111;
+    store_parcel(&mut mem, 0x2, jal as HalfWord);
+    store_parcel(&mut mem, 0x4, (jal >> 16) as HalfWord);
+    cpu.pc = 0x2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x8);
+    assert_eq!(cpu.reg[1], 0x6);
+}
+
+/// An illegal compressed instruction reports its 16 bits in tval
+#[test]
+fn compressed_illegal_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // c.lwsp x0, 0(sp) followed by a valid parcel that must not be fetched
+    store_parcel(&mut mem, 0x0, 0x4002);
+    store_parcel(&mut mem, 0x2, 0x0505);
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, 0x4002))
+    );
+    assert_eq!(cpu.pc, 0x0);
+}
+
+/// A 32-bit instruction whose upper half is out of bounds faults at that half
+#[test]
+fn fetch_upper_half_out_of_bounds() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let last: usize = 0xFFFFFE; // last halfword of memory
+    store_parcel(&mut mem, last, 0x0513);
+    cpu.pc = last as Word;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAccessFault, 0x1000000))
+    );
+}
+
+/* -------------------- Disabling C -------------------- */
+
+/// Without C, compressed encodings are illegal 32-bit instructions
+#[test]
+fn compressed_without_c_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap();
+    store_parcel(&mut mem, 0x0, 0x0505); // c.addi a0, 1
+    store_parcel(&mut mem, 0x2, 0x0505);
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, 0x0505_0505))
+    );
+}
+
+/// Clearing C is ignored when the next instruction is not 4-byte aligned
+#[test]
+fn clearing_c_needs_aligned_next_pc() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x2: CSRRW x0, misa, x0
+    let csrrw_instruction: Word = 0b001100000001_00000_001_00000_1110011;
+    store_parcel(&mut mem, 0x2, csrrw_instruction as HalfWord);
+    store_parcel(&mut mem, 0x4, (csrrw_instruction >> 16) as HalfWord);
+    // 0x6: CSRRW x0, misa, x0 (now at an aligned pc + 4)
+    store_parcel(&mut mem, 0x6, 0x0001); // c.nop
+    store_parcel(&mut mem, 0x8, csrrw_instruction as HalfWord);
+    store_parcel(&mut mem, 0xA, (csrrw_instruction >> 16) as HalfWord);
+    cpu.pc = 0x2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_ne!(cpu.csr.read(MISA).unwrap() & MISA_C, 0);
+
+    cpu.clock_cycle(&mut mem).unwrap(); // c.nop
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(MISA).unwrap() & MISA_C, 0);
+    assert_eq!(cpu.pc, 0xC);
+}
@@ -0,0 +1,233 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const NOP: Word = 0b000000000000_00000_000_00000_0010011; // addi x0, x0, 0
+
+/// csrr rd, csr
+fn csrr(csr: usize, rd: Word) -> Word {
+    ((csr as Word) << 20) | (0b010 << 12) | (rd << 7) | 0b1110011
+}
+
+/// Stores `program` from address 0 onwards
+fn load_program(mem: &mut Memory, program: &[Word]) {
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+}
+
+/* -------------------- Zicntr -------------------- */
+
+/// rdcycle / rdinstret see every instruction before them
+#[test]
+fn rdcycle_rdinstret() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[NOP, NOP, csrr(CYCLE, 1), csrr(INSTRET, 2)]);
+
+    for _ in 0..4 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(cpu.reg[1], 2);
+    assert_eq!(cpu.reg[2], 3);
+    assert_eq!(cpu.csr.read(MCYCLE), Ok(4));
+    assert_eq!(cpu.csr.read(MINSTRET), Ok(4));
+    assert_eq!(cpu.csr.read(TIME), Ok(4));
+}
+
+/// An instruction that traps takes a cycle but does not retire
+#[test]
+fn trapping_instruction_not_retired() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[0]);
+
+    assert!(cpu.clock_cycle(&mut mem).is_err());
+    assert_eq!(cpu.csr.read(CYCLE), Ok(1));
+    assert_eq!(cpu.csr.read(INSTRET), Ok(0));
+}
+
+/// A write to mcycle is seen by the next instruction without being incremented
+#[test]
+fn mcycle_write() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    // csrw mcycle, x1 ; csrr x2, mcycle
+    load_program(
+        &mut mem,
+        &[0b101100000000_00001_001_00000_1110011, csrr(MCYCLE, 2)],
+    );
+    cpu.reg[1] = 100;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 100);
+    assert_eq!(cpu.csr.read(MCYCLE), Ok(101));
+}
+
+/// On RV32 the upper halves are read through cycleh / instreth and carry over
+#[test]
+fn rv32_counter_high_halves() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[NOP, NOP]);
+    cpu.csr.write(MCYCLE, 0xFFFF_FFFF).unwrap();
+    cpu.csr.write(MINSTRETH, 7).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(CYCLE), Ok(0));
+    assert_eq!(cpu.csr.read(CYCLEH), Ok(1));
+    assert_eq!(cpu.csr.read(MINSTRETH), Ok(7));
+    assert_eq!(cpu.csr.read(INSTRETH), Ok(7));
+}
+
+/// On RV64 the counters are 64 bits wide and the h CSRs do not exist
+#[test]
+fn rv64_counters() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[NOP]);
+    cpu.csr.write(MCYCLE, 0xFFFF_FFFF).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap_err();
+
+    assert_eq!(cpu.csr.read(CYCLE), Ok(0x1_0000_0000));
+    for csr in [CYCLEH, TIMEH, INSTRETH, MCYCLEH, MINSTRETH, MHPMCOUNTER3H] {
+        assert_eq!(cpu.csr.read(csr), Err(Exception::IllegalInstruction));
+    }
+}
+
+/// The user-level counters are read-only shadows
+#[test]
+fn user_counters_read_only() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    // csrw cycle, x1
+    let instruction: Word = 0b110000000000_00001_001_00000_1110011;
+    load_program(&mut mem, &[instruction]);
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/// mcountinhibit stops cycle and instret, time cannot be inhibited
+#[test]
+fn mcountinhibit() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[NOP, NOP]);
+    cpu.csr.write(MCOUNTINHIBIT, 0b111).unwrap();
+    assert_eq!(cpu.csr.read(MCOUNTINHIBIT), Ok(0b101));
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(CYCLE), Ok(0));
+    assert_eq!(cpu.csr.read(INSTRET), Ok(0));
+    assert_eq!(cpu.csr.read(TIME), Ok(2));
+}
+
+/// set_time overrides the free-running time counter
+#[test]
+fn set_time() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[csrr(TIME, 1)]);
+    cpu.csr.set_time(5000);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[1], 5000);
+}
+
+/* -------------------- Zihpm -------------------- */
+
+/// mhpmcounterN counts the event selected by mhpmeventN
+#[test]
+fn hpm_counters_count_events() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(
+        &mut mem,
+        &[
+            0b000100000000_00000_010_00001_0000011,  // lw x1, 0x100(x0)
+            0b0001000_00001_00000_010_00100_0100011, // sw x1, 0x104(x0)
+            0b0000000_00000_00000_001_01000_1100011, // bne x0, x0, 8
+            0b0000000_00000_00000_000_01000_1100011, // beq x0, x0, 8
+            NOP,
+            0b000100000000_00000_010_00010_0000011, // lw x2, 0x100(x0)
+        ],
+    );
+    let events: [Word; 5] = [
+        HPM_EVENT_LOAD,
+        HPM_EVENT_STORE,
+        HPM_EVENT_BRANCH,
+        HPM_EVENT_BRANCH_TAKEN,
+        HPM_EVENT_JUMP,
+    ];
+    for (i, event) in events.iter().enumerate() {
+        cpu.csr.write(MHPMEVENT3 + i, *event).unwrap();
+    }
+
+    for _ in 0..5 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(cpu.pc, 0x18);
+    let counts: Vec<Word> = (0..5)
+        .map(|i| cpu.csr.read(HPMCOUNTER3 + i).unwrap())
+        .collect();
+    assert_eq!(counts, [2, 1, 2, 1, 0]);
+    // counters without an event stay at zero
+    assert_eq!(cpu.csr.read(MHPMCOUNTER31), Ok(0));
+}
+
+/// A branch to the next instruction is still taken, and one not taken is not
+#[test]
+fn hpm_branch_to_next_instruction() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(
+        &mut mem,
+        &[
+            0b0000000_00000_00000_000_00100_1100011, // beq x0, x0, 4
+            0b0000000_00000_00000_001_00100_1100011, // bne x0, x0, 4
+        ],
+    );
+    cpu.csr.write(MHPMEVENT3, HPM_EVENT_BRANCH_TAKEN).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 8);
+    assert_eq!(cpu.csr.read(HPMCOUNTER3), Ok(1));
+}
+
+/// Exceptions are counted even though the instruction does not retire
+#[test]
+fn hpm_counts_exceptions() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[0]);
+    cpu.csr.write(MHPMEVENT3, HPM_EVENT_EXCEPTION).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.csr.read(MHPMCOUNTER3), Ok(1));
+}
+
+/// mhpmevent is WARL: unsupported events read back as zero
+#[test]
+fn mhpmevent_warl() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(MHPMEVENT31, 99).unwrap();
+    assert_eq!(cpu.csr.read(MHPMEVENT31), Ok(HPM_EVENT_NONE));
+    cpu.csr.write(MHPMEVENT31, HPM_EVENT_FP).unwrap();
+    assert_eq!(cpu.csr.read(MHPMEVENT31), Ok(HPM_EVENT_FP));
+}
+
+/// mcounteren only keeps the 32 counter bits
+#[test]
+fn mcounteren() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    cpu.csr.write(MCOUNTEREN, 0xFFFF_FFFF_FFFF_FFFF).unwrap();
+    assert_eq!(cpu.csr.read(MCOUNTEREN), Ok(0xFFFF_FFFF));
+}
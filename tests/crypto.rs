@@ -0,0 +1,292 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/// Executes a single instruction placed at the current pc
+fn run<X: Xlen>(cpu: &mut RISCV<X>, mem: &mut Memory, instruction: Word) -> Result<(), Trap<X>> {
+    mem.store_word(cpu.pc.to_usize(), instruction).unwrap();
+    cpu.clock_cycle(mem)
+}
+
+/// Runs `instruction` on a fresh RV32 hart with x1 and x2 set, returning x3
+fn rv32(instruction: Word, x1: Word, x2: Word) -> Word {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = x1;
+    cpu.reg[2] = x2;
+    run(&mut cpu, &mut mem, instruction).unwrap();
+    cpu.reg[3]
+}
+
+/// Runs `instruction` on a fresh RV64 hart with x1 and x2 set, returning x3
+fn rv64(instruction: Word, x1: u64, x2: u64) -> u64 {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = x1;
+    cpu.reg[2] = x2;
+    run(&mut cpu, &mut mem, instruction).unwrap();
+    cpu.reg[3]
+}
+
+/// aes32*i x3, x1, x2, bs
+fn aes32(funct5: Word, bs: Word) -> Word {
+    (bs << 30) | (funct5 << 25) | (0b00010_00001_000_00011_0110011)
+}
+
+const AES32ESI: Word = 0b10001;
+const AES32ESMI: Word = 0b10011;
+const AES32DSI: Word = 0b10101;
+const AES32DSMI: Word = 0b10111;
+
+/* -------------------- Decoding -------------------- */
+
+/// The byte select of the AES instructions sits in the top two bits
+#[test]
+fn crypto_decode() {
+    assert_eq!(
+        Instruction::parse_instruction(EncodingVariant::get_encoding(aes32(AES32ESMI, 3)).unwrap()),
+        Ok(Instruction::AES32ESMI {
+            bs: 3,
+            rs1: 1,
+            rs2: 2,
+            rd: 3
+        })
+    );
+    // sha256sig1 x3, x1
+    assert_eq!(
+        Instruction::parse_instruction(
+            EncodingVariant::get_encoding(0b0001000_00011_00001_001_00011_0010011).unwrap()
+        ),
+        Ok(Instruction::SHA256SIG1 { rs1: 1, rd: 3 })
+    );
+}
+
+/// The register-pair AES, SHA-512 and zip instructions only exist on RV32
+#[test]
+fn crypto_rv32_only() {
+    let instructions: [Word; 4] = [
+        aes32(AES32ESI, 0),
+        0b0101010_00010_00001_000_00011_0110011, // sha512sig0l
+        0b0000100_01111_00001_001_00011_0010011, // zip
+        0b0000100_01111_00001_101_00011_0010011, // unzip
+    ];
+    for instruction in instructions {
+        let encoding: EncodingVariant = EncodingVariant::get_encoding(instruction).unwrap();
+        assert!(Instruction::parse_instruction(encoding).is_ok());
+        let encoding: EncodingVariant = EncodingVariant::get_encoding(instruction).unwrap();
+        assert_eq!(
+            Instruction::parse_instruction_xlen(encoding, 64),
+            Err(Exception::IllegalInstruction)
+        );
+    }
+}
+
+/* -------------------- Zkne / Zknd -------------------- */
+
+/// One column of the first AES-128 round from FIPS 197 appendix B
+#[test]
+fn aes32_encrypt_round() {
+    // state at the start of round 1, one little-endian word per column
+    let state: [Word; 4] = [0xBEE3_3D19, 0x2BE2_F4A0, 0x2A8D_C69A, 0x0848_F8E9];
+    let round_key: Word = 0x17FE_FAA0;
+
+    // ShiftRows takes row i of the output column from column i
+    let mut column: Word = round_key;
+    for (bs, word) in state.iter().enumerate() {
+        column = rv32(aes32(AES32ESMI, bs as Word), column, *word);
+    }
+    assert_eq!(column, 0xF27F_9CA4);
+
+    // without MixColumns only the S-box output is placed in its row
+    assert_eq!(rv32(aes32(AES32ESI, 0), 0, 0x19), 0xD4);
+    assert_eq!(rv32(aes32(AES32ESI, 2), 0xFF, 0x0019_0000), 0x00D4_00FF);
+}
+
+/// The decryption forms undo the S-box and MixColumns
+#[test]
+fn aes32_decrypt_round() {
+    assert_eq!(rv32(aes32(AES32DSI, 0), 0, 0xD4), 0x19);
+    assert_eq!(rv32(aes32(AES32DSI, 3), 0, 0xD400_0000), 0x1900_0000);
+
+    // S-box outputs of the FIPS 197 MixColumns result 04 66 81 E5
+    let sub_bytes: Word = Word::from_le_bytes([0xF2, 0x33, 0x0C, 0xD9]);
+    let mut column: Word = 0;
+    for bs in 0..4 {
+        column = rv32(aes32(AES32DSMI, bs), column, sub_bytes);
+    }
+    assert_eq!(column, Word::from_le_bytes([0xD4, 0xBF, 0x5D, 0x30]));
+}
+
+/* -------------------- Zknh -------------------- */
+
+/// The SHA-256 sigma functions applied to the first initial hash word
+#[test]
+fn sha256_functions() {
+    let h0: Word = 0x6A09_E667;
+    assert_eq!(
+        rv32(0b0001000_00000_00001_001_00011_0010011, h0, 0),
+        0xCE20_B47E
+    );
+    assert_eq!(
+        rv32(0b0001000_00001_00001_001_00011_0010011, h0, 0),
+        0x55B6_5510
+    );
+    assert_eq!(
+        rv32(0b0001000_00010_00001_001_00011_0010011, h0, 0),
+        0xBA0C_F582
+    );
+    assert_eq!(
+        rv32(0b0001000_00011_00001_001_00011_0010011, h0, 0),
+        0xCFE5_DA3C
+    );
+    // sign-extended on RV64
+    assert_eq!(
+        rv64(0b0001000_00000_00001_001_00011_0010011, h0 as u64, 0),
+        0xFFFF_FFFF_CE20_B47E
+    );
+}
+
+/// The RV32 SHA-512 halves match the 64-bit sigma functions
+#[test]
+fn sha512_register_pairs() {
+    let x: u64 = 0x6A09_E667_F3BC_C908;
+    let (low, high): (Word, Word) = (x as Word, (x >> 32) as Word);
+    let halves = |low_funct7: Word, high_funct7: Word| -> u64 {
+        let low_half: Word = rv32(
+            (low_funct7 << 25) | 0b00010_00001_000_00011_0110011,
+            low,
+            high,
+        );
+        let high_half: Word = rv32(
+            (high_funct7 << 25) | 0b00010_00001_000_00011_0110011,
+            high,
+            low,
+        );
+        ((high_half as u64) << 32) | low_half as u64
+    };
+
+    let sig0: u64 = x.rotate_right(1) ^ x.rotate_right(8) ^ (x >> 7);
+    let sig1: u64 = x.rotate_right(19) ^ x.rotate_right(61) ^ (x >> 6);
+    let sum0: u64 = x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39);
+    let sum1: u64 = x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41);
+    assert_eq!(halves(0b0101010, 0b0101110), sig0);
+    assert_eq!(halves(0b0101011, 0b0101111), sig1);
+    assert_eq!(halves(0b0101000, 0b0101000), sum0);
+    assert_eq!(halves(0b0101001, 0b0101001), sum1);
+}
+
+/* -------------------- Zbkb / Zbkx -------------------- */
+
+/// pack / packh / packw concatenate the lower halves of rs1 and rs2
+#[test]
+fn zbkb_pack() {
+    assert_eq!(
+        rv32(
+            0b0000100_00010_00001_100_00011_0110011,
+            0xAAAA_1234,
+            0xBBBB_5678
+        ),
+        0x5678_1234
+    );
+    assert_eq!(
+        rv32(
+            0b0000100_00010_00001_111_00011_0110011,
+            0xAAAA_1234,
+            0xBBBB_5678
+        ),
+        0x7834
+    );
+    assert_eq!(
+        rv64(
+            0b0000100_00010_00001_100_00011_0110011,
+            0xAAAA_AAAA_1234_5678,
+            0x9ABC_DEF0
+        ),
+        0x9ABC_DEF0_1234_5678
+    );
+    assert_eq!(
+        rv64(0b0000100_00010_00001_100_00011_0111011, 0xAAAA_1234, 0x8000),
+        0xFFFF_FFFF_8000_1234
+    );
+}
+
+/// brev8 reverses each byte, zip / unzip interleave the halves
+#[test]
+fn zbkb_bit_permutations() {
+    assert_eq!(
+        rv32(0b0110100_00111_00001_101_00011_0010011, 0x0102_0380, 0),
+        0x8040_C001
+    );
+    assert_eq!(
+        rv64(
+            0b0110100_00111_00001_101_00011_0010011,
+            0x0100_0000_0000_0000,
+            0
+        ),
+        0x8000_0000_0000_0000
+    );
+
+    assert_eq!(
+        rv32(0b0000100_01111_00001_001_00011_0010011, 0x0000_FFFF, 0),
+        0x5555_5555
+    );
+    assert_eq!(
+        rv32(0b0000100_01111_00001_001_00011_0010011, 0xFFFF_0000, 0),
+        0xAAAA_AAAA
+    );
+    assert_eq!(
+        rv32(0b0000100_01111_00001_101_00011_0010011, 0x5555_5555, 0),
+        0x0000_FFFF
+    );
+    let zipped: Word = rv32(0b0000100_01111_00001_001_00011_0010011, 0x1234_5678, 0);
+    assert_eq!(
+        rv32(0b0000100_01111_00001_101_00011_0010011, zipped, 0),
+        0x1234_5678
+    );
+}
+
+/// xperm4 / xperm8 look up nibbles / bytes of rs1, out-of-range indices give 0
+#[test]
+fn zbkx_crossbar() {
+    assert_eq!(
+        rv32(
+            0b0010100_00010_00001_100_00011_0110011,
+            0x4433_2211,
+            0x0001_0203
+        ),
+        0x1122_3344
+    );
+    assert_eq!(
+        rv32(
+            0b0010100_00010_00001_100_00011_0110011,
+            0x4433_2211,
+            0x0004_0100
+        ),
+        0x1100_2211
+    );
+    assert_eq!(
+        rv32(
+            0b0010100_00010_00001_010_00011_0110011,
+            0xFEDC_BA98,
+            0x0123_4567
+        ),
+        0x89AB_CDEF
+    );
+    assert_eq!(
+        rv32(
+            0b0010100_00010_00001_010_00011_0110011,
+            0xFEDC_BA98,
+            0x0000_0080
+        ),
+        0x8888_8808
+    );
+    // the table is eight bytes wide on RV64
+    assert_eq!(
+        rv64(
+            0b0010100_00010_00001_100_00011_0110011,
+            0x8877_6655_4433_2211,
+            0x07
+        ),
+        0x1111_1111_1111_1188
+    );
+}
@@ -0,0 +1,252 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/* -------------------- CSRRW tests -------------------- */
+
+/// CSRRW instruction fetch
+#[test]
+fn csrrw_instruction_fetch() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRW x1, mscratch, x2
+    let csrrw_instruction: Word = 0b001101000000_00010_001_00001_1110011;
+    mem.store_word(0x0, csrrw_instruction).unwrap();
+
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
+    assert_eq!(instruction, csrrw_instruction);
+}
+
+/// CSRRW swaps a register with a CSR
+#[test]
+fn csrrw_swaps_register_and_csr() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRW x1, mscratch, x2
+    let csrrw_instruction: Word = 0b001101000000_00010_001_00001_1110011;
+    mem.store_word(0x0, csrrw_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0x1111).unwrap();
+    cpu.reg[2] = 0x2222;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0x1111);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0x2222);
+    assert_eq!(cpu.pc, 4);
+}
+
+/// CSRRW with rd = rs1 reads the old value before writing
+#[test]
+fn csrrw_same_source_and_destination() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRW x5, mscratch, x5
+    let csrrw_instruction: Word = 0b001101000000_00101_001_00101_1110011;
+    mem.store_word(0x0, csrrw_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0xAAAA).unwrap();
+    cpu.reg[5] = 0xBBBB;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[5], 0xAAAA);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0xBBBB);
+}
+
+/// CSRRW to a read-only CSR is illegal even when rd = x0
+#[test]
+fn csrrw_read_only_csr_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRW x0, mhartid, x1
+    let csrrw_instruction: Word = 0b111100010100_00001_001_00000_1110011;
+    mem.store_word(0x0, csrrw_instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, csrrw_instruction))
+    );
+    assert_eq!(cpu.pc, 0);
+}
+
+/* -------------------- CSRRS / CSRRC tests -------------------- */
+
+/// CSRRS sets the bits given in rs1 and returns the old value
+#[test]
+fn csrrs_sets_bits() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRS x3, mscratch, x4
+    let csrrs_instruction: Word = 0b001101000000_00100_010_00011_1110011;
+    mem.store_word(0x0, csrrs_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0b1010).unwrap();
+    cpu.reg[4] = 0b0101;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[3], 0b1010);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0b1111);
+}
+
+/// CSRRS with rs1 = x0 only reads, so read-only CSRs are allowed
+#[test]
+fn csrrs_x0_reads_read_only_csr() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRS x6, misa, x0 (csrr x6, misa)
+    let csrr_instruction: Word = 0b001100000001_00000_010_00110_1110011;
+    // CSRRS x7, mhartid, x0 (csrr x7, mhartid)
+    let csrr_hartid: Word = 0b111100010100_00000_010_00111_1110011;
+    mem.store_word(0x0, csrr_instruction).unwrap();
+    mem.store_word(0x4, csrr_hartid).unwrap();
+
+    cpu.reg[7] = 0xFFFF;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[6] >> 30, 1); // MXL = 32 bits
+    assert_ne!(cpu.reg[6] & (1 << 8), 0); // I base
+    assert_eq!(cpu.reg[7], 0);
+}
+
+/// CSRRS with a non-zero rs1 on a read-only CSR is illegal
+#[test]
+fn csrrs_read_only_csr_write_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRS x1, mvendorid, x2
+    let csrrs_instruction: Word = 0b111100010001_00010_010_00001_1110011;
+    mem.store_word(0x0, csrrs_instruction).unwrap();
+
+    cpu.reg[2] = 0; // rs1 is not x0 even though its value is zero
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, csrrs_instruction))
+    );
+    assert_eq!(cpu.reg[1], 0);
+}
+
+/// CSRRC clears the bits given in rs1
+#[test]
+fn csrrc_clears_bits() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRC x3, mscratch, x4
+    let csrrc_instruction: Word = 0b001101000000_00100_011_00011_1110011;
+    mem.store_word(0x0, csrrc_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0xFF).unwrap();
+    cpu.reg[4] = 0x0F;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[3], 0xFF);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0xF0);
+}
+
+/* -------------------- Immediate forms -------------------- */
+
+/// CSRRWI writes the zero-extended 5-bit immediate
+#[test]
+fn csrrwi_writes_immediate() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRWI x1, mscratch, 31
+    let csrrwi_instruction: Word = 0b001101000000_11111_101_00001_1110011;
+    mem.store_word(0x0, csrrwi_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0xDEADBEEF).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0xDEADBEEF);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 31);
+}
+
+/// CSRRSI and CSRRCI set and clear immediate bits
+#[test]
+fn csrrsi_and_csrrci() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRSI x0, mscratch, 0b10101
+    let csrrsi_instruction: Word = 0b001101000000_10101_110_00000_1110011;
+    // CSRRCI x2, mscratch, 0b00101
+    let csrrci_instruction: Word = 0b001101000000_00101_111_00010_1110011;
+    mem.store_word(0x0, csrrsi_instruction).unwrap();
+    mem.store_word(0x4, csrrci_instruction).unwrap();
+
+    cpu.csr.write(MSCRATCH, 0x100).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0x115);
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 0x115);
+    assert_eq!(cpu.csr.read(MSCRATCH).unwrap(), 0x110);
+}
+
+/// CSRRSI with a zero immediate does not write, so read-only CSRs are allowed
+#[test]
+fn csrrsi_zero_immediate_reads_read_only_csr() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRSI x1, marchid, 0
+    let csrrsi_instruction: Word = 0b111100010010_00000_110_00001_1110011;
+    mem.store_word(0x0, csrrsi_instruction).unwrap();
+
+    cpu.reg[1] = 0x1234;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[1], 0);
+}
+
+/* -------------------- Unimplemented CSRs -------------------- */
+
+/// Accessing a CSR that does not exist raises an illegal instruction exception
+#[test]
+fn unimplemented_csr_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRS x1, 0x7FF, x0
+    let csrrs_instruction: Word = 0b011111111111_00000_010_00001_1110011;
+    mem.store_word(0x0, csrrs_instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, csrrs_instruction))
+    );
+}
+
+/// Writes to misa only change the C extension bit
+#[test]
+fn misa_writes_only_toggle_c() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // CSRRW x0, misa, x1
+    let csrrw_instruction: Word = 0b001100000001_00001_001_00000_1110011;
+    mem.store_word(0x0, csrrw_instruction).unwrap();
+
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    assert_ne!(misa & MISA_C, 0);
+    cpu.reg[1] = 0;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(MISA).unwrap(), misa & !MISA_C);
+}
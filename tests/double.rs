@@ -0,0 +1,322 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const RNE: Word = 0b000;
+const RTZ: Word = 0b001;
+
+/// Encodes a double-precision OP-FP instruction
+fn op_fp_d(funct5: Word, rs2: Word, rs1: Word, rm: Word, rd: Word) -> Word {
+    (funct5 << 27) | (0b01 << 25) | (rs2 << 20) | (rs1 << 15) | (rm << 12) | (rd << 7) | 0b1010011
+}
+
+/// Encodes a single-precision OP-FP instruction
+fn op_fp_s(funct5: Word, rs2: Word, rs1: Word, rm: Word, rd: Word) -> Word {
+    (funct5 << 27) | (rs2 << 20) | (rs1 << 15) | (rm << 12) | (rd << 7) | 0b1010011
+}
+
+/// NaN-boxes a single-precision value as it sits in a 64-bit f register
+fn boxed(value: f32) -> DoubleWord {
+    0xFFFF_FFFF_0000_0000 | value.to_bits() as DoubleWord
+}
+
+/// Executes a single instruction placed at the current pc
+fn run(cpu: &mut RISCV, instruction: Word) -> Result<(), Trap> {
+    let mut mem: Memory = Memory::new();
+    mem.store_word(cpu.pc as usize, instruction).unwrap();
+    cpu.clock_cycle(&mut mem)
+}
+
+/// Executes `f1 <op> f2` into f3 and returns the result together with the raised flags
+fn binary(funct5: Word, rm: Word, a: f64, b: f64) -> (f64, Word) {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = a.to_bits();
+    cpu.freg[2] = b.to_bits();
+    run(&mut cpu, op_fp_d(funct5, 2, 1, rm, 3)).unwrap();
+    (f64::from_bits(cpu.freg[3]), cpu.csr.read(FFLAGS).unwrap())
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// FLD, FSD and the conversions between formats decode from their 32-bit encodings
+#[test]
+fn double_decode() {
+    let decode = |instruction: Word| {
+        Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+    };
+    // fld f1, 8(x2)
+    assert_eq!(
+        decode(0b000000001000_00010_011_00001_0000111),
+        Ok(Instruction::FLD {
+            offset: 8,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // fsd f1, 8(x2)
+    assert_eq!(
+        decode(0b0000000_00001_00010_011_01000_0100111),
+        Ok(Instruction::FSD {
+            offset: 8,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+    // fcvt.s.d f3, f1
+    assert_eq!(
+        decode(op_fp_s(0b01000, 1, 1, RNE, 3)),
+        Ok(Instruction::FCVTSD {
+            rs1: 1,
+            rd: 3,
+            rm: 0
+        })
+    );
+    // fcvt.d.s f3, f1
+    assert_eq!(
+        decode(op_fp_d(0b01000, 0, 1, RNE, 3)),
+        Ok(Instruction::FCVTDS {
+            rs1: 1,
+            rd: 3,
+            rm: 0
+        })
+    );
+    // fmv.x.d only exists on RV64
+    assert_eq!(
+        decode(op_fp_d(0b11100, 0, 1, 0b000, 3)),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/// C.FLD, C.FSD, C.FLDSP and C.FSDSP expand to FLD / FSD
+#[test]
+fn compressed_double_decode() {
+    // c.fld fa0, 8(a1)
+    assert_eq!(
+        Instruction::parse_compressed(0x2588),
+        Ok(Instruction::FLD {
+            offset: 8,
+            rs1: 11,
+            rd: 10
+        })
+    );
+    // c.fsd fa0, 8(a1)
+    assert_eq!(
+        Instruction::parse_compressed(0xA588),
+        Ok(Instruction::FSD {
+            offset: 8,
+            rs1: 11,
+            rs2: 10
+        })
+    );
+    // c.fldsp fa0, 8(sp)
+    assert_eq!(
+        Instruction::parse_compressed(0x2522),
+        Ok(Instruction::FLD {
+            offset: 8,
+            rs1: 2,
+            rd: 10
+        })
+    );
+    // c.fsdsp fa0, 16(sp)
+    assert_eq!(
+        Instruction::parse_compressed(0xA82A),
+        Ok(Instruction::FSD {
+            offset: 16,
+            rs1: 2,
+            rs2: 10
+        })
+    );
+}
+
+/// misa reports the D extension
+#[test]
+fn misa_has_d() {
+    let cpu: RISCV = RISCV::reset();
+    assert_ne!(cpu.csr.read(MISA).unwrap() & (1 << 3), 0);
+}
+
+/* -------------------- Arithmetic -------------------- */
+
+/// Double-precision arithmetic is exact where f64 is, and rounds at 53 bits
+#[test]
+fn double_arithmetic() {
+    assert_eq!(binary(0b00000, RNE, 0.1, 0.2), (0.1 + 0.2, FFLAGS_NX));
+    assert_eq!(binary(0b00001, RNE, 1.5, 2.25), (-0.75, 0));
+    assert_eq!(
+        binary(0b00010, RNE, 1e200, 1e200),
+        (f64::INFINITY, FFLAGS_OF | FFLAGS_NX)
+    );
+    assert_eq!(binary(0b00011, RNE, 1.0, 3.0), (1.0 / 3.0, FFLAGS_NX));
+    assert_eq!(binary(0b00011, RNE, 1.0, 0.0), (f64::INFINITY, FFLAGS_DZ));
+    // a tie at the 53rd bit rounds to even
+    let half_ulp: f64 = f64::EPSILON / 2.0;
+    assert_eq!(binary(0b00000, RNE, 1.0, half_ulp), (1.0, FFLAGS_NX));
+
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 2.0f64.to_bits();
+    run(&mut cpu, op_fp_d(0b01011, 0, 1, RNE, 3)).unwrap(); // fsqrt.d
+    assert_eq!(f64::from_bits(cpu.freg[3]), 2.0f64.sqrt());
+}
+
+/// FMADD.D rounds once
+#[test]
+fn double_fused_multiply_add() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = (1.0 + f64::EPSILON).to_bits();
+    cpu.freg[2] = (1.0 - f64::EPSILON).to_bits();
+    cpu.freg[3] = (-1.0f64).to_bits();
+
+    // fmadd.d f4, f1, f2, f3
+    run(
+        &mut cpu,
+        (3 << 27) | (0b01 << 25) | (2 << 20) | (1 << 15) | (4 << 7) | 0b1000011,
+    )
+    .unwrap();
+    assert_eq!(f64::from_bits(cpu.freg[4]), -(f64::EPSILON * f64::EPSILON));
+}
+
+/// Double compares and FCLASS.D
+#[test]
+fn double_compare_and_classify() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 1.0f64.to_bits();
+    cpu.freg[2] = f64::NAN.to_bits();
+    run(&mut cpu, op_fp_d(0b10100, 1, 1, 0b010, 5)).unwrap(); // feq.d x5, f1, f1
+    assert_eq!(cpu.reg[5], 1);
+
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_d(0b10100, 2, 1, 0b001, 5)).unwrap(); // flt.d x5, f1, f2
+    assert_eq!(cpu.reg[5], 0);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_NV);
+
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_d(0b11100, 0, 2, 0b001, 5)).unwrap(); // fclass.d x5, f2
+    assert_eq!(cpu.reg[5], 1 << 9);
+}
+
+/* -------------------- NaN-boxing -------------------- */
+
+/// Single-precision results are NaN-boxed, and improperly boxed inputs read as the canonical NaN
+#[test]
+fn nan_boxing() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 1.0f64.to_bits(); // not a valid single
+    cpu.freg[2] = boxed(1.0);
+
+    run(&mut cpu, op_fp_s(0b00000, 2, 1, RNE, 3)).unwrap(); // fadd.s f3, f1, f2
+    assert_eq!(cpu.freg[3], 0xFFFFFFFF_7FC00000);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0);
+
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_s(0b00000, 2, 2, RNE, 3)).unwrap(); // fadd.s f3, f2, f2
+    assert_eq!(cpu.freg[3], 0xFFFFFFFF_40000000);
+}
+
+/// FMV.X.W and FSW use the low bits of the register without checking the box
+#[test]
+fn single_moves_ignore_box() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: fmv.x.w x5, f1
+    // 0x4: fsw f1, 0x100(x0)
+    mem.store_word(0x0, op_fp_s(0b11100, 0, 1, 0b000, 5))
+        .unwrap();
+    mem.store_word(0x4, 0b0001000_00001_00000_010_00000_0100111)
+        .unwrap();
+    cpu.freg[1] = 0x12345678_9ABCDEF0;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[5], 0x9ABCDEF0);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0x9ABCDEF0);
+}
+
+/* -------------------- Conversions -------------------- */
+
+/// FCVT.S.D rounds and may overflow, FCVT.D.S is exact
+#[test]
+fn convert_between_formats() {
+    let fcvt_s_d = |rm: Word, a: f64| {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.freg[1] = a.to_bits();
+        run(&mut cpu, op_fp_s(0b01000, 1, 1, rm, 3)).unwrap();
+        assert_eq!(cpu.freg[3] >> 32, 0xFFFFFFFF);
+        (
+            f32::from_bits(cpu.freg[3] as Word),
+            cpu.csr.read(FFLAGS).unwrap(),
+        )
+    };
+    assert_eq!(fcvt_s_d(RNE, 0.1), (0.1f32, FFLAGS_NX));
+    assert_eq!(fcvt_s_d(RNE, 1e300), (f32::INFINITY, FFLAGS_OF | FFLAGS_NX));
+    assert_eq!(fcvt_s_d(RTZ, 1e300), (f32::MAX, FFLAGS_OF | FFLAGS_NX));
+    assert_eq!(fcvt_s_d(RNE, 1e-300), (0.0, FFLAGS_UF | FFLAGS_NX));
+
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(0.1);
+    run(&mut cpu, op_fp_d(0b01000, 0, 1, RNE, 3)).unwrap(); // fcvt.d.s f3, f1
+    assert_eq!(f64::from_bits(cpu.freg[3]), 0.1f32 as f64);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0);
+
+    // a signaling NaN converts to the canonical NaN and raises invalid
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 0x7FF0000000000001;
+    run(&mut cpu, op_fp_s(0b01000, 1, 1, RNE, 3)).unwrap();
+    assert_eq!(cpu.freg[3], 0xFFFFFFFF_7FC00000);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_NV);
+}
+
+/// Conversions between doubles and 32-bit integers
+#[test]
+fn convert_integers() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.reg[1] = -5i32 as Word;
+    run(&mut cpu, op_fp_d(0b11010, 0, 1, RNE, 3)).unwrap(); // fcvt.d.w f3, x1
+    assert_eq!(f64::from_bits(cpu.freg[3]), -5.0);
+
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_d(0b11010, 1, 1, RNE, 3)).unwrap(); // fcvt.d.wu f3, x1
+    assert_eq!(f64::from_bits(cpu.freg[3]), 4294967291.0);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0); // every 32-bit integer is exact
+
+    cpu.freg[1] = 4294967295.5f64.to_bits();
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_d(0b11000, 1, 1, RTZ, 5)).unwrap(); // fcvt.wu.d x5, f1, rtz
+    assert_eq!(cpu.reg[5], 0xFFFFFFFF);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_NX);
+
+    cpu.pc = 0;
+    run(&mut cpu, op_fp_d(0b11000, 0, 1, RTZ, 5)).unwrap(); // fcvt.w.d x5, f1, rtz
+    assert_eq!(cpu.reg[5], i32::MAX as Word);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_NX | FFLAGS_NV);
+}
+
+/* -------------------- Loads and stores -------------------- */
+
+/// FLD and FSD move all 64 bits and require 8-byte alignment
+#[test]
+fn double_loads_and_stores() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: fld f1, 0x100(x0)
+    // 0x4: fsd f1, 0x108(x0)
+    // 0x8: fld f2, 0x104(x0)
+    mem.store_word(0x0, 0b000100000000_00000_011_00001_0000111)
+        .unwrap();
+    mem.store_word(0x4, 0b0001000_00001_00000_011_01000_0100111)
+        .unwrap();
+    mem.store_word(0x8, 0b000100000100_00000_011_00010_0000111)
+        .unwrap();
+    mem.store_doubleword(0x100, 0x0123456789ABCDEF).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.freg[1], 0x0123456789ABCDEF);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(mem.fetch_doubleword(0x108).unwrap(), 0x0123456789ABCDEF);
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x104))
+    );
+}
@@ -0,0 +1,391 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use rust_risc_v::*;
+
+const ECALL_INSTRUCTION: Word = 0b000000000000_00000_000_00000_1110011;
+const EBREAK_INSTRUCTION: Word = 0b000000000001_00000_000_00000_1110011;
+
+/// Output sink that stays readable after the handler is moved into the cpu
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Handler that doubles a0 and counts its calls
+struct DoublingHandler {
+    calls: Rc<RefCell<u32>>,
+}
+
+impl EcallHandler for DoublingHandler {
+    fn ecall(&mut self, cpu: &mut RISCV, _mem: &mut Memory) -> EcallAction {
+        *self.calls.borrow_mut() += 1;
+        cpu.reg[10] = cpu.reg[10].wrapping_mul(2);
+        EcallAction::Continue
+    }
+}
+
+/* -------------------- Without a handler -------------------- */
+
+/// ECALL without a handler raises an environment call exception
+#[test]
+fn ecall_without_handler_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.pc = 0x10;
+    mem.store_word(0x10, ECALL_INSTRUCTION).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::EnvironmentCallFromMMode, 0))
+    );
+    assert_eq!(cpu.pc, 0x10);
+}
+
+/// EBREAK without a handler raises a breakpoint exception with the pc in tval
+#[test]
+fn ebreak_without_handler_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.pc = 0x20;
+    mem.store_word(0x20, EBREAK_INSTRUCTION).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::Breakpoint, 0x20))
+    );
+}
+
+/// ECALL taken through mtvec reports cause 11
+#[test]
+fn ecall_enters_trap_handler() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MTVEC, 0x100).unwrap();
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.pc, 0x100);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 11);
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x0);
+}
+
+/// ECALL encodings with non-zero rd or rs1 are illegal
+#[test]
+fn ecall_with_nonzero_fields_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let bad_ecall: Word = 0b000000000000_00000_000_00001_1110011;
+    mem.store_word(0x0, bad_ecall).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, bad_ecall))
+    );
+}
+
+/* -------------------- Custom handler -------------------- */
+
+/// A custom handler services the call and execution continues
+#[test]
+fn custom_handler_continues() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let calls: Rc<RefCell<u32>> = Rc::new(RefCell::new(0));
+    cpu.set_ecall_handler(Box::new(DoublingHandler {
+        calls: calls.clone(),
+    }));
+
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    mem.store_word(0x4, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[10] = 21;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[10], 84);
+    assert_eq!(*calls.borrow(), 2);
+    assert_eq!(cpu.pc, 8);
+    assert_eq!(cpu.halt, None);
+}
+
+/// A handler that only implements ecall still traps on EBREAK
+#[test]
+fn custom_handler_default_ebreak_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(DoublingHandler {
+        calls: Rc::new(RefCell::new(0)),
+    }));
+    mem.store_word(0x0, EBREAK_INSTRUCTION).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::Breakpoint, 0x0))
+    );
+}
+
+/* -------------------- Syscall handler -------------------- */
+
+/// exit(code) halts the hart and later cycles do nothing
+#[test]
+fn syscall_exit_halts() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        std::io::sink(),
+    )));
+
+    // ADDI a0, x0, 3
+    // ADDI a7, x0, 93
+    // ECALL
+    // ADDI a0, x0, 7 (never executed)
+    mem.store_word(0x0, 0b000000000011_00000_000_01010_0010011)
+        .unwrap();
+    mem.store_word(0x4, 0b000001011101_00000_000_10001_0010011)
+        .unwrap();
+    mem.store_word(0x8, ECALL_INSTRUCTION).unwrap();
+    mem.store_word(0xC, 0b000000000111_00000_000_01010_0010011)
+        .unwrap();
+
+    for _ in 0..5 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+
+    assert_eq!(cpu.halt, Some(Halt::Exit(3)));
+    assert_eq!(cpu.reg[10], 3);
+    assert_eq!(cpu.pc, 0xC);
+}
+
+/// write(1, buf, len) sends guest memory to the output stream
+#[test]
+fn syscall_write_prints() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let output: SharedOutput = SharedOutput::default();
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        output.clone(),
+    )));
+
+    for (i, byte) in b"Hi!\n".iter().enumerate() {
+        mem.store_byte(0x200 + i, *byte).unwrap();
+    }
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[17] = SYS_WRITE;
+    cpu.reg[10] = 1;
+    cpu.reg[11] = 0x200;
+    cpu.reg[12] = 4;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(output.0.borrow().as_slice(), b"Hi!\n");
+    assert_eq!(cpu.reg[10], 4);
+    assert_eq!(cpu.pc, 4);
+}
+
+/// write to an unsupported file descriptor returns -1
+#[test]
+fn syscall_write_bad_fd_fails() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let output: SharedOutput = SharedOutput::default();
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        output.clone(),
+    )));
+
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[17] = SYS_WRITE;
+    cpu.reg[10] = 7;
+    cpu.reg[11] = 0x200;
+    cpu.reg[12] = 4;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert!(output.0.borrow().is_empty());
+    assert_eq!(cpu.reg[10] as i32, -1);
+}
+
+/// read(0, buf, len) copies host input into guest memory
+#[test]
+fn syscall_read_input() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(&b"abc"[..], std::io::sink())));
+
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[17] = SYS_READ;
+    cpu.reg[10] = 0;
+    cpu.reg[11] = 0x300;
+    cpu.reg[12] = 16;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[10], 3);
+    assert_eq!(mem[0x300], b'a');
+    assert_eq!(mem[0x301], b'b');
+    assert_eq!(mem[0x302], b'c');
+    assert_eq!(mem[0x303], 0);
+}
+
+/// read never allocates more than the memory left after the buffer, whatever the count
+#[test]
+fn syscall_read_huge_count() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(&b"abc"[..], std::io::sink())));
+
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[17] = SYS_READ as u64;
+    cpu.reg[10] = 0;
+    cpu.reg[11] = (MEM_SIZE - 2) as u64;
+    cpu.reg[12] = u64::MAX;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[10], 2);
+    assert_eq!(mem[MEM_SIZE - 1], b'b');
+
+    // a buffer outside of memory fails
+    cpu.pc = 0;
+    cpu.reg[10] = 0;
+    cpu.reg[11] = MEM_SIZE as u64;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[10] as i64, -1);
+}
+
+/// Buffers are checked with the permissions of the calling mode
+#[test]
+fn syscall_buffers_are_checked() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let output: SharedOutput = SharedOutput::default();
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(&b"abc"[..], output.clone())));
+
+    // U-mode may read and execute 0..0x1000, and nothing else
+    cpu.csr.write(PMPADDR0, 0x1FF).unwrap();
+    cpu.csr
+        .write(PMPCFG0, (PMP_NAPOT | PMP_R | PMP_X) as Word)
+        .unwrap();
+    cpu.privilege = Privilege::User;
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    mem.store_byte(0x300, b'!').unwrap();
+
+    let mut syscall = |number: Word, fd: Word, buf: Word| -> Word {
+        cpu.pc = 0;
+        cpu.reg[17] = number;
+        cpu.reg[10] = fd;
+        cpu.reg[11] = buf;
+        cpu.reg[12] = 1;
+        cpu.clock_cycle(&mut mem).unwrap();
+        cpu.reg[10]
+    };
+    assert_eq!(syscall(SYS_WRITE, 1, 0x300), 1);
+    assert_eq!(syscall(SYS_WRITE, 1, 0x2000) as i32, -1);
+    assert_eq!(syscall(SYS_READ, 0, 0x300) as i32, -1);
+    assert_eq!(output.0.borrow().as_slice(), b"!");
+    assert_eq!(mem[0x300], b'!');
+}
+
+/// Unknown syscall numbers are passed on to the guest as an exception
+#[test]
+fn syscall_unknown_number_traps() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        std::io::sink(),
+    )));
+    mem.store_word(0x0, ECALL_INSTRUCTION).unwrap();
+    cpu.reg[17] = 1234;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::EnvironmentCallFromMMode, 0))
+    );
+}
+
+/// EBREAK stops on the instruction and the host can resume past it
+#[test]
+fn syscall_ebreak_hands_control_to_debugger() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        std::io::sink(),
+    )));
+
+    // EBREAK
+    // ADDI x1, x0, 1
+    mem.store_word(0x0, EBREAK_INSTRUCTION).unwrap();
+    mem.store_word(0x4, 0b000000000001_00000_000_00001_0010011)
+        .unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.halt, Some(Halt::Breakpoint));
+    assert_eq!(cpu.pc, 0x0);
+
+    cpu.step(&mut mem); // still halted
+    assert_eq!(cpu.pc, 0x0);
+
+    // the debugger resumes after the breakpoint
+    cpu.resume();
+    assert_eq!(cpu.halt, None);
+    assert_eq!(cpu.pc, 0x4);
+    cpu.step(&mut mem);
+    assert_eq!(cpu.reg[1], 1);
+    assert_eq!(cpu.pc, 0x8);
+}
+
+/// Resuming from a compressed C.EBREAK steps over its two bytes
+#[test]
+fn resume_after_compressed_ebreak() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        std::io::sink(),
+    )));
+
+    // C.EBREAK
+    // ADDI x1, x0, 1
+    let addi: Word = 0b000000000001_00000_000_00001_0010011;
+    mem.store_halfword(0x0, 0x9002).unwrap();
+    mem.store_halfword(0x2, addi as HalfWord).unwrap();
+    mem.store_halfword(0x4, (addi >> 16) as HalfWord).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!((cpu.halt, cpu.pc), (Some(Halt::Breakpoint), 0x0));
+    cpu.resume();
+    cpu.step(&mut mem);
+    assert_eq!(cpu.reg[1], 1);
+    assert_eq!(cpu.pc, 0x6);
+}
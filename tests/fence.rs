@@ -0,0 +1,277 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+fn decode(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+}
+
+/* -------------------- FENCE decoding -------------------- */
+
+/// fence rw, rw
+#[test]
+fn fence_rw_rw_decode() {
+    let fence: Word = 0b0000_0011_0011_00000_000_00000_0001111;
+    assert_eq!(
+        decode(fence),
+        Ok(Instruction::FENCE {
+            fm: 0,
+            pred: Instruction::FENCE_R | Instruction::FENCE_W,
+            succ: Instruction::FENCE_R | Instruction::FENCE_W,
+        })
+    );
+}
+
+/// fence iorw, iorw
+#[test]
+fn fence_iorw_iorw_decode() {
+    let fence: Word = 0b0000_1111_1111_00000_000_00000_0001111;
+    assert_eq!(
+        decode(fence),
+        Ok(Instruction::FENCE {
+            fm: 0,
+            pred: 0b1111,
+            succ: 0b1111,
+        })
+    );
+}
+
+/// fence.tso uses fm = 1000 with pred = succ = rw
+#[test]
+fn fence_tso_decode() {
+    let fence_tso: Word = 0b1000_0011_0011_00000_000_00000_0001111;
+    assert_eq!(
+        decode(fence_tso),
+        Ok(Instruction::FENCE {
+            fm: Instruction::FENCE_TSO_FM,
+            pred: Instruction::FENCE_R | Instruction::FENCE_W,
+            succ: Instruction::FENCE_R | Instruction::FENCE_W,
+        })
+    );
+}
+
+/// fence.i
+#[test]
+fn fence_i_decode() {
+    let fence_i: Word = 0b000000000000_00000_001_00000_0001111;
+    assert_eq!(decode(fence_i), Ok(Instruction::FENCEI));
+}
+
+/// Unknown MISC-MEM funct3 values are illegal
+#[test]
+fn misc_mem_unknown_funct3_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    let instruction: Word = 0b000000000000_00000_011_00000_0001111;
+    mem.store_word(0x0, instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/* -------------------- FENCE execution -------------------- */
+
+/// FENCE does not change any state besides the pc
+#[test]
+fn fence_is_a_no_op() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // fence rw, rw
+    mem.store_word(0x0, 0b0000_0011_0011_00000_000_00000_0001111)
+        .unwrap();
+    // fence.tso
+    mem.store_word(0x4, 0b1000_0011_0011_00000_000_00000_0001111)
+        .unwrap();
+    cpu.reg[1] = 0x55;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.pc, 8);
+    assert_eq!(cpu.reg[1], 0x55);
+    assert_eq!(cpu.reg[0], 0);
+}
+
+/// Reserved rs1/rd fields of FENCE are ignored
+#[test]
+fn fence_reserved_fields_are_ignored() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // fence rw, rw with rs1 = x1 and rd = x2
+    mem.store_word(0x0, 0b0000_0011_0011_00001_000_00010_0001111)
+        .unwrap();
+    cpu.reg[1] = 0x1234;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.pc, 4);
+    assert_eq!(cpu.reg[2], 0);
+}
+
+/// FENCE.I after overwriting an instruction executes the new instruction
+#[test]
+fn fence_i_self_modifying_code() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x00: ADDI x1, x1, 1
+    // 0x04: SW x2, 0(x0)   (overwrite 0x00 with ADDI x1, x1, 16)
+    // 0x08: FENCE.I
+    // 0x0C: JAL x0, -12    (back to 0x00)
+    let addi_1: Word = 0b000000000001_00001_000_00001_0010011;
+    let addi_16: Word = 0b000000010000_00001_000_00001_0010011;
+    mem.store_word(0x00, addi_1).unwrap();
+    mem.store_word(0x04, 0b0000000_00010_00000_010_00000_0100011)
+        .unwrap();
+    mem.store_word(0x08, 0b000000000000_00000_001_00000_0001111)
+        .unwrap();
+    mem.store_word(0x0C, 0b1_1111111010_1_11111111_00000_1101111)
+        .unwrap();
+    cpu.reg[2] = addi_16;
+
+    for _ in 0..5 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+
+    assert_eq!(cpu.reg[1], 17); // 1 from the original, 16 from the rewritten instruction
+    assert_eq!(cpu.pc, 4);
+}
+
+/// Flushing the decode cache does not change the results of execution
+#[test]
+fn flush_decode_cache_keeps_state() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // ADDI x1, x1, 3 ; JAL x0, -4 (loop)
+    mem.store_word(0x0, 0b000000000011_00001_000_00001_0010011)
+        .unwrap();
+    mem.store_word(0x4, 0b1_1111111110_1_11111111_00000_1101111)
+        .unwrap();
+
+    for i in 0..10 {
+        if i % 3 == 0 {
+            cpu.flush_decode_cache();
+        }
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+
+    assert_eq!(cpu.reg[1], 15);
+    assert_eq!(cpu.pc, 0);
+}
+
+/* -------------------- Cache-block management -------------------- */
+
+/// cbo.* share funct3 = 010 and are told apart by imm, prefetch.* are ORI writing x0
+#[test]
+fn cbo_prefetch_decode() {
+    assert_eq!(
+        decode(0b000000000001_00001_010_00000_0001111),
+        Ok(Instruction::CBOCLEAN { rs1: 1 })
+    );
+    assert_eq!(
+        decode(0b000000000010_00001_010_00000_0001111),
+        Ok(Instruction::CBOFLUSH { rs1: 1 })
+    );
+    assert_eq!(
+        decode(0b000000000000_00001_010_00000_0001111),
+        Ok(Instruction::CBOINVAL { rs1: 1 })
+    );
+    assert_eq!(
+        decode(0b000000000100_00001_010_00000_0001111),
+        Ok(Instruction::CBOZERO { rs1: 1 })
+    );
+    assert_eq!(
+        decode(0b000000000011_00001_010_00000_0001111),
+        Err(Exception::IllegalInstruction)
+    );
+
+    // prefetch.r -32(x1) and prefetch.w 64(x1)
+    assert_eq!(
+        decode(0b1111111_00001_00001_110_00000_0010011),
+        Ok(Instruction::PREFETCHR {
+            offset: -32,
+            rs1: 1
+        })
+    );
+    assert_eq!(
+        decode(0b0000010_00011_00001_110_00000_0010011),
+        Ok(Instruction::PREFETCHW { offset: 64, rs1: 1 })
+    );
+    // other ORI x0 forms stay plain hints
+    assert_eq!(
+        decode(0b0000000_00010_00001_110_00000_0010011),
+        Ok(Instruction::ORI {
+            imm: 2,
+            rs1: 1,
+            rd: 0
+        })
+    );
+}
+
+/// cbo.zero clears the whole aligned block containing x[rs1]
+#[test]
+fn cbo_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    mem.store_word(0x0, 0b000000000100_00001_010_00000_0001111) // cbo.zero (x1)
+        .unwrap();
+    for address in (0x100..0x180).step_by(4) {
+        mem.store_word(address, 0xFFFF_FFFF).unwrap();
+    }
+    cpu.reg[1] = 0x125;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(mem.fetch_word(0x0FC), Ok(0));
+    assert_eq!(mem.fetch_word(0x100), Ok(0));
+    assert_eq!(mem.fetch_word(0x13C), Ok(0));
+    assert_eq!(mem.fetch_word(0x140), Ok(0xFFFF_FFFF));
+
+    // the block size is configurable
+    cpu.set_cache_block_size(128).unwrap();
+    assert_eq!(cpu.cache_block_size(), 128);
+    cpu.pc = 0;
+    cpu.reg[1] = 0x17F;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(mem.fetch_word(0x17C), Ok(0));
+}
+
+/// cbo.* on a block outside memory raise a store access fault at x[rs1], prefetches never trap
+#[test]
+fn cbo_faults_prefetch_does_not() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    mem.store_word(0x0, 0b000000000010_00001_010_00000_0001111) // cbo.flush (x1)
+        .unwrap();
+    mem.store_word(0x4, 0b0000000_00001_00001_110_00000_0010011) // prefetch.r 0(x1)
+        .unwrap();
+    cpu.reg[1] = 0x100_0010;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAccessFault, 0x100_0010))
+    );
+    cpu.pc = 4;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 8);
+}
+
+/// Cache blocks must be a power of two bytes no larger than a page, other sizes are rejected
+#[test]
+fn cache_block_size_must_be_a_power_of_two() {
+    let mut cpu: RISCV = RISCV::reset();
+    assert_eq!(
+        cpu.set_cache_block_size(48),
+        Err(ConfigError::CacheBlockSize)
+    );
+    assert_eq!(
+        cpu.set_cache_block_size(2 * PAGE_SIZE),
+        Err(ConfigError::CacheBlockSize)
+    );
+    assert_eq!(cpu.cache_block_size(), DEFAULT_CACHE_BLOCK_SIZE);
+}
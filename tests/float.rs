@@ -0,0 +1,460 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const OP_FP: Word = 0b1010011;
+const RNE: Word = 0b000;
+const RTZ: Word = 0b001;
+const RUP: Word = 0b011;
+const DYN: Word = 0b111;
+
+/// Encodes a single-precision OP-FP instruction
+fn op_fp(funct5: Word, rs2: Word, rs1: Word, rm: Word, rd: Word) -> Word {
+    (funct5 << 27) | (rs2 << 20) | (rs1 << 15) | (rm << 12) | (rd << 7) | OP_FP
+}
+
+/// Encodes a single-precision fused multiply-add style instruction
+fn r4(opcode: Word, rs3: Word, rs2: Word, rs1: Word, rm: Word, rd: Word) -> Word {
+    (rs3 << 27) | (rs2 << 20) | (rs1 << 15) | (rm << 12) | (rd << 7) | opcode
+}
+
+/// NaN-boxes a single-precision value as it sits in a 64-bit f register
+fn boxed(value: f32) -> DoubleWord {
+    0xFFFF_FFFF_0000_0000 | value.to_bits() as DoubleWord
+}
+
+/// Executes a single instruction placed at the current pc
+fn run(cpu: &mut RISCV, instruction: Word) -> Result<(), Trap> {
+    let mut mem: Memory = Memory::new();
+    mem.store_word(cpu.pc as usize, instruction).unwrap();
+    cpu.clock_cycle(&mut mem)
+}
+
+/// Executes `f1 <op> f2` into f3 and returns the result together with the raised flags
+fn binary(funct5: Word, rm: Word, a: f32, b: f32) -> (Word, Word) {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(a);
+    cpu.freg[2] = boxed(b);
+    run(&mut cpu, op_fp(funct5, 2, 1, rm, 3)).unwrap();
+    assert_eq!(cpu.freg[3] >> 32, 0xFFFFFFFF); // single results are NaN-boxed
+    (cpu.freg[3] as Word, cpu.csr.read(FFLAGS).unwrap())
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// Loads, stores and register moves decode from their 32-bit encodings
+#[test]
+fn float_decode() {
+    let decode = |instruction: Word| {
+        Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+    };
+    // flw f1, 8(x2)
+    assert_eq!(
+        decode(0b000000001000_00010_010_00001_0000111),
+        Ok(Instruction::FLW {
+            offset: 8,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // fsw f1, -4(x2)
+    assert_eq!(
+        decode(0b1111111_00001_00010_010_11100_0100111),
+        Ok(Instruction::FSW {
+            offset: -4,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+    // fmv.x.w with a non-zero rs2 is illegal
+    assert_eq!(
+        decode(op_fp(0b11100, 1, 1, 0, 3)),
+        Err(Exception::IllegalInstruction)
+    );
+    // the quad-precision fmt is not supported
+    assert_eq!(
+        decode(op_fp(0b00000, 2, 1, RNE, 3) | (0b11 << 25)),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/// C.FLW, C.FSW, C.FLWSP and C.FSWSP expand to FLW / FSW
+#[test]
+fn compressed_float_decode() {
+    // c.flw fa0, 4(a1)
+    assert_eq!(
+        Instruction::parse_compressed(0x61C8),
+        Ok(Instruction::FLW {
+            offset: 4,
+            rs1: 11,
+            rd: 10
+        })
+    );
+    // c.fsw fa0, 4(a1)
+    assert_eq!(
+        Instruction::parse_compressed(0xE1C8),
+        Ok(Instruction::FSW {
+            offset: 4,
+            rs1: 11,
+            rs2: 10
+        })
+    );
+    // c.flwsp f0, 0(sp) is legal, unlike c.lwsp x0
+    assert_eq!(
+        Instruction::parse_compressed(0x6002),
+        Ok(Instruction::FLW {
+            offset: 0,
+            rs1: 2,
+            rd: 0
+        })
+    );
+    // c.fswsp fa0, 12(sp)
+    assert_eq!(
+        Instruction::parse_compressed(0xE62A),
+        Ok(Instruction::FSW {
+            offset: 12,
+            rs1: 2,
+            rs2: 10
+        })
+    );
+}
+
+/* -------------------- Arithmetic -------------------- */
+
+/// Exact results raise no flags
+#[test]
+fn exact_arithmetic() {
+    assert_eq!(binary(0b00000, RNE, 1.5, 2.25), (3.75f32.to_bits(), 0));
+    assert_eq!(binary(0b00001, RNE, 1.5, 2.25), ((-0.75f32).to_bits(), 0));
+    assert_eq!(binary(0b00010, RNE, 1.5, 2.25), (3.375f32.to_bits(), 0));
+    assert_eq!(binary(0b00011, RNE, 3.0, 0.5), (6.0f32.to_bits(), 0));
+
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(2.25);
+    run(&mut cpu, op_fp(0b01011, 0, 1, RNE, 3)).unwrap(); // fsqrt.s
+    assert_eq!(cpu.freg[3], boxed(1.5));
+}
+
+/// The static rounding mode controls how inexact results are rounded
+#[test]
+fn static_rounding_modes() {
+    let half_ulp: f32 = f32::EPSILON / 2.0;
+    assert_eq!(
+        binary(0b00000, RNE, 1.0, half_ulp),
+        (1.0f32.to_bits(), FFLAGS_NX)
+    );
+    assert_eq!(
+        binary(0b00000, RTZ, 1.0, half_ulp),
+        (1.0f32.to_bits(), FFLAGS_NX)
+    );
+    assert_eq!(
+        binary(0b00000, RUP, 1.0, half_ulp),
+        ((1.0 + f32::EPSILON).to_bits(), FFLAGS_NX)
+    );
+    // 1/3 rounds down towards zero and to nearest
+    let (third, flags) = binary(0b00011, RTZ, 1.0, 3.0);
+    assert_eq!(third, 0x3EAAAAAA);
+    assert_eq!(flags, FFLAGS_NX);
+}
+
+/// rm = 0b111 uses frm; a reserved frm makes the instruction illegal
+#[test]
+fn dynamic_rounding_mode() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(1.0);
+    cpu.freg[2] = boxed(f32::EPSILON / 2.0);
+    cpu.csr.write(FRM, RUP).unwrap();
+    run(&mut cpu, op_fp(0b00000, 2, 1, DYN, 3)).unwrap();
+    assert_eq!(cpu.freg[3], boxed(1.0 + f32::EPSILON));
+
+    let fadd_dyn: Word = op_fp(0b00000, 2, 1, DYN, 3);
+    cpu.pc = 0;
+    cpu.csr.write(FRM, 0b101).unwrap();
+    assert_eq!(
+        run(&mut cpu, fadd_dyn),
+        Err(Trap::new(Exception::IllegalInstruction, fadd_dyn))
+    );
+}
+
+/// A reserved static rounding mode is illegal
+#[test]
+fn reserved_static_rounding_mode() {
+    let mut cpu: RISCV = RISCV::reset();
+    let fadd_reserved: Word = op_fp(0b00000, 2, 1, 0b110, 3);
+    assert_eq!(
+        run(&mut cpu, fadd_reserved),
+        Err(Trap::new(Exception::IllegalInstruction, fadd_reserved))
+    );
+}
+
+/// Division by zero, invalid operations, overflow and underflow raise their flags
+#[test]
+fn exception_flags() {
+    assert_eq!(
+        binary(0b00011, RNE, 1.0, 0.0),
+        (f32::INFINITY.to_bits(), FFLAGS_DZ)
+    );
+    // 0 / 0 and inf - inf produce the canonical NaN
+    assert_eq!(binary(0b00011, RNE, 0.0, 0.0), (0x7FC00000, FFLAGS_NV));
+    assert_eq!(
+        binary(0b00001, RNE, f32::INFINITY, f32::INFINITY),
+        (0x7FC00000, FFLAGS_NV)
+    );
+    assert_eq!(
+        binary(0b00010, RNE, f32::MAX, 2.0),
+        (f32::INFINITY.to_bits(), FFLAGS_OF | FFLAGS_NX)
+    );
+    // overflow rounds to the largest finite value when rounding towards zero
+    assert_eq!(
+        binary(0b00010, RTZ, f32::MAX, 2.0),
+        (f32::MAX.to_bits(), FFLAGS_OF | FFLAGS_NX)
+    );
+    // half of the smallest subnormal ties to even, i.e. zero
+    let min_subnormal: f32 = f32::from_bits(1);
+    assert_eq!(
+        binary(0b00010, RNE, min_subnormal, 0.5),
+        (0, FFLAGS_UF | FFLAGS_NX)
+    );
+    // an exact subnormal result does not underflow
+    assert_eq!(
+        binary(0b00010, RNE, f32::MIN_POSITIVE, 0.5),
+        ((f32::MIN_POSITIVE / 2.0).to_bits(), 0)
+    );
+}
+
+/// NaN inputs produce the canonical NaN; only signaling NaNs raise NV
+#[test]
+fn nan_propagation() {
+    let signaling_nan: f32 = f32::from_bits(0x7F800001);
+    assert_eq!(binary(0b00000, RNE, f32::NAN, 1.0), (0x7FC00000, 0));
+    assert_eq!(
+        binary(0b00000, RNE, signaling_nan, 1.0),
+        (0x7FC00000, FFLAGS_NV)
+    );
+}
+
+/// Fused multiply-add rounds only once
+#[test]
+fn fused_multiply_add() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(1.0 + f32::EPSILON);
+    cpu.freg[2] = boxed(1.0 - f32::EPSILON);
+    cpu.freg[3] = boxed(-1.0);
+
+    // fmadd.s: (1 + e)(1 - e) - 1 = -e^2, which a separate multiply would round to 0
+    run(&mut cpu, r4(0b1000011, 3, 2, 1, RNE, 4)).unwrap();
+    assert_eq!(cpu.freg[4], boxed(-(f32::EPSILON * f32::EPSILON)));
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0);
+
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(2.0);
+    cpu.freg[2] = boxed(3.0);
+    cpu.freg[3] = boxed(1.0);
+    let fused = |cpu: &mut RISCV, opcode: Word| {
+        cpu.pc = 0;
+        run(cpu, r4(opcode, 3, 2, 1, RNE, 4)).unwrap();
+        f32::from_bits(cpu.freg[4] as Word)
+    };
+    assert_eq!(fused(&mut cpu, 0b1000011), 7.0); // fmadd.s
+    assert_eq!(fused(&mut cpu, 0b1000111), 5.0); // fmsub.s
+    assert_eq!(fused(&mut cpu, 0b1001011), -5.0); // fnmsub.s
+    assert_eq!(fused(&mut cpu, 0b1001111), -7.0); // fnmadd.s
+}
+
+/// 0 * inf + c is invalid even when c is a quiet NaN
+#[test]
+fn fused_multiply_add_invalid() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(0.0);
+    cpu.freg[2] = boxed(f32::INFINITY);
+    cpu.freg[3] = boxed(f32::NAN);
+    run(&mut cpu, r4(0b1000011, 3, 2, 1, RNE, 4)).unwrap();
+    assert_eq!(cpu.freg[4], boxed(f32::NAN));
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_NV);
+}
+
+/* -------------------- Sign injection, min/max, compares -------------------- */
+
+/// FSGNJ, FSGNJN and FSGNJX only touch the sign bit
+#[test]
+fn sign_injection() {
+    assert_eq!(binary(0b00100, 0b000, 2.0, -1.0).0, (-2.0f32).to_bits());
+    assert_eq!(binary(0b00100, 0b001, 2.0, -1.0).0, 2.0f32.to_bits());
+    assert_eq!(binary(0b00100, 0b010, -2.0, -1.0).0, 2.0f32.to_bits());
+    // sign injection does not canonicalize NaNs
+    assert_eq!(
+        binary(0b00100, 0b000, f32::from_bits(0x7F800001), -1.0).0,
+        0xFF800001
+    );
+}
+
+/// FMIN/FMAX order -0 before +0 and return the other operand for a single NaN
+#[test]
+fn min_max() {
+    assert_eq!(binary(0b00101, 0b000, -0.0, 0.0).0, (-0.0f32).to_bits());
+    assert_eq!(binary(0b00101, 0b001, -0.0, 0.0).0, 0.0f32.to_bits());
+    assert_eq!(binary(0b00101, 0b000, f32::NAN, 1.0), (1.0f32.to_bits(), 0));
+    assert_eq!(
+        binary(0b00101, 0b001, f32::from_bits(0x7F800001), 1.0),
+        (1.0f32.to_bits(), FFLAGS_NV)
+    );
+    assert_eq!(binary(0b00101, 0b000, f32::NAN, f32::NAN).0, 0x7FC00000);
+}
+
+/// FEQ is quiet, FLT and FLE signal on any NaN
+#[test]
+fn compares() {
+    let compare = |funct3: Word, a: f32, b: f32| {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.freg[1] = boxed(a);
+        cpu.freg[2] = boxed(b);
+        run(&mut cpu, op_fp(0b10100, 2, 1, funct3, 5)).unwrap();
+        (cpu.reg[5], cpu.csr.read(FFLAGS).unwrap())
+    };
+    assert_eq!(compare(0b010, -0.0, 0.0), (1, 0)); // feq
+    assert_eq!(compare(0b001, 1.0, 2.0), (1, 0)); // flt
+    assert_eq!(compare(0b000, 2.0, 2.0), (1, 0)); // fle
+    assert_eq!(compare(0b010, f32::NAN, 1.0), (0, 0));
+    assert_eq!(compare(0b001, f32::NAN, 1.0), (0, FFLAGS_NV));
+    assert_eq!(compare(0b000, 1.0, f32::NAN), (0, FFLAGS_NV));
+}
+
+/// FCLASS sets exactly one bit describing the operand
+#[test]
+fn classify() {
+    let fclass = |a: f32| {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.freg[1] = boxed(a);
+        run(&mut cpu, op_fp(0b11100, 0, 1, 0b001, 5)).unwrap();
+        cpu.reg[5]
+    };
+    assert_eq!(fclass(f32::NEG_INFINITY), 1 << 0);
+    assert_eq!(fclass(-1.0), 1 << 1);
+    assert_eq!(fclass(-f32::from_bits(1)), 1 << 2);
+    assert_eq!(fclass(-0.0), 1 << 3);
+    assert_eq!(fclass(0.0), 1 << 4);
+    assert_eq!(fclass(f32::from_bits(1)), 1 << 5);
+    assert_eq!(fclass(1.0), 1 << 6);
+    assert_eq!(fclass(f32::INFINITY), 1 << 7);
+    assert_eq!(fclass(f32::from_bits(0x7F800001)), 1 << 8);
+    assert_eq!(fclass(f32::NAN), 1 << 9);
+}
+
+/* -------------------- Conversions and moves -------------------- */
+
+/// FCVT.W[U].S rounds, and saturates out-of-range inputs with NV
+#[test]
+fn convert_to_integer() {
+    let fcvt = |rs2: Word, rm: Word, a: f32| {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.freg[1] = boxed(a);
+        run(&mut cpu, op_fp(0b11000, rs2, 1, rm, 5)).unwrap();
+        (cpu.reg[5], cpu.csr.read(FFLAGS).unwrap())
+    };
+    assert_eq!(fcvt(0, RNE, 2.5), (2, FFLAGS_NX));
+    assert_eq!(fcvt(0, RUP, 2.5), (3, FFLAGS_NX));
+    assert_eq!(fcvt(0, RTZ, -2.5), (-2i32 as Word, FFLAGS_NX));
+    assert_eq!(fcvt(0, RNE, 3e9), (i32::MAX as Word, FFLAGS_NV));
+    assert_eq!(fcvt(0, RNE, -3e9), (i32::MIN as Word, FFLAGS_NV));
+    assert_eq!(fcvt(0, RNE, f32::NAN), (i32::MAX as Word, FFLAGS_NV));
+    assert_eq!(fcvt(1, RNE, 3e9), (3_000_000_000, 0));
+    assert_eq!(fcvt(1, RNE, -1.5), (0, FFLAGS_NV));
+    // -0.4 rounds to 0 and is in range for the unsigned conversion
+    assert_eq!(fcvt(1, RNE, -0.4), (0, FFLAGS_NX));
+}
+
+/// FCVT.S.W[U] rounds integers that do not fit the significand
+#[test]
+fn convert_from_integer() {
+    let fcvt = |rs2: Word, value: Word| {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.reg[1] = value;
+        run(&mut cpu, op_fp(0b11010, rs2, 1, RNE, 5)).unwrap();
+        (
+            f32::from_bits(cpu.freg[5] as Word),
+            cpu.csr.read(FFLAGS).unwrap(),
+        )
+    };
+    assert_eq!(fcvt(0, -7i32 as Word), (-7.0, 0));
+    assert_eq!(fcvt(1, -7i32 as Word), (4294967296.0, FFLAGS_NX));
+    assert_eq!(fcvt(0, 16777217), (16777216.0, FFLAGS_NX));
+}
+
+/// FMV.X.W and FMV.W.X copy raw bits, FLW and FSW move words to and from memory
+#[test]
+fn moves_loads_and_stores() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x0: fmv.w.x f1, x1
+    // 0x4: fsw f1, 0x100(x0)
+    // 0x8: flw f2, 0x100(x0)
+    // 0xC: fmv.x.w x2, f2
+    mem.store_word(0x0, op_fp(0b11110, 0, 1, 0, 1)).unwrap();
+    mem.store_word(0x4, 0b0001000_00001_00000_010_00000_0100111)
+        .unwrap();
+    mem.store_word(0x8, 0b000100000000_00000_010_00010_0000111)
+        .unwrap();
+    mem.store_word(0xC, op_fp(0b11100, 0, 2, 0, 2)).unwrap();
+    cpu.reg[1] = 0x7F800001; // a signaling NaN is moved unchanged
+
+    for _ in 0..4 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0x7F800001);
+    assert_eq!(cpu.reg[2], 0x7F800001);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0);
+}
+
+/* -------------------- fcsr and mstatus.FS -------------------- */
+
+/// fflags and frm are views of fcsr
+#[test]
+fn fcsr_views() {
+    let mut cpu: RISCV = RISCV::reset();
+
+    cpu.csr.write(FCSR, 0xFFFFFFFF).unwrap();
+    assert_eq!(cpu.csr.read(FCSR).unwrap(), 0xFF);
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), 0b11111);
+    assert_eq!(cpu.csr.read(FRM).unwrap(), 0b111);
+
+    cpu.csr.write(FFLAGS, 0).unwrap();
+    cpu.csr.write(FRM, RTZ).unwrap();
+    assert_eq!(cpu.csr.read(FCSR).unwrap(), RTZ << 5);
+}
+
+/// Flags accrue across instructions and mark the FP state dirty
+#[test]
+fn flags_accrue_and_dirty_fs() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap() & MSTATUS_FS, FS_INITIAL);
+
+    cpu.freg[0] = boxed(0.0);
+    cpu.freg[1] = boxed(1.0);
+    mem.store_word(0x0, op_fp(0b00011, 0, 1, RNE, 2)).unwrap(); // fdiv.s f2, f1, f0
+    mem.store_word(0x4, op_fp(0b00011, 0, 0, RNE, 3)).unwrap(); // fdiv.s f3, f0, f0
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.csr.read(FFLAGS).unwrap(), FFLAGS_DZ | FFLAGS_NV);
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & MSTATUS_FS, FS_DIRTY);
+    assert_ne!(mstatus & MSTATUS_SD, 0);
+}
+
+/// With mstatus.FS = Off, FP instructions and FP CSRs are illegal
+#[test]
+fn fs_off_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    cpu.csr.write(MSTATUS, mstatus & !MSTATUS_FS).unwrap();
+
+    let fadd: Word = op_fp(0b00000, 2, 1, RNE, 3);
+    assert_eq!(
+        run(&mut cpu, fadd),
+        Err(Trap::new(Exception::IllegalInstruction, fadd))
+    );
+    assert_eq!(cpu.csr.read(FCSR), Err(Exception::IllegalInstruction));
+    assert_eq!(cpu.csr.write(FRM, 0), Err(Exception::IllegalInstruction));
+}
@@ -0,0 +1,332 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const RNE: Word = 0b000;
+const RTZ: Word = 0b001;
+const RDN: Word = 0b010;
+const RUP: Word = 0b011;
+
+const FMT_S: Word = 0b00;
+const FMT_D: Word = 0b01;
+const FMT_H: Word = 0b10;
+
+const ONE: u64 = 0x3C00; // 1.0 in half precision
+const ONE_AND_HALF: u64 = 0x3E00;
+const TWO: u64 = 0x4000;
+const HALF_NAN: u64 = 0x7E00; // canonical NaN
+
+/// Encodes an OP-FP instruction of format `fmt`
+fn op_fp(funct5: Word, fmt: Word, rs2: Word, rs1: Word, rm: Word, rd: Word) -> Word {
+    (funct5 << 27) | (fmt << 25) | (rs2 << 20) | (rs1 << 15) | (rm << 12) | (rd << 7) | 0b1010011
+}
+
+/// NaN-boxes a half-precision value into a 64-bit register
+fn boxed(bits: u64) -> DoubleWord {
+    0xFFFF_FFFF_FFFF_0000 | bits
+}
+
+/// Executes a single instruction placed at the current pc
+fn run<X: Xlen>(cpu: &mut RISCV<X>, instruction: Word) -> Result<(), Trap<X>> {
+    let mut mem: Memory = Memory::new();
+    mem.store_word(cpu.pc.to_usize(), instruction).unwrap();
+    cpu.clock_cycle(&mut mem)
+}
+
+fn decode(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+}
+
+/* -------------------- Zfh -------------------- */
+
+/// FLH, FSH and the .H forms decode next to their single and double counterparts
+#[test]
+fn half_decode() {
+    // flh f1, 8(x2)
+    assert_eq!(
+        decode(0b000000001000_00010_001_00001_0000111),
+        Ok(Instruction::FLH {
+            offset: 8,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // fsh f1, 8(x2)
+    assert_eq!(
+        decode(0b0000000_00001_00010_001_01000_0100111),
+        Ok(Instruction::FSH {
+            offset: 8,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+    assert_eq!(
+        decode(op_fp(0b00000, FMT_H, 2, 1, RNE, 3)),
+        Ok(Instruction::FADDH {
+            rs1: 1,
+            rs2: 2,
+            rd: 3,
+            rm: RNE
+        })
+    );
+    // fcvt.s.h and fcvt.h.d
+    assert_eq!(
+        decode(op_fp(0b01000, FMT_S, FMT_H, 1, RNE, 3)),
+        Ok(Instruction::FCVTSH {
+            rs1: 1,
+            rd: 3,
+            rm: RNE
+        })
+    );
+    assert_eq!(
+        decode(op_fp(0b01000, FMT_H, FMT_D, 1, RNE, 3)),
+        Ok(Instruction::FCVTHD {
+            rs1: 1,
+            rd: 3,
+            rm: RNE
+        })
+    );
+    // fcvt.l.h only exists on RV64
+    assert_eq!(
+        decode(op_fp(0b11000, FMT_H, 0b00010, 1, RTZ, 3)),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/// .H arithmetic rounds to 11 significant bits and NaN-boxes its result
+#[test]
+fn half_arithmetic() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(ONE);
+    cpu.freg[2] = boxed(ONE_AND_HALF);
+
+    run(&mut cpu, op_fp(0b00000, FMT_H, 2, 1, RNE, 3)).unwrap(); // fadd.h
+    assert_eq!(cpu.freg[3], boxed(0x4100)); // 2.5
+    run(&mut cpu, op_fp(0b00011, FMT_H, 2, 1, RNE, 3)).unwrap(); // fdiv.h
+    assert_eq!(cpu.freg[3], boxed(0x3955)); // 0.6667, rounded
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NX));
+
+    // 65504 + 32 rounds up to infinity
+    cpu.freg[1] = boxed(0x7BFF);
+    cpu.freg[2] = boxed(0x5000);
+    run(&mut cpu, op_fp(0b00000, FMT_H, 2, 1, RNE, 3)).unwrap();
+    assert_eq!(cpu.freg[3], boxed(0x7C00));
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NX | FFLAGS_OF));
+
+    // an improperly boxed operand reads as the canonical NaN
+    cpu.freg[1] = ONE;
+    cpu.freg[2] = boxed(ONE);
+    run(&mut cpu, op_fp(0b00010, FMT_H, 2, 1, RNE, 3)).unwrap(); // fmul.h
+    assert_eq!(cpu.freg[3], boxed(HALF_NAN));
+}
+
+/// fmadd.h rounds once, compares and classify see half-precision values
+#[test]
+fn half_fused_and_compare() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = boxed(TWO);
+    cpu.freg[2] = boxed(ONE_AND_HALF);
+    cpu.freg[3] = boxed(ONE);
+    // fmadd.h f4, f1, f2, f3
+    run(
+        &mut cpu,
+        (3 << 27) | (FMT_H << 25) | (2 << 20) | (1 << 15) | (4 << 7) | 0b1000011,
+    )
+    .unwrap();
+    assert_eq!(cpu.freg[4], boxed(0x4400)); // 4.0
+
+    run(&mut cpu, op_fp(0b10100, FMT_H, 2, 1, 0b001, 5)).unwrap(); // flt.h
+    assert_eq!(cpu.reg[5], 0);
+    run(&mut cpu, op_fp(0b10100, FMT_H, 1, 2, 0b000, 5)).unwrap(); // fle.h
+    assert_eq!(cpu.reg[5], 1);
+    cpu.freg[6] = boxed(0x0001); // smallest subnormal
+    run(&mut cpu, op_fp(0b11100, FMT_H, 0, 6, 0b001, 5)).unwrap(); // fclass.h
+    assert_eq!(cpu.reg[5], 1 << 5);
+}
+
+/// Conversions between half and the other formats and integers
+#[test]
+fn half_conversions() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    cpu.freg[1] = boxed(0xC500); // -5.0
+
+    run(&mut cpu, op_fp(0b01000, FMT_D, FMT_H, 1, RNE, 2)).unwrap(); // fcvt.d.h
+    assert_eq!(f64::from_bits(cpu.freg[2]), -5.0);
+    run(&mut cpu, op_fp(0b01000, FMT_S, FMT_H, 1, RNE, 2)).unwrap(); // fcvt.s.h
+    assert_eq!(
+        cpu.freg[2],
+        0xFFFF_FFFF_0000_0000 | (-5.0f32).to_bits() as u64
+    );
+
+    cpu.freg[3] = 1.0e6f64.to_bits();
+    run(&mut cpu, op_fp(0b01000, FMT_H, FMT_D, 3, RNE, 4)).unwrap(); // fcvt.h.d
+    assert_eq!(cpu.freg[4], boxed(0x7C00)); // too large for half precision
+
+    run(&mut cpu, op_fp(0b11000, FMT_H, 0b00000, 1, RTZ, 5)).unwrap(); // fcvt.w.h
+    assert_eq!(cpu.reg[5], -5i64 as u64);
+    run(&mut cpu, op_fp(0b11000, FMT_H, 0b00011, 1, RTZ, 5)).unwrap(); // fcvt.lu.h
+    assert_eq!(cpu.reg[5], 0);
+    assert_eq!(
+        cpu.csr.read(FFLAGS),
+        Ok((FFLAGS_NX | FFLAGS_OF | FFLAGS_NV) as u64)
+    );
+
+    cpu.reg[6] = 3;
+    run(&mut cpu, op_fp(0b11010, FMT_H, 0b00010, 6, RNE, 7)).unwrap(); // fcvt.h.l
+    assert_eq!(cpu.freg[7], boxed(0x4200));
+}
+
+/// fmv.x.h sign-extends the raw bits, fmv.h.x boxes the low 16 bits of x[rs1]
+#[test]
+fn half_moves_loads_and_stores() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.freg[1] = boxed(0xBC00);
+    run(&mut cpu, op_fp(0b11100, FMT_H, 0, 1, 0b000, 2)).unwrap();
+    assert_eq!(cpu.reg[2], 0xFFFF_BC00);
+    cpu.reg[3] = 0x1234_3C00;
+    run(&mut cpu, op_fp(0b11110, FMT_H, 0, 3, 0b000, 4)).unwrap();
+    assert_eq!(cpu.freg[4], boxed(ONE));
+
+    // fsh f4, 0x100(x0) ; flh f5, 0x100(x0)
+    mem.store_word(0x0, 0b0001000_00100_00000_001_00000_0100111)
+        .unwrap();
+    mem.store_word(0x4, 0b000100000000_00000_001_00101_0000111)
+        .unwrap();
+    mem.store_word(0x100, 0xAAAA_AAAA).unwrap();
+    cpu.pc = 0;
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(mem.fetch_word(0x100), Ok(0xAAAA_3C00));
+    assert_eq!(cpu.freg[5], boxed(ONE));
+}
+
+/* -------------------- Zfa -------------------- */
+
+/// fli loads the constant table, rounded into each format
+#[test]
+fn fli() {
+    let mut cpu: RISCV = RISCV::reset();
+    let fli = |fmt: Word, index: Word, rd: Word| op_fp(0b11110, fmt, 0b00001, index, 0, rd);
+    assert_eq!(
+        decode(fli(FMT_D, 7, 1)),
+        Ok(Instruction::FLID { index: 7, rd: 1 })
+    );
+
+    run(&mut cpu, fli(FMT_D, 0, 1)).unwrap();
+    assert_eq!(f64::from_bits(cpu.freg[1]), -1.0);
+    run(&mut cpu, fli(FMT_D, 1, 1)).unwrap();
+    assert_eq!(f64::from_bits(cpu.freg[1]), f64::MIN_POSITIVE);
+    run(&mut cpu, fli(FMT_S, 9, 1)).unwrap();
+    assert_eq!(
+        cpu.freg[1],
+        0xFFFF_FFFF_0000_0000 | 0.3125f32.to_bits() as u64
+    );
+    run(&mut cpu, fli(FMT_S, 29, 1)).unwrap();
+    assert_eq!(cpu.freg[1] as Word, 65536.0f32.to_bits());
+    run(&mut cpu, fli(FMT_S, 31, 1)).unwrap();
+    assert_eq!(cpu.freg[1] as Word, 0x7FC0_0000);
+
+    run(&mut cpu, fli(FMT_H, 2, 1)).unwrap();
+    assert_eq!(cpu.freg[1], boxed(0x0100)); // 2^-16 is subnormal
+    run(&mut cpu, fli(FMT_H, 29, 1)).unwrap();
+    assert_eq!(cpu.freg[1], boxed(0x7C00)); // 2^16 overflows
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(0));
+}
+
+/// fminm / fmaxm return NaN when either operand is NaN, fmin / fmax the other operand
+#[test]
+fn fminm_fmaxm() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 2.0f64.to_bits();
+    cpu.freg[2] = f64::NAN.to_bits();
+
+    run(&mut cpu, op_fp(0b00101, FMT_D, 2, 1, 0b000, 3)).unwrap(); // fmin.d
+    assert_eq!(f64::from_bits(cpu.freg[3]), 2.0);
+    run(&mut cpu, op_fp(0b00101, FMT_D, 2, 1, 0b010, 3)).unwrap(); // fminm.d
+    assert_eq!(cpu.freg[3], 0x7FF8_0000_0000_0000);
+
+    cpu.freg[2] = (-0.0f64).to_bits();
+    cpu.freg[1] = 0.0f64.to_bits();
+    run(&mut cpu, op_fp(0b00101, FMT_D, 2, 1, 0b011, 3)).unwrap(); // fmaxm.d
+    assert_eq!(cpu.freg[3], 0.0f64.to_bits());
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(0));
+}
+
+/// fround rounds to an integer in the same format, only fround.nx raises inexact
+#[test]
+fn fround() {
+    let mut cpu: RISCV = RISCV::reset();
+    let fround = |rm: Word| op_fp(0b01000, FMT_D, 0b00100, 1, rm, 2);
+    let froundnx = |rm: Word| op_fp(0b01000, FMT_D, 0b00101, 1, rm, 2);
+    cpu.freg[1] = (-2.5f64).to_bits();
+
+    for (rm, expected) in [(RNE, -2.0), (RTZ, -2.0), (RDN, -3.0), (RUP, -2.0)] {
+        run(&mut cpu, fround(rm)).unwrap();
+        assert_eq!(f64::from_bits(cpu.freg[2]), expected);
+    }
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(0));
+    run(&mut cpu, froundnx(RNE)).unwrap();
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NX));
+
+    // values below one round to a signed zero, huge values are already integers
+    cpu.freg[1] = (-0.25f64).to_bits();
+    run(&mut cpu, fround(RNE)).unwrap();
+    assert_eq!(cpu.freg[2], (-0.0f64).to_bits());
+    cpu.freg[1] = 1.0e300f64.to_bits();
+    run(&mut cpu, fround(RNE)).unwrap();
+    assert_eq!(f64::from_bits(cpu.freg[2]), 1.0e300);
+
+    // half precision
+    cpu.freg[1] = boxed(ONE_AND_HALF);
+    run(&mut cpu, op_fp(0b01000, FMT_H, 0b00100, 1, RNE, 2)).unwrap();
+    assert_eq!(cpu.freg[2], boxed(TWO));
+}
+
+/// fcvtmod.w.d keeps the low 32 bits of the truncated integer
+#[test]
+fn fcvtmod() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let fcvtmod: Word = op_fp(0b11000, FMT_D, 0b01000, 1, RTZ, 2);
+    assert_eq!(
+        decode(op_fp(0b11000, FMT_D, 0b01000, 1, RNE, 2)),
+        Err(Exception::IllegalInstruction)
+    );
+
+    cpu.freg[1] = (-7.9f64).to_bits();
+    run(&mut cpu, fcvtmod).unwrap();
+    assert_eq!(cpu.reg[2], -7i64 as u64);
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NX as u64));
+
+    // 2^32 + 0x8000_0005 wraps to a negative word, sign-extended on RV64
+    cpu.csr.write(FFLAGS, 0).unwrap();
+    cpu.freg[1] = ((1u64 << 32) as f64 + 0x8000_0005u64 as f64).to_bits();
+    run(&mut cpu, fcvtmod).unwrap();
+    assert_eq!(cpu.reg[2], 0xFFFF_FFFF_8000_0005);
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NV as u64));
+
+    cpu.freg[1] = f64::INFINITY.to_bits();
+    run(&mut cpu, fcvtmod).unwrap();
+    assert_eq!(cpu.reg[2], 0);
+}
+
+/// fleq / fltq only raise invalid for signaling NaNs
+#[test]
+fn quiet_compares() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.freg[1] = 1.0f64.to_bits();
+    cpu.freg[2] = f64::NAN.to_bits();
+
+    run(&mut cpu, op_fp(0b10100, FMT_D, 2, 1, 0b001, 3)).unwrap(); // flt.d
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(FFLAGS_NV));
+    cpu.csr.write(FFLAGS, 0).unwrap();
+    run(&mut cpu, op_fp(0b10100, FMT_D, 2, 1, 0b101, 3)).unwrap(); // fltq.d
+    assert_eq!(cpu.reg[3], 0);
+    assert_eq!(cpu.csr.read(FFLAGS), Ok(0));
+
+    cpu.freg[2] = 1.0f64.to_bits();
+    run(&mut cpu, op_fp(0b10100, FMT_D, 2, 1, 0b100, 3)).unwrap(); // fleq.d
+    assert_eq!(cpu.reg[3], 1);
+    run(&mut cpu, op_fp(0b10100, FMT_D, 2, 1, 0b101, 3)).unwrap(); // fltq.d
+    assert_eq!(cpu.reg[3], 0);
+}
@@ -0,0 +1,141 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+fn decode(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+}
+
+/* -------------------- Zicond -------------------- */
+
+/// czero.eqz / czero.nez decode from OPRR with funct7 = 0000111
+#[test]
+fn czero_decode() {
+    assert_eq!(
+        decode(0b0000111_00010_00001_101_00011_0110011),
+        Ok(Instruction::CZEROEQZ {
+            rs1: 1,
+            rs2: 2,
+            rd: 3
+        })
+    );
+    assert_eq!(
+        decode(0b0000111_00010_00001_111_00011_0110011),
+        Ok(Instruction::CZERONEZ {
+            rs1: 1,
+            rs2: 2,
+            rd: 3
+        })
+    );
+    assert_eq!(
+        decode(0b0000111_00010_00001_000_00011_0110011),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/// czero.eqz zeroes rd when rs2 is zero, czero.nez when it is not
+#[test]
+fn czero_execute() {
+    let mut mem: Memory = Memory::new();
+    // czero.eqz x3, x1, x2 ; czero.nez x4, x1, x2
+    mem.store_word(0x0, 0b0000111_00010_00001_101_00011_0110011)
+        .unwrap();
+    mem.store_word(0x4, 0b0000111_00010_00001_111_00100_0110011)
+        .unwrap();
+
+    for (condition, eqz, nez) in [(0, 0, 42), (7, 42, 0)] {
+        let mut cpu: RISCV = RISCV::reset();
+        cpu.reg[1] = 42;
+        cpu.reg[2] = condition;
+        cpu.clock_cycle(&mut mem).unwrap();
+        cpu.clock_cycle(&mut mem).unwrap();
+        assert_eq!((cpu.reg[3], cpu.reg[4]), (eqz, nez));
+    }
+}
+
+/* -------------------- Zihintpause -------------------- */
+
+/// pause is fence w, 0 and only that exact encoding
+#[test]
+fn pause_decode() {
+    assert_eq!(
+        decode(0b0000_0001_0000_00000_000_00000_0001111),
+        Ok(Instruction::PAUSE)
+    );
+    // fence w, r still orders memory
+    assert_eq!(
+        decode(0b0000_0001_0010_00000_000_00000_0001111),
+        Ok(Instruction::FENCE {
+            fm: 0,
+            pred: Instruction::FENCE_W,
+            succ: Instruction::FENCE_R,
+        })
+    );
+}
+
+/// pause only advances the pc
+#[test]
+fn pause_execute() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    mem.store_word(0x0, 0b0000_0001_0000_00000_000_00000_0001111)
+        .unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 4);
+    assert_eq!(cpu.reg, [0; 32]);
+}
+
+/* -------------------- Zihintntl -------------------- */
+
+/// add x0, x0, x2..x5 and their compressed forms are the NTL hints
+#[test]
+fn ntl_decode() {
+    let hints: [Instruction; 4] = [
+        Instruction::NTLP1,
+        Instruction::NTLPALL,
+        Instruction::NTLS1,
+        Instruction::NTLALL,
+    ];
+    for (rs2, hint) in (2..=5).zip(hints) {
+        let add: Word = (rs2 << 20) | 0b0000000_00000_00000_000_00000_0110011;
+        assert_eq!(decode(add), Ok(hint));
+        let c_add: HalfWord = 0b1001_00000_00000_10 | ((rs2 as HalfWord) << 2);
+        assert_eq!(Instruction::parse_compressed(c_add), Ok(hint));
+    }
+
+    // other registers are plain (no-op) adds
+    assert_eq!(
+        decode(0b0000000_00110_00000_000_00000_0110011),
+        Ok(Instruction::ADD {
+            rs1: 0,
+            rs2: 6,
+            rd: 0
+        })
+    );
+    assert_eq!(
+        decode(0b0000000_00010_00001_000_00000_0110011),
+        Ok(Instruction::ADD {
+            rs1: 1,
+            rs2: 2,
+            rd: 0
+        })
+    );
+}
+
+/// An NTL hint followed by a load behaves like the load alone
+#[test]
+fn ntl_execute() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    mem.store_word(0x0, 0b0000000_00101_00000_000_00000_0110011) // ntl.all
+        .unwrap();
+    mem.store_word(0x4, 0b000100000000_00000_010_00001_0000011) // lw x1, 0x100(x0)
+        .unwrap();
+    mem.store_word(0x100, 0xCAFE).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 4);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[1], 0xCAFE);
+}
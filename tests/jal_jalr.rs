@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 /// Test that a JAL instruction can be fetched correctly
@@ -12,9 +14,9 @@ fn jal_instruction_fetch() {
     // For a small positive offset like 8:
     // imm = 0b00000000000100000000 (represents 8)
     let jal_instruction: Word = 0b000000000001_00000000_00001_1101111;
-    mem.store_word(0x0, jal_instruction);
+    mem.store_word(0x0, jal_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, jal_instruction);
 }
 
@@ -27,9 +29,9 @@ fn jal_basic_forward_jump() {
     // JAL x1, +8  (PC + 8)
     // PC starts at 0, so we’ll jump to address 0x8
     let jal_instruction: Word = 0b0_0000000100_0_00000000_00001_1101111; // imm=8
-    mem.store_word(0x0, jal_instruction);
+    mem.store_word(0x0, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution:
     // x1 = return address = PC + 4 = 4
@@ -52,9 +54,9 @@ fn jal_backward_jump() {
     // Encode offset properly with sign bit
     // For test purposes we can simulate an instruction that produces -8
     let jal_instruction: Word = 0b1_1111111100_1_11111111_00001_1101111; // imm = -8
-    mem.store_word(0x10, jal_instruction);
+    mem.store_word(0x10, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x08);
     assert_eq!(cpu.reg[1], 0x14); // return address = 0x10 + 4
@@ -68,9 +70,9 @@ fn jal_to_nonzero_rd() {
 
     // JAL x5, 12  → jump to PC + 12, store return address in x5
     let jal_instruction: Word = 0b0_0000000110_0_00000000_00101_1101111;
-    mem.store_word(0x0, jal_instruction);
+    mem.store_word(0x0, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 12);
     assert_eq!(cpu.reg[5], 4);
@@ -84,9 +86,9 @@ fn jal_rd_x0_no_link_written() {
 
     // JAL x0, 16 → jump but do not save return address
     let jal_instruction: Word = 0b0_0000001000_0_00000000_00000_1101111;
-    mem.store_word(0x0, jal_instruction);
+    mem.store_word(0x0, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 16);
     assert_eq!(cpu.reg[0], 0);
@@ -100,9 +102,9 @@ fn jal_large_forward_offset() {
 
     // JAL x1, +1024 (0x400)
     let jal_instruction: Word = 0b0_1000000000_0_00000000_00001_1101111;
-    mem.store_word(0x0, jal_instruction);
+    mem.store_word(0x0, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x400);
     assert_eq!(cpu.reg[1], 4);
@@ -119,9 +121,9 @@ fn jal_large_negative_offset() {
 
     // JAL x1, -512 (0xFFFF_FE00)
     let jal_instruction: Word = 0b1_1100000000_1_11111111_00001_1101111;
-    mem.store_word(0x800, jal_instruction);
+    mem.store_word(0x800, jal_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x600); // 0x800 - 0x200
     assert_eq!(cpu.reg[1], 0x804); // return address
@@ -141,23 +143,23 @@ fn jal_with_lui_interaction() {
 
     // LUI x2, 0x1
     let lui_x2 = 0b00000000000000000001_00010_0110111;
-    mem.store_word(0x00, lui_x2);
+    mem.store_word(0x00, lui_x2).unwrap();
 
     // JAL x1, 8 (PC + 8)
     let jal_x1 = 0b0_0000000100_0_00000000_00001_1101111;
-    mem.store_word(0x04, jal_x1);
+    mem.store_word(0x04, jal_x1).unwrap();
 
     // LUI x3, 0x2 (should be skipped)
     let lui_x3 = 0b00000000000000000010_00011_0110111;
-    mem.store_word(0x08, lui_x3);
+    mem.store_word(0x08, lui_x3).unwrap();
 
     // LUI x4, 0x3 (target)
     let lui_x4 = 0b00000000000000000011_00100_0110111;
-    mem.store_word(0x0C, lui_x4);
+    mem.store_word(0x0C, lui_x4).unwrap();
 
-    cpu.clock_cycle(&mut mem); // LUI x2
-    cpu.clock_cycle(&mut mem); // JAL (to 0x0C)
-    cpu.clock_cycle(&mut mem); // LUI x4
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x2
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL (to 0x0C)
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x4
 
     assert_eq!(cpu.reg[2], 0x1000);
     assert_eq!(cpu.reg[3], 0); // skipped
@@ -178,20 +180,20 @@ fn jal_with_auipc_interaction() {
     // 0x0C: LUI x8, 3     → executed after jump
 
     let auipc_x5 = 0b00000000000000000001_00101_0010111;
-    mem.store_word(0x00, auipc_x5);
+    mem.store_word(0x00, auipc_x5).unwrap();
 
     let jal_x6 = 0b0_0000000100_0_00000000_00110_1101111;
-    mem.store_word(0x04, jal_x6);
+    mem.store_word(0x04, jal_x6).unwrap();
 
     let lui_x7 = 0b000000000000000000010_00111_0110111;
-    mem.store_word(0x08, lui_x7);
+    mem.store_word(0x08, lui_x7).unwrap();
 
     let lui_x8 = 0b00000000000000000011_01000_0110111;
-    mem.store_word(0x0C, lui_x8);
+    mem.store_word(0x0C, lui_x8).unwrap();
 
-    cpu.clock_cycle(&mut mem); // AUIPC
-    cpu.clock_cycle(&mut mem); // JAL
-    cpu.clock_cycle(&mut mem); // LUI x8
+    cpu.clock_cycle(&mut mem).unwrap(); // AUIPC
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x8
 
     assert_eq!(cpu.reg[5], 0x1000);
     assert_eq!(cpu.reg[6], 0x08); // return address
@@ -200,18 +202,24 @@ fn jal_with_auipc_interaction() {
     assert_eq!(cpu.pc, 0x10);
 }
 
-/// JAL with an illegal offset not 4-byte aligned (should panic)
+/// JAL with an illegal offset not 4-byte aligned (should trap without linking)
 #[test]
-#[should_panic]
-fn jal_illegal_unaligned_jump_panics() {
+fn jal_illegal_unaligned_jump_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
     // Force an illegal jump target that is not 4-byte aligned (e.g., 2)
     let jal_instruction: Word = 0b0_0000000001_0_00000000_00001_1101111;
-    mem.store_word(0x0, jal_instruction);
-
-    cpu.clock_cycle(&mut mem); // Emulator should panic or raise an exception
+    mem.store_word(0x0, jal_instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
+    assert_eq!(cpu.reg[1], 0); // rd is not written on a trap
 }
 
 /// Test that a JALR instruction can be fetched correctly
@@ -225,9 +233,9 @@ fn jalr_instruction_fetch() {
     // imm[11:0] | rs1 | funct3=000 | rd | opcode=1100111
     // imm=8 (0b000000001000)
     let jalr_instruction: Word = 0b000000001000_00010_000_00001_1100111;
-    mem.store_word(0x0, jalr_instruction);
+    mem.store_word(0x0, jalr_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, jalr_instruction);
 }
 
@@ -242,9 +250,9 @@ fn jalr_basic_forward_jump() {
 
     // JALR x1, x2, 12  → target = x2 + 12 = 112
     let jalr_instruction: Word = 0b000000001100_00010_000_00001_1100111;
-    mem.store_word(0x0, jalr_instruction);
+    mem.store_word(0x0, jalr_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution:
     // x1 = return address = PC + 4 = 4
@@ -264,9 +272,9 @@ fn jalr_negative_offset() {
 
     // JALR x1, x2, -8 → target = 0x120 - 8 = 0x118
     let jalr_instruction: Word = 0b111111111000_00010_000_00001_1100111;
-    mem.store_word(0x100, jalr_instruction);
+    mem.store_word(0x100, jalr_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x118);
     assert_eq!(cpu.reg[1], 0x104);
@@ -282,9 +290,9 @@ fn jalr_to_nonzero_rd() {
 
     // JALR x10, x5, 16 → jump to 0x90, link in x10
     let jalr_instruction: Word = 0b000000010000_00101_000_01010_1100111;
-    mem.store_word(0x0, jalr_instruction);
+    mem.store_word(0x0, jalr_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x90);
     assert_eq!(cpu.reg[10], 4);
@@ -300,9 +308,9 @@ fn jalr_rd_x0_no_link_written() {
 
     // JALR x0, x1, 8 → jump but don’t link
     let jalr_instruction: Word = 0b000000001000_00001_000_00000_1100111;
-    mem.store_word(0x0, jalr_instruction);
+    mem.store_word(0x0, jalr_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 0x208);
     assert_eq!(cpu.reg[0], 0); // still zero
@@ -318,9 +326,9 @@ fn jalr_alignment_mask() {
 
     // JALR x1, x2, 8 → target = (101 + 8) & !1 = 108
     let jalr_instruction: Word = 0b000000001000_00010_000_00001_1100111;
-    mem.store_word(0x0, jalr_instruction);
+    mem.store_word(0x0, jalr_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.pc, 108); // aligned down
     assert_eq!(cpu.reg[1], 4);
@@ -345,15 +353,15 @@ fn jalr_and_jal_combined() {
     let lui_x4 = 0b00000000000000000011_00100_0110111;
     let jalr_x5 = 0b000000000100_00100_000_00101_1100111;
 
-    mem.store_word(0x00, jal_x1);
-    mem.store_word(0x04, lui_x2);
-    mem.store_word(0x08, lui_x3);
-    mem.store_word(0x0C, lui_x4);
-    mem.store_word(0x10, jalr_x5);
+    mem.store_word(0x00, jal_x1).unwrap();
+    mem.store_word(0x04, lui_x2).unwrap();
+    mem.store_word(0x08, lui_x3).unwrap();
+    mem.store_word(0x0C, lui_x4).unwrap();
+    mem.store_word(0x10, jalr_x5).unwrap();
 
-    cpu.clock_cycle(&mut mem); // JAL
-    cpu.clock_cycle(&mut mem); // LUI x4
-    cpu.clock_cycle(&mut mem); // JALR x5, x4, 4
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x4
+    cpu.clock_cycle(&mut mem).unwrap(); // JALR x5, x4, 4
 
     assert_eq!(cpu.reg[1], 0x04);
     assert_eq!(cpu.reg[4], 0x3000);
@@ -377,14 +385,14 @@ fn jalr_with_lui_interaction() {
     let lui_x3 = 0b00000000000000000010_00011_0110111;
     let lui_x4 = 0b00000000000000000011_00100_0110111;
 
-    mem.store_word(0x00, lui_x2);
-    mem.store_word(0x04, jalr_x1);
-    mem.store_word(0x08, lui_x3);
-    mem.store_word(0x100C, lui_x4);
+    mem.store_word(0x00, lui_x2).unwrap();
+    mem.store_word(0x04, jalr_x1).unwrap();
+    mem.store_word(0x08, lui_x3).unwrap();
+    mem.store_word(0x100C, lui_x4).unwrap();
 
-    cpu.clock_cycle(&mut mem); // LUI
-    cpu.clock_cycle(&mut mem); // JALR
-    cpu.clock_cycle(&mut mem); // LUI at 0x100C
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI
+    cpu.clock_cycle(&mut mem).unwrap(); // JALR
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI at 0x100C
 
     assert_eq!(cpu.reg[2], 0x1000);
     assert_eq!(cpu.reg[3], 0);
@@ -409,14 +417,14 @@ fn jalr_with_auipc_interaction() {
     let lui_x7 = 0b00000000000000000010_00111_0110111;
     let lui_x8 = 0b00000000000000000011_01000_0110111;
 
-    mem.store_word(0x00, auipc_x5);
-    mem.store_word(0x04, jalr_x6);
-    mem.store_word(0x08, lui_x7);
-    mem.store_word(0x1008, lui_x8);
+    mem.store_word(0x00, auipc_x5).unwrap();
+    mem.store_word(0x04, jalr_x6).unwrap();
+    mem.store_word(0x08, lui_x7).unwrap();
+    mem.store_word(0x1008, lui_x8).unwrap();
 
-    cpu.clock_cycle(&mut mem); // AUIPC
-    cpu.clock_cycle(&mut mem); // JALR
-    cpu.clock_cycle(&mut mem); // LUI x8
+    cpu.clock_cycle(&mut mem).unwrap(); // AUIPC
+    cpu.clock_cycle(&mut mem).unwrap(); // JALR
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI x8
 
     assert_eq!(cpu.reg[5], 0x1000);
     assert_eq!(cpu.reg[6], 0x08);
@@ -425,20 +433,26 @@ fn jalr_with_auipc_interaction() {
     assert_eq!(cpu.pc, 0x100C);
 }
 
-/// JALR with unaligned jump target (should panic)
+/// JALR with unaligned jump target (should trap without linking)
 #[test]
-#[should_panic]
-fn jalr_illegal_unaligned_target_panics() {
+fn jalr_illegal_unaligned_target_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
 
-    // x2 = 3, imm = 1 → target = 4
+    // x2 = 3, imm = 0 → target = 3 & !1 = 2
     cpu.reg[2] = 3;
 
     let jalr_instruction: Word = 0b000000000000_00010_000_00001_1100111;
-    mem.store_word(0x0, jalr_instruction);
-
-    cpu.clock_cycle(&mut mem); // Emulator should panic due to unaligned PC
+    mem.store_word(0x0, jalr_instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x2))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
+    assert_eq!(cpu.reg[1], 0); // rd is not written on a trap
 }
 
 /// Full chain JAL + JALR return test
@@ -456,11 +470,11 @@ fn jal_and_jalr_return_sequence() {
     let jal = 0b0_0000000110_0_00000000_00001_1101111;
     let jalr = 0b000000000000_00001_000_00000_1100111;
 
-    mem.store_word(0x00, jal);
-    mem.store_word(0x0C, jalr);
+    mem.store_word(0x00, jal).unwrap();
+    mem.store_word(0x0C, jalr).unwrap();
 
-    cpu.clock_cycle(&mut mem); // JAL
-    cpu.clock_cycle(&mut mem); // JALR (return)
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL
+    cpu.clock_cycle(&mut mem).unwrap(); // JALR (return)
 
     assert_eq!(cpu.pc, 0x04); // returned to link address
 }
@@ -485,19 +499,19 @@ fn jal_and_jalr_combined_with_regular_instructions() {
     let add_func: Word = 0b0000000_01011_01010_000_01010_0110011; // add x10, x10, x11
     let jalr_return: Word = 0b000000000000_00001_000_00000_1100111; // jalr x0, x1, 0
 
-    mem.store_word(0x00, addi_a0_5);
-    mem.store_word(0x04, addi_a1_3);
-    mem.store_word(0x08, jal_to_add_func);
-    mem.store_word(0x0C, end_marker);
-    mem.store_word(0x10, add_func);
-    mem.store_word(0x14, jalr_return);
+    mem.store_word(0x00, addi_a0_5).unwrap();
+    mem.store_word(0x04, addi_a1_3).unwrap();
+    mem.store_word(0x08, jal_to_add_func).unwrap();
+    mem.store_word(0x0C, end_marker).unwrap();
+    mem.store_word(0x10, add_func).unwrap();
+    mem.store_word(0x14, jalr_return).unwrap();
 
     // Execute instructions
-    cpu.clock_cycle(&mut mem); // ADDI x10, x0, 5
-    cpu.clock_cycle(&mut mem); // ADDI x11, x0, 3
-    cpu.clock_cycle(&mut mem); // JAL to add_func
-    cpu.clock_cycle(&mut mem); // ADD x10, x10, x11
-    cpu.clock_cycle(&mut mem); // JALR return
+    cpu.clock_cycle(&mut mem).unwrap(); // ADDI x10, x0, 5
+    cpu.clock_cycle(&mut mem).unwrap(); // ADDI x11, x0, 3
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL to add_func
+    cpu.clock_cycle(&mut mem).unwrap(); // ADD x10, x10, x11
+    cpu.clock_cycle(&mut mem).unwrap(); // JALR return
 
     // After execution, x10 should contain 8 (5 + 3)
     assert_eq!(cpu.reg[10], 8);
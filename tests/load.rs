@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 /* -------------------- LW tests -------------------- */
@@ -11,9 +13,9 @@ fn lw_instruction_fetch() {
     // LW x5, 0(x1)
     // funct3 = 010, opcode = 0000011
     let lw_instruction: Word = 0b000000000000_00001_010_00101_0000011;
-    mem.store_word(0x0, lw_instruction);
+    mem.store_word(0x0, lw_instruction).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lw_instruction);
 }
 
@@ -25,13 +27,13 @@ fn lw_basic_load() {
 
     // LW x5, 0(x1)
     let lw_instr: Word = 0b000000000000_00001_010_00101_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     // Setup memory and base register
     cpu.reg[1] = 0x100; // base
-    mem.store_word(0x100, 0xDEADBEEF);
+    mem.store_word(0x100, 0xDEADBEEF).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[5], 0xDEADBEEF);
     assert_eq!(cpu.pc, 4);
@@ -45,12 +47,12 @@ fn lw_with_positive_offset() {
 
     // LW x6, 8(x2)
     let lw_instr: Word = 0b000000001000_00010_010_00110_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[2] = 0x200;
-    mem.store_word(0x208, 0x12345678);
+    mem.store_word(0x208, 0x12345678).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[6], 0x12345678);
     assert_eq!(cpu.pc, 4);
@@ -65,12 +67,12 @@ fn lw_with_negative_offset() {
     // LW x7, -4(x3)
     // immediate = 0xFFC (-4 in 12-bit two’s complement)
     let lw_instr: Word = 0b111111111100_00011_010_00111_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[3] = 0x300;
-    mem.store_word(0x2FC, 0xCAFEBABE);
+    mem.store_word(0x2FC, 0xCAFEBABE).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[7], 0xCAFEBABE);
     assert_eq!(cpu.pc, 4);
@@ -84,32 +86,35 @@ fn lw_sign_extension_check() {
 
     // LW x8, 0(x4)
     let lw_instr: Word = 0b000000000000_00100_010_01000_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[4] = 0x400;
-    mem.store_word(0x400, 0x8000_0001);
+    mem.store_word(0x400, 0x8000_0001).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // LW loads full 32-bit word as-is, no sign-extension change
     assert_eq!(cpu.reg[8], 0x8000_0001);
 }
 
-/// LW from unaligned address should panic
+/// LW from unaligned address should trap
 #[test]
-#[should_panic]
-fn lw_unaligned_address_panics() {
+fn lw_unaligned_address_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
 
     // LW x9, 2(x5) → unaligned address
     let lw_instr: Word = 0b000000000010_00101_010_01001_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[5] = 0x100; // base
-    mem.store_word(0x102, 0x12345678);
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x102))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
+    assert_eq!(cpu.reg[9], 0);
 }
 
 /// LW overwriting same register as base (x1 used as base and destination)
@@ -120,12 +125,12 @@ fn lw_overwrite_base_register() {
 
     // LW x1, 0(x1)
     let lw_instr: Word = 0b000000000000_00001_010_00001_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[1] = 0x500;
-    mem.store_word(0x500, 0xFACE_FEED);
+    mem.store_word(0x500, 0xFACE_FEED).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0xFACE_FEED);
     assert_eq!(cpu.pc, 4);
@@ -139,12 +144,12 @@ fn lw_to_x0_does_not_write() {
 
     // LW x0, 0(x1)
     let lw_instr: Word = 0b000000000000_00001_010_00000_0000011;
-    mem.store_word(0x0, lw_instr);
+    mem.store_word(0x0, lw_instr).unwrap();
 
     cpu.reg[1] = 0x600;
-    mem.store_word(0x600, 0xABCD1234);
+    mem.store_word(0x600, 0xABCD1234).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 always stays zero
     assert_eq!(cpu.reg[0], 0);
@@ -162,14 +167,14 @@ fn lw_then_addi_dependency() {
     let lw_x10_0x1: Word = 0b000000000000_00001_010_01010_0000011;
     let addi_x11_x10_5: Word = 0b000000000101_01010_000_01011_0010011;
 
-    mem.store_word(0x00, lw_x10_0x1);
-    mem.store_word(0x04, addi_x11_x10_5);
+    mem.store_word(0x00, lw_x10_0x1).unwrap();
+    mem.store_word(0x04, addi_x11_x10_5).unwrap();
 
     cpu.reg[1] = 0x700;
-    mem.store_word(0x700, 0x10);
+    mem.store_word(0x700, 0x10).unwrap();
 
-    cpu.clock_cycle(&mut mem); // LW
-    cpu.clock_cycle(&mut mem); // ADDI
+    cpu.clock_cycle(&mut mem).unwrap(); // LW
+    cpu.clock_cycle(&mut mem).unwrap(); // ADDI
 
     assert_eq!(cpu.reg[10], 0x10);
     assert_eq!(cpu.reg[11], 0x15); // 0x10 + 5
@@ -187,9 +192,9 @@ fn lh_instruction_fetch() {
     // LH x5, 0(x1)
     // funct3 = 001, opcode = 0000011
     let lh_instr: Word = 0b000000000000_00001_001_00101_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lh_instr);
 }
 
@@ -201,13 +206,13 @@ fn lh_basic_load_positive() {
 
     // LH x6, 0(x2)
     let lh_instr: Word = 0b000000000000_00010_001_00110_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[2] = 0x100;
     // Memory at 0x100 = 0x1234 (little-endian)
-    mem.store_halfword(0x100, 0x1234);
+    mem.store_halfword(0x100, 0x1234).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x1234 stays as 0x00001234 after sign extension
     assert_eq!(cpu.reg[6], 0x0000_1234);
@@ -222,12 +227,12 @@ fn lh_sign_extension_negative() {
 
     // LH x7, 0(x3)
     let lh_instr: Word = 0b000000000000_00011_001_00111_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[3] = 0x200;
-    mem.store_halfword(0x200, 0xF234); // negative 16-bit value
+    mem.store_halfword(0x200, 0xF234).unwrap(); // negative 16-bit value
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Sign-extended: 0xFFFF_F234
     assert_eq!(cpu.reg[7], 0xFFFF_F234);
@@ -242,12 +247,12 @@ fn lh_with_positive_offset() {
 
     // LH x8, 4(x4)
     let lh_instr: Word = 0b000000000100_00100_001_01000_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[4] = 0x300;
-    mem.store_halfword(0x304, 0x7FFF);
+    mem.store_halfword(0x304, 0x7FFF).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[8], 0x0000_7FFF);
     assert_eq!(cpu.pc, 4);
@@ -261,33 +266,36 @@ fn lh_with_negative_offset() {
 
     // LH x9, -2(x5)
     let lh_instr: Word = 0b111111111110_00101_001_01001_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[5] = 0x400;
-    mem.store_halfword(0x3FE, 0xBEEF);
+    mem.store_halfword(0x3FE, 0xBEEF).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xBEEF sign-extends → 0xFFFF_BEEF
     assert_eq!(cpu.reg[9], 0xFFFF_BEEF);
     assert_eq!(cpu.pc, 4);
 }
 
-/// LH unaligned address → should panic
+/// LH unaligned address → should trap
 #[test]
-#[should_panic]
-fn lh_unaligned_address_panics() {
+fn lh_unaligned_address_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
 
     // LH x10, 1(x6)
     let lh_instr: Word = 0b000000000001_00110_001_01010_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[6] = 0x100;
-    mem.store_halfword(0x101, 0xABCD);
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x101))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
+    assert_eq!(cpu.reg[10], 0);
 }
 
 /// LH destination is x0 → should not write
@@ -298,12 +306,12 @@ fn lh_to_x0_does_not_write() {
 
     // LH x0, 0(x7)
     let lh_instr: Word = 0b000000000000_00111_001_00000_0000011;
-    mem.store_word(0x0, lh_instr);
+    mem.store_word(0x0, lh_instr).unwrap();
 
     cpu.reg[7] = 0x500;
-    mem.store_halfword(0x500, 0xAAAA);
+    mem.store_halfword(0x500, 0xAAAA).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
     assert_eq!(cpu.pc, 4);
@@ -320,9 +328,9 @@ fn lhu_instruction_fetch() {
     // LHU x5, 0(x1)
     // funct3 = 101, opcode = 0000011
     let lhu_instr: Word = 0b000000000000_00001_101_00101_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lhu_instr);
 }
 
@@ -334,12 +342,12 @@ fn lhu_basic_load_positive() {
 
     // LHU x6, 0(x2)
     let lhu_instr: Word = 0b000000000000_00010_101_00110_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
     cpu.reg[2] = 0x600;
-    mem.store_halfword(0x600, 0x1234);
+    mem.store_halfword(0x600, 0x1234).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Zero-extended
     assert_eq!(cpu.reg[6], 0x0000_1234);
@@ -354,12 +362,12 @@ fn lhu_zero_extension_from_negative() {
 
     // LHU x7, 0(x3)
     let lhu_instr: Word = 0b000000000000_00011_101_00111_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
     cpu.reg[3] = 0x700;
-    mem.store_halfword(0x700, 0xF234); // 16-bit negative
+    mem.store_halfword(0x700, 0xF234).unwrap(); // 16-bit negative
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Zero-extended → 0x0000_F234
     assert_eq!(cpu.reg[7], 0x0000_F234);
@@ -374,32 +382,35 @@ fn lhu_with_positive_offset() {
 
     // LHU x8, 6(x4)
     let lhu_instr: Word = 0b000000000110_00100_101_01000_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
     cpu.reg[4] = 0x800;
-    mem.store_halfword(0x806, 0xBEEF);
+    mem.store_halfword(0x806, 0xBEEF).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[8], 0x0000_BEEF);
     assert_eq!(cpu.pc, 4);
 }
 
-/// LHU unaligned address → should panic
+/// LHU unaligned address → should trap
 #[test]
-#[should_panic]
-fn lhu_unaligned_address_panics() {
+fn lhu_unaligned_address_traps() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
 
     // LHU x9, 3(x5)
     let lhu_instr: Word = 0b000000000011_00101_101_01001_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
     cpu.reg[5] = 0x900;
-    mem.store_halfword(0x903, 0xABCD);
 
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x903))
+    );
+    assert_eq!(cpu.pc, 0x0); // pc stays on the faulting instruction
+    assert_eq!(cpu.reg[9], 0);
 }
 
 /// LHU destination is x0 → should not write
@@ -410,12 +421,12 @@ fn lhu_to_x0_does_not_write() {
 
     // LHU x0, 0(x6)
     let lhu_instr: Word = 0b000000000000_00110_101_00000_0000011;
-    mem.store_word(0x0, lhu_instr);
+    mem.store_word(0x0, lhu_instr).unwrap();
 
     cpu.reg[6] = 0xA00;
-    mem.store_halfword(0xA00, 0x1111);
+    mem.store_halfword(0xA00, 0x1111).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
     assert_eq!(cpu.pc, 4);
@@ -432,9 +443,9 @@ fn lb_instruction_fetch() {
     // LB x5, 0(x1)
     // funct3 = 000, opcode = 0000011
     let lb_instr: Word = 0b000000000000_00001_000_00101_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lb_instr);
 }
 
@@ -446,12 +457,12 @@ fn lb_basic_load_positive() {
 
     // LB x6, 0(x2)
     let lb_instr: Word = 0b000000000000_00010_000_00110_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
     cpu.reg[2] = 0x100;
     mem[0x100] = 0x12;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x12 sign-extends to 0x0000_0012
     assert_eq!(cpu.reg[6], 0x0000_0012);
@@ -466,12 +477,12 @@ fn lb_sign_extension_negative() {
 
     // LB x7, 0(x3)
     let lb_instr: Word = 0b000000000000_00011_000_00111_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
     cpu.reg[3] = 0x200;
     mem[0x200] = 0xF2; // negative 8-bit value
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Sign-extended: 0xFFFF_FFF2
     assert_eq!(cpu.reg[7], 0xFFFF_FFF2);
@@ -486,12 +497,12 @@ fn lb_with_positive_offset() {
 
     // LB x8, 3(x4)
     let lb_instr: Word = 0b000000000011_00100_000_01000_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
     cpu.reg[4] = 0x300;
     mem[0x303] = 0x7F;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[8], 0x0000_007F);
     assert_eq!(cpu.pc, 4);
@@ -505,12 +516,12 @@ fn lb_with_negative_offset() {
 
     // LB x9, -1(x5)
     let lb_instr: Word = 0b111111111111_00101_000_01001_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
     cpu.reg[5] = 0x400;
     mem[0x3FF] = 0x80; // negative 8-bit value (-128)
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Sign-extended: 0xFFFF_FF80
     assert_eq!(cpu.reg[9], 0xFFFF_FF80);
@@ -525,12 +536,12 @@ fn lb_to_x0_does_not_write() {
 
     // LB x0, 0(x6)
     let lb_instr: Word = 0b000000000000_00110_000_00000_0000011;
-    mem.store_word(0x0, lb_instr);
+    mem.store_word(0x0, lb_instr).unwrap();
 
     cpu.reg[6] = 0x500;
     mem[0x500] = 0xAB;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
     assert_eq!(cpu.pc, 4);
@@ -547,9 +558,9 @@ fn lbu_instruction_fetch() {
     // LBU x5, 0(x1)
     // funct3 = 100, opcode = 0000011
     let lbu_instr: Word = 0b000000000000_00001_100_00101_0000011;
-    mem.store_word(0x0, lbu_instr);
+    mem.store_word(0x0, lbu_instr).unwrap();
 
-    let instruction = cpu.fetch_instruction(&mut mem);
+    let instruction = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lbu_instr);
 }
 
@@ -561,12 +572,12 @@ fn lbu_basic_load_positive() {
 
     // LBU x6, 0(x2)
     let lbu_instr: Word = 0b000000000000_00010_100_00110_0000011;
-    mem.store_word(0x0, lbu_instr);
+    mem.store_word(0x0, lbu_instr).unwrap();
 
     cpu.reg[2] = 0x600;
     mem[0x600] = 0x12;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Zero-extended
     assert_eq!(cpu.reg[6], 0x0000_0012);
@@ -581,12 +592,12 @@ fn lbu_zero_extension_from_negative() {
 
     // LBU x7, 0(x3)
     let lbu_instr: Word = 0b000000000000_00011_100_00111_0000011;
-    mem.store_word(0x0, lbu_instr);
+    mem.store_word(0x0, lbu_instr).unwrap();
 
     cpu.reg[3] = 0x700;
     mem[0x700] = 0xF2; // 0xF2 = -14 signed
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Zero-extended → 0x0000_00F2
     assert_eq!(cpu.reg[7], 0x0000_00F2);
@@ -601,12 +612,12 @@ fn lbu_with_positive_offset() {
 
     // LBU x8, 5(x4)
     let lbu_instr: Word = 0b000000000101_00100_100_01000_0000011;
-    mem.store_word(0x0, lbu_instr);
+    mem.store_word(0x0, lbu_instr).unwrap();
 
     cpu.reg[4] = 0x800;
     mem[0x805] = 0xA5;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[8], 0x0000_00A5);
     assert_eq!(cpu.pc, 4);
@@ -620,12 +631,12 @@ fn lbu_to_x0_does_not_write() {
 
     // LBU x0, 0(x6)
     let lbu_instr: Word = 0b000000000000_00110_100_00000_0000011;
-    mem.store_word(0x0, lbu_instr);
+    mem.store_word(0x0, lbu_instr).unwrap();
 
     cpu.reg[6] = 0xA00;
     mem[0xA00] = 0x99;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
     assert_eq!(cpu.pc, 4);
@@ -1,3 +1,5 @@
+#![allow(clippy::identity_op)]
+
 use rust_risc_v::*;
 
 /// Test fetching a LUI instruction from memory
@@ -9,9 +11,9 @@ fn lui_instruction_fetch() {
     // Example: LUI x1, 0x12345
     // opcode = 0110111, rd = 00001, imm[31:12] = 0x12345
     let lui_instruction: Word = (0x12345 << 12) | (1 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, lui_instruction);
 }
 
@@ -23,9 +25,9 @@ fn lui_basic_operation() {
 
     // LUI x2, 0xABCDE
     let lui_instruction: Word = (0xABCDE << 12) | (2 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x2 = 0xABCDE000
     assert_eq!(cpu.reg[2], 0xABCDE000);
@@ -41,9 +43,9 @@ fn lui_overwrites_previous_value() {
 
     // LUI x3, 0x1
     let lui_instruction: Word = (0x1 << 12) | (3 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Overwritten with upper immediate only
     assert_eq!(cpu.reg[3], 0x00001000);
@@ -57,11 +59,11 @@ fn lui_with_zero_immediate() {
 
     // LUI x4, 0x0
     let lui_instruction: Word = (0x0 << 12) | (4 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
     cpu.reg[4] = 0xDEADBEEF; // prefill
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Should zero out the register
     assert_eq!(cpu.reg[4], 0x00000000);
@@ -75,9 +77,9 @@ fn lui_with_max_positive_immediate() {
 
     // LUI x5, 0x7FFFF
     let lui_instruction: Word = (0x7FFFF << 12) | (5 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x7FFFF000
     assert_eq!(cpu.reg[5], 0x7FFFF000);
@@ -91,9 +93,9 @@ fn lui_with_negative_immediate_sign_extend() {
 
     // LUI x6, 0xFFFFF (i.e., -1 << 12)
     let lui_instruction: Word = (0xFFFFF << 12) | (6 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Should sign extend: 0xFFFFF000 treated as -4096
     assert_eq!(cpu.reg[6], 0xFFFFF000);
@@ -107,9 +109,9 @@ fn lui_write_to_x0_ignored() {
 
     // LUI x0, 0x12345
     let lui_instruction: Word = (0x12345 << 12) | (0 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Register x0 must remain 0
     assert_eq!(cpu.reg[0], 0);
@@ -123,14 +125,14 @@ fn lui_and_addi_link_behavior() {
 
     // LUI x1, 0x12345
     let lui_instruction: Word = (0x12345 << 12) | (1 << 7) | 0b0110111;
-    mem.store_word(0x0, lui_instruction);
+    mem.store_word(0x0, lui_instruction).unwrap();
 
     // ADDI x1, x1, 0x678 (add lower 12 bits)
     let addi_instruction: Word = (0x678 << 20) | (1 << 15) | (0b000 << 12) | (1 << 7) | 0b0010011;
-    mem.store_word(0x4, addi_instruction);
+    mem.store_word(0x4, addi_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem); // clock_cycle LUI
-    cpu.clock_cycle(&mut mem); // clock_cycle ADDI
+    cpu.clock_cycle(&mut mem).unwrap(); // clock_cycle LUI
+    cpu.clock_cycle(&mut mem).unwrap(); // clock_cycle ADDI
 
     // Result should combine upper and lower bits: 0x12345678
     assert_eq!(cpu.reg[1], 0x12345678);
@@ -145,9 +147,9 @@ fn auipc_instruction_fetch() {
     // Example: AUIPC x1, 0x12345
     // opcode = 0010111, rd = 00001, imm[31:12] = 0x12345
     let auipc_instruction: Word = (0x12345 << 12) | (1 << 7) | 0b0010111;
-    mem.store_word(0x0, auipc_instruction);
+    mem.store_word(0x0, auipc_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, auipc_instruction);
 }
 
@@ -161,9 +163,9 @@ fn auipc_basic_operation() {
 
     // AUIPC x2, 0xABCDE
     let auipc_instruction: Word = (0xABCDE << 12) | (2 << 7) | 0b0010111;
-    mem.store_word(0x1000, auipc_instruction);
+    mem.store_word(0x1000, auipc_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Result: PC + (imm << 12)
     let expected = 0x1000u32.wrapping_add(0xABCDE000);
@@ -178,11 +180,11 @@ fn auipc_uses_current_pc() {
 
     // AUIPC x3, 0x1
     let auipc_instruction: Word = (0x1 << 12) | (3 << 7) | 0b0010111;
-    mem.store_word(0x2000, auipc_instruction);
+    mem.store_word(0x2000, auipc_instruction).unwrap();
 
     cpu.pc = 0x2000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Result = 0x2000 + 0x1000 = 0x3000
     assert_eq!(cpu.reg[3], 0x3000);
@@ -198,9 +200,9 @@ fn auipc_with_zero_immediate() {
 
     // AUIPC x4, 0x0
     let auipc_instruction: Word = (0x0 << 12) | (4 << 7) | 0b0010111;
-    mem.store_word(0x4000, auipc_instruction);
+    mem.store_word(0x4000, auipc_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Result = PC + 0 = 0x4000
     assert_eq!(cpu.reg[4], 0x4000);
@@ -216,9 +218,9 @@ fn auipc_with_negative_immediate_sign_extend() {
 
     // AUIPC x5, 0xFFFFF (i.e. -1 << 12)
     let auipc_instruction: Word = (0xFFFFF << 12) | (5 << 7) | 0b0010111;
-    mem.store_word(0x1000, auipc_instruction);
+    mem.store_word(0x1000, auipc_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Should sign extend: (imm << 12) = 0xFFFFF000 (-4096)
     let expected = 0x1000u32.wrapping_add(0xFFFFF000);
@@ -235,9 +237,9 @@ fn auipc_with_large_immediate() {
 
     // AUIPC x6, 0x7FFFF
     let auipc_instruction: Word = (0x7FFFF << 12) | (6 << 7) | 0b0010111;
-    mem.store_word(0x1000, auipc_instruction);
+    mem.store_word(0x1000, auipc_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     let expected = 0x1000u32.wrapping_add(0x7FFFF000);
     assert_eq!(cpu.reg[6], expected);
@@ -253,27 +255,33 @@ fn auipc_write_to_x0_ignored() {
 
     // AUIPC x0, 0xABCDE
     let auipc_instruction: Word = (0xABCDE << 12) | (0 << 7) | 0b0010111;
-    mem.store_word(0x1234, auipc_instruction);
+    mem.store_word(0x1234, auipc_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Register x0 must remain 0
     assert_eq!(cpu.reg[0], 0);
 }
-/// Writing to x0 should have no effect
+/// Fetching from outside of memory raises an instruction access fault
 #[test]
-#[should_panic]
 fn auipc_memory_out_of_bounds() {
     let mut cpu: RISCV = RISCV::reset();
     let mut mem: Memory = Memory::new();
 
     cpu.pc = 0x80000000;
 
-    // AUIPC x0, 0xABCDE
+    // AUIPC x0, 0xABCDE cannot even be placed there
     let auipc_instruction: Word = (0xABCDE << 12) | (0 << 7) | 0b0010111;
-    mem.store_word(0x80000000, auipc_instruction);
-
-    cpu.clock_cycle(&mut mem);
+    assert_eq!(
+        mem.store_word(0x80000000, auipc_instruction),
+        Err(MemoryError::OutOfBounds)
+    );
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAccessFault, 0x80000000))
+    );
+    assert_eq!(cpu.pc, 0x80000000);
 }
 
 /// AUIPC result should depend on the PC at execution time, not fetch time
@@ -284,11 +292,11 @@ fn auipc_with_pc_change_before_execute() {
 
     // AUIPC x7, 0x123
     let auipc_instruction: Word = (0x123 << 12) | (7 << 7) | 0b0010111;
-    mem.store_word(0x0, auipc_instruction);
+    mem.store_word(0x0, auipc_instruction).unwrap();
 
-    cpu.fetch_instruction(&mut mem);
+    cpu.fetch_instruction(&mem).unwrap();
     cpu.pc = 0x800; // changed before execute
-    cpu.execute(&mut mem);
+    cpu.execute(&mut mem).unwrap();
     cpu.increment_pc();
 
     // Result = 0x800 + (0x123 << 12) = 0x123800
@@ -304,20 +312,20 @@ fn auipc_with_pc_change_before_execute() {
 
 //     // AUIPC x1, 0x100
 //     let auipc_instruction: Word = (0x100 << 12) | (1 << 7) | 0b0010111;
-//     mem.store_word(0x0, auipc_instruction);
+//     mem.store_word(0x0, auipc_instruction).unwrap();
 
 //     // JALR x2, 0(x1) -> jumps to PC = x1 + 0
 //     let jalr_instruction: Word = (0x0 << 20) | (1 << 15) | (0b000 << 12) | (2 << 7) | 0b1100111;
-//     mem.store_word(0x4, jalr_instruction);
+//     mem.store_word(0x4, jalr_instruction).unwrap();
 
 //     cpu.pc = 0x1000;
 
 //     // Execute AUIPC first
-//     cpu.clock_cycle(&mut mem);
+//     cpu.clock_cycle(&mut mem).unwrap();
 
 //     // Next instruction
 //     cpu.pc = 0x4;
-//     cpu.clock_cycle(&mut mem);
+//     cpu.clock_cycle(&mut mem).unwrap();
 
 //     // x1 = 0x1000 + (0x100 << 12) = 0x101000
 //     // JALR jumps to x1 + 0
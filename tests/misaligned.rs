@@ -0,0 +1,120 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/// Stores `program` from address 0 onwards
+fn load_program(mem: &mut Memory, program: &[Word]) {
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+}
+
+/// By default a misaligned load or store raises the misaligned exception with the address
+#[test]
+fn misaligned_traps_by_default() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    // lw x2, 0(x1) ; sh x2, 0(x1)
+    load_program(
+        &mut mem,
+        &[
+            0b000000000000_00001_010_00010_0000011,
+            0b0000000_00010_00001_001_00000_0100011,
+        ],
+    );
+    cpu.reg[1] = 0x101;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x101))
+    );
+    cpu.pc = 4;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAddressMisaligned, 0x101))
+    );
+}
+
+/// With emulation the access is composed of bytes and may straddle a word boundary
+#[test]
+fn misaligned_emulated() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    // lw x2, 0(x1) ; lh x3, 3(x1) ; sw x2, 0x10(x1)
+    load_program(
+        &mut mem,
+        &[
+            0b000000000000_00001_010_00010_0000011,
+            0b000000000011_00001_001_00011_0000011,
+            0b0000000_00010_00001_010_10000_0100011,
+        ],
+    );
+    mem.store_word(0x100, 0x4433_2211).unwrap();
+    mem.store_word(0x104, 0x8877_6655).unwrap();
+    cpu.reg[1] = 0x103;
+    cpu.misaligned_access = MisalignedAccess::Emulate { penalty: 0 };
+
+    for _ in 0..3 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(cpu.reg[2], 0x7766_5544);
+    assert_eq!(cpu.reg[3], 0xFFFF_8877); // sign-extended
+    assert_eq!(mem.fetch_word(0x110), Ok(0x4400_0000));
+    assert_eq!(mem.fetch_word(0x114), Ok(0x0077_6655));
+    assert_eq!(cpu.csr.read(MCYCLE), Ok(3));
+}
+
+/// The penalty is charged to mcycle only for the accesses that are actually misaligned
+#[test]
+fn misaligned_penalty() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+    // ld x2, 0(x1) ; ld x2, 1(x1)
+    load_program(
+        &mut mem,
+        &[
+            0b000000000000_00001_011_00010_0000011,
+            0b000000000001_00001_011_00010_0000011,
+        ],
+    );
+    cpu.reg[1] = 0x100;
+    cpu.misaligned_access = MisalignedAccess::Emulate { penalty: 10 };
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(MCYCLE), Ok(1));
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.csr.read(MCYCLE), Ok(12));
+    assert_eq!(cpu.csr.read(MINSTRET), Ok(2));
+}
+
+/// An emulated access running off the end of memory is an access fault and writes nothing
+#[test]
+fn misaligned_out_of_bounds() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[0b0000000_00010_00001_010_00000_0100011]); // sw x2, 0(x1)
+    cpu.reg[1] = 0xFF_FFFE;
+    cpu.reg[2] = 0xFFFF_FFFF;
+    cpu.misaligned_access = MisalignedAccess::Emulate { penalty: 0 };
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAccessFault, 0xFF_FFFE))
+    );
+    assert_eq!(mem.fetch_halfword(0xFF_FFFE), Ok(0));
+}
+
+/// LR / SC and AMOs stay aligned-only
+#[test]
+fn atomics_still_trap() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    load_program(&mut mem, &[0b00010_0_0_00000_00001_010_00010_0101111]); // lr.w x2, (x1)
+    cpu.reg[1] = 0x102;
+    cpu.misaligned_access = MisalignedAccess::Emulate { penalty: 0 };
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x102))
+    );
+}
@@ -0,0 +1,411 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/* -------------------- MUL tests -------------------- */
+
+/// Test that a MUL instruction can be fetched correctly
+#[test]
+fn mul_instruction_fetch() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // Example: MUL x1, x2, x3
+    // funct7=0000001, rs2=00011, rs1=00010, funct3=000, rd=00001, opcode=0110011
+    let mul_instruction: Word = 0b0000001_00011_00010_000_00001_0110011;
+    mem.store_word(0x0, mul_instruction).unwrap();
+
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
+    assert_eq!(instruction, mul_instruction);
+}
+
+/// Basic MUL operation test
+#[test]
+fn mul_basic_operation() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MUL x1, x2, x3
+    let mul_instruction: Word = 0b0000001_00011_00010_000_00001_0110011;
+    mem.store_word(0x0, mul_instruction).unwrap();
+
+    cpu.reg[2] = 6;
+    cpu.reg[3] = 7;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 42);
+    assert_eq!(cpu.pc, 4);
+}
+
+/// MUL with negative operands gives the low 32 bits of the signed product
+#[test]
+fn mul_negative_operands() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MUL x4, x5, x6
+    let mul_instruction: Word = 0b0000001_00110_00101_000_00100_0110011;
+    mem.store_word(0x0, mul_instruction).unwrap();
+
+    cpu.reg[5] = (-3i32) as u32;
+    cpu.reg[6] = 5;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[4] as i32, -15);
+}
+
+/// MUL overflow keeps only the lower 32 bits
+#[test]
+fn mul_overflow_wraps_around() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MUL x7, x8, x9
+    let mul_instruction: Word = 0b0000001_01001_01000_000_00111_0110011;
+    mem.store_word(0x0, mul_instruction).unwrap();
+
+    cpu.reg[8] = 0x8000_0001;
+    cpu.reg[9] = 4;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[7], 0x0000_0004);
+}
+
+/// MUL with destination register x0 (should not modify x0)
+#[test]
+fn mul_write_to_x0_is_ignored() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MUL x0, x1, x2
+    let mul_instruction: Word = 0b0000001_00010_00001_000_00000_0110011;
+    mem.store_word(0x0, mul_instruction).unwrap();
+
+    cpu.reg[1] = 3;
+    cpu.reg[2] = 4;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[0], 0);
+}
+
+/* -------------------- MULH / MULHSU / MULHU tests -------------------- */
+
+/// MULH returns the upper 32 bits of the signed x signed product
+#[test]
+fn mulh_signed_upper_bits() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MULH x1, x2, x3
+    let mulh_instruction: Word = 0b0000001_00011_00010_001_00001_0110011;
+    mem.store_word(0x0, mulh_instruction).unwrap();
+
+    cpu.reg[2] = (-2i32) as u32;
+    cpu.reg[3] = 0x4000_0000;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    // -2 * 2^30 = -2^31 -> upper bits are all ones
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFF);
+}
+
+/// MULH of MIN * MIN is positive 2^62
+#[test]
+fn mulh_min_times_min() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MULH x1, x2, x3
+    let mulh_instruction: Word = 0b0000001_00011_00010_001_00001_0110011;
+    mem.store_word(0x0, mulh_instruction).unwrap();
+
+    cpu.reg[2] = 0x8000_0000;
+    cpu.reg[3] = 0x8000_0000;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0x4000_0000);
+}
+
+/// MULHSU treats rs1 as signed and rs2 as unsigned
+#[test]
+fn mulhsu_mixed_signedness() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MULHSU x1, x2, x3
+    let mulhsu_instruction: Word = 0b0000001_00011_00010_010_00001_0110011;
+    mem.store_word(0x0, mulhsu_instruction).unwrap();
+
+    cpu.reg[2] = (-1i32) as u32;
+    cpu.reg[3] = 0xFFFF_FFFF; // 2^32 - 1 unsigned
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    // -1 * (2^32 - 1) = -(2^32 - 1) -> upper word is -1
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFF);
+}
+
+/// MULHSU with the most negative rs1 and largest rs2
+#[test]
+fn mulhsu_extremes() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MULHSU x1, x2, x3
+    let mulhsu_instruction: Word = 0b0000001_00011_00010_010_00001_0110011;
+    mem.store_word(0x0, mulhsu_instruction).unwrap();
+
+    cpu.reg[2] = 0x8000_0000;
+    cpu.reg[3] = 0xFFFF_FFFF;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    // -2^31 * (2^32 - 1) = -2^63 + 2^31 = 0x80000000_80000000
+    assert_eq!(cpu.reg[1], 0x8000_0000);
+}
+
+/// MULHU returns the upper 32 bits of the unsigned product
+#[test]
+fn mulhu_unsigned_upper_bits() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // MULHU x1, x2, x3
+    let mulhu_instruction: Word = 0b0000001_00011_00010_011_00001_0110011;
+    mem.store_word(0x0, mulhu_instruction).unwrap();
+
+    cpu.reg[2] = 0xFFFF_FFFF;
+    cpu.reg[3] = 0xFFFF_FFFF;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    // (2^32 - 1)^2 = 0xFFFFFFFE_00000001
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFE);
+}
+
+/* -------------------- DIV / DIVU tests -------------------- */
+
+/// DIV rounds towards zero
+#[test]
+fn div_rounds_towards_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // DIV x1, x2, x3
+    let div_instruction: Word = 0b0000001_00011_00010_100_00001_0110011;
+    mem.store_word(0x0, div_instruction).unwrap();
+
+    cpu.reg[2] = (-7i32) as u32;
+    cpu.reg[3] = 2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1] as i32, -3);
+}
+
+/// DIV by zero returns -1
+#[test]
+fn div_by_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // DIV x1, x2, x0
+    let div_instruction: Word = 0b0000001_00000_00010_100_00001_0110011;
+    mem.store_word(0x0, div_instruction).unwrap();
+
+    cpu.reg[2] = 1234;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFF);
+}
+
+/// DIV overflow (MIN / -1) returns MIN
+#[test]
+fn div_signed_overflow() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // DIV x1, x2, x3
+    let div_instruction: Word = 0b0000001_00011_00010_100_00001_0110011;
+    mem.store_word(0x0, div_instruction).unwrap();
+
+    cpu.reg[2] = 0x8000_0000;
+    cpu.reg[3] = (-1i32) as u32;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0x8000_0000);
+}
+
+/// DIVU treats operands as unsigned
+#[test]
+fn divu_unsigned_operands() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // DIVU x1, x2, x3
+    let divu_instruction: Word = 0b0000001_00011_00010_101_00001_0110011;
+    mem.store_word(0x0, divu_instruction).unwrap();
+
+    cpu.reg[2] = 0xFFFF_FFFE;
+    cpu.reg[3] = 2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0x7FFF_FFFF);
+}
+
+/// DIVU by zero returns all ones
+#[test]
+fn divu_by_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // DIVU x1, x2, x3
+    let divu_instruction: Word = 0b0000001_00011_00010_101_00001_0110011;
+    mem.store_word(0x0, divu_instruction).unwrap();
+
+    cpu.reg[2] = 55;
+    cpu.reg[3] = 0;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFF);
+}
+
+/* -------------------- REM / REMU tests -------------------- */
+
+/// REM takes the sign of the dividend
+#[test]
+fn rem_sign_follows_dividend() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // REM x1, x2, x3
+    let rem_instruction: Word = 0b0000001_00011_00010_110_00001_0110011;
+    mem.store_word(0x0, rem_instruction).unwrap();
+
+    cpu.reg[2] = (-7i32) as u32;
+    cpu.reg[3] = 2;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1] as i32, -1);
+}
+
+/// REM by zero returns the dividend
+#[test]
+fn rem_by_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // REM x1, x2, x3
+    let rem_instruction: Word = 0b0000001_00011_00010_110_00001_0110011;
+    mem.store_word(0x0, rem_instruction).unwrap();
+
+    cpu.reg[2] = (-9i32) as u32;
+    cpu.reg[3] = 0;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1] as i32, -9);
+}
+
+/// REM overflow (MIN % -1) returns 0
+#[test]
+fn rem_signed_overflow() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // REM x1, x2, x3
+    let rem_instruction: Word = 0b0000001_00011_00010_110_00001_0110011;
+    mem.store_word(0x0, rem_instruction).unwrap();
+
+    cpu.reg[1] = 0x1234;
+    cpu.reg[2] = 0x8000_0000;
+    cpu.reg[3] = (-1i32) as u32;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0);
+}
+
+/// REMU treats operands as unsigned
+#[test]
+fn remu_unsigned_operands() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // REMU x1, x2, x3
+    let remu_instruction: Word = 0b0000001_00011_00010_111_00001_0110011;
+    mem.store_word(0x0, remu_instruction).unwrap();
+
+    cpu.reg[2] = 0xFFFF_FFFF;
+    cpu.reg[3] = 10;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 5); // 4294967295 % 10
+}
+
+/// REMU by zero returns the dividend
+#[test]
+fn remu_by_zero() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // REMU x1, x2, x0
+    let remu_instruction: Word = 0b0000001_00000_00010_111_00001_0110011;
+    mem.store_word(0x0, remu_instruction).unwrap();
+
+    cpu.reg[2] = 0xDEAD_BEEF;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.reg[1], 0xDEAD_BEEF);
+}
+
+/* -------------------- Integration -------------------- */
+
+/// misa reports the M extension
+#[test]
+fn misa_reports_m_extension() {
+    let cpu: RISCV = RISCV::reset();
+    assert_ne!(cpu.csr.read(MISA).unwrap() & (1 << 12), 0);
+}
+
+/// Computes 10! with a MUL loop
+#[test]
+fn factorial_loop() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // 0x00: ADDI x1, x0, 1      (acc)
+    // 0x04: ADDI x2, x0, 10     (n)
+    // 0x08: MUL  x1, x1, x2
+    // 0x0C: ADDI x2, x2, -1
+    // 0x10: BNE  x2, x0, -8
+    mem.store_word(0x00, 0b000000000001_00000_000_00001_0010011)
+        .unwrap();
+    mem.store_word(0x04, 0b000000001010_00000_000_00010_0010011)
+        .unwrap();
+    mem.store_word(0x08, 0b0000001_00010_00001_000_00001_0110011)
+        .unwrap();
+    mem.store_word(0x0C, 0b111111111111_00010_000_00010_0010011)
+        .unwrap();
+    mem.store_word(0x10, 0b1_111111_00000_00010_001_1100_1_1100011)
+        .unwrap();
+
+    while cpu.pc != 0x14 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+
+    assert_eq!(cpu.reg[1], 3628800);
+    assert_eq!(cpu.reg[2], 0);
+}
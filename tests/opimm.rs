@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 #[test]
@@ -7,9 +9,9 @@ fn addi_instruction_fetch() {
 
     // Example instruction: ADDI x1, x0, 10 (opcode for OPIMM is 0b0010011)
     let addi_instruction: Word = 0b00000000001000000000000010010011; // This is the binary representation of the instruction
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, addi_instruction); // The fetched instruction should match the stored one
 }
 
@@ -20,13 +22,13 @@ fn addi_regular_operation() {
 
     // Example instruction: ADDI x1, x2, 2
     let addi_instruction: Word = 0b0000000_00010_00010_000_00001_0010011;
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
     // set register x2 to 8
     cpu.reg[2] = 8;
 
     // Execute the instruction at 0x0
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution, register x1 should contain 10
     assert_eq!(cpu.reg[1], 10);
@@ -39,10 +41,10 @@ fn addi_with_reg_0() {
 
     // Example instruction: ADDI x1, x0, 3 (like MOV x1, 3)
     let addi_instruction: Word = 0b0000000_00011_00000_000_00001_0010011;
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
     // Execute the instruction at 0x0
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution, register x1 should contain 3
     assert_eq!(cpu.reg[1], 3);
@@ -55,10 +57,10 @@ fn addi_with_all_zeros_reg_0() {
 
     // Example instruction: ADDI x31, x0, 0 (like MOV x31, 0)
     let addi_instruction: Word = 0b0000000_00000_00000_000_11111_0010011;
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
     // Execute the instruction at 0x0
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution, register x31 should contain 0
     assert_eq!(cpu.reg[1], 0);
@@ -71,10 +73,10 @@ fn addi_with_min_neg_imm_value() {
 
     // Example instruction: ADDI x2, x0, -2048
     let addi_instruction: Word = 0b1000000_00000_00000_000_00010_0010011;
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
     // Execute the instruction at 0x0
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution, register x2 should contain -2048 (4294965248 as u32)
     assert_eq!(cpu.reg[2], 4294965248);
@@ -87,13 +89,13 @@ fn addi_with_all_ones_in_reg() {
 
     // Example instruction: ADDI x2, x1, 2
     let addi_instruction: Word = 0b0000000_00010_00001_000_00010_0010011;
-    mem.store_word(0x0, addi_instruction); // Store the instruction at address 0
+    mem.store_word(0x0, addi_instruction).unwrap(); // Store the instruction at address 0
 
     // set register x1 to all ones (u32::MAX)
     cpu.reg[1] = u32::MAX;
 
     // Execute the instruction at 0x0
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // After execution, register x2 should contain 1 (wrapping around)
     assert_eq!(cpu.reg[2], 1);
@@ -107,9 +109,9 @@ fn slti_instruction_fetch() {
     // Example instruction: SLTI x1, x0, 10 (opcode for OPIMM is 0b0010011, funct3 = 010)
     // Binary fields: imm=000000001010, rs1=00000, funct3=010, rd=00001, opcode=0010011
     let slti_instruction: Word = 0b000000001010_00000_010_00001_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, slti_instruction);
 }
 
@@ -120,11 +122,11 @@ fn slti_regular_less_than() {
 
     // SLTI x1, x2, 10  -> if (x2 < 10) then x1 = 1 else x1 = 0
     let slti_instruction: Word = 0b000000001010_00010_010_00001_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
     cpu.reg[2] = 5; // x2 = 5 < 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1); // should set
 }
@@ -136,11 +138,11 @@ fn slti_regular_not_less_than() {
 
     // SLTI x1, x2, 10
     let slti_instruction: Word = 0b000000001010_00010_010_00001_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
     cpu.reg[2] = 20; // x2 = 20 >= 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0);
 }
@@ -152,17 +154,17 @@ fn slti_with_negative_immediate() {
 
     // SLTI x3, x4, -1 (imm=0xFFF, 12-bit two’s complement)
     let slti_instruction: Word = 0b111111111111_00100_010_00011_0010011;
-    mem.store_word(0x0, slti_instruction);
-    mem.store_word(0x4, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
+    mem.store_word(0x4, slti_instruction).unwrap();
 
     cpu.reg[4] = 0; // 0 < -1 ? false
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[3], 0);
 
     // Try another case: x4 = -2 (signed)
     cpu.reg[4] = (-2i32) as u32;
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[3], 1);
 }
 
@@ -173,9 +175,9 @@ fn slti_with_reg_0() {
 
     // SLTI x1, x0, 5 → compare 0 < 5 → true → x1 = 1
     let slti_instruction: Word = 0b000000000101_00000_010_00001_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1);
 }
@@ -187,12 +189,12 @@ fn slti_with_negative_register_value() {
 
     // SLTI x5, x6, 1
     let slti_instruction: Word = 0b000000000001_00110_010_00101_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
     // x6 = -10 (signed)
     cpu.reg[6] = (-10i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -10 < 1 → true
     assert_eq!(cpu.reg[5], 1);
@@ -205,11 +207,11 @@ fn slti_with_same_value_as_immediate() {
 
     // SLTI x2, x3, 5
     let slti_instruction: Word = 0b000000000101_00011_010_00010_0010011;
-    mem.store_word(0x0, slti_instruction);
+    mem.store_word(0x0, slti_instruction).unwrap();
 
     cpu.reg[3] = 5;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 5 < 5 → false
     assert_eq!(cpu.reg[2], 0);
@@ -223,9 +225,9 @@ fn sltiu_instruction_fetch() {
     // Example instruction: SLTIU x1, x0, 10 (opcode 0010011, funct3 = 011)
     // imm = 10, rs1 = 0, funct3 = 011, rd = 1, opcode = 0010011
     let sltiu_instruction: Word = 0b000000001010_00000_011_00001_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, sltiu_instruction);
 }
 
@@ -236,11 +238,11 @@ fn sltiu_regular_less_than_unsigned() {
 
     // SLTIU x1, x2, 10
     let sltiu_instruction: Word = 0b000000001010_00010_011_00001_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     cpu.reg[2] = 5; // 5 < 10 (unsigned)
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1);
 }
@@ -252,11 +254,11 @@ fn sltiu_regular_not_less_than_unsigned() {
 
     // SLTIU x1, x2, 10
     let sltiu_instruction: Word = 0b000000001010_00010_011_00001_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     cpu.reg[2] = 20; // 20 >= 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0);
 }
@@ -268,12 +270,12 @@ fn sltiu_with_negative_register_value_interpreted_unsigned() {
 
     // SLTIU x3, x4, 10
     let sltiu_instruction: Word = 0b000000001010_00100_011_00011_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     // x4 = -1 signed, which is 0xFFFF_FFFF unsigned (large number)
     cpu.reg[4] = (-1i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // As unsigned: 0xFFFF_FFFF > 10 → false
     assert_eq!(cpu.reg[3], 0);
@@ -286,9 +288,9 @@ fn sltiu_with_reg_0() {
 
     // SLTIU x1, x0, 5 → compare 0 < 5 (unsigned) → true
     let sltiu_instruction: Word = 0b000000000101_00000_011_00001_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1);
 }
@@ -300,11 +302,11 @@ fn sltiu_with_equal_value() {
 
     // SLTIU x2, x3, 255
     let sltiu_instruction: Word = 0b000011111111_00011_011_00010_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     cpu.reg[3] = 255;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 255 < 255 (unsigned) → false
     assert_eq!(cpu.reg[2], 0);
@@ -317,12 +319,12 @@ fn sltiu_with_large_unsigned_comparison() {
 
     // SLTIU x5, x6, 0xFFF
     let sltiu_instruction: Word = 0b111111111111_00110_011_00101_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     // x6 = 0xFFFF_FFFE
     cpu.reg[6] = 0xFFFF_FFFE;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // unsigned: 0xFFFF_FFFE < 0xFFFF_FFFF → true
     assert_eq!(cpu.reg[5], 1);
@@ -335,11 +337,11 @@ fn sltiu_with_small_unsigned_register_value() {
 
     // SLTIU x5, x6, 0xFFF
     let sltiu_instruction: Word = 0b111111111111_00110_011_00101_0010011;
-    mem.store_word(0x0, sltiu_instruction);
+    mem.store_word(0x0, sltiu_instruction).unwrap();
 
     cpu.reg[6] = 100;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[5], 1);
 }
@@ -351,9 +353,9 @@ fn andi_instruction_fetch() {
 
     // Example: ANDI x1, x0, 0b1010 (rd=x1, rs1=x0, imm=10)
     let andi_instruction: Word = 0b000000001010_00000_111_00001_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, andi_instruction);
 }
 
@@ -364,11 +366,11 @@ fn andi_basic_operation() {
 
     // ANDI x1, x2, 0b1100
     let andi_instruction: Word = 0b000000001100_00010_111_00001_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
     cpu.reg[2] = 0b1010; // 10 decimal
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0b1010 & 0b1100 = 0b1000 (8)
     assert_eq!(cpu.reg[1], 0b1000);
@@ -381,9 +383,9 @@ fn andi_with_reg_0() {
 
     // ANDI x1, x0, 0b1111
     let andi_instruction: Word = 0b000000001111_00000_111_00001_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 = 0, so result = 0 & imm = 0
     assert_eq!(cpu.reg[1], 0);
@@ -396,11 +398,11 @@ fn andi_with_negative_immediate() {
 
     // ANDI x1, x2, -4 (0xFFF...C 12-bit sign-extended)
     let andi_instruction: Word = 0b111111111100_00010_111_00001_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
     cpu.reg[2] = 0b1010; // 10 decimal
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -4 = 0xFFFF_FFFC, 0b1010 & 0xFFFF_FFFC = 0b1000 (8)
     assert_eq!(cpu.reg[1], 0b1000);
@@ -413,11 +415,11 @@ fn andi_with_all_ones_register() {
 
     // ANDI x3, x1, 0b1010
     let andi_instruction: Word = 0b000000001010_00001_111_00011_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
     cpu.reg[1] = u32::MAX; // all ones
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // u32::MAX & 0b1010 = 0b1010
     assert_eq!(cpu.reg[3], 0b1010);
@@ -430,11 +432,11 @@ fn andi_with_zero_immediate() {
 
     // ANDI x2, x1, 0
     let andi_instruction: Word = 0b000000000000_00001_111_00010_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
     cpu.reg[1] = 0b1101;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Any number & 0 = 0
     assert_eq!(cpu.reg[2], 0);
@@ -447,11 +449,11 @@ fn andi_with_all_ones_immediate() {
 
     // ANDI x2, x1, -1 (0xFFF sign-extended = 0xFFFF_FFFF)
     let andi_instruction: Word = 0b111111111111_00001_111_00010_0010011;
-    mem.store_word(0x0, andi_instruction);
+    mem.store_word(0x0, andi_instruction).unwrap();
 
     cpu.reg[1] = 0b101010;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x1 & -1 = x1
     assert_eq!(cpu.reg[2], 0b101010);
@@ -464,9 +466,9 @@ fn ori_instruction_fetch() {
 
     // Example: ORI x1, x0, 0b1010 (rd=x1, rs1=x0, imm=10)
     let ori_instruction: Word = 0b000000001010_00000_110_00001_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, ori_instruction);
 }
 
@@ -477,11 +479,11 @@ fn ori_basic_operation() {
 
     // ORI x1, x2, 0b1100
     let ori_instruction: Word = 0b000000001100_00010_110_00001_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
     cpu.reg[2] = 0b1010; // 10 decimal
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0b1010 | 0b1100 = 0b1110 (14)
     assert_eq!(cpu.reg[1], 0b1110);
@@ -494,9 +496,9 @@ fn ori_with_reg_0() {
 
     // ORI x1, x0, 0b1111
     let ori_instruction: Word = 0b000000001111_00000_110_00001_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 = 0, so result = 0 | imm = 0b1111
     assert_eq!(cpu.reg[1], 0b1111);
@@ -509,11 +511,11 @@ fn ori_with_negative_immediate() {
 
     // ORI x1, x2, -4 (0xFFF...C 12-bit sign-extended)
     let ori_instruction: Word = 0b111111111100_00010_110_00001_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
     cpu.reg[2] = 0b1010; // 10 decimal
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -4 = 0xFFFF_FFFC, 0b1010 | 0xFFFF_FFFC = 0xFFFF_FFFE
     assert_eq!(cpu.reg[1], 0xFFFF_FFFE);
@@ -526,11 +528,11 @@ fn ori_with_all_ones_register() {
 
     // ORI x3, x1, 0b1010
     let ori_instruction: Word = 0b000000001010_00001_110_00011_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
     cpu.reg[1] = u32::MAX; // all ones
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // all ones | 0b1010 = all ones
     assert_eq!(cpu.reg[3], u32::MAX);
@@ -543,11 +545,11 @@ fn ori_with_zero_immediate() {
 
     // ORI x2, x1, 0
     let ori_instruction: Word = 0b000000000000_00001_110_00010_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
     cpu.reg[1] = 0b1101;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x1 | 0 = x1
     assert_eq!(cpu.reg[2], 0b1101);
@@ -560,11 +562,11 @@ fn ori_with_all_ones_immediate() {
 
     // ORI x2, x1, -1 (0xFFF sign-extended = 0xFFFF_FFFF)
     let ori_instruction: Word = 0b111111111111_00001_110_00010_0010011;
-    mem.store_word(0x0, ori_instruction);
+    mem.store_word(0x0, ori_instruction).unwrap();
 
     cpu.reg[1] = 0b101010;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x1 | -1 = -1 (all ones)
     assert_eq!(cpu.reg[2], u32::MAX);
@@ -577,9 +579,9 @@ fn xori_instruction_fetch() {
 
     // Example instruction: XORI x1, x0, 5
     let xori_instruction: Word = 0b000000000101_00000_100_00001_0010011; // imm=5, rs1=x0, funct3=100, rd=x1, opcode=0010011
-    mem.store_word(0x0, xori_instruction);
+    mem.store_word(0x0, xori_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, xori_instruction);
 }
 
@@ -590,11 +592,11 @@ fn xori_regular_operation() {
 
     // XORI x3, x2, 0xF0F
     let xori_instruction: Word = 0b111100001111_00010_100_00011_0010011;
-    mem.store_word(0x0, xori_instruction);
+    mem.store_word(0x0, xori_instruction).unwrap();
 
     cpu.reg[2] = 0xAAAA_AAAA; // rs1 = 0xAAAA_AAAA
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected: 0xAAAA_AAAA XOR 0xF0F0 = 0xAAAA_5A5A
     assert_eq!(cpu.reg[3], 0xAAAA_AAAA ^ 0xFFFF_FF0F);
@@ -607,9 +609,9 @@ fn xori_with_zero_register() {
 
     // XORI x1, x0, 0xFF (should just set x1 = 0xFF)
     let xori_instruction: Word = 0b000011111111_00000_100_00001_0010011;
-    mem.store_word(0x0, xori_instruction);
+    mem.store_word(0x0, xori_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0xFF);
 }
@@ -621,11 +623,11 @@ fn xori_negative_immediate() {
 
     // XORI x1, x2, -1 (imm = 0xFFF in 12-bit two's complement)
     let xori_instruction: Word = 0b111111111111_00010_100_00001_0010011;
-    mem.store_word(0x0, xori_instruction);
+    mem.store_word(0x0, xori_instruction).unwrap();
 
     cpu.reg[2] = 0x12345678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected: x1 = x2 XOR 0xFFFF_FFFF = bitwise NOT of x2
     assert_eq!(cpu.reg[1], !0x12345678);
@@ -638,11 +640,11 @@ fn xori_all_ones_register() {
 
     // XORI x4, x1, 0xF0
     let xori_instruction: Word = 0b000011110000_00001_100_00100_0010011;
-    mem.store_word(0x0, xori_instruction);
+    mem.store_word(0x0, xori_instruction).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected: 0xFFFF_FFFF XOR 0x0000_00F0 = 0xFFFF_FF0F
     assert_eq!(cpu.reg[4], 0xFFFF_FF0F);
@@ -655,11 +657,11 @@ fn not_pseudo_instruction() {
 
     // NOT x5, x6  ==  XORI x5, x6, -1
     let not_instruction: Word = 0b111111111111_00110_100_00101_0010011;
-    mem.store_word(0x0, not_instruction);
+    mem.store_word(0x0, not_instruction).unwrap();
 
     cpu.reg[6] = 0xDEADBEEF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected: x5 = ~x6
     assert_eq!(cpu.reg[5], !0xDEADBEEF);
@@ -674,9 +676,9 @@ fn slli_instruction_fetch() {
     // Example instruction: SLLI x1, x2, 3
     // Format: funct7(0000000), shamt(00011), rs1(00010), funct3(001), rd(00001), opcode(0010011)
     let slli_instruction: Word = 0b0000000_00011_00010_001_00001_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, slli_instruction);
 }
 
@@ -688,11 +690,11 @@ fn slli_basic_operation() {
 
     // SLLI x1, x2, 2  -> x1 = x2 << 2
     let slli_instruction: Word = 0b0000000_00010_00010_001_00001_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[2] = 5; // 0b0101
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 5 << 2 = 20
     assert_eq!(cpu.reg[1], 20);
@@ -706,11 +708,11 @@ fn slli_with_zero_shift() {
 
     // SLLI x3, x4, 0
     let slli_instruction: Word = 0b0000000_00000_00100_001_00011_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[4] = 0x12345678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Shift by 0 → unchanged
     assert_eq!(cpu.reg[3], 0x12345678);
@@ -724,11 +726,11 @@ fn slli_with_large_shift_amount() {
 
     // SLLI x5, x6, 31
     let slli_instruction: Word = 0b0000000_11111_00110_001_00101_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[6] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1 << 31 = 0x80000000
     assert_eq!(cpu.reg[5], 0x80000000);
@@ -742,11 +744,11 @@ fn slli_small_shift_behavior() {
 
     // SLLI x7, x8, 4
     let slli_instruction: Word = 0b0000000_00100_01000_001_00111_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[8] = 0x0000_00F0; // 240
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xF0 << 4 = 0xF00
     assert_eq!(cpu.reg[7], 0x0000_0F00);
@@ -760,9 +762,9 @@ fn slli_with_reg_0() {
 
     // SLLI x1, x0, 5
     let slli_instruction: Word = 0b0000000_00101_00000_001_00001_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 << anything = 0
     assert_eq!(cpu.reg[1], 0);
@@ -776,11 +778,11 @@ fn slli_with_all_ones_register() {
 
     // SLLI x3, x1, 1
     let slli_instruction: Word = 0b0000000_00001_00001_001_00011_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[1] = u32::MAX; // 0xFFFF_FFFF
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Shift left 1: drops top bit, becomes 0xFFFF_FFFE
     assert_eq!(cpu.reg[3], 0xFFFF_FFFE);
@@ -794,12 +796,12 @@ fn slli_logical_shift_behavior() {
 
     // SLLI x2, x3, 1
     let slli_instruction: Word = 0b0000000_00001_00011_001_00010_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     // x3 = 0x80000000 (MSB set)
     cpu.reg[3] = 0x80000000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical shift left by 1 = 0x00000000 (MSB bit shifted out)
     assert_eq!(cpu.reg[2], 0x00000000);
@@ -813,11 +815,11 @@ fn slli_mid_shift_example() {
 
     // SLLI x10, x11, 8
     let slli_instruction: Word = 0b0000000_01000_01011_001_01010_0010011;
-    mem.store_word(0x0, slli_instruction);
+    mem.store_word(0x0, slli_instruction).unwrap();
 
     cpu.reg[11] = 0x0000_00AB;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xAB << 8 = 0xAB00
     assert_eq!(cpu.reg[10], 0x0000_AB00);
@@ -832,9 +834,9 @@ fn srli_instruction_fetch() {
     // Example instruction: SRLI x1, x2, 3
     // funct7 = 0000000 (SRLI), shamt = 00011, rs1 = 00010, funct3 = 101, rd = 00001, opcode = 0010011
     let srli_instruction: Word = 0b0000000_00011_00010_101_00001_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, srli_instruction);
 }
 
@@ -846,11 +848,11 @@ fn srli_basic_operation() {
 
     // SRLI x1, x2, 2  -> x1 = x2 >> 2 (logical)
     let srli_instruction: Word = 0b0000000_00010_00010_101_00001_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     cpu.reg[2] = 16; // 0b10000
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 16 >> 2 = 4
     assert_eq!(cpu.reg[1], 4);
@@ -864,11 +866,11 @@ fn srli_with_zero_shift() {
 
     // SRLI x3, x4, 0
     let srli_instruction: Word = 0b0000000_00000_00100_101_00011_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     cpu.reg[4] = 0x12345678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // No shift → unchanged
     assert_eq!(cpu.reg[3], 0x12345678);
@@ -882,11 +884,11 @@ fn srli_with_large_shift_amount() {
 
     // SRLI x5, x6, 31
     let srli_instruction: Word = 0b0000000_11111_00110_101_00101_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     cpu.reg[6] = 0x80000000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical right shift by 31: only LSB remains (1)
     assert_eq!(cpu.reg[5], 1);
@@ -900,12 +902,12 @@ fn srli_logical_shift_behavior() {
 
     // SRLI x2, x3, 1
     let srli_instruction: Word = 0b0000000_00001_00011_101_00010_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     // x3 = 0x80000000 (MSB set)
     cpu.reg[3] = 0x80000000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical shift right → introduces 0s on the left
     // 0x80000000 >> 1 = 0x40000000
@@ -920,11 +922,11 @@ fn srli_with_all_ones_register() {
 
     // SRLI x3, x1, 4
     let srli_instruction: Word = 0b0000000_00100_00001_101_00011_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     cpu.reg[1] = u32::MAX; // 0xFFFF_FFFF
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_FFFF >> 4 = 0x0FFF_FFFF
     assert_eq!(cpu.reg[3], 0x0FFF_FFFF);
@@ -938,9 +940,9 @@ fn srli_with_reg_0() {
 
     // SRLI x1, x0, 10
     let srli_instruction: Word = 0b0000000_01010_00000_101_00001_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 >> anything = 0
     assert_eq!(cpu.reg[1], 0);
@@ -954,11 +956,11 @@ fn srli_small_shift_behavior() {
 
     // SRLI x7, x8, 3
     let srli_instruction: Word = 0b0000000_00011_01000_101_00111_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     cpu.reg[8] = 0b10100000; // 0xA0
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0b10100000 >> 3 = 0b00010100 (0x14)
     assert_eq!(cpu.reg[7], 0x14);
@@ -972,12 +974,12 @@ fn srli_does_not_sign_extend() {
 
     // SRLI x10, x11, 4
     let srli_instruction: Word = 0b0000000_00100_01011_101_01010_0010011;
-    mem.store_word(0x0, srli_instruction);
+    mem.store_word(0x0, srli_instruction).unwrap();
 
     // x11 = 0xF0000000 (high nibble set)
     cpu.reg[11] = 0xF0000000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical right shift by 4 = 0x0F000000 (no sign extension)
     assert_eq!(cpu.reg[10], 0x0F000000);
@@ -992,9 +994,9 @@ fn srai_instruction_fetch() {
     // Example instruction: SRAI x1, x2, 3
     // funct7 = 0100000 (SRAI), shamt = 00011, rs1 = 00010, funct3 = 101, rd = 00001, opcode = 0010011
     let srai_instruction: Word = 0b0100000_00011_00010_101_00001_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, srai_instruction);
 }
 
@@ -1006,11 +1008,11 @@ fn srai_basic_operation() {
 
     // SRAI x1, x2, 2  -> x1 = x2 >> 2 (arithmetic)
     let srai_instruction: Word = 0b0100000_00010_00010_101_00001_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     cpu.reg[2] = 16; // 0b0001_0000 (positive)
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 16 >> 2 = 4
     assert_eq!(cpu.reg[1], 4);
@@ -1024,12 +1026,12 @@ fn srai_preserves_sign_bit() {
 
     // SRAI x3, x4, 1
     let srai_instruction: Word = 0b0100000_00001_00100_101_00011_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     // -8 in 32-bit two's complement = 0xFFFF_FFF8
     cpu.reg[4] = (-8i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Arithmetic right shift by 1: 0xFFFF_FFFC (still negative)
     assert_eq!(cpu.reg[3], 0xFFFF_FFFC);
@@ -1043,11 +1045,11 @@ fn srai_with_zero_shift() {
 
     // SRAI x5, x6, 0
     let srai_instruction: Word = 0b0100000_00000_00110_101_00101_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     cpu.reg[6] = 0x12345678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Shift by 0 → unchanged
     assert_eq!(cpu.reg[5], 0x12345678);
@@ -1061,17 +1063,17 @@ fn srai_with_large_shift_amount() {
 
     // SRAI x7, x8, 31
     let srai_instruction: Word = 0b0100000_11111_01000_101_00111_0010011;
-    mem.store_word(0x0, srai_instruction);
-    mem.store_word(0x4, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
+    mem.store_word(0x4, srai_instruction).unwrap();
 
     // Positive number
     cpu.reg[8] = 0x40000000;
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[7], 0x0);
 
     // Negative number
     cpu.reg[8] = 0x80000000;
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[7], 0xFFFFFFFF);
 }
 
@@ -1083,12 +1085,12 @@ fn srai_sign_extension_small_shift() {
 
     // SRAI x9, x10, 3
     let srai_instruction: Word = 0b0100000_00011_01010_101_01001_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     // -16 (0xFFFFFFF0)
     cpu.reg[10] = (-16i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Arithmetic shift right 3: expected -2 (0xFFFFFFFE)
     assert_eq!(cpu.reg[9], 0xFFFFFFFE);
@@ -1102,11 +1104,11 @@ fn srai_with_all_ones_register() {
 
     // SRAI x11, x1, 4
     let srai_instruction: Word = 0b0100000_00100_00001_101_01011_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     cpu.reg[1] = u32::MAX; // all ones (-1)
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Arithmetic right shift keeps -1 (sign bit stays)
     assert_eq!(cpu.reg[11], u32::MAX);
@@ -1120,9 +1122,9 @@ fn srai_with_reg_0() {
 
     // SRAI x1, x0, 10
     let srai_instruction: Word = 0b0100000_01010_00000_101_00001_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0);
 }
@@ -1135,17 +1137,17 @@ fn srai_mixed_sign_behavior() {
 
     // SRAI x12, x13, 4
     let srai_instruction: Word = 0b0100000_00100_01101_101_01100_0010011;
-    mem.store_word(0x0, srai_instruction);
-    mem.store_word(0x4, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
+    mem.store_word(0x4, srai_instruction).unwrap();
 
     // Case 1: Positive
     cpu.reg[13] = 0x0000_F000;
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[12], 0x00000F00);
 
     // Case 2: Negative
     cpu.reg[13] = (-4096i32) as u32; // 0xFFFF_F000
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.reg[12], 0xFFFF_FF00);
 }
 
@@ -1157,12 +1159,12 @@ fn srai_multiple_sign_extension_bits() {
 
     // SRAI x14, x15, 8
     let srai_instruction: Word = 0b0100000_01000_01111_101_01110_0010011;
-    mem.store_word(0x0, srai_instruction);
+    mem.store_word(0x0, srai_instruction).unwrap();
 
     // Negative number
     cpu.reg[15] = 0xFFFF_0000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Right shift by 8 → should stay negative
     // Expected: 0xFFFFFF00
@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 /// Test that an ADD instruction can be fetched correctly
@@ -9,9 +11,9 @@ fn add_instruction_fetch() {
     // Example: ADD x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=000, rd=00001, opcode=0110011
     let add_instruction: Word = 0b0000000_00011_00010_000_00001_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, add_instruction);
 }
 
@@ -23,12 +25,12 @@ fn add_basic_operation() {
 
     // ADD x1, x2, x3
     let add_instruction: Word = 0b0000000_00011_00010_000_00001_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     cpu.reg[2] = 5; // x2
     cpu.reg[3] = 7; // x3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 5 + 7 = 12
     assert_eq!(cpu.reg[1], 12);
@@ -42,13 +44,13 @@ fn add_with_negative_operands() {
 
     // ADD x4, x5, x6
     let add_instruction: Word = 0b0000000_00110_00101_000_00100_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     // x5 = -10, x6 = 3
     cpu.reg[5] = (-10i32) as u32;
     cpu.reg[6] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected result: -7
     assert_eq!(cpu.reg[4] as i32, -7);
@@ -62,13 +64,13 @@ fn add_two_negative_numbers() {
 
     // ADD x7, x8, x9
     let add_instruction: Word = 0b0000000_01001_01000_000_00111_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     // x8 = -5, x9 = -7
     cpu.reg[8] = (-5i32) as u32;
     cpu.reg[9] = (-7i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -5 + -7 = -12
     assert_eq!(cpu.reg[7] as i32, -12);
@@ -82,13 +84,13 @@ fn add_overflow_wraps_around() {
 
     // ADD x10, x11, x12
     let add_instruction: Word = 0b0000000_01100_01011_000_01010_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     // x11 = u32::MAX, x12 = 1
     cpu.reg[11] = u32::MAX;
     cpu.reg[12] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Overflow wraps around: 0xFFFF_FFFF + 1 = 0
     assert_eq!(cpu.reg[10], 0);
@@ -102,11 +104,11 @@ fn add_with_x0_operand() {
 
     // ADD x1, x0, x2 → x1 = 0 + x2 = x2
     let add_instruction: Word = 0b0000000_00010_00000_000_00001_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     cpu.reg[2] = 99;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 99);
 }
@@ -119,12 +121,12 @@ fn add_write_to_x0_is_ignored() {
 
     // ADD x0, x1, x2
     let add_instruction: Word = 0b0000000_00010_00001_000_00000_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     cpu.reg[1] = 42;
     cpu.reg[2] = 58;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 is hardwired to zero
     assert_eq!(cpu.reg[0], 0);
@@ -138,12 +140,12 @@ fn add_with_large_values() {
 
     // ADD x3, x4, x5
     let add_instruction: Word = 0b0000000_00101_00100_000_00011_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     cpu.reg[4] = 0xFFFF_0000;
     cpu.reg[5] = 0x0000_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_0000 + 0x0000_FFFF = 0xFFFF_FFFF
     assert_eq!(cpu.reg[3], 0xFFFF_FFFF);
@@ -157,11 +159,11 @@ fn add_with_same_source_registers() {
 
     // ADD x1, x1, x1 (effectively x1 = x1 * 2)
     let add_instruction: Word = 0b0000000_00001_00001_000_00001_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
     cpu.reg[1] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 246);
 }
@@ -174,9 +176,9 @@ fn add_all_zeros() {
 
     // ADD x31, x0, x0
     let add_instruction: Word = 0b0000000_00000_00000_000_11111_0110011;
-    mem.store_word(0x0, add_instruction);
+    mem.store_word(0x0, add_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -190,9 +192,9 @@ fn sub_instruction_fetch() {
     // Example: SUB x1, x2, x3
     // funct7=0100000, rs2=00011, rs1=00010, funct3=000, rd=00001, opcode=0110011
     let sub_instruction: Word = 0b0100000_00011_00010_000_00001_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, sub_instruction);
 }
 
@@ -204,12 +206,12 @@ fn sub_basic_operation() {
 
     // SUB x1, x2, x3
     let sub_instruction: Word = 0b0100000_00011_00010_000_00001_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     cpu.reg[2] = 10; // x2
     cpu.reg[3] = 4; // x3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 10 - 4 = 6
     assert_eq!(cpu.reg[1], 6);
@@ -223,13 +225,13 @@ fn sub_with_negative_operands() {
 
     // SUB x4, x5, x6
     let sub_instruction: Word = 0b0100000_00110_00101_000_00100_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     // x5 = -10, x6 = 3
     cpu.reg[5] = (-10i32) as u32;
     cpu.reg[6] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Expected: -10 - 3 = -13
     assert_eq!(cpu.reg[4] as i32, -13);
@@ -243,13 +245,13 @@ fn sub_two_negative_numbers() {
 
     // SUB x7, x8, x9
     let sub_instruction: Word = 0b0100000_01001_01000_000_00111_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     // x8 = -5, x9 = -7
     cpu.reg[8] = (-5i32) as u32;
     cpu.reg[9] = (-7i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -5 - (-7) = 2
     assert_eq!(cpu.reg[7] as i32, 2);
@@ -263,13 +265,13 @@ fn sub_underflow_wraps_around() {
 
     // SUB x10, x11, x12
     let sub_instruction: Word = 0b0100000_01100_01011_000_01010_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     // x11 = 0, x12 = 1
     cpu.reg[11] = 0;
     cpu.reg[12] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 - 1 wraps to 0xFFFF_FFFF
     assert_eq!(cpu.reg[10], 0xFFFF_FFFF);
@@ -283,11 +285,11 @@ fn sub_with_x0_operand() {
 
     // SUB x1, x0, x2 → x1 = 0 - x2 = -x2
     let sub_instruction: Word = 0b0100000_00010_00000_000_00001_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     cpu.reg[2] = 99;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1] as i32, -99);
 }
@@ -300,12 +302,12 @@ fn sub_write_to_x0_is_ignored() {
 
     // SUB x0, x1, x2
     let sub_instruction: Word = 0b0100000_00010_00001_000_00000_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     cpu.reg[1] = 42;
     cpu.reg[2] = 58;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 must stay zero
     assert_eq!(cpu.reg[0], 0);
@@ -320,12 +322,12 @@ fn sub_with_large_values() {
     // SUB x3, x4, x5
     // Correction: rs1 = x4 (00100), rs2 = x5 (00101)
     let sub_instruction: Word = 0b0100000_00101_00100_000_00011_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     cpu.reg[4] = 0x0000_FFFF;
     cpu.reg[5] = 0xFFFF_0000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x0000_FFFF - 0xFFFF_0000 = 0x0001_FFFF (wrap-around result)
     assert_eq!(cpu.reg[3], 0x0001_FFFF);
@@ -339,11 +341,11 @@ fn sub_with_same_source_registers() {
 
     // SUB x1, x1, x1 → x1 = 0
     let sub_instruction: Word = 0b0100000_00001_00001_000_00001_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
     cpu.reg[1] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0);
 }
@@ -356,9 +358,9 @@ fn sub_all_zeros() {
 
     // SUB x31, x0, x0 → 0 - 0 = 0
     let sub_instruction: Word = 0b0100000_00000_00000_000_11111_0110011;
-    mem.store_word(0x0, sub_instruction);
+    mem.store_word(0x0, sub_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -372,9 +374,9 @@ fn slt_instruction_fetch() {
     // Example: SLT x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=010, rd=00001, opcode=0110011
     let slt_instruction: Word = 0b0000000_00011_00010_010_00001_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, slt_instruction);
 }
 
@@ -386,12 +388,12 @@ fn slt_basic_less_than() {
 
     // SLT x1, x2, x3 → x1 = (x2 < x3) ? 1 : 0
     let slt_instruction: Word = 0b0000000_00011_00010_010_00001_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[2] = 5; // x2
     cpu.reg[3] = 7; // x3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 5 < 7 → x1 = 1
     assert_eq!(cpu.reg[1], 1);
@@ -405,12 +407,12 @@ fn slt_greater_than() {
 
     // SLT x4, x5, x6
     let slt_instruction: Word = 0b0000000_00110_00101_010_00100_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[5] = 9;
     cpu.reg[6] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 9 < 3 → false → 0
     assert_eq!(cpu.reg[4], 0);
@@ -424,12 +426,12 @@ fn slt_equal_operands() {
 
     // SLT x7, x8, x9
     let slt_instruction: Word = 0b0000000_01001_01000_010_00111_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[8] = 42;
     cpu.reg[9] = 42;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 42 < 42 → false
     assert_eq!(cpu.reg[7], 0);
@@ -443,12 +445,12 @@ fn slt_with_negative_operands() {
 
     // SLT x10, x11, x12
     let slt_instruction: Word = 0b0000000_01100_01011_010_01010_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[11] = (-5i32) as u32; // x11 = -5
     cpu.reg[12] = 3; // x12 = 3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -5 < 3 → true
     assert_eq!(cpu.reg[10], 1);
@@ -462,12 +464,12 @@ fn slt_two_negative_numbers() {
 
     // SLT x13, x14, x15
     let slt_instruction: Word = 0b0000000_01111_01110_010_01101_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[14] = (-10i32) as u32; // x14 = -10
     cpu.reg[15] = (-3i32) as u32; // x15 = -3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -10 < -3 → true
     assert_eq!(cpu.reg[13], 1);
@@ -481,12 +483,12 @@ fn slt_negative_vs_positive() {
 
     // SLT x16, x17, x18
     let slt_instruction: Word = 0b0000000_10010_10001_010_10000_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[17] = (-1i32) as u32;
     cpu.reg[18] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[16], 1);
 }
@@ -499,12 +501,12 @@ fn slt_positive_vs_negative() {
 
     // SLT x19, x20, x21
     let slt_instruction: Word = 0b0000000_10101_10100_010_10011_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[20] = 5;
     cpu.reg[21] = (-5i32) as u32;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[19], 0);
 }
@@ -517,11 +519,11 @@ fn slt_zero_vs_positive() {
 
     // SLT x1, x0, x2 → x1 = (0 < x2)
     let slt_instruction: Word = 0b0000000_00010_00000_010_00001_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[2] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1);
 }
@@ -534,12 +536,12 @@ fn slt_write_to_x0_is_ignored() {
 
     // SLT x0, x1, x2
     let slt_instruction: Word = 0b0000000_00010_00001_010_00000_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[1] = 10;
     cpu.reg[2] = 20;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
 }
@@ -552,11 +554,11 @@ fn slt_same_registers() {
 
     // SLT x3, x3, x3
     let slt_instruction: Word = 0b0000000_00011_00011_010_00011_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[3] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[3], 0);
 }
@@ -569,12 +571,12 @@ fn slt_large_values_signed_compare() {
 
     // SLT x5, x6, x7
     let slt_instruction: Word = 0b0000000_00111_00110_010_00101_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
     cpu.reg[6] = 0x8000_0000; // -2147483648 in signed
     cpu.reg[7] = 0x7FFF_FFFF; //  2147483647 in signed
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -2147483648 < 2147483647 → true
     assert_eq!(cpu.reg[5], 1);
@@ -588,9 +590,9 @@ fn slt_all_zeros() {
 
     // SLT x31, x0, x0 → 0 < 0 = false
     let slt_instruction: Word = 0b0000000_00000_00000_010_11111_0110011;
-    mem.store_word(0x0, slt_instruction);
+    mem.store_word(0x0, slt_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -604,9 +606,9 @@ fn sltu_instruction_fetch() {
     // Example: SLTU x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=011, rd=00001, opcode=0110011
     let sltu_instruction: Word = 0b0000000_00011_00010_011_00001_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, sltu_instruction);
 }
 
@@ -618,12 +620,12 @@ fn sltu_basic_less_than() {
 
     // SLTU x1, x2, x3 → x1 = (x2 < x3) ? 1 : 0
     let sltu_instruction: Word = 0b0000000_00011_00010_011_00001_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[2] = 5;
     cpu.reg[3] = 7;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 5 < 7 → true
     assert_eq!(cpu.reg[1], 1);
@@ -637,12 +639,12 @@ fn sltu_greater_than() {
 
     // SLTU x4, x5, x6
     let sltu_instruction: Word = 0b0000000_00110_00101_011_00100_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[5] = 9;
     cpu.reg[6] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[4], 0);
 }
@@ -655,12 +657,12 @@ fn sltu_equal_operands() {
 
     // SLTU x7, x8, x9
     let sltu_instruction: Word = 0b0000000_01001_01000_011_00111_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[8] = 42;
     cpu.reg[9] = 42;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[7], 0);
 }
@@ -673,13 +675,13 @@ fn sltu_signed_negative_operands() {
 
     // SLTU x10, x11, x12
     let sltu_instruction: Word = 0b0000000_01100_01011_011_01010_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     // x11 = -1 (0xFFFF_FFFF), x12 = 3
     cpu.reg[11] = (-1i32) as u32;
     cpu.reg[12] = 3;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Unsigned compare: 0xFFFF_FFFF < 3 → false
     assert_eq!(cpu.reg[10], 0);
@@ -693,12 +695,12 @@ fn sltu_unsigned_less_than_large_value() {
 
     // SLTU x13, x14, x15
     let sltu_instruction: Word = 0b0000000_01111_01110_011_01101_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[14] = 1;
     cpu.reg[15] = 0xFFFF_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[13], 1);
 }
@@ -711,11 +713,11 @@ fn sltu_zero_vs_positive() {
 
     // SLTU x1, x0, x2
     let sltu_instruction: Word = 0b0000000_00010_00000_011_00001_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[2] = 123;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 1);
 }
@@ -728,11 +730,11 @@ fn sltu_positive_vs_zero() {
 
     // SLTU x3, x2, x0
     let sltu_instruction: Word = 0b0000000_00000_00010_011_00011_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[2] = 50;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 50 < 0 → false
     assert_eq!(cpu.reg[3], 0);
@@ -746,12 +748,12 @@ fn sltu_write_to_x0_is_ignored() {
 
     // SLTU x0, x1, x2
     let sltu_instruction: Word = 0b0000000_00010_00001_011_00000_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[1] = 1;
     cpu.reg[2] = 2;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
 }
@@ -764,11 +766,11 @@ fn sltu_same_registers() {
 
     // SLTU x5, x5, x5
     let sltu_instruction: Word = 0b0000000_00101_00101_011_00101_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[5] = 999;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[5], 0);
 }
@@ -781,12 +783,12 @@ fn sltu_large_unsigned_compare() {
 
     // SLTU x6, x7, x8
     let sltu_instruction: Word = 0b0000000_01000_00111_011_00110_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[7] = 0x7FFF_FFFF;
     cpu.reg[8] = 0x8000_0000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Unsigned: 0x7FFFFFFF < 0x80000000 → true
     assert_eq!(cpu.reg[6], 1);
@@ -800,12 +802,12 @@ fn sltu_large_reversed_unsigned_compare() {
 
     // SLTU x9, x10, x11
     let sltu_instruction: Word = 0b0000000_01011_01010_011_01001_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
     cpu.reg[10] = 0x8000_0000;
     cpu.reg[11] = 0x7FFF_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Unsigned: 0x80000000 < 0x7FFFFFFF → false
     assert_eq!(cpu.reg[9], 0);
@@ -819,9 +821,9 @@ fn sltu_all_zeros() {
 
     // SLTU x31, x0, x0 → 0 < 0 = false
     let sltu_instruction: Word = 0b0000000_00000_00000_011_11111_0110011;
-    mem.store_word(0x0, sltu_instruction);
+    mem.store_word(0x0, sltu_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -835,9 +837,9 @@ fn and_instruction_fetch() {
     // Example: AND x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=111, rd=00001, opcode=0110011
     let and_instruction: Word = 0b0000000_00011_00010_111_00001_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, and_instruction);
 }
 
@@ -849,12 +851,12 @@ fn and_basic_operation() {
 
     // AND x1, x2, x3
     let and_instruction: Word = 0b0000000_00011_00010_111_00001_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[2] = 0b1100; // x2 = 12
     cpu.reg[3] = 0b1010; // x3 = 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1100 & 1010 = 1000 (8)
     assert_eq!(cpu.reg[1], 0b1000);
@@ -868,12 +870,12 @@ fn and_with_all_bits_set() {
 
     // AND x4, x5, x6
     let and_instruction: Word = 0b0000000_00110_00101_111_00100_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[5] = 0xFFFF_FFFF;
     cpu.reg[6] = 0x1234_5678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_FFFF & 0x12345678 = 0x12345678
     assert_eq!(cpu.reg[4], 0x1234_5678);
@@ -887,12 +889,12 @@ fn and_with_zeros() {
 
     // AND x7, x8, x9
     let and_instruction: Word = 0b0000000_01001_01000_111_00111_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[8] = 0xFFFF_FFFF;
     cpu.reg[9] = 0x0000_0000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // anything & 0 = 0
     assert_eq!(cpu.reg[7], 0);
@@ -906,11 +908,11 @@ fn and_same_operands() {
 
     // AND x10, x11, x11
     let and_instruction: Word = 0b0000000_01011_01011_111_01010_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[11] = 0xDEAD_BEEF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x11 & x11 = x11
     assert_eq!(cpu.reg[10], 0xDEAD_BEEF);
@@ -924,12 +926,12 @@ fn and_alternating_bit_patterns() {
 
     // AND x12, x13, x14
     let and_instruction: Word = 0b0000000_01110_01101_111_01100_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[13] = 0xAAAA_AAAA; // 1010...
     cpu.reg[14] = 0x5555_5555; // 0101...
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1010... & 0101... = 0000...
     assert_eq!(cpu.reg[12], 0x0000_0000);
@@ -943,11 +945,11 @@ fn and_with_x0_operand() {
 
     // AND x1, x0, x2 → x1 = 0 & x2 = 0
     let and_instruction: Word = 0b0000000_00010_00000_111_00001_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[2] = 0xFFFF_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0);
 }
@@ -960,12 +962,12 @@ fn and_write_to_x0_is_ignored() {
 
     // AND x0, x1, x2
     let and_instruction: Word = 0b0000000_00010_00001_111_00000_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[1] = 0xAAAA_AAAA;
     cpu.reg[2] = 0x5555_5555;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
 }
@@ -978,12 +980,12 @@ fn and_large_values() {
 
     // AND x3, x4, x5
     let and_instruction: Word = 0b0000000_00101_00100_111_00011_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
     cpu.reg[4] = 0x1234_ABCD;
     cpu.reg[5] = 0xFFFF_00FF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x1234ABCD & 0xFFFF00FF = 0x123400CD
     assert_eq!(cpu.reg[3], 0x1234_00CD);
@@ -997,9 +999,9 @@ fn and_all_zeros() {
 
     // AND x31, x0, x0 → 0 & 0 = 0
     let and_instruction: Word = 0b0000000_00000_00000_111_11111_0110011;
-    mem.store_word(0x0, and_instruction);
+    mem.store_word(0x0, and_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -1013,9 +1015,9 @@ fn or_instruction_fetch() {
     // Example: OR x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=110, rd=00001, opcode=0110011
     let or_instruction: Word = 0b0000000_00011_00010_110_00001_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, or_instruction);
 }
 
@@ -1027,12 +1029,12 @@ fn or_basic_operation() {
 
     // OR x1, x2, x3
     let or_instruction: Word = 0b0000000_00011_00010_110_00001_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[2] = 0b1100; // 12
     cpu.reg[3] = 0b1010; // 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1100 | 1010 = 1110 (14)
     assert_eq!(cpu.reg[1], 0b1110);
@@ -1046,12 +1048,12 @@ fn or_with_all_bits_set() {
 
     // OR x4, x5, x6
     let or_instruction: Word = 0b0000000_00110_00101_110_00100_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[5] = 0xFFFF_FFFF;
     cpu.reg[6] = 0x1234_5678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_FFFF | anything = 0xFFFF_FFFF
     assert_eq!(cpu.reg[4], 0xFFFF_FFFF);
@@ -1065,12 +1067,12 @@ fn or_with_zeros() {
 
     // OR x7, x8, x9
     let or_instruction: Word = 0b0000000_01001_01000_110_00111_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[8] = 0xFFFF_0000;
     cpu.reg[9] = 0x0000_0000;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x8 | 0 = x8
     assert_eq!(cpu.reg[7], 0xFFFF_0000);
@@ -1084,11 +1086,11 @@ fn or_same_operands() {
 
     // OR x10, x11, x11
     let or_instruction: Word = 0b0000000_01011_01011_110_01010_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[11] = 0xDEAD_BEEF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x11 | x11 = x11
     assert_eq!(cpu.reg[10], 0xDEAD_BEEF);
@@ -1102,12 +1104,12 @@ fn or_alternating_bit_patterns() {
 
     // OR x12, x13, x14
     let or_instruction: Word = 0b0000000_01110_01101_110_01100_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[13] = 0xAAAA_AAAA; // 1010...
     cpu.reg[14] = 0x5555_5555; // 0101...
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1010... | 0101... = 1111...
     assert_eq!(cpu.reg[12], 0xFFFF_FFFF);
@@ -1121,11 +1123,11 @@ fn or_with_x0_operand() {
 
     // OR x1, x0, x2 → x1 = 0 | x2 = x2
     let or_instruction: Word = 0b0000000_00010_00000_110_00001_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[2] = 0x1234_5678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0x1234_5678);
 }
@@ -1138,12 +1140,12 @@ fn or_write_to_x0_is_ignored() {
 
     // OR x0, x1, x2
     let or_instruction: Word = 0b0000000_00010_00001_110_00000_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[1] = 0xAAAA_AAAA;
     cpu.reg[2] = 0x5555_5555;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 is hardwired to zero
     assert_eq!(cpu.reg[0], 0);
@@ -1157,12 +1159,12 @@ fn or_large_values() {
 
     // OR x3, x4, x5
     let or_instruction: Word = 0b0000000_00101_00100_110_00011_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
     cpu.reg[4] = 0x1234_0000;
     cpu.reg[5] = 0x0000_ABCD;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x12340000 | 0x0000ABCD = 0x1234ABCD
     assert_eq!(cpu.reg[3], 0x1234_ABCD);
@@ -1176,9 +1178,9 @@ fn or_all_zeros() {
 
     // OR x31, x0, x0 → 0 | 0 = 0
     let or_instruction: Word = 0b0000000_00000_00000_110_11111_0110011;
-    mem.store_word(0x0, or_instruction);
+    mem.store_word(0x0, or_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -1192,9 +1194,9 @@ fn xor_instruction_fetch() {
     // Example: XOR x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=100, rd=00001, opcode=0110011
     let xor_instruction: Word = 0b0000000_00011_00010_100_00001_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, xor_instruction);
 }
 
@@ -1206,12 +1208,12 @@ fn xor_basic_operation() {
 
     // XOR x1, x2, x3
     let xor_instruction: Word = 0b0000000_00011_00010_100_00001_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[2] = 0b1100; // 12
     cpu.reg[3] = 0b1010; // 10
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1100 ^ 1010 = 0110 (6)
     assert_eq!(cpu.reg[1], 0b0110);
@@ -1225,11 +1227,11 @@ fn xor_same_operands() {
 
     // XOR x4, x5, x5
     let xor_instruction: Word = 0b0000000_00101_00101_100_00100_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[5] = 0xFFFF_FFFF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // a ^ a = 0
     assert_eq!(cpu.reg[4], 0);
@@ -1243,12 +1245,12 @@ fn xor_with_all_bits_set() {
 
     // XOR x6, x7, x8
     let xor_instruction: Word = 0b0000000_01000_00111_100_00110_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[7] = 0xFFFF_FFFF;
     cpu.reg[8] = 0x1234_5678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_FFFF ^ 0x12345678 = bitwise NOT of 0x12345678
     assert_eq!(cpu.reg[6], !0x1234_5678);
@@ -1262,12 +1264,12 @@ fn xor_with_zeros() {
 
     // XOR x9, x10, x11
     let xor_instruction: Word = 0b0000000_01011_01010_100_01001_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[10] = 0x0000_0000;
     cpu.reg[11] = 0xABCD_1234;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 ^ a = a
     assert_eq!(cpu.reg[9], 0xABCD_1234);
@@ -1281,12 +1283,12 @@ fn xor_alternating_bit_patterns() {
 
     // XOR x12, x13, x14
     let xor_instruction: Word = 0b0000000_01110_01101_100_01100_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[13] = 0xAAAA_AAAA; // 1010...
     cpu.reg[14] = 0x5555_5555; // 0101...
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1010... ^ 0101... = 1111...
     assert_eq!(cpu.reg[12], 0xFFFF_FFFF);
@@ -1300,11 +1302,11 @@ fn xor_with_x0_operand() {
 
     // XOR x1, x0, x2 → x1 = 0 ^ x2 = x2
     let xor_instruction: Word = 0b0000000_00010_00000_100_00001_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[2] = 0x1234_5678;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[1], 0x1234_5678);
 }
@@ -1317,12 +1319,12 @@ fn xor_write_to_x0_is_ignored() {
 
     // XOR x0, x1, x2
     let xor_instruction: Word = 0b0000000_00010_00001_100_00000_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[1] = 0xAAAA_AAAA;
     cpu.reg[2] = 0x5555_5555;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
 }
@@ -1335,12 +1337,12 @@ fn xor_large_values() {
 
     // XOR x3, x4, x5
     let xor_instruction: Word = 0b0000000_00101_00100_100_00011_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
     cpu.reg[4] = 0x1234_ABCD;
     cpu.reg[5] = 0xFFFF_00FF;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x1234ABCD ^ 0xFFFF00FF = 0xEDCB_AB32
     assert_eq!(cpu.reg[3], 0xEDCB_AB32);
@@ -1354,9 +1356,9 @@ fn xor_all_zeros() {
 
     // XOR x31, x0, x0 → 0 ^ 0 = 0
     let xor_instruction: Word = 0b0000000_00000_00000_100_11111_0110011;
-    mem.store_word(0x0, xor_instruction);
+    mem.store_word(0x0, xor_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -1370,9 +1372,9 @@ fn sll_instruction_fetch() {
     // Example: SLL x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=001, rd=00001, opcode=0110011
     let sll_instruction: Word = 0b0000000_00011_00010_001_00001_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, sll_instruction);
 }
 
@@ -1384,12 +1386,12 @@ fn sll_basic_operation() {
 
     // SLL x1, x2, x3 → x1 = x2 << (x3 & 0x1F)
     let sll_instruction: Word = 0b0000000_00011_00010_001_00001_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[2] = 0b0001; // 1
     cpu.reg[3] = 2; // shift by 2 bits
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1 << 2 = 4
     assert_eq!(cpu.reg[1], 0b0100);
@@ -1403,12 +1405,12 @@ fn sll_shift_by_zero() {
 
     // SLL x4, x5, x6
     let sll_instruction: Word = 0b0000000_00110_00101_001_00100_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[5] = 0x1234_5678;
     cpu.reg[6] = 0; // shift by 0
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // No change expected
     assert_eq!(cpu.reg[4], 0x1234_5678);
@@ -1422,12 +1424,12 @@ fn sll_shift_by_31() {
 
     // SLL x7, x8, x9
     let sll_instruction: Word = 0b0000000_01001_01000_001_00111_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[8] = 0x1;
     cpu.reg[9] = 31;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1 << 31 = 0x8000_0000
     assert_eq!(cpu.reg[7], 0x8000_0000);
@@ -1441,12 +1443,12 @@ fn sll_shift_by_more_than_31() {
 
     // SLL x10, x11, x12
     let sll_instruction: Word = 0b0000000_01100_01011_001_01010_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[11] = 0x1;
     cpu.reg[12] = 35; // 35 & 0x1F = 3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 1 << 3 = 8
     assert_eq!(cpu.reg[10], 0x8);
@@ -1460,12 +1462,12 @@ fn sll_alternating_bits() {
 
     // SLL x13, x14, x15
     let sll_instruction: Word = 0b0000000_01111_01110_001_01101_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[14] = 0xAAAA_AAAA; // 1010...
     cpu.reg[15] = 1; // shift left by 1
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Shift left by 1, low bit becomes 0
     assert_eq!(cpu.reg[13], 0x5555_5554);
@@ -1479,11 +1481,11 @@ fn sll_with_zero_source() {
 
     // SLL x16, x0, x17
     let sll_instruction: Word = 0b0000000_10001_00000_001_10000_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[17] = 10;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 << anything = 0
     assert_eq!(cpu.reg[16], 0);
@@ -1497,12 +1499,12 @@ fn sll_write_to_x0_is_ignored() {
 
     // SLL x0, x1, x2
     let sll_instruction: Word = 0b0000000_00010_00001_001_00000_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[1] = 0xF0F0_F0F0;
     cpu.reg[2] = 4;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 remains 0
     assert_eq!(cpu.reg[0], 0);
@@ -1516,12 +1518,12 @@ fn sll_large_value_shift() {
 
     // SLL x3, x4, x5
     let sll_instruction: Word = 0b0000000_00101_00100_001_00011_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
     cpu.reg[4] = 0x000F_FFFF;
     cpu.reg[5] = 8;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0x000FFFFF << 8 = 0x0FFFFF00
     assert_eq!(cpu.reg[3], 0x0FFF_FF00);
@@ -1535,9 +1537,9 @@ fn sll_all_zeros() {
 
     // SLL x31, x0, x0 → 0 << 0 = 0
     let sll_instruction: Word = 0b0000000_00000_00000_001_11111_0110011;
-    mem.store_word(0x0, sll_instruction);
+    mem.store_word(0x0, sll_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -1551,9 +1553,9 @@ fn srl_instruction_fetch() {
     // Example: SRL x1, x2, x3
     // funct7=0000000, rs2=00011, rs1=00010, funct3=101, rd=00001, opcode=0110011
     let srl_instruction: Word = 0b0000000_00011_00010_101_00001_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, srl_instruction);
 }
 
@@ -1565,12 +1567,12 @@ fn srl_basic_operation() {
 
     // SRL x1, x2, x3 → x1 = x2 >> (x3 & 0x1F)
     let srl_instruction: Word = 0b0000000_00011_00010_101_00001_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[2] = 0b1000; // 8
     cpu.reg[3] = 2; // shift by 2
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 8 >> 2 = 2
     assert_eq!(cpu.reg[1], 0b10);
@@ -1584,12 +1586,12 @@ fn srl_shift_by_zero() {
 
     // SRL x4, x5, x6
     let srl_instruction: Word = 0b0000000_00110_00101_101_00100_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[5] = 0xF0F0_F0F0;
     cpu.reg[6] = 0; // shift by 0
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[4], 0xF0F0_F0F0);
 }
@@ -1602,12 +1604,12 @@ fn srl_shift_by_31() {
 
     // SRL x7, x8, x9
     let srl_instruction: Word = 0b0000000_01001_01000_101_00111_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[8] = 0x8000_0000;
     cpu.reg[9] = 31;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical right shift: only bit 31 moves to bit 0
     assert_eq!(cpu.reg[7], 1);
@@ -1621,12 +1623,12 @@ fn srl_shift_by_more_than_31() {
 
     // SRL x10, x11, x12
     let srl_instruction: Word = 0b0000000_01100_01011_101_01010_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[11] = 0xFFFF_FFFF;
     cpu.reg[12] = 35; // 35 & 0x1F = 3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_FFFF >> 3 = 0x1FFF_FFFF
     assert_eq!(cpu.reg[10], 0x1FFF_FFFF);
@@ -1640,12 +1642,12 @@ fn srl_alternating_bits() {
 
     // SRL x13, x14, x15
     let srl_instruction: Word = 0b0000000_01111_01110_101_01101_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[14] = 0xAAAA_AAAA; // 1010...
     cpu.reg[15] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Logical shift right by 1
     assert_eq!(cpu.reg[13], 0x5555_5555);
@@ -1659,11 +1661,11 @@ fn srl_with_zero_source() {
 
     // SRL x16, x0, x17
     let srl_instruction: Word = 0b0000000_10001_00000_101_10000_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[17] = 10;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0 >> anything = 0
     assert_eq!(cpu.reg[16], 0);
@@ -1677,12 +1679,12 @@ fn srl_write_to_x0_is_ignored() {
 
     // SRL x0, x1, x2
     let srl_instruction: Word = 0b0000000_00010_00001_101_00000_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[1] = 0xFFFF_FFFF;
     cpu.reg[2] = 4;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // x0 must stay zero
     assert_eq!(cpu.reg[0], 0);
@@ -1696,12 +1698,12 @@ fn srl_large_value_shift() {
 
     // SRL x3, x4, x5
     let srl_instruction: Word = 0b0000000_00101_00100_101_00011_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[4] = 0xFFFF_0000;
     cpu.reg[5] = 8;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xFFFF_0000 >> 8 = 0x00FF_FF00
     assert_eq!(cpu.reg[3], 0x00FF_FF00);
@@ -1715,9 +1717,9 @@ fn srl_all_zeros() {
 
     // SRL x31, x0, x0 → 0 >> 0 = 0
     let srl_instruction: Word = 0b0000000_00000_00000_101_11111_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
@@ -1730,10 +1732,10 @@ fn srl_same_registers() {
 
     // SRL x5, x5, x5 (x5 = x5 >> (x5 & 0x1F))
     let srl_instruction: Word = 0b0000000_00101_00101_101_00101_0110011;
-    mem.store_word(0x0, srl_instruction);
+    mem.store_word(0x0, srl_instruction).unwrap();
 
     cpu.reg[5] = 0xF0F0_F0F0; // value = F0F0_F0F0 (shift amount = 0x10 = 16)
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 0xF0F0_F0F0 >> 16 = 0x0000_F0F0
     assert_eq!(cpu.reg[5], 0x0000_F0F0);
@@ -1748,9 +1750,9 @@ fn sra_instruction_fetch() {
     // Example: SRA x1, x2, x3
     // funct7=0100000, rs2=00011, rs1=00010, funct3=101, rd=00001, opcode=0110011
     let sra_instruction: Word = 0b0100000_00011_00010_101_00001_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
-    let instruction: u32 = cpu.fetch_instruction(&mut mem);
+    let instruction: u32 = cpu.fetch_instruction(&mem).unwrap();
     assert_eq!(instruction, sra_instruction);
 }
 
@@ -1762,12 +1764,12 @@ fn sra_basic_operation_positive() {
 
     // SRA x1, x2, x3 → x1 = (x2 as i32) >> (x3 & 0x1F)
     let sra_instruction: Word = 0b0100000_00011_00010_101_00001_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[2] = 0b1000; // 8
     cpu.reg[3] = 2; // shift by 2
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // 8 >> 2 = 2
     assert_eq!(cpu.reg[1], 0b10);
@@ -1781,13 +1783,13 @@ fn sra_negative_number_sign_extend() {
 
     // SRA x4, x5, x6
     let sra_instruction: Word = 0b0100000_00110_00101_101_00100_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     // x5 = -8 (0xFFFF_FFF8)
     cpu.reg[5] = (-8i32) as u32;
     cpu.reg[6] = 2;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Arithmetic right shift keeps sign bits: -8 >> 2 = -2
     assert_eq!(cpu.reg[4] as i32, -2);
@@ -1801,12 +1803,12 @@ fn sra_shift_by_zero() {
 
     // SRA x7, x8, x9
     let sra_instruction: Word = 0b0100000_01001_01000_101_00111_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[8] = 0xF000_0000;
     cpu.reg[9] = 0; // shift by 0
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[7], 0xF000_0000);
 }
@@ -1819,12 +1821,12 @@ fn sra_shift_by_31() {
 
     // SRA x10, x11, x12
     let sra_instruction: Word = 0b0100000_01100_01011_101_01010_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[11] = (-1i32) as u32; // 0xFFFF_FFFF
     cpu.reg[12] = 31;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -1 >> 31 = -1 (sign bit stays 1)
     assert_eq!(cpu.reg[10] as i32, -1);
@@ -1838,12 +1840,12 @@ fn sra_shift_by_more_than_31() {
 
     // SRA x13, x14, x15
     let sra_instruction: Word = 0b0100000_01111_01110_101_01101_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[14] = (-128i32) as u32; // 0xFFFFFF80
     cpu.reg[15] = 35; // 35 & 0x1F = 3
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -128 >> 3 = -16
     assert_eq!(cpu.reg[13] as i32, -16);
@@ -1857,12 +1859,12 @@ fn sra_alternating_bits_negative() {
 
     // SRA x19, x20, x21
     let sra_instruction: Word = 0b0100000_10101_10100_101_10011_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[20] = 0xAAAA_AAAA; // signed = -1431655766
     cpu.reg[21] = 1;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // Arithmetic shift preserves sign bit (1)
     assert_eq!(cpu.reg[19], 0xD555_5555);
@@ -1876,11 +1878,11 @@ fn sra_with_zero_source() {
 
     // SRA x22, x0, x23
     let sra_instruction: Word = 0b0100000_10111_00000_101_10110_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[23] = 10;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[22], 0);
 }
@@ -1893,12 +1895,12 @@ fn sra_write_to_x0_is_ignored() {
 
     // SRA x0, x1, x2
     let sra_instruction: Word = 0b0100000_00010_00001_101_00000_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[1] = (-1i32) as u32;
     cpu.reg[2] = 4;
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[0], 0);
 }
@@ -1911,11 +1913,11 @@ fn sra_same_registers() {
 
     // SRA x5, x5, x5
     let sra_instruction: Word = 0b0100000_00101_00101_101_00101_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
     cpu.reg[5] = (-256i32) as u32; // 0xFFFF_FF00 → shift by 0
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     // -256 >> 16 = -1 (0xFFFF_FFFF)
     assert_eq!(cpu.reg[5] as i32, -256);
@@ -1929,9 +1931,9 @@ fn sra_all_zeros() {
 
     // SRA x31, x0, x0 → 0 >> 0 = 0
     let sra_instruction: Word = 0b0100000_00000_00000_101_11111_0110011;
-    mem.store_word(0x0, sra_instruction);
+    mem.store_word(0x0, sra_instruction).unwrap();
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
 
     assert_eq!(cpu.reg[31], 0);
 }
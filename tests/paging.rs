@@ -0,0 +1,455 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const ROOT: usize = 0x10000; // root page table
+const CODE_TABLE: usize = 0x11000; // level-0 table for the 4 MiB at virtual address 0
+const DATA_TABLE: usize = 0x12000; // level-0 table for the 4 MiB at DATA
+const DATA: Word = 0x4000_0000; // virtual address of the data pages
+const SATP_SV32: Word = (1 << 31) | (ROOT >> 12) as Word;
+
+const LW: Word = 0b000000000000_00001_010_00010_0000011; // lw x2, 0(x1)
+const SW: Word = 0b0000000_00010_00001_010_00100_0100011; // sw x2, 4(x1)
+const SFENCE_VMA: Word = 0b0001001_00000_00000_000_00000_1110011; // sfence.vma x0, x0
+const LEAF: Word = (PTE_V | PTE_A | PTE_D) as Word;
+const R: Word = PTE_R as Word;
+const W: Word = PTE_W as Word;
+const X: Word = PTE_X as Word;
+const U: Word = PTE_U as Word;
+
+/// csrr rd, csr
+fn csrr(csr: usize, rd: Word) -> Word {
+    ((csr as Word) << 20) | (0b010 << 12) | (rd << 7) | 0b1110011
+}
+
+/// Maps the 4 KiB page at `va` to `pa` through the level-0 page table at `table`
+fn map_page(mem: &mut Memory, table: usize, va: Word, pa: Word, flags: Word) {
+    let vpn1: usize = (va >> 22) as usize;
+    let vpn0: usize = ((va >> 12) & 0x3FF) as usize;
+    let pointer: Word = ((table as Word >> 12) << 10) | PTE_V as Word;
+    mem.store_word(ROOT + 4 * vpn1, pointer).unwrap();
+    mem.store_word(table + 4 * vpn0, ((pa >> 12) << 10) | flags)
+        .unwrap();
+}
+
+/// Grants S-mode and U-mode all of memory through PMP entry 0, as firmware does at boot
+fn open_pmp<X: Xlen>(cpu: &mut RISCV<X>) {
+    cpu.csr.write(PMPADDR0, X::from_u64(u64::MAX)).unwrap(); // NAPOT over the whole address space
+    cpu.csr
+        .write(
+            PMPCFG0,
+            X::from((PMP_NAPOT | PMP_R | PMP_W | PMP_X) as Word),
+        )
+        .unwrap();
+}
+
+/// A hart in `privilege` with Sv32 enabled and `program` in an identity-mapped code page
+fn setup(privilege: Privilege, program: &[Word]) -> (RISCV, Memory) {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+    let user: Word = if privilege == Privilege::User { U } else { 0 };
+    map_page(&mut mem, CODE_TABLE, 0, 0, LEAF | R | X | user);
+    cpu.csr.write(SATP, SATP_SV32).unwrap();
+    open_pmp(&mut cpu);
+    cpu.privilege = privilege;
+    (cpu, mem)
+}
+
+/* -------------------- Translation -------------------- */
+
+/// Loads and stores go to the physical page the virtual page is mapped to
+#[test]
+fn translated_load_store() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[LW, SW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R | W | U);
+    mem.store_word(0x3010, 0xCAFE).unwrap();
+    cpu.reg[1] = DATA + 0x10;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 0xCAFE);
+    assert_eq!(mem.fetch_word(0x3014), Ok(0xCAFE));
+}
+
+/// A leaf in the root table maps a 4 MiB superpage, which must be aligned
+#[test]
+fn superpages() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[LW, LW]);
+    // 0x8040_0000 -> 0x40_0000, 0x8080_0000 -> the misaligned 0x40_1000
+    mem.store_word(ROOT + 4 * 0x201, (0x400 << 10) | LEAF | R)
+        .unwrap();
+    mem.store_word(ROOT + 4 * 0x202, (0x401 << 10) | LEAF | R)
+        .unwrap();
+    mem.store_word(0x40_0124, 0x1234_5678).unwrap();
+
+    cpu.reg[1] = 0x8040_0124;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 0x1234_5678);
+
+    cpu.reg[1] = 0x8080_0124;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, 0x8080_0124))
+    );
+}
+
+/// M-mode ignores satp, unless MPRV makes its loads and stores use the mode in MPP
+#[test]
+fn machine_mode_and_mprv() {
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[LW, LW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R | W);
+    mem.store_word(0x3000, 7).unwrap();
+
+    cpu.reg[1] = 0x3000;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 7);
+
+    cpu.csr
+        .write(MSTATUS, MSTATUS_MPRV | Privilege::Supervisor.mpp())
+        .unwrap();
+    cpu.reg[1] = DATA;
+    cpu.reg[2] = 0;
+    cpu.clock_cycle(&mut mem).unwrap(); // fetched untranslated, loaded through the page
+    assert_eq!(cpu.reg[2], 7);
+}
+
+/* -------------------- Page faults -------------------- */
+
+/// Each kind of access checks its own permission bit and raises its own page fault
+#[test]
+fn permission_faults() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[LW, SW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R | U); // read-only
+    cpu.reg[1] = DATA;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StorePageFault, DATA + 4))
+    );
+
+    // a page without U is out of reach of user mode
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R | W);
+    cpu.sfence_vma(None, None); // the old entry is cached
+    cpu.pc = 0;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, DATA))
+    );
+
+    // instructions can only be fetched from executable pages
+    map_page(&mut mem, CODE_TABLE, 0, 0, LEAF | R | U);
+    cpu.sfence_vma(None, None);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionPageFault, 0))
+    );
+}
+
+/// Invalid entries, the reserved W-without-R encoding and clear A or D bits all fault
+#[test]
+fn invalid_entries_fault() {
+    let cases: [(Word, Word, Exception); 5] = [
+        (0, LW, Exception::LoadPageFault),
+        (LEAF | W | U, LW, Exception::LoadPageFault),
+        (
+            (PTE_V | PTE_D) as Word | R | U,
+            LW,
+            Exception::LoadPageFault,
+        ),
+        (
+            (PTE_V | PTE_A) as Word | R | W | U,
+            SW,
+            Exception::StorePageFault,
+        ),
+        (
+            (PTE_V | PTE_A) as Word | R | W | U,
+            LW,
+            Exception::LoadAccessFault,
+        ),
+    ];
+    for (flags, instruction, cause) in cases {
+        let (mut cpu, mut mem) = setup(Privilege::User, &[instruction]);
+        // the last case maps a physical page outside of memory
+        let pa: Word = if cause == Exception::LoadAccessFault {
+            0x4000_0000
+        } else {
+            0x3000
+        };
+        map_page(&mut mem, DATA_TABLE, DATA, pa, flags);
+        cpu.reg[1] = DATA;
+        let tval: Word = if instruction == SW { DATA + 4 } else { DATA };
+        assert_eq!(cpu.clock_cycle(&mut mem), Err(Trap::new(cause, tval)));
+    }
+}
+
+/// S-mode reaches user pages only with SUM and never executes them; MXR makes
+/// execute-only pages readable
+#[test]
+fn sum_and_mxr() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[LW, LW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R | U);
+    cpu.reg[1] = DATA;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, DATA))
+    );
+    cpu.csr.write(SSTATUS, MSTATUS_SUM).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | X);
+    cpu.sfence_vma(None, None); // the old entry is cached
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, DATA))
+    );
+    cpu.csr.write(SSTATUS, MSTATUS_MXR).unwrap();
+    cpu.pc = 4;
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    map_page(&mut mem, CODE_TABLE, 0, 0, LEAF | R | X | U);
+    cpu.sfence_vma(None, None);
+    cpu.csr.write(SSTATUS, MSTATUS_SUM).unwrap();
+    cpu.pc = 0;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionPageFault, 0))
+    );
+}
+
+/// An instruction straddling two pages faults on the address of the unmapped half
+#[test]
+fn fetch_across_pages() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[]);
+    mem.store_halfword(0xFFE, (LW & 0xFFFF) as HalfWord)
+        .unwrap();
+    cpu.pc = 0xFFE;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionPageFault, 0x1000))
+    );
+
+    map_page(&mut mem, CODE_TABLE, 0x1000, 0x5000, LEAF | R | X);
+    mem.store_halfword(0x5000, (LW >> 16) as HalfWord).unwrap();
+    cpu.reg[1] = 0;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x1002);
+    assert_eq!(cpu.reg[2], mem.fetch_word(0).unwrap());
+}
+
+/// An emulated misaligned access is split between two pages that are not contiguous
+#[test]
+fn misaligned_across_pages() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[LW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R);
+    map_page(&mut mem, DATA_TABLE, DATA + 0x1000, 0x8000, LEAF | R);
+    mem.store_halfword(0x3FFE, 0x5678).unwrap();
+    mem.store_halfword(0x8000, 0x1234).unwrap();
+    cpu.misaligned_access = MisalignedAccess::Emulate { penalty: 0 };
+    cpu.reg[1] = DATA + 0xFFE;
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 0x1234_5678);
+}
+
+/* -------------------- satp and SFENCE.VMA -------------------- */
+
+/// Writes selecting an unsupported mode leave satp unchanged
+#[test]
+fn satp_warl() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(SATP, SATP_SV32).unwrap();
+    assert_eq!(cpu.csr.read(SATP), Ok(SATP_SV32));
+
+    let mut cpu: RISCV64 = RISCV64::reset();
+    cpu.csr.write(SATP, (15 << 60) | 0x10).unwrap();
+    assert_eq!(cpu.csr.read(SATP), Ok(0));
+}
+
+/// SFENCE.VMA is illegal in U-mode, and with satp in S-mode while TVM is set
+#[test]
+fn sfence_vma_and_tvm() {
+    assert_eq!(
+        Instruction::parse_instruction(EncodingVariant::get_encoding(SFENCE_VMA).unwrap()),
+        Ok(Instruction::SFENCEVMA { rs1: 0, rs2: 0 })
+    );
+
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[SFENCE_VMA, csrr(SATP, 1)]);
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[1], SATP_SV32);
+
+    cpu.csr.write(MSTATUS, MSTATUS_TVM).unwrap();
+    for pc in [0, 4] {
+        cpu.pc = pc;
+        assert!(cpu.clock_cycle(&mut mem).is_err());
+    }
+
+    let (mut cpu, mut mem) = setup(Privilege::User, &[SFENCE_VMA]);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, SFENCE_VMA))
+    );
+}
+
+/* -------------------- Sv39 / Sv48 / Sv57 -------------------- */
+
+const ROOT64: usize = 0x20000; // root table, followed by one table for each lower level
+const LD: Word = 0b000000000000_00001_011_00010_0000011; // ld x2, 0(x1)
+const LEAF64: u64 = PTE_V | PTE_A | PTE_D | PTE_R | PTE_W;
+
+/// Maps `va` to `pa` with a leaf at `leaf_level` of a `levels`-deep RV64 page table. Every
+/// level uses a single table, so mappings must share their upper VPNs or have distinct ones
+/// at each level.
+fn map64(mem: &mut Memory, levels: u32, va: u64, pa: u64, flags: u64, leaf_level: u32) {
+    let mut table: usize = ROOT64;
+    for level in (leaf_level + 1..levels).rev() {
+        let vpn: usize = ((va >> (12 + 9 * level)) & 0x1FF) as usize;
+        let next: usize = ROOT64 + 0x1000 * (levels - level) as usize;
+        mem.store_doubleword(table + 8 * vpn, ((next as u64 >> 12) << 10) | PTE_V)
+            .unwrap();
+        table = next;
+    }
+    let vpn: usize = ((va >> (12 + 9 * leaf_level)) & 0x1FF) as usize;
+    mem.store_doubleword(table + 8 * vpn, ((pa >> 12) << 10) | flags)
+        .unwrap();
+}
+
+/// An RV64 hart in S-mode translating with `mode` (8, 9 or 10), running `program` from an
+/// identity-mapped code page
+fn setup64(mode: u64, program: &[Word]) -> (RISCV64, Memory) {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+    let levels: u32 = mode as u32 - 5;
+    map64(&mut mem, levels, 0, 0, PTE_V | PTE_A | PTE_R | PTE_X, 0);
+    cpu.csr
+        .write(SATP, (mode << 60) | (ROOT64 >> 12) as u64)
+        .unwrap();
+    assert_eq!(cpu.csr.read(SATP).unwrap() >> 60, mode);
+    open_pmp(&mut cpu);
+    cpu.privilege = Privilege::Supervisor;
+    (cpu, mem)
+}
+
+/// Sv39, Sv48 and Sv57 walk three, four and five levels of 8-byte entries
+#[test]
+fn rv64_walks() {
+    for mode in [8, 9, 10] {
+        let levels: u32 = mode as u32 - 5;
+        let (mut cpu, mut mem) = setup64(mode, &[LD]);
+        // the top-level VPN is nonzero, the address stays below the sign bit
+        let va: u64 = (1 << (12 + 9 * (levels - 1))) | 0x1000;
+        map64(&mut mem, levels, va, 0x3000, LEAF64, 0);
+        mem.store_doubleword(0x3008, 0x0123_4567_89AB_CDEF).unwrap();
+
+        cpu.reg[1] = va + 8;
+        cpu.clock_cycle(&mut mem).unwrap();
+        assert_eq!(cpu.reg[2], 0x0123_4567_89AB_CDEF);
+    }
+}
+
+/// Leaves above level 0 map 2 MiB megapages and 1 GiB gigapages, aligned to their size
+#[test]
+fn rv64_superpages() {
+    let (mut cpu, mut mem) = setup64(8, &[LD, LD, LD]);
+    map64(&mut mem, 3, 0x4020_0000, 0x20_0000, LEAF64, 1);
+    map64(&mut mem, 3, 0x8000_0000, 0, LEAF64, 2);
+    map64(&mut mem, 3, 0xC000_0000, 0x20_0000, LEAF64, 2); // misaligned
+    mem.store_doubleword(0x20_1238, 42).unwrap();
+
+    cpu.reg[1] = 0x4020_1238;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 42);
+
+    cpu.reg[1] = 0x8020_1238;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 42);
+
+    cpu.reg[1] = 0xC000_0000;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, 0xC000_0000))
+    );
+}
+
+/// Addresses whose upper bits do not copy the top virtual-address bit page fault
+#[test]
+fn canonical_addresses() {
+    let (mut cpu, mut mem) = setup64(8, &[LD, LD]);
+    // the upper half of the Sv39 address space
+    let high: u64 = 0xFFFF_FFC0_0000_1000;
+    map64(&mut mem, 3, high, 0x3000, LEAF64, 0);
+    mem.store_doubleword(0x3000, 7).unwrap();
+
+    cpu.reg[1] = high;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 7);
+
+    // same VPNs, but bit 39 is not a copy of bit 38
+    cpu.reg[1] = 0x0000_0040_0000_1000;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, 0x0000_0040_0000_1000))
+    );
+}
+
+/// A 64 KiB Svnapot page takes the low 4 PPN bits from the VPN
+#[test]
+fn svnapot() {
+    let (mut cpu, mut mem) = setup64(8, &[LD]);
+    // VPN[0] = 0x25 inside the NAPOT range whose PPN is 0x10-0x1F
+    map64(&mut mem, 3, 0x2_5000, 0x1_8000, LEAF64 | PTE_N, 0);
+    mem.store_doubleword(0x1_5008, 99).unwrap();
+    cpu.reg[1] = 0x2_5008;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 99);
+
+    // other NAPOT sizes are reserved, and N cannot mark a superpage
+    for (pa, level) in [(0x1_4000, 0), (0x20_0000 | 0x8000, 1)] {
+        let (mut cpu, mut mem) = setup64(8, &[LD]);
+        map64(&mut mem, 3, 0x4020_5000, pa, LEAF64 | PTE_N, level);
+        cpu.reg[1] = 0x4020_5000;
+        assert_eq!(
+            cpu.clock_cycle(&mut mem),
+            Err(Trap::new(Exception::LoadPageFault, 0x4020_5000))
+        );
+    }
+}
+
+/// Svpbmt memory types are accepted on leaves; the reserved type, PBMT on a pointer and the
+/// reserved PTE bits page fault
+#[test]
+fn svpbmt_and_reserved_bits() {
+    for pbmt in [PBMT_PMA, PBMT_NC, PBMT_IO] {
+        let (mut cpu, mut mem) = setup64(8, &[LD]);
+        map64(&mut mem, 3, 0x5000, 0x3000, LEAF64 | pbmt, 0);
+        cpu.reg[1] = 0x5000;
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+
+    for flags in [LEAF64 | PTE_PBMT, LEAF64 | (1 << 54)] {
+        let (mut cpu, mut mem) = setup64(8, &[LD]);
+        map64(&mut mem, 3, 0x5000, 0x3000, flags, 0);
+        cpu.reg[1] = 0x5000;
+        assert_eq!(
+            cpu.clock_cycle(&mut mem),
+            Err(Trap::new(Exception::LoadPageFault, 0x5000))
+        );
+    }
+
+    // the root entry for the gigabyte at 0x4000_0000 is a pointer
+    let (mut cpu, mut mem) = setup64(8, &[LD]);
+    map64(&mut mem, 3, 0x4000_5000, 0x3000, LEAF64, 0);
+    let pointer: u64 = mem.fetch_doubleword(ROOT64 + 8).unwrap();
+    mem.store_doubleword(ROOT64 + 8, pointer | PBMT_NC).unwrap();
+    cpu.reg[1] = 0x4000_5000;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, 0x4000_5000))
+    );
+}
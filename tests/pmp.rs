@@ -0,0 +1,221 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const LW: Word = 0b000000000000_00001_010_00010_0000011; // lw x2, 0(x1)
+const SW: Word = 0b0000000_00010_00001_010_00000_0100011; // sw x2, 0(x1)
+const ROOT: usize = 0x10000; // Sv32 root page table
+
+/// pmpaddr value of the naturally aligned `size`-byte region at `base`
+fn napot(base: Word, size: Word) -> Word {
+    (base >> 2) | ((size >> 3) - 1)
+}
+
+/// A pmpcfg register holding `cfgs` in entry order
+fn pmpcfg(cfgs: &[u8]) -> Word {
+    cfgs.iter()
+        .enumerate()
+        .map(|(i, &cfg)| (cfg as Word) << (8 * i))
+        .sum()
+}
+
+/// A hart running in `privilege` with `program` stored from address 0 onwards
+fn setup(privilege: Privilege, program: &[Word]) -> (RISCV, Memory) {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+    cpu.privilege = privilege;
+    (cpu, mem)
+}
+
+/// Runs the instruction at `pc` with x1 = `address`
+fn run_at(cpu: &mut RISCV, mem: &mut Memory, pc: Word, address: Word) -> Result<(), Trap> {
+    cpu.pc = pc;
+    cpu.reg[1] = address;
+    cpu.clock_cycle(mem)
+}
+
+/* -------------------- CSRs -------------------- */
+
+/// Reserved cfg bits and the write-only encoding are dropped; RV64 packs eight entries in
+/// each even pmpcfg register and holds 54 address bits
+#[test]
+fn pmp_csrs() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr
+        .write(PMPCFG0 + 1, pmpcfg(&[0x60 | PMP_W, PMP_R | PMP_W]))
+        .unwrap();
+    assert_eq!(cpu.csr.read(PMPCFG0 + 1), Ok(pmpcfg(&[0, PMP_R | PMP_W])));
+    assert_eq!(cpu.csr.pmp_cfg(5), PMP_R | PMP_W);
+    cpu.csr.write(PMPADDR63, Word::MAX).unwrap();
+    assert_eq!(cpu.csr.pmp_addr(63), Word::MAX as u64);
+
+    let mut cpu: RISCV64 = RISCV64::reset();
+    assert_eq!(
+        cpu.csr.write(PMPCFG0 + 1, 0),
+        Err(Exception::IllegalInstruction)
+    );
+    cpu.csr.write(PMPCFG0 + 2, (PMP_X as u64) << 8).unwrap();
+    assert_eq!(cpu.csr.pmp_cfg(9), PMP_X);
+    cpu.csr.write(PMPADDR0, u64::MAX).unwrap();
+    assert_eq!(cpu.csr.read(PMPADDR0), Ok((1 << 54) - 1));
+}
+
+/// PMP registers are machine-level CSRs
+#[test]
+fn pmp_csrs_need_machine_mode() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[0x3B00_2173]); // csrrs x2, pmpaddr0, x0
+    cpu.csr.write(PMPADDR0, napot(0, 0x1000)).unwrap();
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[PMP_NAPOT | PMP_X]))
+        .unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, 0x3B00_2173))
+    );
+}
+
+/// A locked entry ignores writes to its cfg and address, and so does the address below a
+/// locked TOR entry
+#[test]
+fn locked_entries() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(PMPADDR0, 0x3000 >> 2).unwrap();
+    cpu.csr.write(PMPADDR0 + 2, 0x4000 >> 2).unwrap();
+    let locked: Word = pmpcfg(&[PMP_NA4 | PMP_R | PMP_L, 0, 0, PMP_TOR | PMP_L]);
+    cpu.csr.write(PMPCFG0, locked).unwrap();
+
+    cpu.csr.write(PMPCFG0, pmpcfg(&[0, PMP_R, 0, 0])).unwrap();
+    assert_eq!(cpu.csr.read(PMPCFG0), Ok(locked | pmpcfg(&[0, PMP_R])));
+    cpu.csr.write(PMPADDR0, 0).unwrap();
+    cpu.csr.write(PMPADDR0 + 1, 0x100).unwrap();
+    cpu.csr.write(PMPADDR0 + 2, 0).unwrap();
+    assert_eq!(cpu.csr.read(PMPADDR0), Ok(0x3000 >> 2));
+    assert_eq!(cpu.csr.read(PMPADDR0 + 1), Ok(0x100));
+    assert_eq!(cpu.csr.read(PMPADDR0 + 2), Ok(0x4000 >> 2));
+}
+
+/* -------------------- Access checks -------------------- */
+
+/// Once configured, U-mode only reaches the regions granting it the access, and M-mode only
+/// answers to locked entries
+#[test]
+fn user_sandbox() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[LW, SW]);
+    // with every entry off, U-mode cannot even fetch
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 0, 0x3000),
+        Err(Trap::new(Exception::InstructionAccessFault, 0))
+    );
+
+    // code at 0..0x1000, read-only data at 0x2000..0x3000, a read-write word at 0x3000
+    cpu.csr.write(PMPADDR0, napot(0, 0x1000)).unwrap();
+    cpu.csr.write(PMPADDR0 + 1, 0x2000 >> 2).unwrap();
+    cpu.csr.write(PMPADDR0 + 2, 0x3000 >> 2).unwrap();
+    cpu.csr.write(PMPADDR0 + 3, 0x3000 >> 2).unwrap();
+    let cfgs: [u8; 4] = [
+        PMP_NAPOT | PMP_R | PMP_X,
+        PMP_OFF,
+        PMP_TOR | PMP_R,
+        PMP_NA4 | PMP_R | PMP_W,
+    ];
+    cpu.csr.write(PMPCFG0, pmpcfg(&cfgs)).unwrap();
+
+    run_at(&mut cpu, &mut mem, 0, 0x2FFC).unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 4, 0x2000),
+        Err(Trap::new(Exception::StoreAccessFault, 0x2000))
+    );
+    run_at(&mut cpu, &mut mem, 0, 0x3000).unwrap();
+    run_at(&mut cpu, &mut mem, 4, 0x3000).unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 0, 0x3004),
+        Err(Trap::new(Exception::LoadAccessFault, 0x3004))
+    );
+    mem.store_word(0x2000, LW).unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 0x2000, 0x3000),
+        Err(Trap::new(Exception::InstructionAccessFault, 0x2000))
+    );
+
+    // M-mode ignores unlocked entries and succeeds where nothing matches
+    cpu.privilege = Privilege::Machine;
+    run_at(&mut cpu, &mut mem, 4, 0x2000).unwrap();
+    run_at(&mut cpu, &mut mem, 0, 0x5000).unwrap();
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[cfgs[0], 0, cfgs[2] | PMP_L]))
+        .unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 4, 0x2000),
+        Err(Trap::new(Exception::StoreAccessFault, 0x2000))
+    );
+}
+
+/// MPRV makes M-mode loads and stores checked as the mode in MPP
+#[test]
+fn mprv_uses_mpp() {
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[LW]);
+    cpu.csr.write(PMPADDR0, napot(0, 0x1000)).unwrap();
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[PMP_NAPOT | PMP_X]))
+        .unwrap();
+    run_at(&mut cpu, &mut mem, 0, 0x100).unwrap();
+    cpu.csr
+        .write(MSTATUS, MSTATUS_MPRV | Privilege::User.mpp())
+        .unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 0, 0x100),
+        Err(Trap::new(Exception::LoadAccessFault, 0x100))
+    );
+}
+
+/// The lowest-numbered matching entry decides, and it must cover every byte of the access
+#[test]
+fn priority_and_partial_matches() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(PMPADDR0, 0x3000 >> 2).unwrap();
+    cpu.csr.write(PMPADDR0 + 1, napot(0x3000, 0x10)).unwrap();
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[PMP_NA4, PMP_NAPOT | PMP_R | PMP_W]))
+        .unwrap();
+    let csr: &CSRFile = &cpu.csr;
+    assert!(!csr.pmp_allows(0x3000, 4, AccessType::Load, Privilege::User));
+    assert!(csr.pmp_allows(0x3004, 4, AccessType::Store, Privilege::User));
+    assert!(!csr.pmp_allows(0x3004, 4, AccessType::Fetch, Privilege::User));
+    // straddling entries 0 and 1, or the end of entry 1
+    assert!(!csr.pmp_allows(0x3002, 4, AccessType::Load, Privilege::User));
+    assert!(!csr.pmp_allows(0x3002, 4, AccessType::Load, Privilege::Machine));
+    assert!(!csr.pmp_allows(0x300E, 4, AccessType::Load, Privilege::User));
+    assert_eq!(csr.pmp_range(1), Some((0x3000, 0x3010)));
+}
+
+/// Page-table walks are S-mode reads, and a denied entry faults like the access itself
+#[test]
+fn page_walks_are_checked() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[LW]);
+    // a 4 MiB identity superpage, with the root table in its own PMP region
+    let leaf: Word = (PTE_V | PTE_R | PTE_W | PTE_X | PTE_A | PTE_D) as Word;
+    mem.store_word(ROOT, leaf).unwrap();
+    cpu.csr
+        .write(SATP, (1 << 31) | (ROOT >> 12) as Word)
+        .unwrap();
+    cpu.csr
+        .write(PMPADDR0, napot(ROOT as Word, 0x1000))
+        .unwrap();
+    cpu.csr.write(PMPADDR0 + 1, napot(0, 0x100_0000)).unwrap();
+    let everything: u8 = PMP_NAPOT | PMP_R | PMP_W | PMP_X;
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[PMP_NAPOT, everything]))
+        .unwrap();
+    assert_eq!(
+        run_at(&mut cpu, &mut mem, 0, 0x3000),
+        Err(Trap::new(Exception::InstructionAccessFault, 0))
+    );
+
+    cpu.csr
+        .write(PMPCFG0, pmpcfg(&[PMP_NAPOT | PMP_R, everything]))
+        .unwrap();
+    run_at(&mut cpu, &mut mem, 0, 0x3000).unwrap();
+}
@@ -0,0 +1,268 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const MRET_INSTRUCTION: Word = 0b0011000_00010_00000_000_00000_1110011;
+
+/* -------------------- Trap entry -------------------- */
+
+/// An illegal instruction jumps to mtvec and records the trap state
+#[test]
+fn illegal_instruction_enters_trap_handler() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MTVEC, 0x200).unwrap();
+    cpu.pc = 0x40;
+    let illegal_instruction: Word = 0xFFFF_FFFF;
+    mem.store_word(0x40, illegal_instruction).unwrap();
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.pc, 0x200);
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x40);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 2);
+    assert_eq!(cpu.csr.read(MTVAL).unwrap(), illegal_instruction);
+}
+
+/// A misaligned load reports the faulting address in mtval
+#[test]
+fn misaligned_load_sets_mtval() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MTVEC, 0x100).unwrap();
+    // LW x5, 1(x1)
+    mem.store_word(0x0, 0b000000000001_00001_010_00101_0000011)
+        .unwrap();
+    cpu.reg[1] = 0x800;
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.pc, 0x100);
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x0);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 4);
+    assert_eq!(cpu.csr.read(MTVAL).unwrap(), 0x801);
+}
+
+/// Taking a trap moves MIE into MPIE, clears MIE and sets MPP to machine mode
+#[test]
+fn trap_entry_updates_mstatus() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MSTATUS, MSTATUS_MIE).unwrap();
+
+    cpu.step(&mut mem); // all-zero word is illegal
+
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & MSTATUS_MIE, 0);
+    assert_eq!(mstatus & MSTATUS_MPIE, MSTATUS_MPIE);
+    assert_eq!(mstatus & MSTATUS_MPP, MSTATUS_MPP);
+}
+
+/// Exceptions go to BASE even when mtvec is in vectored mode
+#[test]
+fn vectored_mode_exceptions_use_base() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MTVEC, 0x400 | MTVEC_VECTORED).unwrap();
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.pc, 0x400);
+}
+
+/// A successful instruction behaves exactly like clock_cycle
+#[test]
+fn step_without_trap_advances_pc() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // ADDI x1, x0, 7
+    mem.store_word(0x0, 0b000000000111_00000_000_00001_0010011)
+        .unwrap();
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.reg[1], 7);
+    assert_eq!(cpu.pc, 4);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 0);
+}
+
+/* -------------------- MRET -------------------- */
+
+/// MRET jumps to mepc, restores MIE from MPIE and leaves MPP at user mode
+#[test]
+fn mret_returns_to_mepc() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, MRET_INSTRUCTION).unwrap();
+    cpu.csr.write(MEPC, 0x1234).unwrap();
+    cpu.csr.write(MSTATUS, MSTATUS_MPIE | MSTATUS_MPP).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.pc, 0x1234);
+    assert_eq!(cpu.privilege, Privilege::Machine);
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & MSTATUS_MIE, MSTATUS_MIE);
+    assert_eq!(mstatus & MSTATUS_MPIE, MSTATUS_MPIE);
+    assert_eq!(mstatus & MSTATUS_MPP, 0);
+}
+
+/// MRET with MPIE clear leaves interrupts disabled
+#[test]
+fn mret_with_mpie_clear() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_word(0x0, MRET_INSTRUCTION).unwrap();
+    cpu.csr.write(MEPC, 0x80).unwrap();
+    cpu.csr.write(MSTATUS, MSTATUS_MIE).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    assert_eq!(cpu.pc, 0x80);
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & MSTATUS_MIE, 0);
+    assert_eq!(mstatus & MSTATUS_MPIE, MSTATUS_MPIE);
+}
+
+/// Firmware handler that skips over an illegal instruction and resumes
+#[test]
+fn trap_handler_skips_faulting_instruction() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // main:
+    // 0x00: ADDI x1, x0, 0x100
+    // 0x04: CSRRW x0, mtvec, x1
+    // 0x08: illegal
+    // 0x0C: ADDI x2, x0, 42
+    mem.store_word(0x00, 0b000100000000_00000_000_00001_0010011)
+        .unwrap();
+    mem.store_word(0x04, 0b001100000101_00001_001_00000_1110011)
+        .unwrap();
+    mem.store_word(0x08, 0xFFFF_FFFF).unwrap();
+    mem.store_word(0x0C, 0b000000101010_00000_000_00010_0010011)
+        .unwrap();
+
+    // handler:
+    // 0x100: CSRRS x5, mepc, x0
+    // 0x104: ADDI x5, x5, 4
+    // 0x108: CSRRW x0, mepc, x5
+    // 0x10C: MRET
+    mem.store_word(0x100, 0b001101000001_00000_010_00101_1110011)
+        .unwrap();
+    mem.store_word(0x104, 0b000000000100_00101_000_00101_0010011)
+        .unwrap();
+    mem.store_word(0x108, 0b001101000001_00101_001_00000_1110011)
+        .unwrap();
+    mem.store_word(0x10C, MRET_INSTRUCTION).unwrap();
+
+    for _ in 0..8 {
+        cpu.step(&mut mem);
+    }
+
+    assert_eq!(cpu.reg[2], 42);
+    assert_eq!(cpu.pc, 0x10);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 2);
+}
+
+/* -------------------- Interrupts -------------------- */
+
+/// A pending and enabled interrupt is taken before the next instruction
+#[test]
+fn timer_interrupt_direct_mode() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.pc = 0x20;
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+    cpu.csr.write(MIE, MIP_MTIP).unwrap();
+    cpu.csr.write(MSTATUS, MSTATUS_MIE).unwrap();
+    cpu.set_interrupt_pending(Interrupt::MachineTimer, true);
+
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.pc, 0x300);
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x20);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), (1 << 31) | 7);
+    assert_eq!(cpu.csr.read(MTVAL).unwrap(), 0);
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap() & MSTATUS_MIE, 0);
+}
+
+/// In vectored mode interrupts jump to BASE + 4 * cause
+#[test]
+fn external_interrupt_vectored_mode() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MTVEC, 0x300 | MTVEC_VECTORED).unwrap();
+    cpu.csr.write(MIE, MIP_MEIP | MIP_MTIP).unwrap();
+    cpu.csr.write(MSTATUS, MSTATUS_MIE).unwrap();
+    cpu.set_interrupt_pending(Interrupt::MachineTimer, true);
+    cpu.set_interrupt_pending(Interrupt::MachineExternal, true);
+
+    cpu.step(&mut mem);
+
+    // external interrupts have priority over timer interrupts
+    assert_eq!(cpu.pc, 0x300 + 4 * 11);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), (1 << 31) | 11);
+}
+
+/// Interrupts are not taken while mstatus.MIE or the mie bit is clear
+#[test]
+fn masked_interrupts_are_not_taken() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // ADDI x1, x1, 1 twice
+    mem.store_word(0x0, 0b000000000001_00001_000_00001_0010011)
+        .unwrap();
+    mem.store_word(0x4, 0b000000000001_00001_000_00001_0010011)
+        .unwrap();
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+    cpu.set_interrupt_pending(Interrupt::MachineSoftware, true);
+
+    cpu.csr.write(MIE, MIP_MSIP).unwrap(); // enabled, but MIE is clear
+    cpu.step(&mut mem);
+
+    cpu.csr.write(MIE, 0).unwrap();
+    cpu.csr.write(MSTATUS, MSTATUS_MIE).unwrap(); // globally enabled, but masked
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.reg[1], 2);
+    assert_eq!(cpu.pc, 8);
+}
+
+/* -------------------- WARL fields -------------------- */
+
+/// Reserved mtvec modes fall back to direct mode and mepc ignores the low bits
+#[test]
+fn warl_fields() {
+    let mut cpu: RISCV = RISCV::reset();
+
+    cpu.csr.write(MTVEC, 0x1002).unwrap();
+    assert_eq!(cpu.csr.read(MTVEC).unwrap(), 0x1000);
+
+    cpu.csr.write(MEPC, 0x1237).unwrap();
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x1236); // IALIGN = 16 with C
+
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap();
+    assert_eq!(cpu.csr.read(MEPC).unwrap(), 0x1234); // mepc[1] reads as 0 without C
+
+    // MPP holds U, S or M, the reserved value 2 leaves it unchanged
+    cpu.csr.write(MSTATUS, Privilege::Supervisor.mpp()).unwrap();
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap(), Privilege::Supervisor.mpp());
+    cpu.csr.write(MSTATUS, 2 << 11).unwrap();
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap(), Privilege::Supervisor.mpp());
+
+    // only the supervisor bits of mip can be written by software
+    cpu.csr.write(MIP, 0xFFFF_FFFF).unwrap();
+    assert_eq!(cpu.csr.read(MIP).unwrap(), MIP_SSIP | MIP_STIP | MIP_SEIP);
+}
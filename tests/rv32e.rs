@@ -0,0 +1,135 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/// Executes a single instruction placed at the current pc
+fn run(cpu: &mut RISCV, mem: &mut Memory, instruction: Word) -> Result<(), Trap> {
+    mem.store_word(cpu.pc as usize, instruction).unwrap();
+    cpu.clock_cycle(mem)
+}
+
+/// misa reports the E base ISA instead of I
+#[test]
+fn rv32e_misa() {
+    let cpu: RISCV = RISCV::reset_embedded();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    assert_eq!(misa & (1 << 4), 1 << 4); // E
+    assert_eq!(misa & (1 << 8), 0); // I
+    assert!(cpu.csr.is_embedded());
+    assert!(!RISCV::<Word>::reset().csr.is_embedded());
+}
+
+/// Registers x0-x15 work as usual
+#[test]
+fn rv32e_low_registers() {
+    let mut cpu: RISCV = RISCV::reset_embedded();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = 5;
+    cpu.reg[15] = 7;
+
+    // add x3, x1, x15
+    assert_eq!(
+        run(&mut cpu, &mut mem, 0b0000000_01111_00001_000_00011_0110011),
+        Ok(())
+    );
+    assert_eq!(cpu.reg[3], 12);
+    assert_eq!(cpu.pc, 4);
+}
+
+/// Writing, reading or storing from x16-x31 raises an illegal-instruction exception
+#[test]
+fn rv32e_high_registers_illegal() {
+    let instructions: [Word; 4] = [
+        0b0000000_00010_00001_000_10000_0110011, // add x16, x1, x2
+        0b000000000000_10100_010_00001_0000011,  // lw x1, 0(x20)
+        0b0000000_10001_00000_010_00000_0100011, // sw x17, 0(x0)
+        0b001100000001_00000_010_11111_1110011,  // csrr x31, misa
+    ];
+    for instruction in instructions {
+        let mut cpu: RISCV = RISCV::reset_embedded();
+        let mut mem: Memory = Memory::new();
+        cpu.reg[1] = 5;
+        cpu.reg[2] = 7;
+
+        assert_eq!(
+            run(&mut cpu, &mut mem, instruction),
+            Err(Trap::new(Exception::IllegalInstruction, instruction))
+        );
+        assert_eq!(cpu.reg[16..], [0; 16]);
+        assert_eq!(cpu.pc, 0);
+    }
+}
+
+/// The same instructions are fine on RV32I
+#[test]
+fn rv32i_high_registers_legal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = 5;
+    cpu.reg[2] = 7;
+
+    // add x16, x1, x2
+    assert_eq!(
+        run(&mut cpu, &mut mem, 0b0000000_00010_00001_000_10000_0110011),
+        Ok(())
+    );
+    assert_eq!(cpu.reg[16], 12);
+}
+
+/// Compressed instructions naming x16-x31 are rejected as well
+#[test]
+fn rv32e_compressed_high_register_illegal() {
+    let mut cpu: RISCV = RISCV::reset_embedded();
+    let mut mem: Memory = Memory::new();
+
+    // c.mv x16, x1
+    let instruction: HalfWord = 0b1000_10000_00001_10;
+    mem.store_halfword(0, instruction).unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(
+            Exception::IllegalInstruction,
+            instruction as Word
+        ))
+    );
+}
+
+/// All 32 floating-point registers remain, only integer operands are limited
+#[test]
+fn rv32e_float_registers() {
+    let mut cpu: RISCV = RISCV::reset_embedded();
+    let mut mem: Memory = Memory::new();
+
+    // fadd.s f20, f21, f22
+    assert_eq!(
+        run(&mut cpu, &mut mem, 0b0000000_10110_10101_000_10100_1010011),
+        Ok(())
+    );
+
+    // flw f20, 0(x16)
+    let instruction: Word = 0b000000000000_10000_010_10100_0000111;
+    assert_eq!(
+        run(&mut cpu, &mut mem, instruction),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/// Without a7 the syscall number is passed in t0
+#[test]
+fn rv32e_syscall_number_in_t0() {
+    let mut cpu: RISCV = RISCV::reset_embedded();
+    let mut mem: Memory = Memory::new();
+    cpu.set_ecall_handler(Box::new(SyscallHandler::new(
+        std::io::empty(),
+        std::io::sink(),
+    )));
+    cpu.reg[5] = SYS_EXIT;
+    cpu.reg[10] = 3;
+
+    // ecall
+    assert_eq!(
+        run(&mut cpu, &mut mem, 0b000000000000_00000_000_00000_1110011),
+        Ok(())
+    );
+    assert_eq!(cpu.halt, Some(Halt::Exit(3)));
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::unusual_byte_groupings)]
+
 use rust_risc_v::*;
 
 /* =========================================================
@@ -15,14 +17,14 @@ fn lui_and_auipc_basic() {
 
     // LUI x1, 0x12345  => x1 = 0x12345000
     let lui_x1: Word = 0b00010010001101000101_00001_0110111;
-    mem.store_word(0x00, lui_x1);
+    mem.store_word(0x00, lui_x1).unwrap();
 
     // AUIPC x2, 0x1    => x2 = PC + (1 << 12) = 0x0004 + 0x1000 = 0x1004
     let auipc_x2: Word = 0b00000000000000000001_00010_0010111;
-    mem.store_word(0x04, auipc_x2);
+    mem.store_word(0x04, auipc_x2).unwrap();
 
-    cpu.clock_cycle(&mut mem); // LUI
-    cpu.clock_cycle(&mut mem); // AUIPC
+    cpu.clock_cycle(&mut mem).unwrap(); // LUI
+    cpu.clock_cycle(&mut mem).unwrap(); // AUIPC
 
     assert_eq!(cpu.reg[1], 0x12345000);
     assert_eq!(cpu.reg[2], 0x1004);
@@ -38,24 +40,24 @@ fn jal_and_jalr_basic_flow() {
 
     // JAL x1, +8 -> jumps to 0x08, x1 = return address (0x4)
     let jal_x1_plus8: Word = 0b0_0000000100_0_00000000_00001_1101111;
-    mem.store_word(0x00, jal_x1_plus8);
+    mem.store_word(0x00, jal_x1_plus8).unwrap();
 
     // ADDI x2, x0, 99 -> target at 0x08
     let addi_x2_99: Word = 0b000001100011_00000_000_00010_0010011;
-    mem.store_word(0x08, addi_x2_99);
+    mem.store_word(0x08, addi_x2_99).unwrap();
 
     // JALR x0, 0(x1) -> jump back to 0x4 (infinite loop)
     let jalr_x0_x1_0: Word = 0b000000000000_00001_000_00000_1100111;
-    mem.store_word(0x0C, jalr_x0_x1_0);
+    mem.store_word(0x0C, jalr_x0_x1_0).unwrap();
 
-    cpu.clock_cycle(&mut mem); // JAL to 0x08
+    cpu.clock_cycle(&mut mem).unwrap(); // JAL to 0x08
     assert_eq!(cpu.pc, 0x08);
     assert_eq!(cpu.reg[1], 0x4);
 
-    cpu.clock_cycle(&mut mem); // ADDI x2, x0, 99
+    cpu.clock_cycle(&mut mem).unwrap(); // ADDI x2, x0, 99
     assert_eq!(cpu.reg[2], 99);
 
-    cpu.clock_cycle(&mut mem);
+    cpu.clock_cycle(&mut mem).unwrap();
     assert_eq!(cpu.pc, 0x04);
 }
 
@@ -77,14 +79,14 @@ fn alu_immediate_ops() {
     // XORI x7, x3, 6   -> 5 ^ 6 = 3
     let xori_x7_x3_6: Word = 0b000000000110_00011_100_00111_0010011;
 
-    mem.store_word(0x00, addi_x3_5);
-    mem.store_word(0x04, slti_x4_x3_10);
-    mem.store_word(0x08, andi_x5_x3_3);
-    mem.store_word(0x0C, ori_x6_x3_8);
-    mem.store_word(0x10, xori_x7_x3_6);
+    mem.store_word(0x00, addi_x3_5).unwrap();
+    mem.store_word(0x04, slti_x4_x3_10).unwrap();
+    mem.store_word(0x08, andi_x5_x3_3).unwrap();
+    mem.store_word(0x0C, ori_x6_x3_8).unwrap();
+    mem.store_word(0x10, xori_x7_x3_6).unwrap();
 
     for _ in 0..5 {
-        cpu.clock_cycle(&mut mem);
+        cpu.clock_cycle(&mut mem).unwrap();
     }
 
     assert_eq!(cpu.reg[3], 5);
@@ -121,11 +123,11 @@ fn alu_register_ops() {
 
     let instrs = [add_x3, sub_x4, and_x5, or_x6, xor_x7, sll_x8, srl_x9];
     for (i, &inst) in instrs.iter().enumerate() {
-        mem.store_word(i * 4, inst);
+        mem.store_word(i * 4, inst).unwrap();
     }
 
     for _ in 0..instrs.len() {
-        cpu.clock_cycle(&mut mem);
+        cpu.clock_cycle(&mut mem).unwrap();
     }
 
     assert_eq!(cpu.reg[3], 11);
@@ -152,13 +154,13 @@ fn load_and_store_interaction() {
     // LW x11, 0(x1)
     let lw_instr: Word = 0b000000000000_00001_010_01011_0000011;
 
-    mem.store_word(0x00, sw_instr);
-    mem.store_word(0x04, lw_instr);
+    mem.store_word(0x00, sw_instr).unwrap();
+    mem.store_word(0x04, lw_instr).unwrap();
 
-    cpu.clock_cycle(&mut mem); // SW
-    cpu.clock_cycle(&mut mem); // LW
+    cpu.clock_cycle(&mut mem).unwrap(); // SW
+    cpu.clock_cycle(&mut mem).unwrap(); // LW
 
-    assert_eq!(mem.fetch_word(0x100), 0xABCD1234);
+    assert_eq!(mem.fetch_word(0x100).unwrap(), 0xABCD1234);
     assert_eq!(cpu.reg[11], 0xABCD1234);
 }
 
@@ -186,12 +188,12 @@ fn branch_and_loop_program() {
 
     let instrs: [u32; 6] = [addi_sum, addi_i, addi_limit, add_sum, inc_i, blt_back];
     for (i, &inst) in instrs.iter().enumerate() {
-        mem.store_word(i * 4, inst);
+        mem.store_word(i * 4, inst).unwrap();
     }
 
     // Run enough cycles to finish loop
     for _ in 0..18 {
-        cpu.clock_cycle(&mut mem);
+        cpu.clock_cycle(&mut mem).unwrap();
     }
 
     assert_eq!(cpu.reg[1], 15);
@@ -253,7 +255,7 @@ fn full_program_integration() {
     ];
 
     for (i, &inst) in instrs.iter().enumerate() {
-        mem.store_word(i * 4, inst);
+        mem.store_word(i * 4, inst).unwrap();
     }
 
     for i in 0..28 {
@@ -261,9 +263,9 @@ fn full_program_integration() {
             "{} | PC: 0x{:02x} X13: {} X14: {} X15: {}",
             i, cpu.pc, cpu.reg[13], cpu.reg[14], cpu.reg[15]
         );
-        cpu.clock_cycle(&mut mem);
+        cpu.clock_cycle(&mut mem).unwrap();
     }
 
-    let result = mem.fetch_word(0x100);
+    let result = mem.fetch_word(0x100).unwrap();
     assert_eq!(result, 45); // 5 * 7 = 35 >= 30, so +10
 }
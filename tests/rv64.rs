@@ -0,0 +1,399 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+fn decode64(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction_xlen(EncodingVariant::get_encoding(instruction)?, 64)
+}
+
+fn decode32(instruction: Word) -> Result<Instruction, Exception> {
+    Instruction::parse_instruction(EncodingVariant::get_encoding(instruction)?)
+}
+
+/// Executes a single instruction placed at the current pc
+fn run(cpu: &mut RISCV64, mem: &mut Memory, instruction: Word) -> Result<(), Trap<u64>> {
+    mem.store_word(cpu.pc as usize, instruction).unwrap();
+    cpu.clock_cycle(mem)
+}
+
+/* -------------------- Decoding -------------------- */
+
+/// The word ops and doubleword loads and stores decode on RV64
+#[test]
+fn rv64_decode() {
+    // addiw x1, x2, -1
+    assert_eq!(
+        decode64(0b111111111111_00010_000_00001_0011011),
+        Ok(Instruction::ADDIW {
+            imm: -1,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // sraiw x1, x2, 4
+    assert_eq!(
+        decode64(0b0100000_00100_00010_101_00001_0011011),
+        Ok(Instruction::SRAIW {
+            shamt: 4,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // subw x3, x1, x2
+    assert_eq!(
+        decode64(0b0100000_00010_00001_000_00011_0111011),
+        Ok(Instruction::SUBW {
+            rs1: 1,
+            rs2: 2,
+            rd: 3
+        })
+    );
+    // divuw x3, x1, x2
+    assert_eq!(
+        decode64(0b0000001_00010_00001_101_00011_0111011),
+        Ok(Instruction::DIVUW {
+            rs1: 1,
+            rs2: 2,
+            rd: 3
+        })
+    );
+    // ld x1, 8(x2)
+    assert_eq!(
+        decode64(0b000000001000_00010_011_00001_0000011),
+        Ok(Instruction::LD {
+            offset: 8,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // lwu x1, 0(x2)
+    assert_eq!(
+        decode64(0b000000000000_00010_110_00001_0000011),
+        Ok(Instruction::LWU {
+            offset: 0,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // sd x1, 8(x2)
+    assert_eq!(
+        decode64(0b0000000_00001_00010_011_01000_0100011),
+        Ok(Instruction::SD {
+            offset: 8,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+    // srli x1, x2, 32
+    assert_eq!(
+        decode64(0b000000_100000_00010_101_00001_0010011),
+        Ok(Instruction::SRLI {
+            shamt: 32,
+            rs1: 2,
+            rd: 1
+        })
+    );
+}
+
+/// The RV64-only encodings stay illegal on RV32
+#[test]
+fn rv64_only_encodings_are_illegal_on_rv32() {
+    for instruction in [
+        0b000000000001_00010_000_00001_0011011,  // addiw
+        0b0000000_00010_00001_000_00011_0111011, // addw
+        0b000000001000_00010_011_00001_0000011,  // ld
+        0b000000000000_00010_110_00001_0000011,  // lwu
+        0b0000000_00001_00010_011_01000_0100011, // sd
+        0b000000_100000_00010_101_00001_0010011, // srli x1, x2, 32
+        0b1110001_00000_00001_000_00001_1010011, // fmv.x.d
+    ] {
+        assert_eq!(decode32(instruction), Err(Exception::IllegalInstruction));
+    }
+}
+
+/// Word shifts with shamt[5] set are reserved even on RV64
+#[test]
+fn word_shift_with_six_bit_shamt_is_illegal() {
+    // slliw x1, x2, 32
+    assert_eq!(
+        decode64(0b0000001_00000_00010_001_00001_0011011),
+        Err(Exception::IllegalInstruction)
+    );
+}
+
+/// RV64C swaps in C.ADDIW, C.LD / C.SD, C.LDSP / C.SDSP and C.SUBW / C.ADDW
+#[test]
+fn compressed_rv64_decode() {
+    let decode = |parcel: HalfWord| Instruction::parse_compressed_xlen(parcel, 64);
+    // c.addiw a0, 1 (c.jal on RV32)
+    assert_eq!(
+        decode(0x2505),
+        Ok(Instruction::ADDIW {
+            imm: 1,
+            rs1: 10,
+            rd: 10
+        })
+    );
+    // c.addiw with rd = x0 is reserved
+    assert_eq!(decode(0x2005), Err(Exception::IllegalInstruction));
+    // c.ld a0, 8(a1)
+    assert_eq!(
+        decode(0x6588),
+        Ok(Instruction::LD {
+            offset: 8,
+            rs1: 11,
+            rd: 10
+        })
+    );
+    // c.sd a0, 8(a1)
+    assert_eq!(
+        decode(0xE588),
+        Ok(Instruction::SD {
+            offset: 8,
+            rs1: 11,
+            rs2: 10
+        })
+    );
+    // c.ldsp ra, 8(sp)
+    assert_eq!(
+        decode(0x60A2),
+        Ok(Instruction::LD {
+            offset: 8,
+            rs1: 2,
+            rd: 1
+        })
+    );
+    // c.sdsp ra, 8(sp)
+    assert_eq!(
+        decode(0xE406),
+        Ok(Instruction::SD {
+            offset: 8,
+            rs1: 2,
+            rs2: 1
+        })
+    );
+    // c.subw a0, a1 / c.addw a0, a1
+    assert_eq!(
+        decode(0x9D0D),
+        Ok(Instruction::SUBW {
+            rs1: 10,
+            rs2: 11,
+            rd: 10
+        })
+    );
+    assert_eq!(
+        decode(0x9D2D),
+        Ok(Instruction::ADDW {
+            rs1: 10,
+            rs2: 11,
+            rd: 10
+        })
+    );
+    // c.slli a0, 32
+    assert_eq!(
+        decode(0x1502),
+        Ok(Instruction::SLLI {
+            shamt: 32,
+            rs1: 10,
+            rd: 10
+        })
+    );
+}
+
+/* -------------------- Execution -------------------- */
+
+/// misa reports MXL = 2 and mstatush does not exist on RV64
+#[test]
+fn rv64_misa() {
+    let cpu: RISCV64 = RISCV64::reset();
+    assert_eq!(cpu.csr.read(MISA).unwrap() >> 62, 2);
+    assert_eq!(cpu.csr.read(MSTATUSH), Err(Exception::IllegalInstruction));
+}
+
+/// LUI, ADDI and LW results are sign-extended to 64 bits
+#[test]
+fn results_sign_extend_to_64_bits() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    // lui x1, 0x80000
+    run(&mut cpu, &mut mem, 0b10000000000000000000_00001_0110111).unwrap();
+    assert_eq!(cpu.reg[1], 0xFFFF_FFFF_8000_0000);
+    // addi x2, x0, -1
+    run(&mut cpu, &mut mem, 0b111111111111_00000_000_00010_0010011).unwrap();
+    assert_eq!(cpu.reg[2], u64::MAX);
+    // add x3, x1, x1 carries out of the low word
+    run(&mut cpu, &mut mem, 0b0000000_00001_00001_000_00011_0110011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_0000_0000);
+
+    // lw x4, 0x100(x0) sign-extends, lwu x5, 0x100(x0) zero-extends
+    mem.store_word(0x100, 0x8000_0001).unwrap();
+    run(&mut cpu, &mut mem, 0b000100000000_00000_010_00100_0000011).unwrap();
+    run(&mut cpu, &mut mem, 0b000100000000_00000_110_00101_0000011).unwrap();
+    assert_eq!(cpu.reg[4], 0xFFFF_FFFF_8000_0001);
+    assert_eq!(cpu.reg[5], 0x8000_0001);
+}
+
+/// The *W instructions operate on the low word and sign-extend the result
+#[test]
+fn word_operations() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.reg[1] = 0x1234_5678_7FFF_FFFF;
+    cpu.reg[2] = 0x0000_0001_0000_0004; // only the low word (4) takes part
+    // addiw x3, x1, 1
+    run(&mut cpu, &mut mem, 0b000000000001_00001_000_00011_0011011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_8000_0000);
+    // addw x3, x1, x2
+    run(&mut cpu, &mut mem, 0b0000000_00010_00001_000_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_8000_0003);
+    // subw x3, x2, x1
+    run(&mut cpu, &mut mem, 0b0100000_00001_00010_000_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_8000_0005);
+    // sllw x3, x1, x2
+    run(&mut cpu, &mut mem, 0b0000000_00010_00001_001_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_FFFF_FFF0);
+    // srlw x3, x3, x2 works on the low word only
+    run(&mut cpu, &mut mem, 0b0000000_00010_00011_101_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 0x0FFF_FFFF);
+    // sraiw x3, x1, 4 is an arithmetic shift of the low word
+    cpu.reg[1] = 0x8000_0000;
+    run(&mut cpu, &mut mem, 0b0100000_00100_00001_101_00011_0011011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_F800_0000);
+}
+
+/// Shift amounts are 6 bits wide on RV64
+#[test]
+fn six_bit_shift_amounts() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.reg[2] = 1;
+    // slli x1, x2, 32
+    run(&mut cpu, &mut mem, 0b000000_100000_00010_001_00001_0010011).unwrap();
+    assert_eq!(cpu.reg[1], 0x1_0000_0000);
+    // srai x1, x1, 63 of a negative value gives -1
+    cpu.reg[1] = 0x8000_0000_0000_0000;
+    run(&mut cpu, &mut mem, 0b010000_111111_00001_101_00001_0010011).unwrap();
+    assert_eq!(cpu.reg[1], u64::MAX);
+    // sll x3, x2, x4 uses the low 6 bits of x4
+    cpu.reg[4] = 0x40 | 40;
+    run(&mut cpu, &mut mem, 0b0000000_00100_00010_001_00011_0110011).unwrap();
+    assert_eq!(cpu.reg[3], 1 << 40);
+}
+
+/// SD and LD move full doublewords
+#[test]
+fn load_store_doubleword() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.reg[1] = 0x0123_4567_89AB_CDEF;
+    cpu.reg[2] = 0x200;
+    // sd x1, 8(x2)
+    run(&mut cpu, &mut mem, 0b0000000_00001_00010_011_01000_0100011).unwrap();
+    assert_eq!(mem.fetch_doubleword(0x208).unwrap(), 0x0123_4567_89AB_CDEF);
+    // ld x3, 8(x2)
+    run(&mut cpu, &mut mem, 0b000000001000_00010_011_00011_0000011).unwrap();
+    assert_eq!(cpu.reg[3], 0x0123_4567_89AB_CDEF);
+
+    // a misaligned ld faults with the full 64-bit address
+    cpu.reg[2] = 0x1_0000_0004;
+    assert_eq!(
+        run(&mut cpu, &mut mem, 0b000000000000_00010_011_00011_0000011),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x1_0000_0004))
+    );
+}
+
+/// M extension results use the full 64-bit width and the *W forms their own overflow cases
+#[test]
+fn rv64_multiply_divide() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.reg[1] = u64::MAX; // -1
+    cpu.reg[2] = 2;
+    // mulh x3, x1, x2: upper half of -2 is all ones
+    run(&mut cpu, &mut mem, 0b0000001_00010_00001_001_00011_0110011).unwrap();
+    assert_eq!(cpu.reg[3], u64::MAX);
+    // mulhu x3, x1, x2
+    run(&mut cpu, &mut mem, 0b0000001_00010_00001_011_00011_0110011).unwrap();
+    assert_eq!(cpu.reg[3], 1);
+
+    // divw x3, x1, x0: division by zero gives -1
+    run(&mut cpu, &mut mem, 0b0000001_00000_00001_100_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], u64::MAX);
+    // divw x3, x4, x1: i32::MIN / -1 overflows to i32::MIN, sign-extended
+    cpu.reg[4] = 0x8000_0000;
+    run(&mut cpu, &mut mem, 0b0000001_00001_00100_100_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_8000_0000);
+    // remuw x3, x1, x2 sees 0xFFFFFFFF % 2
+    run(&mut cpu, &mut mem, 0b0000001_00010_00001_111_00011_0111011).unwrap();
+    assert_eq!(cpu.reg[3], 1);
+}
+
+/// The .D atomics operate on doublewords, the .W atomics sign-extend their result
+#[test]
+fn rv64_atomics() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    mem.store_doubleword(0x100, 0xFFFF_FFFF).unwrap();
+    cpu.reg[1] = 0x100;
+    cpu.reg[2] = 1;
+    // amoadd.d x3, x2, (x1)
+    run(
+        &mut cpu,
+        &mut mem,
+        0b00000_0_0_00010_00001_011_00011_0101111,
+    )
+    .unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF);
+    assert_eq!(mem.fetch_doubleword(0x100).unwrap(), 0x1_0000_0000);
+    // amoswap.w x3, x2, (x1) returns the old low word sign-extended
+    mem.store_word(0x100, 0x8000_0000).unwrap();
+    run(
+        &mut cpu,
+        &mut mem,
+        0b00001_0_0_00010_00001_010_00011_0101111,
+    )
+    .unwrap();
+    assert_eq!(cpu.reg[3], 0xFFFF_FFFF_8000_0000);
+}
+
+/// FCVT.L.D, FCVT.D.L and the raw FMV.X.D / FMV.D.X moves
+#[test]
+fn rv64_double_conversions() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.freg[1] = (-5.0e12f64).to_bits();
+    // fcvt.l.d x1, f1, rtz
+    run(&mut cpu, &mut mem, 0b1100001_00010_00001_001_00001_1010011).unwrap();
+    assert_eq!(cpu.reg[1] as i64, -5_000_000_000_000);
+    // fcvt.w.d x2, f1, rtz saturates and sign-extends
+    run(&mut cpu, &mut mem, 0b1100001_00000_00001_001_00010_1010011).unwrap();
+    assert_eq!(cpu.reg[2], 0xFFFF_FFFF_8000_0000);
+    // fcvt.d.l f2, x1
+    run(&mut cpu, &mut mem, 0b1101001_00010_00001_000_00010_1010011).unwrap();
+    assert_eq!(f64::from_bits(cpu.freg[2]), -5.0e12);
+    // fmv.x.d x3, f2 and fmv.d.x f3, x3
+    run(&mut cpu, &mut mem, 0b1110001_00000_00010_000_00011_1010011).unwrap();
+    assert_eq!(cpu.reg[3], (-5.0e12f64).to_bits());
+    run(&mut cpu, &mut mem, 0b1111001_00000_00011_000_00011_1010011).unwrap();
+    assert_eq!(cpu.freg[3], (-5.0e12f64).to_bits());
+}
+
+/// Interrupts set the top bit of the 64-bit mcause
+#[test]
+fn rv64_interrupt_cause() {
+    let mut cpu: RISCV64 = RISCV64::reset();
+    let mut mem: Memory = Memory::new();
+
+    cpu.csr.write(MSTATUS, MSTATUS_MIE as u64).unwrap();
+    cpu.csr.write(MIE, MIP_MTIP as u64).unwrap();
+    cpu.set_interrupt_pending(Interrupt::MachineTimer, true);
+    cpu.step(&mut mem);
+    assert_eq!(cpu.csr.get(MCAUSE), (1 << 63) | 7);
+}
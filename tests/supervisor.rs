@@ -0,0 +1,390 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const ECALL: Word = 0b000000000000_00000_000_00000_1110011;
+const SRET: Word = 0b0001000_00010_00000_000_00000_1110011;
+const MRET: Word = 0b0011000_00010_00000_000_00000_1110011;
+const WFI: Word = 0b0001000_00101_00000_000_00000_1110011;
+const CBO_INVAL: Word = 0b000000000000_00001_010_00000_0001111; // cbo.inval (x1)
+const CBO_FLUSH: Word = 0b000000000010_00001_010_00000_0001111; // cbo.flush (x1)
+const CBO_ZERO: Word = 0b000000000100_00001_010_00000_0001111; // cbo.zero (x1)
+
+/// csrr rd, csr
+fn csrr(csr: usize, rd: Word) -> Word {
+    ((csr as Word) << 20) | (0b010 << 12) | (rd << 7) | 0b1110011
+}
+
+/// Grants S-mode and U-mode all of memory through PMP entry 0, as firmware does at boot
+fn open_pmp(cpu: &mut RISCV) {
+    cpu.csr.write(PMPADDR0, Word::MAX).unwrap(); // NAPOT over the whole address space
+    cpu.csr
+        .write(PMPCFG0, (PMP_NAPOT | PMP_R | PMP_W | PMP_X) as Word)
+        .unwrap();
+}
+
+/// A hart running in `privilege` with `program` stored from address 0 onwards, with all of
+/// memory open to it
+fn setup(privilege: Privilege, program: &[Word]) -> (RISCV, Memory) {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+    open_pmp(&mut cpu);
+    cpu.privilege = privilege;
+    (cpu, mem)
+}
+
+/* -------------------- MRET / SRET -------------------- */
+
+/// MRET switches to the mode held in MPP
+#[test]
+fn mret_enters_mpp() {
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[MRET]);
+    cpu.csr.write(MEPC, 0x100).unwrap();
+    cpu.csr
+        .write(MSTATUS, Privilege::Supervisor.mpp() | MSTATUS_MPRV)
+        .unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x100);
+    assert_eq!(cpu.privilege, Privilege::Supervisor);
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & MSTATUS_MPP, Privilege::User.mpp());
+    assert_eq!(mstatus & MSTATUS_MPRV, 0); // cleared when leaving M-mode
+}
+
+/// SRET switches to the mode held in SPP and restores SIE from SPIE
+#[test]
+fn sret_enters_spp() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[SRET]);
+    cpu.csr.write(SEPC, 0x200).unwrap();
+    cpu.csr.write(SSTATUS, MSTATUS_SPIE).unwrap();
+
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.pc, 0x200);
+    assert_eq!(cpu.privilege, Privilege::User);
+    let sstatus: Word = cpu.csr.read(SSTATUS).unwrap();
+    assert_eq!(sstatus & MSTATUS_SIE, MSTATUS_SIE);
+    assert_eq!(sstatus & MSTATUS_SPIE, MSTATUS_SPIE);
+    assert_eq!(sstatus & MSTATUS_SPP, 0);
+}
+
+/// xRET is illegal below its mode, and SRET is illegal in S-mode while TSR is set
+#[test]
+fn xret_privilege_checks() {
+    for (privilege, instruction) in [
+        (Privilege::Supervisor, MRET),
+        (Privilege::User, MRET),
+        (Privilege::User, SRET),
+    ] {
+        let (mut cpu, mut mem) = setup(privilege, &[instruction]);
+        assert_eq!(
+            cpu.clock_cycle(&mut mem),
+            Err(Trap::new(Exception::IllegalInstruction, instruction))
+        );
+    }
+
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[SRET]);
+    cpu.csr.write(MSTATUS, MSTATUS_TSR).unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, SRET))
+    );
+
+    // M-mode may always execute SRET
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[SRET]);
+    cpu.csr.write(MSTATUS, MSTATUS_TSR | MSTATUS_SPP).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.privilege, Privilege::Supervisor);
+}
+
+/// WFI is a hint in M-mode and S-mode, but illegal in U-mode and, with mstatus.TW, in S-mode
+#[test]
+fn wfi_privilege_checks() {
+    assert_eq!(
+        Instruction::parse_instruction(EncodingVariant::get_encoding(WFI).unwrap()),
+        Ok(Instruction::WFI)
+    );
+    for privilege in [Privilege::Machine, Privilege::Supervisor] {
+        let (mut cpu, mut mem) = setup(privilege, &[WFI]);
+        cpu.clock_cycle(&mut mem).unwrap();
+        assert_eq!(cpu.pc, 4);
+    }
+
+    let (mut cpu, mut mem) = setup(Privilege::User, &[WFI]);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, WFI))
+    );
+
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[WFI]);
+    cpu.csr.write(MSTATUS, MSTATUS_TW).unwrap();
+    assert_eq!(cpu.csr.read(MSTATUS), Ok(MSTATUS_TW));
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, WFI))
+    );
+
+    // TW does not apply to M-mode
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[WFI]);
+    cpu.csr.write(MSTATUS, MSTATUS_TW).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+}
+
+/* -------------------- Trap delegation -------------------- */
+
+/// ECALL reports the mode it was executed in
+#[test]
+fn ecall_cause_depends_on_privilege() {
+    for (privilege, cause) in [
+        (Privilege::User, Exception::EnvironmentCallFromUMode),
+        (Privilege::Supervisor, Exception::EnvironmentCallFromSMode),
+        (Privilege::Machine, Exception::EnvironmentCallFromMMode),
+    ] {
+        let (mut cpu, mut mem) = setup(privilege, &[ECALL]);
+        assert_eq!(cpu.clock_cycle(&mut mem), Err(Trap::new(cause, 0)));
+    }
+}
+
+/// Undelegated traps go to M-mode and record the previous mode in MPP
+#[test]
+fn trap_from_user_to_machine() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[ECALL]);
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x300);
+    assert_eq!(cpu.privilege, Privilege::Machine);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 8);
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap() & MSTATUS_MPP, 0);
+}
+
+/// Exceptions delegated through medeleg are taken in S-mode through stvec
+#[test]
+fn delegated_exception_enters_supervisor() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[0]);
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+    cpu.csr.write(STVEC, 0x400 | MTVEC_VECTORED).unwrap();
+    cpu.csr.write(MEDELEG, 1 << 2).unwrap();
+    cpu.csr.write(SSTATUS, MSTATUS_SIE).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x400); // exceptions go to BASE
+    assert_eq!(cpu.privilege, Privilege::Supervisor);
+    assert_eq!(cpu.csr.read(SEPC).unwrap(), 0);
+    assert_eq!(cpu.csr.read(SCAUSE).unwrap(), 2);
+    let sstatus: Word = cpu.csr.read(SSTATUS).unwrap();
+    assert_eq!(
+        sstatus & (MSTATUS_SIE | MSTATUS_SPIE | MSTATUS_SPP),
+        MSTATUS_SPIE
+    );
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 0); // M-mode state is untouched
+}
+
+/// Traps raised in M-mode stay in M-mode even when delegated
+#[test]
+fn machine_traps_are_not_delegated() {
+    let (mut cpu, mut mem) = setup(Privilege::Machine, &[0]);
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+    cpu.csr.write(MEDELEG, 1 << 2).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x300);
+    assert_eq!(cpu.privilege, Privilege::Machine);
+    assert_eq!(cpu.csr.read(MCAUSE).unwrap(), 2);
+}
+
+/// ECALL from M-mode cannot be delegated, and mideleg only holds supervisor interrupts
+#[test]
+fn delegation_warl() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(MEDELEG, 0xFFFF_FFFF).unwrap();
+    assert_eq!(cpu.csr.read(MEDELEG).unwrap(), 0xF7FF);
+    cpu.csr.write(MIDELEG, 0xFFFF_FFFF).unwrap();
+    assert_eq!(
+        cpu.csr.read(MIDELEG).unwrap(),
+        MIP_SSIP | MIP_STIP | MIP_SEIP
+    );
+}
+
+/* -------------------- Interrupts -------------------- */
+
+/// Delegated interrupts are taken in S-mode, and are always enabled while in U-mode
+#[test]
+fn delegated_interrupt_from_user() {
+    let (mut cpu, mut mem) = setup(Privilege::User, &[]);
+    cpu.pc = 0x20;
+    cpu.csr.write(STVEC, 0x400 | MTVEC_VECTORED).unwrap();
+    cpu.csr.write(MIDELEG, MIP_STIP).unwrap();
+    cpu.csr.write(SIE, MIP_STIP).unwrap();
+    cpu.set_interrupt_pending(Interrupt::SupervisorTimer, true);
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x400 + 4 * 5);
+    assert_eq!(cpu.privilege, Privilege::Supervisor);
+    assert_eq!(cpu.csr.read(SEPC).unwrap(), 0x20);
+    assert_eq!(cpu.csr.read(SCAUSE).unwrap(), (1 << 31) | 5);
+    assert_eq!(cpu.csr.read(SIP).unwrap(), MIP_STIP);
+}
+
+/// In S-mode delegated interrupts wait for SIE, and in M-mode they are never taken
+#[test]
+fn delegated_interrupt_masking() {
+    let nop: Word = 0b000000000000_00000_000_00000_0010011;
+    for privilege in [Privilege::Supervisor, Privilege::Machine] {
+        let (mut cpu, mut mem) = setup(privilege, &[nop]);
+        cpu.csr.write(STVEC, 0x400).unwrap();
+        cpu.csr.write(MIDELEG, MIP_SSIP).unwrap();
+        cpu.csr.write(MIE, MIP_SSIP).unwrap();
+        cpu.csr.write(MIP, MIP_SSIP).unwrap();
+        if privilege == Privilege::Machine {
+            cpu.csr.write(MSTATUS, MSTATUS_MIE | MSTATUS_SIE).unwrap();
+        }
+
+        cpu.step(&mut mem);
+        assert_eq!(cpu.pc, 4);
+        assert_eq!(cpu.privilege, privilege);
+    }
+
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[]);
+    cpu.csr.write(STVEC, 0x400).unwrap();
+    cpu.csr.write(MIDELEG, MIP_SSIP).unwrap();
+    cpu.csr.write(SIE, MIP_SSIP).unwrap();
+    cpu.csr.write(SIP, MIP_SSIP).unwrap();
+    cpu.csr.write(SSTATUS, MSTATUS_SIE).unwrap();
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x400);
+    assert_eq!(cpu.csr.read(SCAUSE).unwrap(), (1 << 31) | 1);
+}
+
+/// Machine interrupts preempt S-mode regardless of mstatus.MIE
+#[test]
+fn machine_interrupt_preempts_supervisor() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[]);
+    cpu.csr.write(MTVEC, 0x300).unwrap();
+    cpu.csr.write(MIE, MIP_MTIP).unwrap();
+    cpu.set_interrupt_pending(Interrupt::MachineTimer, true);
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.pc, 0x300);
+    assert_eq!(cpu.privilege, Privilege::Machine);
+    assert_eq!(
+        cpu.csr.read(MSTATUS).unwrap() & MSTATUS_MPP,
+        Privilege::Supervisor.mpp()
+    );
+}
+
+/* -------------------- CSR access -------------------- */
+
+/// sstatus, sie and sip are restricted views of mstatus, mie and mip
+#[test]
+fn supervisor_csr_views() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(SSTATUS, 0xFFFF_FFFF).unwrap();
+    let mstatus: Word = cpu.csr.read(MSTATUS).unwrap();
+    assert_eq!(mstatus & (MSTATUS_MIE | MSTATUS_TSR), 0);
+    assert_eq!(
+        mstatus & (MSTATUS_SIE | MSTATUS_SPIE | MSTATUS_SPP),
+        MSTATUS_SIE | MSTATUS_SPIE | MSTATUS_SPP
+    );
+    assert_eq!(cpu.csr.read(SSTATUS).unwrap() & MSTATUS_MPP, 0);
+
+    cpu.csr.write(MIE, MIP_MTIP | MIP_STIP | MIP_SEIP).unwrap();
+    cpu.csr.write(MIDELEG, MIP_STIP | MIP_SSIP).unwrap();
+    assert_eq!(cpu.csr.read(SIE).unwrap(), MIP_STIP);
+    cpu.csr.write(SIE, 0).unwrap();
+    assert_eq!(cpu.csr.read(MIE).unwrap(), MIP_MTIP | MIP_SEIP);
+
+    // only SSIP is writable through sip
+    cpu.csr.write(SIP, 0xFFFF_FFFF).unwrap();
+    assert_eq!(cpu.csr.read(MIP).unwrap(), MIP_SSIP);
+}
+
+/// CSRs can only be accessed from the mode encoded in their address or above
+#[test]
+fn csr_privilege_checks() {
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &[csrr(SSTATUS, 1), csrr(MSTATUS, 2)]);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, csrr(MSTATUS, 2)))
+    );
+
+    let (mut cpu, mut mem) = setup(Privilege::User, &[csrr(SSCRATCH, 1)]);
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, csrr(SSCRATCH, 1)))
+    );
+}
+
+/// Below M-mode the counters must be enabled by mcounteren, and in U-mode by scounteren too
+#[test]
+fn counter_enables() {
+    let program: [Word; 2] = [csrr(CYCLE, 1), csrr(INSTRET, 2)];
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &program);
+    cpu.csr.write(MCOUNTEREN, 1 << COUNTER_CY).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert!(cpu.clock_cycle(&mut mem).is_err());
+
+    let (mut cpu, mut mem) = setup(Privilege::User, &program);
+    cpu.csr.write(MCOUNTEREN, 0b111).unwrap();
+    cpu.csr.write(SCOUNTEREN, 1 << COUNTER_IR).unwrap();
+    assert!(cpu.clock_cycle(&mut mem).is_err());
+    cpu.pc = 4;
+    cpu.clock_cycle(&mut mem).unwrap();
+}
+
+/// Below M-mode cache-block operations must be enabled by menvcfg, and in U-mode by senvcfg too
+#[test]
+fn cbo_enables() {
+    let program: [Word; 3] = [CBO_ZERO, CBO_FLUSH, CBO_INVAL];
+    let (mut cpu, mut mem) = setup(Privilege::Supervisor, &program);
+    cpu.reg[1] = 0x1000;
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, CBO_ZERO))
+    );
+    cpu.csr.write(MENVCFG, ENVCFG_CBZE | ENVCFG_CBCFE).unwrap();
+    cpu.pc = 0;
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, CBO_INVAL))
+    );
+
+    let (mut cpu, mut mem) = setup(Privilege::User, &program);
+    cpu.reg[1] = 0x1000;
+    cpu.csr.write(MENVCFG, ENVCFG_CBZE | ENVCFG_CBCFE).unwrap();
+    cpu.csr.write(SENVCFG, ENVCFG_CBCFE).unwrap();
+    assert!(cpu.clock_cycle(&mut mem).is_err());
+    cpu.pc = 4;
+    cpu.clock_cycle(&mut mem).unwrap();
+}
+
+/// Only the CBO enables of menvcfg and senvcfg are writable, CBIE = 10 is reserved and the
+/// upper half of menvcfg is hardwired to 0
+#[test]
+fn envcfg_warl() {
+    let mut cpu: RISCV = RISCV::reset();
+    cpu.csr.write(MENVCFG, 0xFFFF_FFFF).unwrap();
+    assert_eq!(
+        cpu.csr.read(MENVCFG),
+        Ok(ENVCFG_CBIE | ENVCFG_CBCFE | ENVCFG_CBZE)
+    );
+    cpu.csr.write(MENVCFG, 0b10 << 4).unwrap();
+    assert_eq!(cpu.csr.read(MENVCFG), Ok(ENVCFG_CBIE));
+    cpu.csr.write(SENVCFG, 0b01 << 4).unwrap();
+    assert_eq!(cpu.csr.read(SENVCFG), Ok(0b01 << 4));
+    cpu.csr.write(MENVCFGH, 0xFFFF_FFFF).unwrap();
+    assert_eq!(cpu.csr.read(MENVCFGH), Ok(0));
+
+    let mut cpu: RISCV64 = RISCV64::reset();
+    assert_eq!(
+        cpu.csr.write(MENVCFGH, 0),
+        Err(Exception::IllegalInstruction)
+    );
+}
@@ -0,0 +1,229 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const ROOT: usize = 0x10000; // root page table
+const CODE_TABLE: usize = 0x11000; // level-0 table for the 4 MiB at virtual address 0
+const DATA_TABLE: usize = 0x12000; // level-0 table for the 4 MiB at DATA
+const DATA: Word = 0x4000_0000; // virtual address of the data pages
+const SATP_SV32: Word = (1 << 31) | (ROOT >> 12) as Word;
+
+const LW: Word = 0b000000000000_00001_010_00010_0000011; // lw x2, 0(x1)
+const SFENCE_VMA: Word = 0b0001001_00000_00000_000_00000_1110011; // sfence.vma x0, x0
+const LEAF: Word = (PTE_V | PTE_A | PTE_D) as Word;
+const R: Word = PTE_R as Word;
+const X: Word = PTE_X as Word;
+const G: Word = PTE_G as Word;
+
+/// sfence.vma rs1, rs2
+fn sfence_vma(rs1: Word, rs2: Word) -> Word {
+    SFENCE_VMA | (rs2 << 20) | (rs1 << 15)
+}
+
+/// satp selecting Sv32 with the shared root table and address space `asid`
+fn satp(asid: Word) -> Word {
+    SATP_SV32 | (asid << 22)
+}
+
+/// Maps the 4 KiB page at `va` to `pa` through the level-0 page table at `table`
+fn map_page(mem: &mut Memory, table: usize, va: Word, pa: Word, flags: Word) {
+    let vpn1: usize = (va >> 22) as usize;
+    let vpn0: usize = ((va >> 12) & 0x3FF) as usize;
+    let pointer: Word = ((table as Word >> 12) << 10) | PTE_V as Word;
+    mem.store_word(ROOT + 4 * vpn1, pointer).unwrap();
+    mem.store_word(table + 4 * vpn0, ((pa >> 12) << 10) | flags)
+        .unwrap();
+}
+
+/// Grants S-mode and U-mode all of memory through PMP entry 0, as firmware does at boot
+fn open_pmp(cpu: &mut RISCV) {
+    cpu.csr.write(PMPADDR0, Word::MAX).unwrap(); // NAPOT over the whole address space
+    cpu.csr
+        .write(PMPCFG0, (PMP_NAPOT | PMP_R | PMP_W | PMP_X) as Word)
+        .unwrap();
+}
+
+/// `cpu` in S-mode with Sv32 enabled, `program` in an identity-mapped code page and DATA
+/// mapped to 0x3000
+fn setup(mut cpu: RISCV, program: &[Word]) -> (RISCV, Memory) {
+    let mut mem: Memory = Memory::new();
+    for (i, instruction) in program.iter().enumerate() {
+        mem.store_word(4 * i, *instruction).unwrap();
+    }
+    map_page(&mut mem, CODE_TABLE, 0, 0, LEAF | R | X);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R);
+    cpu.csr.write(SATP, satp(0)).unwrap();
+    open_pmp(&mut cpu);
+    cpu.privilege = Privilege::Supervisor;
+    cpu.reg[1] = DATA;
+    (cpu, mem)
+}
+
+fn stats(hits: u64, misses: u64, flushes: u64, flushed_entries: u64) -> TlbStats {
+    TlbStats {
+        hits,
+        misses,
+        flushes,
+        flushed_entries,
+    }
+}
+
+/* -------------------- Hits and misses -------------------- */
+
+/// The first access to a page walks the tables, later ones hit in the TLB
+#[test]
+fn hits_and_misses() {
+    let (mut cpu, mut mem) = setup(RISCV::reset(), &[LW, LW, LW]);
+    for _ in 0..3 {
+        cpu.clock_cycle(&mut mem).unwrap();
+    }
+    assert_eq!(cpu.dtlb.stats(), stats(2, 1, 0, 0));
+    assert_eq!(cpu.itlb.as_ref().unwrap().stats(), stats(2, 1, 0, 0));
+
+    // machine mode and bare translation bypass the TLBs
+    cpu.privilege = Privilege::Machine;
+    cpu.reg[1] = 0x3000;
+    cpu.pc = 0;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.dtlb.stats(), stats(2, 1, 0, 0));
+}
+
+/// Faulting translations are not cached
+#[test]
+fn faults_are_not_cached() {
+    let (mut cpu, mut mem) = setup(RISCV::reset(), &[LW]);
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, PTE_V as Word | R); // A clear
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadPageFault, DATA))
+    );
+    map_page(&mut mem, DATA_TABLE, DATA, 0x3000, LEAF | R);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.dtlb.stats(), stats(0, 2, 0, 0));
+}
+
+/// A unified TLB serves fetches too; a single direct-mapped entry thrashes between the code
+/// and data pages
+#[test]
+fn unified_and_direct_mapped() {
+    let (mut cpu, mut mem) = setup(RISCV::reset_with_tlb(1, 1, false).unwrap(), &[LW, LW]);
+    assert!(cpu.itlb.is_none());
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.dtlb.stats(), stats(0, 4, 0, 0));
+
+    let (mut cpu, mut mem) = setup(RISCV::reset_with_tlb(2, 2, false).unwrap(), &[LW, LW]);
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.dtlb.stats(), stats(2, 2, 0, 0));
+}
+
+/// A full set replaces its least recently used entry
+#[test]
+fn lru_replacement() {
+    let leaf = |base: u64| Leaf {
+        pte: PTE_V | PTE_R,
+        base,
+        page_shift: 12,
+        global: false,
+    };
+    let mut tlb: Tlb = Tlb::new(2, 2).unwrap();
+    assert_eq!((tlb.entries(), tlb.ways()), (2, 2));
+    tlb.insert(0x1000, 0, leaf(0xA000));
+    tlb.insert(0x2000, 0, leaf(0xB000));
+    assert_eq!(
+        tlb.lookup(0x1234, 0).map(|l| l.physical(0x1234)),
+        Some(0xA234)
+    );
+    tlb.insert(0x3000, 0, leaf(0xC000)); // evicts 0x2000
+    assert_eq!(tlb.lookup(0x2000, 0), None);
+    assert!(tlb.lookup(0x1000, 0).is_some());
+    assert!(tlb.lookup(0x3000, 0).is_some());
+    assert_eq!(tlb.lookup(0x1000, 1), None); // another address space
+    assert_eq!(tlb.stats(), stats(3, 2, 0, 0));
+}
+
+/// The sets of a TLB must be a power of two, each with at least one way
+#[test]
+fn rejected_geometries() {
+    for (entries, ways) in [(4, 0), (6, 4), (12, 4)] {
+        assert_eq!(
+            Tlb::new(entries, ways).map(|tlb| tlb.entries()),
+            Err(ConfigError::TlbGeometry)
+        );
+    }
+    assert!(RISCV::reset_with_tlb(12, 4, true).is_err());
+}
+
+/* -------------------- SFENCE.VMA -------------------- */
+
+/// A changed mapping is only seen once SFENCE.VMA flushes the stale entry
+#[test]
+fn stale_until_sfence() {
+    let (mut cpu, mut mem) = setup(RISCV::reset(), &[LW, LW, sfence_vma(1, 0), LW]);
+    mem.store_word(0x3000, 1).unwrap();
+    mem.store_word(0x4000, 2).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 1);
+
+    map_page(&mut mem, DATA_TABLE, DATA, 0x4000, LEAF | R);
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 1); // still the cached translation
+    cpu.clock_cycle(&mut mem).unwrap();
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[2], 2);
+    // only the data page was flushed, from both TLBs
+    assert_eq!(cpu.dtlb.stats(), stats(1, 2, 1, 1));
+    assert_eq!(cpu.itlb.as_ref().unwrap().stats(), stats(3, 1, 1, 0));
+}
+
+/// Entries are tagged with the ASID in satp; flushing one address space keeps the others
+/// and the global mappings
+#[test]
+fn asid_flush() {
+    let (mut cpu, mut mem) = setup(RISCV::reset(), &[LW, sfence_vma(0, 3)]);
+    map_page(&mut mem, CODE_TABLE, 0, 0, LEAF | R | X | G);
+    cpu.clock_cycle(&mut mem).unwrap();
+
+    // address space 2 reuses the global code entry but walks again for the data page
+    cpu.csr.write(SATP, satp(2)).unwrap();
+    cpu.pc = 0;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.itlb.as_ref().unwrap().stats(), stats(1, 1, 0, 0));
+    assert_eq!(cpu.dtlb.stats(), stats(0, 2, 0, 0));
+
+    cpu.reg[3] = 2;
+    cpu.clock_cycle(&mut mem).unwrap(); // sfence.vma x0, x3
+    assert_eq!(cpu.dtlb.stats().flushed_entries, 1);
+    assert_eq!(cpu.itlb.as_ref().unwrap().stats().flushed_entries, 0);
+
+    cpu.csr.write(SATP, satp(0)).unwrap();
+    cpu.pc = 0;
+    cpu.clock_cycle(&mut mem).unwrap(); // address space 0 is still cached
+    assert_eq!(cpu.dtlb.stats(), stats(1, 2, 1, 1));
+
+    // flushing every address space drops the global entry too
+    cpu.sfence_vma(None, None);
+    assert_eq!(cpu.itlb.as_ref().unwrap().stats().flushed_entries, 1);
+}
+
+/// Flushing an address inside a superpage drops the whole superpage
+#[test]
+fn superpage_flush() {
+    let leaf: Leaf = Leaf {
+        pte: PTE_V | PTE_R,
+        base: 0x40_0000,
+        page_shift: 22,
+        global: false,
+    };
+    let mut tlb: Tlb = Tlb::default();
+    tlb.insert(0x8040_0000, 0, leaf);
+    tlb.insert(0x8040_1000, 0, leaf); // the same superpage cached through another set
+    assert_eq!(
+        tlb.lookup(0x8040_1234, 0).map(|l| l.physical(0x8040_1234)),
+        Some(0x40_1234)
+    );
+    tlb.flush(Some(0x807F_F000), Some(0));
+    assert_eq!(tlb.stats().flushed_entries, 2);
+    assert_eq!(tlb.lookup(0x8040_0000, 0), None);
+}
@@ -0,0 +1,191 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+/* -------------------- Exception codes -------------------- */
+
+#[test]
+fn exception_codes() {
+    assert_eq!(Exception::InstructionAddressMisaligned.code(), 0);
+    assert_eq!(Exception::InstructionAccessFault.code(), 1);
+    assert_eq!(Exception::IllegalInstruction.code(), 2);
+    assert_eq!(Exception::Breakpoint.code(), 3);
+    assert_eq!(Exception::LoadAddressMisaligned.code(), 4);
+    assert_eq!(Exception::LoadAccessFault.code(), 5);
+    assert_eq!(Exception::StoreAddressMisaligned.code(), 6);
+    assert_eq!(Exception::StoreAccessFault.code(), 7);
+    assert_eq!(Exception::EnvironmentCallFromUMode.code(), 8);
+    assert_eq!(Exception::EnvironmentCallFromSMode.code(), 9);
+    assert_eq!(Exception::EnvironmentCallFromMMode.code(), 11);
+    assert_eq!(Exception::InstructionPageFault.code(), 12);
+    assert_eq!(Exception::LoadPageFault.code(), 13);
+    assert_eq!(Exception::StorePageFault.code(), 15);
+}
+
+/* -------------------- Memory errors -------------------- */
+
+#[test]
+fn memory_reports_misaligned_and_out_of_bounds() {
+    let mut mem: Memory = Memory::new();
+
+    assert_eq!(mem.fetch_word(0x2), Err(MemoryError::Misaligned));
+    assert_eq!(mem.fetch_halfword(0x1), Err(MemoryError::Misaligned));
+    assert_eq!(mem.store_word(0x1, 0), Err(MemoryError::Misaligned));
+    assert_eq!(mem.store_halfword(0x3, 0), Err(MemoryError::Misaligned));
+
+    assert_eq!(mem.fetch_word(0x1000000), Err(MemoryError::OutOfBounds));
+    assert_eq!(
+        mem.fetch_halfword(0xFFFFFFFE),
+        Err(MemoryError::OutOfBounds)
+    );
+    assert_eq!(mem.fetch_byte(0x1000000), Err(MemoryError::OutOfBounds));
+    assert_eq!(mem.store_byte(0x1000000, 0), Err(MemoryError::OutOfBounds));
+
+    // the last word of memory is still accessible
+    assert_eq!(mem.store_word(0xFFFFFC, 0xCAFEBABE), Ok(()));
+    assert_eq!(mem.fetch_word(0xFFFFFC), Ok(0xCAFEBABE));
+}
+
+/* -------------------- Illegal instructions -------------------- */
+
+/// An all-zero word is not a valid instruction
+#[test]
+fn zero_instruction_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, 0))
+    );
+    assert_eq!(cpu.pc, 0);
+}
+
+/// An OPRR instruction with an unknown funct7 reports the instruction bits
+#[test]
+fn unknown_oprr_funct7_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // funct7 = 0b1111111, ADD x1, x2, x3 otherwise
+    let instruction: Word = 0b1111111_00011_00010_000_00001_0110011;
+    mem.store_word(0x0, instruction).unwrap();
+    cpu.reg[2] = 5;
+    cpu.reg[3] = 7;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+    assert_eq!(cpu.reg[1], 0); // nothing was written
+}
+
+/// A LOAD with an unused funct3 is illegal
+#[test]
+fn unknown_load_funct3_is_illegal() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // funct3 = 0b111 is not a load width in RV32I
+    let instruction: Word = 0b000000000000_00001_111_00010_0000011;
+    mem.store_word(0x0, instruction).unwrap();
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/* -------------------- Access faults -------------------- */
+
+/// LW beyond the end of memory raises a load access fault
+#[test]
+fn lw_out_of_bounds_access_fault() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // LW x5, 0(x1)
+    mem.store_word(0x0, 0b000000000000_00001_010_00101_0000011)
+        .unwrap();
+    cpu.reg[1] = 0x2000000;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAccessFault, 0x2000000))
+    );
+}
+
+/// LBU beyond the end of memory raises a load access fault
+#[test]
+fn lbu_out_of_bounds_access_fault() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // LBU x5, -1(x1)
+    mem.store_word(0x0, 0b111111111111_00001_100_00101_0000011)
+        .unwrap();
+    cpu.reg[1] = 0x1000001;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAccessFault, 0x1000000))
+    );
+}
+
+/// SB beyond the end of memory raises a store access fault
+#[test]
+fn sb_out_of_bounds_access_fault() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // SB x2, 0(x1)
+    mem.store_word(0x0, 0b0000000_00010_00001_000_00000_0100011)
+        .unwrap();
+    cpu.reg[1] = 0xFFFF_FFF0;
+    cpu.reg[2] = 0xAB;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::StoreAccessFault, 0xFFFF_FFF0))
+    );
+}
+
+/// Fetching from a misaligned pc raises an instruction address misaligned trap
+#[test]
+fn misaligned_pc_fetch() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    let misa: Word = cpu.csr.read(MISA).unwrap();
+    cpu.csr.write(MISA, misa & !MISA_C).unwrap(); // IALIGN = 32 without the C extension
+
+    cpu.pc = 0x6;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::InstructionAddressMisaligned, 0x6))
+    );
+    assert_eq!(cpu.pc, 0x6);
+}
+
+/// Execution can resume normally after a trap once the cause is fixed
+#[test]
+fn execution_resumes_after_trap() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+
+    // LW x5, 0(x1)
+    mem.store_word(0x0, 0b000000000000_00001_010_00101_0000011)
+        .unwrap();
+    mem.store_word(0x100, 0x1234).unwrap();
+    cpu.reg[1] = 0x102;
+
+    assert_eq!(
+        cpu.clock_cycle(&mut mem),
+        Err(Trap::new(Exception::LoadAddressMisaligned, 0x102))
+    );
+
+    cpu.reg[1] = 0x100;
+    cpu.clock_cycle(&mut mem).unwrap();
+    assert_eq!(cpu.reg[5], 0x1234);
+    assert_eq!(cpu.pc, 4);
+}
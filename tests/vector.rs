@@ -0,0 +1,518 @@
+#![allow(clippy::unusual_byte_groupings)]
+
+use rust_risc_v::*;
+
+const OPV: Word = 0b1010111;
+const OPIVV: Word = 0b000;
+const OPMVV: Word = 0b010;
+const OPIVI: Word = 0b011;
+const OPIVX: Word = 0b100;
+const OPMVX: Word = 0b110;
+
+const E8: Word = 0b000 << 3; // vsew
+const E16: Word = 0b001 << 3;
+const E32: Word = 0b010 << 3;
+const E64: Word = 0b011 << 3;
+const M1: Word = 0b000; // vlmul
+const M2: Word = 0b001;
+const MF2: Word = 0b111;
+
+/// vsetvli rd, rs1, vtypei
+fn vsetvli(rd: Word, rs1: Word, vtypei: Word) -> Word {
+    (vtypei << 20) | (rs1 << 15) | (0b111 << 12) | (rd << 7) | OPV
+}
+
+/// vsetivli rd, uimm, vtypei
+fn vsetivli(rd: Word, uimm: Word, vtypei: Word) -> Word {
+    (0b11 << 30) | (vtypei << 20) | (uimm << 15) | (0b111 << 12) | (rd << 7) | OPV
+}
+
+/// An OP-V arithmetic instruction, `vm` of 0 masks it by v0
+fn opv(funct6: Word, vm: Word, vs2: Word, src: Word, funct3: Word, vd: Word) -> Word {
+    (funct6 << 26) | (vm << 25) | (vs2 << 20) | (src << 15) | (funct3 << 12) | (vd << 7) | OPV
+}
+
+/// A vector load (`opcode` 0000111) or store (0100111) with nf = 1
+fn vmem(opcode: Word, mop: Word, vm: Word, rs2: Word, rs1: Word, width: Word, vd: Word) -> Word {
+    (mop << 26) | (vm << 25) | (rs2 << 20) | (rs1 << 15) | (width << 12) | (vd << 7) | opcode
+}
+
+const LOAD: Word = 0b0000111;
+const STORE: Word = 0b0100111;
+const WIDTH_8: Word = 0b000;
+const WIDTH_16: Word = 0b101;
+const WIDTH_32: Word = 0b110;
+
+/// Executes a single instruction placed at the current pc
+fn run<X: Xlen>(cpu: &mut RISCV<X>, mem: &mut Memory, instruction: Word) -> Result<(), Trap<X>> {
+    mem.store_word(cpu.pc.to_usize(), instruction).unwrap();
+    cpu.clock_cycle(mem)
+}
+
+/// An RV32 hart configured with `vtypei` and vl = `vl`
+fn configured(mem: &mut Memory, vtypei: Word, vl: Word) -> RISCV {
+    let mut cpu: RISCV = RISCV::reset();
+    run(&mut cpu, mem, vsetivli(0, vl, vtypei)).unwrap();
+    cpu
+}
+
+/// The first `count` elements of the group starting at `reg`
+fn elements<X: Xlen>(cpu: &RISCV<X>, reg: usize, count: usize, width: usize) -> Vec<u64> {
+    (0..count)
+        .map(|i| cpu.vreg.element(reg, i, width))
+        .collect()
+}
+
+fn set_elements<X: Xlen>(cpu: &mut RISCV<X>, reg: usize, width: usize, values: &[u64]) {
+    for (i, value) in values.iter().enumerate() {
+        cpu.vreg.set_element(reg, i, width, *value);
+    }
+}
+
+/* -------------------- Configuration -------------------- */
+
+/// vsetvli grants min(AVL, VLMAX) and rs1 = x0 requests VLMAX
+#[test]
+fn vsetvli_sets_vl() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = 100;
+
+    run(&mut cpu, &mut mem, vsetvli(2, 1, E32 | M1)).unwrap();
+    assert_eq!(cpu.reg[2], 4); // VLEN 128 / SEW 32
+    assert_eq!(cpu.csr.read(VL), Ok(4));
+    assert_eq!(cpu.csr.read(VTYPE), Ok(E32 | M1));
+
+    run(&mut cpu, &mut mem, vsetvli(2, 0, E8 | M2)).unwrap();
+    assert_eq!(cpu.reg[2], 32);
+
+    // rd = rs1 = x0 keeps vl, clamped to the new VLMAX
+    run(&mut cpu, &mut mem, vsetvli(0, 0, E16 | MF2)).unwrap();
+    assert_eq!(cpu.csr.read(VL), Ok(4));
+
+    run(&mut cpu, &mut mem, vsetivli(2, 3, E64 | M1)).unwrap();
+    assert_eq!(cpu.reg[2], 2);
+}
+
+/// Unsupported settings set vill and vl = 0, after which arithmetic is illegal
+#[test]
+fn vsetvl_vill() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    cpu.reg[1] = 4;
+    cpu.reg[3] = 0b100; // reserved LMUL
+    // vsetvl x2, x1, x3
+    run(&mut cpu, &mut mem, 0b1000000_00011_00001_111_00010_1010111).unwrap();
+    assert_eq!(cpu.reg[2], 0);
+    assert_eq!(cpu.csr.read(VTYPE), Ok(1 << 31));
+
+    let vadd: Word = opv(0b000000, 1, 2, 3, OPIVV, 1);
+    assert_eq!(
+        run(&mut cpu, &mut mem, vadd),
+        Err(Trap::new(Exception::IllegalInstruction, vadd))
+    );
+}
+
+/// VLEN is configurable and reported by vlenb
+#[test]
+fn configurable_vlen() {
+    let mut cpu: RISCV64 = RISCV64::reset_with_vlen(512, 64).unwrap();
+    let mut mem: Memory = Memory::new();
+    assert_eq!(cpu.csr.read(VLENB), Ok(64));
+
+    run(&mut cpu, &mut mem, vsetvli(2, 0, E64 | M1)).unwrap();
+    assert_eq!(cpu.reg[2], 8);
+
+    // SEW = 64 is wider than ELEN = 32
+    let mut cpu: RISCV = RISCV::reset_with_vlen(256, 32).unwrap();
+    run(&mut cpu, &mut mem, vsetvli(2, 0, E64 | M1)).unwrap();
+    assert_eq!(cpu.reg[2], 0);
+    assert_eq!(cpu.csr.read(VTYPE), Ok(1 << 31));
+}
+
+/// ELEN must be 32 or 64, and VLEN a power of two between ELEN and 65536
+#[test]
+fn rejected_vector_lengths() {
+    assert_eq!(
+        VectorRegisters::new(128, 16).map(|vreg| vreg.vlen()),
+        Err(ConfigError::ElementLength)
+    );
+    for vlen in [96, 32, 1 << 17] {
+        assert_eq!(
+            VectorRegisters::new(vlen, 64).map(|vreg| vreg.vlen()),
+            Err(ConfigError::VectorLength)
+        );
+    }
+    assert!(RISCV::reset_with_vlen(48, 32).is_err());
+}
+
+/// Vector instructions and CSRs are illegal while mstatus.VS is Off, and dirty it otherwise
+#[test]
+fn vector_state_off() {
+    let mut cpu: RISCV = RISCV::reset();
+    let mut mem: Memory = Memory::new();
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap() & MSTATUS_VS, VS_INITIAL);
+    run(&mut cpu, &mut mem, vsetvli(2, 0, E8 | M1)).unwrap();
+    assert_eq!(cpu.csr.read(MSTATUS).unwrap() & MSTATUS_VS, VS_DIRTY);
+
+    cpu.csr.write(MSTATUS, 0).unwrap();
+    assert_eq!(cpu.csr.read(VL), Err(Exception::IllegalInstruction));
+    let instruction: Word = vsetvli(2, 0, E8 | M1);
+    assert_eq!(
+        run(&mut cpu, &mut mem, instruction),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/* -------------------- Loads and stores -------------------- */
+
+/// vle32 / vse32 move vl consecutive elements, leaving the tail alone
+#[test]
+fn unit_stride() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E32 | M1, 3);
+    for (i, value) in [10, 20, 30, 40].iter().enumerate() {
+        mem.store_word(0x100 + 4 * i, *value).unwrap();
+    }
+    cpu.reg[1] = 0x100;
+    cpu.reg[2] = 0x200;
+    cpu.vreg.set_element(1, 3, 32, 99);
+
+    run(&mut cpu, &mut mem, vmem(LOAD, 0b00, 1, 0, 1, WIDTH_32, 1)).unwrap();
+    assert_eq!(elements(&cpu, 1, 4, 32), [10, 20, 30, 99]);
+
+    run(&mut cpu, &mut mem, vmem(STORE, 0b00, 1, 0, 2, WIDTH_32, 1)).unwrap();
+    assert_eq!(mem.fetch_word(0x208), Ok(30));
+    assert_eq!(mem.fetch_word(0x20C), Ok(0));
+}
+
+/// vlse16 steps by x[rs2] bytes, which may be negative
+#[test]
+fn strided() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E16 | M1, 3);
+    for (i, value) in [1, 2, 3, 4, 5, 6].iter().enumerate() {
+        mem.store_halfword(0x100 + 2 * i, *value).unwrap();
+    }
+    cpu.reg[1] = 0x100;
+    cpu.reg[2] = 4;
+    run(&mut cpu, &mut mem, vmem(LOAD, 0b10, 1, 2, 1, WIDTH_16, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 3, 16), [1, 3, 5]);
+
+    cpu.reg[1] = 0x10A;
+    cpu.reg[2] = -2i32 as Word;
+    run(&mut cpu, &mut mem, vmem(LOAD, 0b10, 1, 2, 1, WIDTH_16, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 3, 16), [6, 5, 4]);
+}
+
+/// Indexed loads take byte offsets from vs2, indexed stores scatter to them
+#[test]
+fn indexed() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E32 | M1, 3);
+    for (i, value) in [10, 20, 30, 40].iter().enumerate() {
+        mem.store_word(0x100 + 4 * i, *value).unwrap();
+    }
+    cpu.reg[1] = 0x100;
+    set_elements(&mut cpu, 2, 8, &[12, 0, 8]); // 8-bit indices
+
+    // vluxei8.v v3, (x1), v2
+    run(&mut cpu, &mut mem, vmem(LOAD, 0b01, 1, 2, 1, WIDTH_8, 3)).unwrap();
+    assert_eq!(elements(&cpu, 3, 3, 32), [40, 10, 30]);
+
+    // vsoxei8.v v3, (x1), v2 with the indices shifted by 0x100
+    cpu.reg[1] = 0x200;
+    run(&mut cpu, &mut mem, vmem(STORE, 0b11, 1, 2, 1, WIDTH_8, 3)).unwrap();
+    assert_eq!(mem.fetch_word(0x20C), Ok(40));
+    assert_eq!(mem.fetch_word(0x200), Ok(10));
+}
+
+/// A faulting element leaves vstart pointing at it, fault-only-first trims vl instead
+#[test]
+fn load_faults() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E32 | M1, 4);
+    cpu.reg[1] = 0xFF_FFF8; // the third element is out of bounds
+
+    let vle32: Word = vmem(LOAD, 0b00, 1, 0, 1, WIDTH_32, 1);
+    assert_eq!(
+        run(&mut cpu, &mut mem, vle32),
+        Err(Trap::new(Exception::LoadAccessFault, 0x100_0000))
+    );
+    assert_eq!(cpu.csr.read(VSTART), Ok(2));
+
+    cpu.csr.write(VSTART, 0).unwrap();
+    // vle32ff.v v1, (x1)
+    run(
+        &mut cpu,
+        &mut mem,
+        vmem(LOAD, 0b00, 1, 0b10000, 1, WIDTH_32, 1),
+    )
+    .unwrap();
+    assert_eq!(cpu.csr.read(VL), Ok(2));
+    assert_eq!(cpu.csr.read(VSTART), Ok(0));
+}
+
+/// Masked-off elements are not loaded
+#[test]
+fn masked_load() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 4);
+    mem.store_word(0x100, 0x0403_0201).unwrap();
+    cpu.reg[1] = 0x100;
+    cpu.vreg.set_element(0, 0, 8, 0b0101);
+
+    run(&mut cpu, &mut mem, vmem(LOAD, 0b00, 0, 0, 1, WIDTH_8, 1)).unwrap();
+    assert_eq!(elements(&cpu, 1, 4, 8), [1, 0, 3, 0]);
+
+    // a masked load cannot overwrite the mask
+    let instruction: Word = vmem(LOAD, 0b00, 0, 0, 1, WIDTH_8, 0);
+    assert!(run(&mut cpu, &mut mem, instruction).is_err());
+}
+
+/* -------------------- Integer arithmetic -------------------- */
+
+/// The .vv, .vx and .vi forms of vadd, with elements wrapping at SEW
+#[test]
+fn vadd_forms() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 4);
+    set_elements(&mut cpu, 2, 8, &[1, 2, 3, 0xFF]);
+    set_elements(&mut cpu, 3, 8, &[10, 20, 30, 1]);
+    cpu.reg[5] = 0x101;
+
+    run(&mut cpu, &mut mem, opv(0b000000, 1, 2, 3, OPIVV, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 4, 8), [11, 22, 33, 0]);
+    run(&mut cpu, &mut mem, opv(0b000000, 1, 2, 5, OPIVX, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 4, 8), [2, 3, 4, 0]);
+    run(&mut cpu, &mut mem, opv(0b000000, 1, 2, 0b11111, OPIVI, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 4, 8), [0, 1, 2, 0xFE]);
+    // vrsub.vi v4, v2, 0 negates
+    run(&mut cpu, &mut mem, opv(0b000011, 1, 2, 0, OPIVI, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 4, 8), [0xFF, 0xFE, 0xFD, 1]);
+}
+
+/// A register group with LMUL = 2 spans two registers and must be even
+#[test]
+fn register_groups() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E32 | M2, 8);
+    set_elements(&mut cpu, 2, 32, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // vmul.vx v4, v2, x1
+    cpu.reg[1] = 3;
+    run(&mut cpu, &mut mem, opv(0b100101, 1, 2, 1, OPMVX, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 8, 32), [3, 6, 9, 12, 15, 18, 21, 24]);
+    assert_eq!(cpu.vreg.element(5, 0, 32), 15);
+
+    let instruction: Word = opv(0b100101, 1, 3, 1, OPMVX, 4);
+    assert_eq!(
+        run(&mut cpu, &mut mem, instruction),
+        Err(Trap::new(Exception::IllegalInstruction, instruction))
+    );
+}
+
+/// Signed and unsigned compares, shifts and min / max treat elements as SEW wide
+#[test]
+fn signedness() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E16 | M1, 2);
+    set_elements(&mut cpu, 2, 16, &[0xFFFF, 5]);
+    set_elements(&mut cpu, 3, 16, &[1, 5]);
+
+    // vmslt.vv v1, v2, v3 and vmsltu.vv v4, v2, v3
+    run(&mut cpu, &mut mem, opv(0b011011, 1, 2, 3, OPIVV, 1)).unwrap();
+    assert!(cpu.vreg.mask_bit(1, 0) && !cpu.vreg.mask_bit(1, 1));
+    run(&mut cpu, &mut mem, opv(0b011010, 1, 2, 3, OPIVV, 4)).unwrap();
+    assert!(!cpu.vreg.mask_bit(4, 0) && !cpu.vreg.mask_bit(4, 1));
+
+    // vsra.vi / vsrl.vi by 4
+    run(&mut cpu, &mut mem, opv(0b101001, 1, 2, 4, OPIVI, 5)).unwrap();
+    assert_eq!(elements(&cpu, 5, 2, 16), [0xFFFF, 0]);
+    run(&mut cpu, &mut mem, opv(0b101000, 1, 2, 4, OPIVI, 5)).unwrap();
+    assert_eq!(elements(&cpu, 5, 2, 16), [0x0FFF, 0]);
+
+    // vmin.vv / vmaxu.vv
+    run(&mut cpu, &mut mem, opv(0b000101, 1, 2, 3, OPIVV, 5)).unwrap();
+    assert_eq!(elements(&cpu, 5, 2, 16), [0xFFFF, 5]);
+    run(&mut cpu, &mut mem, opv(0b000110, 1, 2, 3, OPIVV, 5)).unwrap();
+    assert_eq!(elements(&cpu, 5, 2, 16), [0xFFFF, 5]);
+}
+
+/// Division by zero and overflow follow the scalar M rules
+#[test]
+fn vdiv_edge_cases() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 3);
+    set_elements(&mut cpu, 2, 8, &[0x80, 7, 7]);
+    set_elements(&mut cpu, 3, 8, &[0xFF, 0, 2]);
+
+    run(&mut cpu, &mut mem, opv(0b100001, 1, 2, 3, OPMVV, 4)).unwrap(); // vdiv
+    assert_eq!(elements(&cpu, 4, 3, 8), [0x80, 0xFF, 3]);
+    run(&mut cpu, &mut mem, opv(0b100011, 1, 2, 3, OPMVV, 4)).unwrap(); // vrem
+    assert_eq!(elements(&cpu, 4, 3, 8), [0, 7, 1]);
+    run(&mut cpu, &mut mem, opv(0b100000, 1, 2, 3, OPMVV, 4)).unwrap(); // vdivu
+    assert_eq!(elements(&cpu, 4, 3, 8), [0, 0xFF, 3]);
+}
+
+/// Widening ops write 2 * SEW elements, narrowing shifts read them
+#[test]
+fn widening_and_narrowing() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 2);
+    set_elements(&mut cpu, 2, 8, &[0xFF, 100]);
+    set_elements(&mut cpu, 3, 8, &[0xFF, 3]);
+
+    run(&mut cpu, &mut mem, opv(0b110000, 1, 2, 3, OPMVV, 4)).unwrap(); // vwaddu.vv
+    assert_eq!(elements(&cpu, 4, 2, 16), [0x1FE, 103]);
+    run(&mut cpu, &mut mem, opv(0b111011, 1, 2, 3, OPMVV, 4)).unwrap(); // vwmul.vv
+    assert_eq!(elements(&cpu, 4, 2, 16), [1, 300]);
+
+    // vnsrl.wi v6, v4, 1 halves 300 back into 8 bits
+    run(&mut cpu, &mut mem, opv(0b101100, 1, 4, 1, OPIVI, 6)).unwrap();
+    assert_eq!(elements(&cpu, 6, 2, 8), [0, 150]);
+
+    // vsext.vf2 v8, v2 at SEW = 16
+    run(&mut cpu, &mut mem, vsetivli(0, 2, E16 | M1)).unwrap();
+    run(&mut cpu, &mut mem, opv(0b010010, 1, 2, 0b00111, OPMVV, 8)).unwrap();
+    assert_eq!(elements(&cpu, 8, 2, 16), [0xFFFF, 100]);
+}
+
+/// vmacc accumulates into vd, masked-off elements are left undisturbed
+#[test]
+fn masked_multiply_add() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E32 | M1, 4);
+    set_elements(&mut cpu, 2, 32, &[1, 2, 3, 4]);
+    set_elements(&mut cpu, 4, 32, &[100, 100, 100, 100]);
+    cpu.vreg.set_element(0, 0, 8, 0b1010);
+    cpu.reg[1] = 10;
+
+    // vmacc.vx v4, x1, v2, v0.t
+    run(&mut cpu, &mut mem, opv(0b101101, 0, 2, 1, OPMVX, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 4, 32), [100, 120, 100, 140]);
+}
+
+/// vadc adds the carry in v0, vmadc produces the carry out
+#[test]
+fn carry() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 2);
+    set_elements(&mut cpu, 2, 8, &[0xFF, 1]);
+    set_elements(&mut cpu, 3, 8, &[1, 1]);
+    cpu.vreg.set_element(0, 0, 8, 0b10);
+
+    run(&mut cpu, &mut mem, opv(0b010000, 0, 2, 3, OPIVV, 4)).unwrap(); // vadc.vvm
+    assert_eq!(elements(&cpu, 4, 2, 8), [0, 3]);
+    run(&mut cpu, &mut mem, opv(0b010001, 1, 2, 3, OPIVV, 5)).unwrap(); // vmadc.vv
+    assert!(cpu.vreg.mask_bit(5, 0) && !cpu.vreg.mask_bit(5, 1));
+
+    // vmerge.vim v6, v2, 7, v0
+    run(&mut cpu, &mut mem, opv(0b010111, 0, 2, 7, OPIVI, 6)).unwrap();
+    assert_eq!(elements(&cpu, 6, 2, 8), [0xFF, 7]);
+    // vmv.v.i v6, -1
+    run(&mut cpu, &mut mem, opv(0b010111, 1, 0, 0b11111, OPIVI, 6)).unwrap();
+    assert_eq!(elements(&cpu, 6, 2, 8), [0xFF, 0xFF]);
+}
+
+/* -------------------- Reductions -------------------- */
+
+/// Reductions fold vs2 into vs1[0] and write vd[0]
+#[test]
+fn reductions() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E16 | M1, 4);
+    set_elements(&mut cpu, 2, 16, &[5, 0xFFFE, 7, 1]);
+    cpu.vreg.set_element(3, 0, 16, 100);
+
+    run(&mut cpu, &mut mem, opv(0b000000, 1, 2, 3, OPMVV, 4)).unwrap(); // vredsum
+    assert_eq!(cpu.vreg.element(4, 0, 16), 111);
+    run(&mut cpu, &mut mem, opv(0b000101, 1, 2, 3, OPMVV, 4)).unwrap(); // vredmin
+    assert_eq!(cpu.vreg.element(4, 0, 16), 0xFFFE);
+    run(&mut cpu, &mut mem, opv(0b000110, 1, 2, 3, OPMVV, 4)).unwrap(); // vredmaxu
+    assert_eq!(cpu.vreg.element(4, 0, 16), 0xFFFE);
+    run(&mut cpu, &mut mem, opv(0b110001, 1, 2, 3, OPIVV, 4)).unwrap(); // vwredsum
+    assert_eq!(cpu.vreg.element(4, 0, 32), 111);
+    run(&mut cpu, &mut mem, opv(0b110000, 1, 2, 3, OPIVV, 4)).unwrap(); // vwredsumu
+    assert_eq!(cpu.vreg.element(4, 0, 32), 0x1_006F);
+}
+
+/* -------------------- Mask instructions -------------------- */
+
+/// Mask logical ops, vcpop, vfirst and the set-before/including/only-first family
+#[test]
+fn mask_instructions() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 8);
+    cpu.vreg.set_element(2, 0, 8, 0b0110_1000);
+    cpu.vreg.set_element(3, 0, 8, 0b0010_0100);
+
+    let mask = |cpu: &RISCV, reg: usize| cpu.vreg.element(reg, 0, 8);
+    run(&mut cpu, &mut mem, opv(0b011001, 1, 2, 3, OPMVV, 4)).unwrap(); // vmand
+    assert_eq!(mask(&cpu, 4), 0b0010_0000);
+    run(&mut cpu, &mut mem, opv(0b011000, 1, 2, 3, OPMVV, 4)).unwrap(); // vmandn
+    assert_eq!(mask(&cpu, 4), 0b0100_1000);
+    run(&mut cpu, &mut mem, opv(0b011110, 1, 2, 3, OPMVV, 4)).unwrap(); // vmnor
+    assert_eq!(mask(&cpu, 4), 0b1001_0011);
+
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 2, 0b10000, OPMVV, 1)).unwrap(); // vcpop
+    assert_eq!(cpu.reg[1], 3);
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 2, 0b10001, OPMVV, 1)).unwrap(); // vfirst
+    assert_eq!(cpu.reg[1], 3);
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 4, 0b10001, OPMVV, 1)).unwrap();
+    assert_eq!(cpu.reg[1], 0);
+    cpu.vreg.set_element(5, 0, 8, 0);
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 5, 0b10001, OPMVV, 1)).unwrap();
+    assert_eq!(cpu.reg[1], -1i32 as Word);
+
+    run(&mut cpu, &mut mem, opv(0b010100, 1, 2, 0b00001, OPMVV, 4)).unwrap(); // vmsbf
+    assert_eq!(mask(&cpu, 4), 0b0000_0111);
+    run(&mut cpu, &mut mem, opv(0b010100, 1, 2, 0b00011, OPMVV, 4)).unwrap(); // vmsif
+    assert_eq!(mask(&cpu, 4), 0b0000_1111);
+    run(&mut cpu, &mut mem, opv(0b010100, 1, 2, 0b00010, OPMVV, 4)).unwrap(); // vmsof
+    assert_eq!(mask(&cpu, 4), 0b0000_1000);
+}
+
+/// viota counts the set bits below each element, vid writes the indices
+#[test]
+fn viota_vid() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E8 | M1, 6);
+    cpu.vreg.set_element(2, 0, 8, 0b10_1101);
+
+    run(&mut cpu, &mut mem, opv(0b010100, 1, 2, 0b10000, OPMVV, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 6, 8), [0, 1, 1, 2, 3, 3]);
+    run(&mut cpu, &mut mem, opv(0b010100, 1, 0, 0b10001, OPMVV, 4)).unwrap();
+    assert_eq!(elements(&cpu, 4, 6, 8), [0, 1, 2, 3, 4, 5]);
+}
+
+/* -------------------- Moves -------------------- */
+
+/// vmv.x.s sign-extends element 0, vmv.s.x writes it, vmv2r.v copies two registers
+#[test]
+fn moves() {
+    let mut mem: Memory = Memory::new();
+    let mut cpu: RISCV = configured(&mut mem, E16 | M1, 4);
+    cpu.vreg.set_element(2, 0, 16, 0x8001);
+
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 2, 0, OPMVV, 1)).unwrap(); // vmv.x.s
+    assert_eq!(cpu.reg[1], 0xFFFF_8001);
+    cpu.reg[1] = 0x1234_5678;
+    run(&mut cpu, &mut mem, opv(0b010000, 1, 0, 1, OPMVX, 3)).unwrap(); // vmv.s.x
+    assert_eq!(cpu.vreg.element(3, 0, 16), 0x5678);
+
+    run(&mut cpu, &mut mem, opv(0b100111, 1, 2, 1, OPIVI, 6)).unwrap(); // vmv2r.v
+    assert_eq!(cpu.vreg.element(6, 0, 16), 0x8001);
+    assert_eq!(cpu.vreg.element(7, 0, 16), 0x5678);
+}
+
+/// vmv1r.v copies a whole register even when VLEN is narrower than 64 bits
+#[test]
+fn whole_register_move_with_narrow_vlen() {
+    let mut cpu: RISCV = RISCV::reset_with_vlen(32, 32).unwrap();
+    let mut mem: Memory = Memory::new();
+    cpu.vreg.set_element(2, 0, 32, 0xDEAD_BEEF);
+
+    run(&mut cpu, &mut mem, opv(0b100111, 1, 2, 0, OPIVI, 6)).unwrap(); // vmv1r.v
+    assert_eq!(cpu.vreg.element(6, 0, 32), 0xDEAD_BEEF);
+}